@@ -1,24 +1,137 @@
-use rodio::source::Source;
+use crate::settings::DEFAULT_SOUND_VOLUME;
+use rodio::source::{SineWave, Source};
 use rodio::{Decoder, OutputStreamBuilder};
+use serde::Deserialize;
 use std::io::Cursor;
 use std::thread;
 use std::time::Duration;
+use tauri::AppHandle;
 
 /// Types of sounds that can be played
-#[derive(Debug, Clone, Copy)]
+#[derive(Debug, Clone, Copy, Deserialize)]
 pub enum SoundType {
+    #[serde(rename = "start")]
     RecordingStart,
+    #[serde(rename = "stop")]
     RecordingStop,
+    #[serde(rename = "cancel")]
+    RecordingCancel,
+    /// A transcription was inserted
+    #[serde(rename = "complete")]
+    TranscriptionComplete,
+    /// A transcription failed or couldn't be inserted
+    #[serde(rename = "error")]
+    Error,
 }
 
 // Embed audio files at compile time
 const START_SOUND: &[u8] = include_bytes!("assets/start.mp3");
 const STOP_SOUND: &[u8] = include_bytes!("assets/stop.mp3");
 
+/// Built-in completion sound as (frequency Hz, duration ms) notes: a short rising chime
+const COMPLETE_TONES: &[(f32, u64)] = &[(880.0, 70), (1320.0, 110)];
+
+/// Built-in error sound: two low falling notes
+const ERROR_TONES: &[(f32, u64)] = &[(330.0, 120), (220.0, 180)];
+
+/// Level of the generated tones relative to the bundled recordings, which are
+/// mastered well below full scale
+const TONE_LEVEL: f32 = 0.5;
+
+/// Fade applied at the start of each tone so it doesn't click
+const TONE_FADE: Duration = Duration::from_millis(5);
+
+/// Playback speed of the stop sound when used for a cancelled recording
+const CANCEL_SOUND_SPEED: f32 = 0.7;
+
+/// Gain applied at full `sound_volume`; the bundled sounds are mastered loud
+const BASE_SOUND_GAIN: f32 = 0.3;
+
+/// Gain to apply for a `sound_volume` setting.
+///
+/// The volume is clamped to 0.0..=1.0 so the result never exceeds the base
+/// gain, which keeps samples from clipping.
+pub fn sound_gain(volume: f32) -> f32 {
+    if volume.is_nan() {
+        return BASE_SOUND_GAIN;
+    }
+    BASE_SOUND_GAIN * volume.clamp(0.0, 1.0)
+}
+
+/// User overrides for the notification sounds
+#[derive(Debug, Clone)]
+pub struct SoundSettings {
+    /// Playback volume in 0.0..=1.0 (0.0 behaves like sounds being disabled)
+    pub sound_volume: f32,
+    /// Custom WAV/MP3 file played when recording starts
+    pub custom_start_sound_path: Option<String>,
+    /// Custom WAV/MP3 file played when recording stops
+    pub custom_stop_sound_path: Option<String>,
+    /// Custom WAV/MP3 file played when a transcription is inserted
+    pub custom_complete_sound_path: Option<String>,
+    /// Custom WAV/MP3 file played when a transcription fails
+    pub custom_error_sound_path: Option<String>,
+}
+
+impl SoundSettings {
+    /// Read sound settings from the store
+    pub fn from_store(app: &AppHandle) -> Self {
+        let path_setting = |key: &str| {
+            crate::get_setting_from_store::<Option<String>>(app, key, None)
+                .filter(|path| !path.trim().is_empty())
+        };
+
+        Self {
+            sound_volume: crate::get_setting_from_store(app, "sound_volume", DEFAULT_SOUND_VOLUME),
+            custom_start_sound_path: path_setting("custom_start_sound_path"),
+            custom_stop_sound_path: path_setting("custom_stop_sound_path"),
+            custom_complete_sound_path: path_setting("custom_complete_sound_path"),
+            custom_error_sound_path: path_setting("custom_error_sound_path"),
+        }
+    }
+
+    /// Whether playing a sound would produce any output
+    pub fn is_audible(&self) -> bool {
+        sound_gain(self.sound_volume) > 0.0
+    }
+
+    fn custom_path(&self, sound_type: SoundType) -> Option<&str> {
+        match sound_type {
+            SoundType::RecordingStart => self.custom_start_sound_path.as_deref(),
+            SoundType::RecordingStop => self.custom_stop_sound_path.as_deref(),
+            SoundType::RecordingCancel => None,
+            SoundType::TranscriptionComplete => self.custom_complete_sound_path.as_deref(),
+            SoundType::Error => self.custom_error_sound_path.as_deref(),
+        }
+    }
+}
+
+/// Play a sound effect if `sound_enabled` is on (non-blocking)
+pub fn play_sound_if_enabled(app: &AppHandle, sound_type: SoundType) {
+    if crate::get_setting_from_store(app, "sound_enabled", true) {
+        play_sound(sound_type, SoundSettings::from_store(app));
+    }
+}
+
+/// Play `tones` one after another
+fn tone_sequence(tones: &'static [(f32, u64)]) -> Box<dyn Source + Send> {
+    Box::new(rodio::source::from_iter(tones.iter().map(
+        |&(frequency, duration_ms)| {
+            SineWave::new(frequency)
+                .take_duration(Duration::from_millis(duration_ms))
+                .fade_in(TONE_FADE)
+                .amplify(TONE_LEVEL)
+        },
+    )))
+}
+
 /// Play a sound effect (non-blocking)
-pub fn play_sound(sound_type: SoundType) {
+pub fn play_sound(sound_type: SoundType, settings: SoundSettings) {
+    if !settings.is_audible() {
+        return;
+    }
     thread::spawn(move || {
-        if let Err(e) = play_sound_blocking(sound_type) {
+        if let Err(e) = play_sound_blocking(sound_type, &settings) {
             log::warn!("Failed to play sound: {}", e);
         }
     });
@@ -26,16 +139,44 @@ pub fn play_sound(sound_type: SoundType) {
 
 fn play_sound_blocking(
     sound_type: SoundType,
+    settings: &SoundSettings,
 ) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
     let stream = OutputStreamBuilder::open_default_stream()?;
 
-    let sound_data = match sound_type {
-        SoundType::RecordingStart => START_SOUND,
-        SoundType::RecordingStop => STOP_SOUND,
-    };
+    let custom_source =
+        settings
+            .custom_path(sound_type)
+            .and_then(|path| match load_custom_sound(path) {
+                Ok(source) => Some(source),
+                Err(e) => {
+                    log::warn!(
+                        "Failed to load custom sound {}: {}, using built-in sound",
+                        path,
+                        e
+                    );
+                    None
+                }
+            });
 
-    let cursor = Cursor::new(sound_data);
-    let source = Decoder::new(cursor)?.amplify(0.3);
+    let source: Box<dyn Source + Send> = match (custom_source, sound_type) {
+        (Some(source), _) => Box::new(source),
+        (None, SoundType::RecordingStart) => {
+            Box::new(Decoder::new(Cursor::new(START_SOUND.to_vec()))?)
+        }
+        (None, SoundType::RecordingStop | SoundType::RecordingCancel) => {
+            Box::new(Decoder::new(Cursor::new(STOP_SOUND.to_vec()))?)
+        }
+        (None, SoundType::TranscriptionComplete) => tone_sequence(COMPLETE_TONES),
+        (None, SoundType::Error) => tone_sequence(ERROR_TONES),
+    };
+    // The cancel sound is the stop sound slowed down, so it's distinct but familiar
+    let speed = match sound_type {
+        SoundType::RecordingCancel => CANCEL_SOUND_SPEED,
+        _ => 1.0,
+    };
+    let source = source
+        .speed(speed)
+        .amplify(sound_gain(settings.sound_volume));
 
     // Get duration for sleep, default to 500ms if unknown
     let duration = source
@@ -47,3 +188,11 @@ fn play_sound_blocking(
 
     Ok(())
 }
+
+/// Read and decode a user-provided sound file
+fn load_custom_sound(
+    path: &str,
+) -> Result<Decoder<Cursor<Vec<u8>>>, Box<dyn std::error::Error + Send + Sync>> {
+    let bytes = std::fs::read(path)?;
+    Ok(Decoder::new(Cursor::new(bytes))?)
+}
@@ -0,0 +1,165 @@
+//! Linux audio mute control implementation using `pactl`.
+//!
+//! Works with both PulseAudio and PipeWire (via pipewire-pulse), controlling
+//! the default output sink. Requires `pactl` 14 or newer for the
+//! `get-sink-mute` / `get-sink-volume` / `get-default-sink` subcommands.
+
+use super::{
+    parse_pactl_channel_volumes, parse_pactl_mute, AudioControlError, SavedVolume,
+    SystemAudioControl, PA_VOLUME_NORM,
+};
+use std::process::Command;
+use std::sync::{Mutex, OnceLock};
+
+/// Sink name pactl resolves to the current default output
+const DEFAULT_SINK: &str = "@DEFAULT_SINK@";
+
+/// Run `pactl` with the given arguments, returning stdout on success
+fn pactl(args: &[&str]) -> Result<String, String> {
+    let output = Command::new("pactl")
+        .args(args)
+        .output()
+        .map_err(|e| format!("failed to run pactl: {}", e))?;
+    if !output.status.success() {
+        return Err(format!(
+            "pactl {} failed: {}",
+            args.join(" "),
+            String::from_utf8_lossy(&output.stderr).trim()
+        ));
+    }
+    Ok(String::from_utf8_lossy(&output.stdout).into_owned())
+}
+
+/// Check (once) whether a PulseAudio-compatible sound server is reachable
+pub fn is_available() -> bool {
+    static AVAILABLE: OnceLock<bool> = OnceLock::new();
+    *AVAILABLE.get_or_init(|| match pactl(&["info"]) {
+        Ok(_) => true,
+        Err(e) => {
+            log::warn!("No PulseAudio/PipeWire sound server found: {}", e);
+            false
+        }
+    })
+}
+
+/// Linux audio controller driving the default sink through `pactl`.
+pub struct LinuxAudioController {
+    /// Sink we muted, so unmute targets it even if the default output changed
+    muted_sink: Mutex<Option<String>>,
+}
+
+impl LinuxAudioController {
+    /// Create a new Linux audio controller.
+    ///
+    /// Fails if no PulseAudio-compatible sound server is reachable.
+    pub fn new() -> Result<Self, AudioControlError> {
+        if !is_available() {
+            return Err(AudioControlError::InitializationFailed(
+                "pactl could not reach a PulseAudio/PipeWire server".to_string(),
+            ));
+        }
+        Ok(Self {
+            muted_sink: Mutex::new(None),
+        })
+    }
+
+    /// Name of the current default sink, falling back to the `@DEFAULT_SINK@` alias
+    fn default_sink() -> String {
+        pactl(&["get-default-sink"])
+            .map(|name| name.trim().to_string())
+            .ok()
+            .filter(|name| !name.is_empty())
+            .unwrap_or_else(|| DEFAULT_SINK.to_string())
+    }
+
+    /// Raw per-channel volumes of `sink`
+    fn sink_volumes(sink: &str) -> Result<Vec<u32>, AudioControlError> {
+        let output =
+            pactl(&["get-sink-volume", sink]).map_err(AudioControlError::GetPropertyFailed)?;
+        parse_pactl_channel_volumes(&output).ok_or_else(|| {
+            AudioControlError::GetPropertyFailed(format!(
+                "Unexpected pactl output: {}",
+                output.trim()
+            ))
+        })
+    }
+
+    /// Set the raw per-channel volumes of `sink`
+    fn set_sink_volumes(sink: &str, volumes: &[u32]) -> Result<(), AudioControlError> {
+        let volumes: Vec<String> = volumes.iter().map(u32::to_string).collect();
+        let mut args = vec!["set-sink-volume", sink];
+        args.extend(volumes.iter().map(String::as_str));
+        pactl(&args)
+            .map(|_| ())
+            .map_err(AudioControlError::SetPropertyFailed)
+    }
+
+    /// Sink a saved volume was read from
+    fn saved_sink(saved: &SavedVolume) -> Result<&str, AudioControlError> {
+        match saved {
+            SavedVolume::Sink { name, .. } => Ok(name),
+            SavedVolume::Master(_) => Err(AudioControlError::NotSupported),
+        }
+    }
+}
+
+impl SystemAudioControl for LinuxAudioController {
+    fn is_muted(&self) -> Result<bool, AudioControlError> {
+        let output = pactl(&["get-sink-mute", &Self::default_sink()])
+            .map_err(AudioControlError::GetPropertyFailed)?;
+        parse_pactl_mute(&output).ok_or_else(|| {
+            AudioControlError::GetPropertyFailed(format!(
+                "Unexpected pactl output: {}",
+                output.trim()
+            ))
+        })
+    }
+
+    fn set_muted(&self, muted: bool) -> Result<(), AudioControlError> {
+        let mut muted_sink = self
+            .muted_sink
+            .lock()
+            .map_err(|e| AudioControlError::SetPropertyFailed(e.to_string()))?;
+        let sink = if muted {
+            Self::default_sink()
+        } else {
+            muted_sink
+                .take()
+                .unwrap_or_else(|| DEFAULT_SINK.to_string())
+        };
+
+        pactl(&["set-sink-mute", &sink, if muted { "1" } else { "0" }])
+            .map_err(AudioControlError::SetPropertyFailed)?;
+        if muted {
+            *muted_sink = Some(sink);
+        }
+        Ok(())
+    }
+
+    fn save_volume(&self) -> Result<SavedVolume, AudioControlError> {
+        let name = Self::default_sink();
+        let channels = Self::sink_volumes(&name)?;
+        Ok(SavedVolume::Sink { name, channels })
+    }
+
+    fn get_saved_output_volume(&self, saved: &SavedVolume) -> Result<f32, AudioControlError> {
+        let channels = Self::sink_volumes(Self::saved_sink(saved)?)?;
+        Ok(channels.into_iter().max().unwrap_or(0) as f32 / PA_VOLUME_NORM as f32)
+    }
+
+    fn set_saved_output_volume(
+        &self,
+        saved: &SavedVolume,
+        level: f32,
+    ) -> Result<(), AudioControlError> {
+        let raw = (level.max(0.0) * PA_VOLUME_NORM as f32).round() as u32;
+        Self::set_sink_volumes(Self::saved_sink(saved)?, &[raw])
+    }
+
+    fn restore_volume(&self, saved: &SavedVolume) -> Result<(), AudioControlError> {
+        match saved {
+            SavedVolume::Sink { name, channels } => Self::set_sink_volumes(name, channels),
+            SavedVolume::Master(_) => Err(AudioControlError::NotSupported),
+        }
+    }
+}
@@ -5,7 +5,7 @@
 
 use super::{AudioControlError, SystemAudioControl};
 use objc2_core_audio::{
-    kAudioDevicePropertyMute, kAudioDevicePropertyScopeOutput,
+    kAudioDevicePropertyMute, kAudioDevicePropertyScopeOutput, kAudioDevicePropertyVolumeScalar,
     kAudioHardwarePropertyDefaultOutputDevice, kAudioObjectPropertyElementMain,
     kAudioObjectPropertyScopeGlobal, kAudioObjectSystemObject, AudioObjectGetPropertyData,
     AudioObjectPropertyAddress, AudioObjectSetPropertyData,
@@ -101,6 +101,69 @@ impl MacOSAudioController {
         Ok(value)
     }
 
+    /// Get an f32 property from the default output device.
+    fn get_f32_property(&self, selector: u32) -> Result<f32, AudioControlError> {
+        let address = AudioObjectPropertyAddress {
+            mSelector: selector,
+            mScope: kAudioDevicePropertyScopeOutput,
+            mElement: kAudioObjectPropertyElementMain,
+        };
+
+        let mut value: f32 = 0.0;
+        let mut size = std::mem::size_of::<f32>() as u32;
+
+        let status = unsafe {
+            AudioObjectGetPropertyData(
+                self.device_id,
+                NonNull::new(&address as *const _ as *mut _).unwrap(),
+                0,
+                std::ptr::null(),
+                NonNull::new(&mut size as *mut _).unwrap(),
+                NonNull::new(&mut value as *mut _ as *mut c_void).unwrap(),
+            )
+        };
+
+        if status != 0 {
+            return Err(AudioControlError::GetPropertyFailed(format!(
+                "OSStatus: {}",
+                status
+            )));
+        }
+
+        Ok(value)
+    }
+
+    /// Set an f32 property on the default output device.
+    fn set_f32_property(&self, selector: u32, value: f32) -> Result<(), AudioControlError> {
+        let address = AudioObjectPropertyAddress {
+            mSelector: selector,
+            mScope: kAudioDevicePropertyScopeOutput,
+            mElement: kAudioObjectPropertyElementMain,
+        };
+
+        let size = std::mem::size_of::<f32>() as u32;
+
+        let status = unsafe {
+            AudioObjectSetPropertyData(
+                self.device_id,
+                NonNull::new(&address as *const _ as *mut _).unwrap(),
+                0,
+                std::ptr::null(),
+                size,
+                NonNull::new(&value as *const _ as *mut c_void).unwrap(),
+            )
+        };
+
+        if status != 0 {
+            return Err(AudioControlError::SetPropertyFailed(format!(
+                "OSStatus: {}",
+                status
+            )));
+        }
+
+        Ok(())
+    }
+
     /// Set a u32 property on the default output device.
     fn set_u32_property(&self, selector: u32, value: u32) -> Result<(), AudioControlError> {
         let address = AudioObjectPropertyAddress {
@@ -142,4 +205,14 @@ impl SystemAudioControl for MacOSAudioController {
     fn set_muted(&self, muted: bool) -> Result<(), AudioControlError> {
         self.set_u32_property(kAudioDevicePropertyMute, if muted { 1 } else { 0 })
     }
+
+    // Devices without a main-element volume control fail here, and ducking
+    // falls back to a full mute
+    fn get_volume(&self) -> Result<f32, AudioControlError> {
+        self.get_f32_property(kAudioDevicePropertyVolumeScalar)
+    }
+
+    fn set_volume(&self, level: f32) -> Result<(), AudioControlError> {
+        self.set_f32_property(kAudioDevicePropertyVolumeScalar, level.clamp(0.0, 1.0))
+    }
 }
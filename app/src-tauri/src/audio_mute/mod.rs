@@ -3,20 +3,24 @@
 //! This module provides a minimal trait interface for controlling system audio,
 //! making it easy to swap implementations or migrate to a cross-platform library.
 
+use crate::settings::MuteMode;
 use std::fmt;
 use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Mutex;
 
 // Platform-specific implementations
+#[cfg(target_os = "linux")]
+mod linux;
 #[cfg(target_os = "macos")]
 mod macos;
-#[cfg(not(any(target_os = "windows", target_os = "macos")))]
+#[cfg(not(any(target_os = "windows", target_os = "macos", target_os = "linux")))]
 mod stub;
 #[cfg(target_os = "windows")]
 mod windows;
 
 /// Error type for audio control operations
 #[derive(Debug)]
-#[allow(dead_code)] // Variants used on Windows/macOS, not Linux
+#[allow(dead_code)] // Variants unused by the stub controller
 pub enum AudioControlError {
     /// Platform-specific initialization failed
     InitializationFailed(String),
@@ -51,20 +55,179 @@ pub trait SystemAudioControl: Send + Sync {
 
     /// Set system mute state
     fn set_muted(&self, muted: bool) -> Result<(), AudioControlError>;
+
+    /// Mute every application's audio except those matching `exclusions`.
+    ///
+    /// Returns the identifiers of the applications that were actually muted
+    /// (apps that were already muted are skipped), so they can be restored.
+    fn mute_apps_except(&self, _exclusions: &[String]) -> Result<Vec<String>, AudioControlError> {
+        Err(AudioControlError::NotSupported)
+    }
+
+    /// Unmute the given applications
+    fn unmute_apps(&self, _apps: &[String]) -> Result<(), AudioControlError> {
+        Err(AudioControlError::NotSupported)
+    }
+
+    /// Get the master volume as a scalar in 0.0..=1.0
+    fn get_volume(&self) -> Result<f32, AudioControlError> {
+        Err(AudioControlError::NotSupported)
+    }
+
+    /// Set the master volume as a scalar in 0.0..=1.0
+    fn set_volume(&self, _level: f32) -> Result<(), AudioControlError> {
+        Err(AudioControlError::NotSupported)
+    }
+
+    /// Save the exact volume of the current output, for `restore_volume`
+    fn save_volume(&self) -> Result<SavedVolume, AudioControlError> {
+        self.get_volume().map(SavedVolume::Master)
+    }
+
+    /// Current volume of the output `saved` was read from, as a scalar
+    fn get_saved_output_volume(&self, _saved: &SavedVolume) -> Result<f32, AudioControlError> {
+        self.get_volume()
+    }
+
+    /// Set the volume of the output `saved` was read from, as a scalar
+    fn set_saved_output_volume(
+        &self,
+        _saved: &SavedVolume,
+        level: f32,
+    ) -> Result<(), AudioControlError> {
+        self.set_volume(level)
+    }
+
+    /// Put back the volume in `saved` exactly, on the output it was read from
+    fn restore_volume(&self, saved: &SavedVolume) -> Result<(), AudioControlError> {
+        match saved {
+            SavedVolume::Master(level) => self.set_volume(*level),
+            SavedVolume::Sink { .. } => Err(AudioControlError::NotSupported),
+        }
+    }
+}
+
+/// pactl's raw volume for 100%
+pub const PA_VOLUME_NORM: u32 = 65536;
+
+/// Volume saved before ducking, so `unmute()` restores it exactly
+#[derive(Debug, Clone, PartialEq)]
+pub enum SavedVolume {
+    /// Master volume scalar (Windows, macOS)
+    Master(f32),
+    /// A pactl sink and its raw per-channel volumes (`PA_VOLUME_NORM` = 100%,
+    /// higher with software amplification), so balance and boost survive ducking
+    Sink { name: String, channels: Vec<u32> },
+}
+
+impl SavedVolume {
+    /// Volume as a scalar (the loudest channel for a sink), for ducking decisions
+    pub fn level(&self) -> f32 {
+        match self {
+            SavedVolume::Master(level) => *level,
+            SavedVolume::Sink { channels, .. } => {
+                channels.iter().copied().max().unwrap_or(0) as f32 / PA_VOLUME_NORM as f32
+            }
+        }
+    }
 }
 
 /// Check if audio mute is supported on this platform.
+///
+/// On Windows this requires an accessible default render endpoint, and on
+/// Linux a reachable PulseAudio/PipeWire sound server.
 pub fn is_supported() -> bool {
-    #[cfg(any(target_os = "windows", target_os = "macos"))]
+    #[cfg(target_os = "windows")]
+    {
+        windows::is_available()
+    }
+    #[cfg(target_os = "macos")]
     {
         true
     }
-    #[cfg(not(any(target_os = "windows", target_os = "macos")))]
+    #[cfg(target_os = "linux")]
+    {
+        linux::is_available()
+    }
+    #[cfg(not(any(target_os = "windows", target_os = "macos", target_os = "linux")))]
     {
         false
     }
 }
 
+/// Check if muting individual applications is supported on this platform.
+pub fn is_per_app_supported() -> bool {
+    cfg!(target_os = "windows")
+}
+
+/// Check if an application identifier matches an exclusion entry.
+///
+/// Comparison is case-insensitive and ignores a trailing ".exe", so "Spotify"
+/// matches "spotify.exe".
+#[allow(dead_code)] // Used by the Windows implementation, not macOS/Linux
+pub fn app_matches_exclusion(app: &str, exclusion: &str) -> bool {
+    let normalize = |s: &str| {
+        let lower = s.trim().to_lowercase();
+        lower
+            .strip_suffix(".exe")
+            .map(str::to_string)
+            .unwrap_or(lower)
+    };
+    normalize(app) == normalize(exclusion)
+}
+
+/// Parse the output of `pactl get-sink-mute` ("Mute: yes" / "Mute: no")
+#[allow(dead_code)] // Used by the Linux implementation, not Windows/macOS
+pub fn parse_pactl_mute(output: &str) -> Option<bool> {
+    match output.trim().strip_prefix("Mute:")?.trim() {
+        "yes" => Some(true),
+        "no" => Some(false),
+        _ => None,
+    }
+}
+
+/// Parse the output of `pactl get-sink-volume` into raw per-channel volumes, e.g.
+/// "Volume: front-left: 42597 /  65% / -11.23 dB,   front-right: 39321 / ..."
+/// gives [42597, 39321]. Values above 100% (software amplification) are kept.
+#[allow(dead_code)] // Used by the Linux implementation, not Windows/macOS
+pub fn parse_pactl_channel_volumes(output: &str) -> Option<Vec<u32>> {
+    let line = output.lines().next()?.trim().strip_prefix("Volume:")?;
+    let channels = line
+        .split(',')
+        .map(|channel| {
+            let (_, value) = channel.split_once(':')?;
+            value.split('/').next()?.trim().parse::<u32>().ok()
+        })
+        .collect::<Option<Vec<u32>>>()?;
+    (!channels.is_empty()).then_some(channels)
+}
+
+/// Tolerance when comparing volume levels read back from the system, which may
+/// be quantized to the device's volume steps
+const VOLUME_EPSILON: f32 = 0.01;
+
+/// Compute the volume to duck to from the current volume and a percentage.
+///
+/// Returns None if the current volume is already at or below the duck level,
+/// so ducking never raises the volume.
+pub fn duck_target(current: f32, duck_level: u8) -> Option<f32> {
+    let target = f32::from(duck_level.min(100)) / 100.0;
+    (current > target + VOLUME_EPSILON).then_some(target)
+}
+
+/// Decide which volume to restore after ducking.
+///
+/// Returns None if nothing was ducked, or if the volume no longer matches what
+/// we ducked to (the user changed it mid-recording, so their choice is kept).
+/// Otherwise the prior volume is restored exactly as saved.
+pub fn restore_target(prior: &SavedVolume, ducked: f32, current: f32) -> Option<&SavedVolume> {
+    if (prior.level() - ducked).abs() <= VOLUME_EPSILON || (current - ducked).abs() > VOLUME_EPSILON
+    {
+        return None;
+    }
+    Some(prior)
+}
+
 /// Create a platform-appropriate audio controller.
 ///
 /// Returns a boxed trait object that can control system audio.
@@ -80,7 +243,12 @@ pub fn create_controller() -> Result<Box<dyn SystemAudioControl>, AudioControlEr
         macos::MacOSAudioController::new().map(|c| Box::new(c) as Box<dyn SystemAudioControl>)
     }
 
-    #[cfg(not(any(target_os = "windows", target_os = "macos")))]
+    #[cfg(target_os = "linux")]
+    {
+        linux::LinuxAudioController::new().map(|c| Box::new(c) as Box<dyn SystemAudioControl>)
+    }
+
+    #[cfg(not(any(target_os = "windows", target_os = "macos", target_os = "linux")))]
     {
         Ok(Box::new(stub::StubAudioController::new()))
     }
@@ -96,6 +264,10 @@ pub struct AudioMuteManager {
     was_muted_before: AtomicBool,
     /// Are we currently in a muted state (that we caused)?
     is_currently_muting: AtomicBool,
+    /// Apps muted individually (Some when exclusions were applied instead of a system mute)
+    muted_apps: Mutex<Option<Vec<String>>>,
+    /// Prior volume and the level ducked to, when we ducked instead of muting
+    ducked_volume: Mutex<Option<(SavedVolume, f32)>>,
 }
 
 impl AudioMuteManager {
@@ -108,6 +280,8 @@ impl AudioMuteManager {
                 controller,
                 was_muted_before: AtomicBool::new(false),
                 is_currently_muting: AtomicBool::new(false),
+                muted_apps: Mutex::new(None),
+                ducked_volume: Mutex::new(None),
             }),
             Err(e) => {
                 log::warn!("Audio mute not available: {}", e);
@@ -119,13 +293,45 @@ impl AudioMuteManager {
     /// Mute system audio for recording.
     ///
     /// Saves the current mute state so it can be restored later.
+    /// In `Duck` mode the master volume is lowered to `duck_level` percent
+    /// instead, and the prior volume is restored by `unmute()`.
+    /// If `exclusions` is non-empty and per-app muting is supported, every app
+    /// except the excluded ones is muted instead of the whole system.
     /// If already muting, this is a no-op.
-    pub fn mute(&self) -> Result<(), AudioControlError> {
+    pub fn mute(
+        &self,
+        exclusions: &[String],
+        mode: MuteMode,
+        duck_level: u8,
+    ) -> Result<(), AudioControlError> {
         // Check if we're already muting
         if self.is_currently_muting.swap(true, Ordering::SeqCst) {
             return Ok(()); // Already muting, nothing to do
         }
 
+        if mode == MuteMode::Duck {
+            match self.duck(duck_level) {
+                Ok(()) => return Ok(()),
+                Err(e) => log::warn!("Failed to duck audio ({}), muting instead", e),
+            }
+        }
+
+        if !exclusions.is_empty() {
+            if is_per_app_supported() {
+                let muted_apps = self.controller.mute_apps_except(exclusions)?;
+                log::info!(
+                    "Muted {} app(s) for recording, excluding {:?}",
+                    muted_apps.len(),
+                    exclusions
+                );
+                if let Ok(mut guard) = self.muted_apps.lock() {
+                    *guard = Some(muted_apps);
+                }
+                return Ok(());
+            }
+            log::warn!("Per-app mute exclusions not supported on this platform, muting all audio");
+        }
+
         // Check current mute state and save it
         let was_muted = self.controller.is_muted().unwrap_or(false);
         self.was_muted_before.store(was_muted, Ordering::SeqCst);
@@ -151,6 +357,36 @@ impl AudioMuteManager {
             return Ok(()); // Not muting, nothing to do
         }
 
+        // Restore the volume we ducked from
+        let ducked_volume = self
+            .ducked_volume
+            .lock()
+            .ok()
+            .and_then(|mut guard| guard.take());
+        if let Some((prior, ducked)) = ducked_volume {
+            let current = self.controller.get_saved_output_volume(&prior)?;
+            match restore_target(&prior, ducked, current) {
+                Some(saved) => {
+                    self.controller.restore_volume(saved)?;
+                    log::info!("System volume restored to {:?}", saved);
+                }
+                None => log::info!("System volume changed during recording, leaving it"),
+            }
+            return Ok(());
+        }
+
+        // Restore only the apps we muted individually
+        let muted_apps = self
+            .muted_apps
+            .lock()
+            .ok()
+            .and_then(|mut guard| guard.take());
+        if let Some(apps) = muted_apps {
+            self.controller.unmute_apps(&apps)?;
+            log::info!("Unmuted {} app(s) after recording", apps.len());
+            return Ok(());
+        }
+
         // Only unmute if it wasn't already muted before we started
         if !self.was_muted_before.load(Ordering::SeqCst) {
             self.controller.set_muted(false)?;
@@ -161,6 +397,32 @@ impl AudioMuteManager {
 
         Ok(())
     }
+
+    /// Lower the master volume to `duck_level` percent, remembering the prior level
+    fn duck(&self, duck_level: u8) -> Result<(), AudioControlError> {
+        let prior = self.controller.save_volume()?;
+        let current = prior.level();
+        let target = duck_target(current, duck_level);
+        if let Some(level) = target {
+            self.controller.set_saved_output_volume(&prior, level)?;
+        }
+
+        // Record even when skipped so unmute() knows we ducked rather than muted
+        let target = target.unwrap_or(current);
+        if let Ok(mut guard) = self.ducked_volume.lock() {
+            *guard = Some((prior, target));
+        }
+        if target == current {
+            log::info!("System volume already at or below duck level, skipping");
+            return Ok(());
+        }
+        log::info!(
+            "System volume ducked from {:.0}% to {:.0}% for recording",
+            current * 100.0,
+            target * 100.0
+        );
+        Ok(())
+    }
 }
 
 impl Drop for AudioMuteManager {
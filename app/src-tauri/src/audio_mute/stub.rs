@@ -1,4 +1,4 @@
-//! Stub implementation for unsupported platforms (Linux, etc.)
+//! Stub implementation for unsupported platforms (BSDs, etc.)
 //!
 //! This provides a no-op implementation that logs warnings but doesn't fail.
 
@@ -1,20 +1,40 @@
 //! Windows audio mute control implementation using WASAPI.
 //!
 //! Uses the Windows Audio Session API (WASAPI) to control the default audio
-//! output device's mute state.
+//! output device's mute state, and per-application audio sessions for mute
+//! exclusions.
 
-use super::{AudioControlError, SystemAudioControl};
+use super::{app_matches_exclusion, AudioControlError, SystemAudioControl};
+use windows::core::Interface;
 use windows::Win32::{
+    Foundation::S_OK,
     Media::Audio::{
-        eConsole, eRender, Endpoints::IAudioEndpointVolume, IMMDevice, IMMDeviceEnumerator,
+        eConsole, eRender, Endpoints::IAudioEndpointVolume, IAudioSessionControl2,
+        IAudioSessionManager2, IMMDevice, IMMDeviceEnumerator, ISimpleAudioVolume,
         MMDeviceEnumerator,
     },
-    System::Com::{CoCreateInstance, CoInitializeEx, CLSCTX_ALL, COINIT_MULTITHREADED},
+    System::Com::{
+        CoCreateInstance, CoInitializeEx, CoTaskMemFree, CLSCTX_ALL, COINIT_MULTITHREADED,
+    },
 };
 
+/// Check whether the default render endpoint is accessible.
+///
+/// Not cached, since output devices come and go (e.g. unplugged headphones).
+pub fn is_available() -> bool {
+    match WindowsAudioController::new() {
+        Ok(_) => true,
+        Err(e) => {
+            log::warn!("Default audio endpoint unavailable: {}", e);
+            false
+        }
+    }
+}
+
 /// Windows audio controller using WASAPI.
 pub struct WindowsAudioController {
     endpoint_volume: IAudioEndpointVolume,
+    session_manager: IAudioSessionManager2,
 }
 
 // SAFETY: IAudioEndpointVolume is thread-safe when properly initialized with COM
@@ -59,11 +79,75 @@ impl WindowsAudioController {
                     ))
                 })?;
 
-            Ok(Self { endpoint_volume })
+            // Get the session manager for per-application control
+            let session_manager = device
+                .Activate::<IAudioSessionManager2>(CLSCTX_ALL, None)
+                .map_err(|e| {
+                    AudioControlError::InitializationFailed(format!(
+                        "Failed to activate session manager: {}",
+                        e
+                    ))
+                })?;
+
+            Ok(Self {
+                endpoint_volume,
+                session_manager,
+            })
+        }
+    }
+
+    /// Get the active audio sessions as (executable name, volume control) pairs.
+    fn sessions(&self) -> Result<Vec<(String, ISimpleAudioVolume)>, AudioControlError> {
+        unsafe {
+            let enumerator = self.session_manager.GetSessionEnumerator().map_err(|e| {
+                AudioControlError::GetPropertyFailed(format!("GetSessionEnumerator: {}", e))
+            })?;
+            let count = enumerator
+                .GetCount()
+                .map_err(|e| AudioControlError::GetPropertyFailed(format!("GetCount: {}", e)))?;
+
+            let mut sessions = Vec::new();
+            for index in 0..count {
+                let Ok(control) = enumerator.GetSession(index) else {
+                    continue;
+                };
+                let Ok(control2) = control.cast::<IAudioSessionControl2>() else {
+                    continue;
+                };
+                let Some(app) = session_app_name(&control2) else {
+                    continue;
+                };
+                let Ok(volume) = control.cast::<ISimpleAudioVolume>() else {
+                    continue;
+                };
+                sessions.push((app, volume));
+            }
+            Ok(sessions)
         }
     }
 }
 
+/// Get the lowercase executable name owning an audio session.
+///
+/// Session instance identifiers look like
+/// `{...}|\Device\HarddiskVolume3\Program Files\Spotify\Spotify.exe%b{...}`.
+/// Returns None for the system sounds session.
+unsafe fn session_app_name(control: &IAudioSessionControl2) -> Option<String> {
+    if control.IsSystemSoundsSession() == S_OK {
+        return None;
+    }
+
+    let identifier = control.GetSessionInstanceIdentifier().ok()?;
+    let text = identifier.to_string().ok();
+    CoTaskMemFree(Some(identifier.0 as *const _));
+
+    let path = text?.split('|').nth(1)?.split('%').next()?.to_string();
+    path.rsplit('\\')
+        .next()
+        .filter(|name| !name.is_empty())
+        .map(str::to_lowercase)
+}
+
 impl SystemAudioControl for WindowsAudioController {
     fn is_muted(&self) -> Result<bool, AudioControlError> {
         unsafe {
@@ -81,4 +165,71 @@ impl SystemAudioControl for WindowsAudioController {
                 .map_err(|e| AudioControlError::SetPropertyFailed(format!("SetMute: {}", e)))
         }
     }
+
+    fn get_volume(&self) -> Result<f32, AudioControlError> {
+        unsafe {
+            self.endpoint_volume
+                .GetMasterVolumeLevelScalar()
+                .map_err(|e| {
+                    AudioControlError::GetPropertyFailed(format!(
+                        "GetMasterVolumeLevelScalar: {}",
+                        e
+                    ))
+                })
+        }
+    }
+
+    fn set_volume(&self, level: f32) -> Result<(), AudioControlError> {
+        unsafe {
+            self.endpoint_volume
+                .SetMasterVolumeLevelScalar(level.clamp(0.0, 1.0), std::ptr::null())
+                .map_err(|e| {
+                    AudioControlError::SetPropertyFailed(format!(
+                        "SetMasterVolumeLevelScalar: {}",
+                        e
+                    ))
+                })
+        }
+    }
+
+    fn mute_apps_except(&self, exclusions: &[String]) -> Result<Vec<String>, AudioControlError> {
+        let mut muted_apps = Vec::new();
+        for (app, volume) in self.sessions()? {
+            if exclusions
+                .iter()
+                .any(|exclusion| app_matches_exclusion(&app, exclusion))
+            {
+                continue;
+            }
+
+            unsafe {
+                // Leave sessions the user already muted alone so we don't unmute them later
+                if volume.GetMute().map(|m| m.as_bool()).unwrap_or(false) {
+                    continue;
+                }
+                if let Err(e) = volume.SetMute(true, std::ptr::null()) {
+                    log::warn!("Failed to mute {}: {}", app, e);
+                    continue;
+                }
+            }
+
+            if !muted_apps.contains(&app) {
+                muted_apps.push(app);
+            }
+        }
+        Ok(muted_apps)
+    }
+
+    fn unmute_apps(&self, apps: &[String]) -> Result<(), AudioControlError> {
+        for (app, volume) in self.sessions()? {
+            if apps.contains(&app) {
+                unsafe {
+                    if let Err(e) = volume.SetMute(false, std::ptr::null()) {
+                        log::warn!("Failed to unmute {}: {}", app, e);
+                    }
+                }
+            }
+        }
+        Ok(())
+    }
 }
@@ -0,0 +1,38 @@
+//! Command-line flags for controlling an already-running instance, e.g.
+//! `tambourine --toggle` from a terminal or script.
+//!
+//! Only one instance runs at a time: a second launch hands its arguments to the
+//! first (via the single-instance plugin) and exits. The first instance runs the
+//! flag's action the same way the hotkeys would, or shows its main window when
+//! there is none.
+
+use crate::control_server::RemoteAction;
+
+/// Flags and the action each requests
+pub const CLI_ACTIONS: [(&str, RemoteAction); 5] = [
+    ("--toggle", RemoteAction::Toggle),
+    ("--start", RemoteAction::Start),
+    ("--stop", RemoteAction::Stop),
+    ("--cancel", RemoteAction::Cancel),
+    ("--paste-last", RemoteAction::PasteLast),
+];
+
+/// Find the action requested by `args` (the program name first, as in `std::env::args`).
+/// Other arguments are ignored, since the OS and autostart may add their own.
+/// Fails when more than one action is given.
+pub fn parse_cli_action(args: &[String]) -> Result<Option<RemoteAction>, String> {
+    let mut actions = args.iter().skip(1).filter_map(|arg| {
+        CLI_ACTIONS
+            .iter()
+            .find(|(flag, _)| arg == flag)
+            .map(|&(flag, action)| (flag, action))
+    });
+    let first = actions.next();
+    if let (Some((first_flag, _)), Some((second_flag, _))) = (first, actions.next()) {
+        return Err(format!(
+            "Only one action can be given, got {} and {}",
+            first_flag, second_flag
+        ));
+    }
+    Ok(first.map(|(_, action)| action))
+}
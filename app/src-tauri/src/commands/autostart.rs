@@ -0,0 +1,63 @@
+use tauri::AppHandle;
+
+#[cfg(desktop)]
+use tauri_plugin_autostart::ManagerExt;
+#[cfg(desktop)]
+use tauri_plugin_store::StoreExt;
+
+/// Store key for the launch-at-login preference
+#[cfg(desktop)]
+const AUTOSTART_KEY: &str = "autostart";
+
+/// Check whether the app launches at login. Reports the OS state (login item,
+/// registry Run key or autostart desktop entry), which the user may have changed
+/// outside the app.
+#[cfg(desktop)]
+#[tauri::command]
+pub async fn get_autostart(app: AppHandle) -> Result<bool, String> {
+    app.autolaunch()
+        .is_enabled()
+        .map_err(|e| format!("Failed to read launch at login state: {}", e))
+}
+
+// Stub for non-desktop platforms
+#[cfg(not(desktop))]
+#[tauri::command]
+pub async fn get_autostart(_app: AppHandle) -> Result<bool, String> {
+    Err("Launch at login is not supported on this platform".to_string())
+}
+
+/// Register or unregister the app to launch at login, and persist the preference
+#[cfg(desktop)]
+#[tauri::command]
+pub async fn set_autostart(app: AppHandle, enabled: bool) -> Result<(), String> {
+    let autolaunch = app.autolaunch();
+    let result = if enabled {
+        autolaunch.enable()
+    } else {
+        autolaunch.disable()
+    };
+    result.map_err(|e| {
+        format!(
+            "Failed to {} launch at login: {}",
+            if enabled { "enable" } else { "disable" },
+            e
+        )
+    })?;
+
+    let store = app.store("settings.json").map_err(|e| e.to_string())?;
+    store.set(AUTOSTART_KEY, enabled);
+    store.save().map_err(|e| e.to_string())?;
+    log::info!(
+        "Launch at login {}",
+        if enabled { "enabled" } else { "disabled" }
+    );
+    Ok(())
+}
+
+// Stub for non-desktop platforms
+#[cfg(not(desktop))]
+#[tauri::command]
+pub async fn set_autostart(_app: AppHandle, _enabled: bool) -> Result<(), String> {
+    Err("Launch at login is not supported on this platform".to_string())
+}
@@ -0,0 +1,45 @@
+use crate::control_server::{
+    self, ControlServerStatus, CONTROL_SERVER_ENABLED_KEY, CONTROL_SERVER_PORT_KEY,
+    CONTROL_SERVER_TOKEN_KEY,
+};
+use tauri::AppHandle;
+use tauri_plugin_store::StoreExt;
+
+/// Get the control server settings, its token and whether it is listening
+#[tauri::command]
+pub async fn get_control_server(app: AppHandle) -> Result<ControlServerStatus, String> {
+    Ok(control_server::status(&app))
+}
+
+/// Enable or disable the local control API, optionally on a new port.
+/// A token is created the first time it is enabled.
+#[tauri::command]
+pub async fn set_control_server(
+    app: AppHandle,
+    enabled: bool,
+    port: Option<u16>,
+) -> Result<ControlServerStatus, String> {
+    if port == Some(0) {
+        return Err("Control server port must be between 1 and 65535".to_string());
+    }
+    let store = app.store("settings.json").map_err(|e| e.to_string())?;
+    store.set(CONTROL_SERVER_ENABLED_KEY, enabled);
+    if let Some(port) = port {
+        store.set(CONTROL_SERVER_PORT_KEY, port);
+    }
+    store.save().map_err(|e| e.to_string())?;
+
+    control_server::apply(&app)?;
+    Ok(control_server::status(&app))
+}
+
+/// Replace the control server token; requests with the old one are rejected from now on
+#[tauri::command]
+pub async fn regenerate_control_server_token(app: AppHandle) -> Result<String, String> {
+    let token = control_server::generate_token();
+    let store = app.store("settings.json").map_err(|e| e.to_string())?;
+    store.set(CONTROL_SERVER_TOKEN_KEY, token.clone());
+    store.save().map_err(|e| e.to_string())?;
+    log::info!("Generated a new control server token");
+    Ok(token)
+}
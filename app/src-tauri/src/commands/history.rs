@@ -1,35 +1,451 @@
-use crate::history::{HistoryEntry, HistoryStorage};
-use tauri::State;
+use crate::commands::text::{run_on_main_thread, type_text_blocking, InsertionSettings};
+use crate::get_setting_from_store;
+use crate::history::{
+    read_encrypted_history, ExportFormat, HistoryChange, HistoryEncryptionStatus, HistoryEntry,
+    HistoryLimits, HistoryStats, HistoryStorage, ImportResult, DEFAULT_APPEND_WINDOW_MS,
+    DEFAULT_HISTORY_MAX_AGE_DAYS, DEFAULT_HISTORY_MAX_ENTRIES, HISTORY_ENCRYPTION_KEY,
+    INCOGNITO_KEY,
+};
+use crate::history_crypto::{keychain_key, HistoryKey, KdfParams, KeySource};
+use crate::state::AppState;
+use crate::text_processing::{
+    apply_rules, apply_vocabulary, rules_from_store, timestamp_prefix_from_store,
+    vocabulary_from_store,
+};
+use crate::tray::{refresh_last_transcription, refresh_tray_status};
+use crate::webhook::{notify_transcription, TranscriptionPayload};
+use arboard::Clipboard;
+use std::path::PathBuf;
+use tauri::{AppHandle, Emitter, Manager, State};
+use tauri_plugin_store::StoreExt;
 
-/// Add a new entry to the dictation history
+/// Read the history size/age limits from the store
+fn history_limits(app: &AppHandle) -> HistoryLimits {
+    HistoryLimits {
+        max_entries: get_setting_from_store(
+            app,
+            "history_max_entries",
+            DEFAULT_HISTORY_MAX_ENTRIES,
+        ),
+        max_age_days: get_setting_from_store(
+            app,
+            "history_max_age_days",
+            DEFAULT_HISTORY_MAX_AGE_DAYS,
+        ),
+    }
+}
+
+/// Update the tray's last transcription and emit "history-changed" with `change`.
+/// Call after every change to the history.
+pub(crate) fn notify_history_changed(app: &AppHandle, change: HistoryChange) {
+    refresh_last_transcription(app);
+    let _ = app.emit("history-changed", change);
+}
+
+/// Add a new entry to the dictation history. In append mode, a transcription that
+/// completes within `append_window_ms` of the previous one extends that entry instead.
 #[tauri::command]
 pub async fn add_history_entry(
+    app: AppHandle,
     text: String,
+    language: Option<String>,
+    audio_duration_ms: Option<u64>,
     history: State<'_, HistoryStorage>,
 ) -> Result<HistoryEntry, String> {
-    history.add_entry(text)
+    let text = apply_vocabulary(&text, &vocabulary_from_store(&app));
+    let text = apply_rules(&text, &rules_from_store(&app));
+    if text.trim().is_empty() {
+        return Err("Cannot add an empty transcription to history".to_string());
+    }
+    // Sent once the entry is stored; never leaves the machine in incognito mode
+    let webhook_payload = (!history.is_incognito()).then(|| TranscriptionPayload {
+        text: text.clone(),
+        timestamp: chrono::Utc::now(),
+        duration_ms: audio_duration_ms,
+        language: language.clone(),
+    });
+    if get_setting_from_store(&app, "append_mode", false) {
+        let window_ms: u64 =
+            get_setting_from_store(&app, "append_window_ms", DEFAULT_APPEND_WINDOW_MS);
+        if let Some(entry) =
+            history.append_to_latest(&text, audio_duration_ms, window_ms, chrono::Utc::now())?
+        {
+            notify_history_changed(
+                &app,
+                HistoryChange::Updated {
+                    entry: entry.clone(),
+                },
+            );
+            if let Some(payload) = webhook_payload {
+                notify_transcription(&app, payload);
+            }
+            return Ok(entry);
+        }
+    }
+    let text = match timestamp_prefix_from_store(&app, true) {
+        Some(prefix) => format!("{}{}", prefix, text),
+        None => text,
+    };
+    let entry = history.add_entry(text, language, audio_duration_ms)?;
+    let pruned = history.prune(&history_limits(&app))?;
+    // Pruning may have removed older entries too
+    notify_history_changed(
+        &app,
+        if pruned > 0 {
+            HistoryChange::Reset
+        } else {
+            HistoryChange::Added {
+                entry: entry.clone(),
+            }
+        },
+    );
+    if let Some(payload) = webhook_payload {
+        notify_transcription(&app, payload);
+    }
+    Ok(entry)
 }
 
-/// Get dictation history entries
+/// Get dictation history entries (newest first, or pinned first if requested)
 #[tauri::command]
 pub async fn get_history(
     limit: Option<usize>,
+    pinned_first: Option<bool>,
     history: State<'_, HistoryStorage>,
 ) -> Result<Vec<HistoryEntry>, String> {
-    history.get_all(limit)
+    if pinned_first.unwrap_or(false) {
+        history.get_all_pinned_first(limit)
+    } else {
+        history.get_all(limit)
+    }
+}
+
+/// Get totals and averages over the dictation history
+#[tauri::command]
+pub async fn get_history_stats(history: State<'_, HistoryStorage>) -> Result<HistoryStats, String> {
+    history.stats()
+}
+
+/// Turn incognito mode on or off: persist the choice, update the tray and emit
+/// "incognito-changed" so the windows can show it
+pub(crate) fn set_incognito_mode(app: &AppHandle, incognito: bool) -> Result<(), String> {
+    app.state::<HistoryStorage>().set_incognito(incognito);
+    let store = app.store("settings.json").map_err(|e| e.to_string())?;
+    store.set(INCOGNITO_KEY, incognito);
+    store.save().map_err(|e| e.to_string())?;
+    log::info!(
+        "Incognito mode {}",
+        if incognito { "enabled" } else { "disabled" }
+    );
+
+    refresh_tray_status(app);
+    let _ = app.emit("incognito-changed", incognito);
+    Ok(())
+}
+
+/// Keep new history entries in memory only (until quit) instead of saving them
+#[tauri::command]
+pub async fn set_incognito(app: AppHandle, enabled: bool) -> Result<(), String> {
+    set_incognito_mode(&app, enabled)
+}
+
+/// Get whether incognito mode is on
+#[tauri::command]
+pub fn get_incognito(history: State<'_, HistoryStorage>) -> bool {
+    history.is_incognito()
+}
+
+/// Get whether the history file is encrypted and whether it is locked
+#[tauri::command]
+pub async fn get_history_encryption(
+    history: State<'_, HistoryStorage>,
+) -> Result<HistoryEncryptionStatus, String> {
+    history.encryption_status()
+}
+
+/// Encrypt the history file with a key from the OS keychain or derived from
+/// `passphrase`, or store it as plaintext again when `key_source` is `None`.
+/// This also migrates an existing plaintext history. The choice is persisted so
+/// the history starts locked, not as plaintext, if the encrypted file goes missing.
+#[tauri::command]
+pub async fn set_history_encryption(
+    app: AppHandle,
+    key_source: Option<KeySource>,
+    passphrase: Option<String>,
+    history: State<'_, HistoryStorage>,
+) -> Result<HistoryEncryptionStatus, String> {
+    let key = match key_source {
+        None => None,
+        Some(KeySource::Keychain) => Some(keychain_key(true)?),
+        Some(KeySource::Passphrase) => {
+            let passphrase = passphrase.ok_or("A passphrase is required")?;
+            Some(HistoryKey::new_from_passphrase(
+                &passphrase,
+                KdfParams::default(),
+            )?)
+        }
+    };
+    history.set_encryption(key)?;
+    let store = app.store("settings.json").map_err(|e| e.to_string())?;
+    match key_source {
+        Some(source) => store.set(HISTORY_ENCRYPTION_KEY, serde_json::json!(source)),
+        None => {
+            store.delete(HISTORY_ENCRYPTION_KEY);
+        }
+    }
+    store.save().map_err(|e| e.to_string())?;
+    log::info!("History encryption set to {:?}", key_source);
+    history.encryption_status()
+}
+
+/// Unlock an encrypted history whose key wasn't available at startup, using the
+/// keychain or `passphrase` (whichever the file was encrypted with). If the
+/// encrypted file is gone, a new history is started with a key of the same kind.
+/// Returns the number of entries loaded.
+#[tauri::command]
+pub async fn unlock_history(
+    app: AppHandle,
+    passphrase: Option<String>,
+    history: State<'_, HistoryStorage>,
+) -> Result<usize, String> {
+    let file_path = history.file_path()?;
+    let envelope = file_path.parent().and_then(read_encrypted_history);
+    let key = match envelope {
+        Some(envelope) => match envelope.key_source {
+            KeySource::Keychain => keychain_key(false)?,
+            KeySource::Passphrase => HistoryKey::from_passphrase(
+                &passphrase.ok_or("A passphrase is required")?,
+                &envelope,
+            )?,
+        },
+        None => match history.encryption_status()?.key_source {
+            Some(KeySource::Keychain) => keychain_key(true)?,
+            Some(KeySource::Passphrase) => HistoryKey::new_from_passphrase(
+                &passphrase.ok_or("A passphrase is required")?,
+                KdfParams::default(),
+            )?,
+            None => return Err("History is not encrypted".to_string()),
+        },
+    };
+    let count = history.unlock(key)?;
+    notify_history_changed(&app, HistoryChange::Reset);
+    log::info!("Unlocked history ({} entries)", count);
+    Ok(count)
+}
+
+/// Search dictation history entries by text
+#[tauri::command]
+pub async fn search_history(
+    query: String,
+    limit: Option<usize>,
+    history: State<'_, HistoryStorage>,
+) -> Result<Vec<HistoryEntry>, String> {
+    history.search(&query, limit)
+}
+
+/// Get dictation history entries within a time range (inclusive), newest first
+#[tauri::command]
+pub async fn get_history_range(
+    start_ts: chrono::DateTime<chrono::Utc>,
+    end_ts: chrono::DateTime<chrono::Utc>,
+    limit: Option<usize>,
+    history: State<'_, HistoryStorage>,
+) -> Result<Vec<HistoryEntry>, String> {
+    history.get_range(start_ts, end_ts, limit)
 }
 
 /// Delete a history entry by ID
 #[tauri::command]
 pub async fn delete_history_entry(
+    app: AppHandle,
+    id: String,
+    history: State<'_, HistoryStorage>,
+) -> Result<bool, String> {
+    let deleted = history.delete(&id)?;
+    if deleted {
+        notify_history_changed(&app, HistoryChange::Deleted { id });
+    }
+    Ok(deleted)
+}
+
+/// Replace the text of a history entry, e.g. to fix a mistranscribed word
+#[tauri::command]
+pub async fn update_history_entry(
+    app: AppHandle,
+    id: String,
+    new_text: String,
+    history: State<'_, HistoryStorage>,
+) -> Result<HistoryEntry, String> {
+    if new_text.trim().is_empty() {
+        return Err("History entry text cannot be empty".to_string());
+    }
+    let entry = history.update(&id, new_text)?;
+    notify_history_changed(
+        &app,
+        HistoryChange::Updated {
+            entry: entry.clone(),
+        },
+    );
+    Ok(entry)
+}
+
+/// Tag a history entry
+#[tauri::command]
+pub async fn add_history_tag(
+    app: AppHandle,
     id: String,
+    tag: String,
+    history: State<'_, HistoryStorage>,
+) -> Result<HistoryEntry, String> {
+    let entry = history.add_tag(&id, &tag)?;
+    notify_history_changed(
+        &app,
+        HistoryChange::Updated {
+            entry: entry.clone(),
+        },
+    );
+    Ok(entry)
+}
+
+/// Remove a tag from a history entry
+#[tauri::command]
+pub async fn remove_history_tag(
+    app: AppHandle,
+    id: String,
+    tag: String,
+    history: State<'_, HistoryStorage>,
+) -> Result<HistoryEntry, String> {
+    let entry = history.remove_tag(&id, &tag)?;
+    notify_history_changed(
+        &app,
+        HistoryChange::Updated {
+            entry: entry.clone(),
+        },
+    );
+    Ok(entry)
+}
+
+/// Get history entries with the given tag (newest first)
+#[tauri::command]
+pub async fn get_history_by_tag(
+    tag: String,
+    history: State<'_, HistoryStorage>,
+) -> Result<Vec<HistoryEntry>, String> {
+    history.get_by_tag(&tag)
+}
+
+/// Pin or unpin a history entry by ID
+#[tauri::command]
+pub async fn set_history_pinned(
+    app: AppHandle,
+    id: String,
+    pinned: bool,
     history: State<'_, HistoryStorage>,
 ) -> Result<bool, String> {
-    history.delete(&id)
+    let updated = history.set_pinned(&id, pinned)?;
+    if let Some(entry) = history.get(&id)?.filter(|_| updated) {
+        notify_history_changed(&app, HistoryChange::Updated { entry });
+    }
+    Ok(updated)
+}
+
+/// Clear history entries, keeping pinned ones unless `include_pinned` is true
+#[tauri::command]
+pub async fn clear_history(
+    app: AppHandle,
+    include_pinned: Option<bool>,
+    history: State<'_, HistoryStorage>,
+) -> Result<(), String> {
+    history.clear(include_pinned.unwrap_or(false))?;
+    notify_history_changed(&app, HistoryChange::Reset);
+    Ok(())
 }
 
-/// Clear all history entries
+/// Delete history entries from before `before`, keeping pinned ones.
+/// Returns the number of entries removed.
 #[tauri::command]
-pub async fn clear_history(history: State<'_, HistoryStorage>) -> Result<(), String> {
-    history.clear()
+pub async fn clear_history_before(
+    app: AppHandle,
+    before: chrono::DateTime<chrono::Utc>,
+    history: State<'_, HistoryStorage>,
+) -> Result<usize, String> {
+    let removed = history.clear_before(before)?;
+    if removed > 0 {
+        notify_history_changed(&app, HistoryChange::Reset);
+    }
+    log::info!("Cleared {} history entries from before {}", removed, before);
+    Ok(removed)
+}
+
+/// Export history to a file as JSON or plain text. Returns the number of entries written.
+#[tauri::command]
+pub async fn export_history(
+    format: ExportFormat,
+    path: String,
+    oldest_first: Option<bool>,
+    history: State<'_, HistoryStorage>,
+) -> Result<usize, String> {
+    history.export(format, &PathBuf::from(path), oldest_first.unwrap_or(false))
+}
+
+/// Merge entries from a JSON history export, optionally skipping duplicates
+#[tauri::command]
+pub async fn import_history(
+    app: AppHandle,
+    path: String,
+    dedupe: bool,
+    history: State<'_, HistoryStorage>,
+) -> Result<ImportResult, String> {
+    let result = history.import(&PathBuf::from(path), dedupe)?;
+    history.prune(&history_limits(&app))?;
+    notify_history_changed(&app, HistoryChange::Reset);
+    Ok(result)
+}
+
+/// Apply the configured history size/age limits now. Returns the number of entries removed.
+#[tauri::command]
+pub async fn prune_history(
+    app: AppHandle,
+    history: State<'_, HistoryStorage>,
+) -> Result<usize, String> {
+    let removed = history.prune(&history_limits(&app))?;
+    if removed > 0 {
+        notify_history_changed(&app, HistoryChange::Reset);
+    }
+    Ok(removed)
+}
+
+/// Copy a history entry's text to the clipboard without pasting it
+#[tauri::command]
+pub async fn copy_history_entry(
+    id: String,
+    history: State<'_, HistoryStorage>,
+) -> Result<(), String> {
+    let entry = history
+        .get(&id)?
+        .ok_or_else(|| format!("History entry not found: {}", id))?;
+
+    let mut clipboard = Clipboard::new().map_err(|e| e.to_string())?;
+    clipboard
+        .set_text(entry.text)
+        .map_err(|e| format!("Failed to copy to clipboard: {}", e))
+}
+
+/// Insert a history entry's text into the focused application, using the same
+/// insertion settings as a new transcription
+#[tauri::command]
+pub async fn paste_history_entry(
+    app: AppHandle,
+    id: String,
+    history: State<'_, HistoryStorage>,
+) -> Result<(), String> {
+    let entry = history
+        .get(&id)?
+        .ok_or_else(|| format!("History entry not found: {}", id))?;
+
+    let settings = InsertionSettings::from_store(&app);
+    let inserted_chars =
+        run_on_main_thread(&app, move || type_text_blocking(&entry.text, settings))?;
+    app.state::<AppState>()
+        .record_insertion(inserted_chars, crate::now_millis());
+    Ok(())
 }
@@ -1,4 +1,14 @@
+pub mod autostart;
+pub mod control;
 pub mod history;
 pub mod overlay;
+pub mod permissions;
+pub mod profiles;
+pub mod recordings;
+pub mod server;
 pub mod settings;
+pub mod sound;
+pub mod storage;
 pub mod text;
+pub mod webhook;
+pub mod window;
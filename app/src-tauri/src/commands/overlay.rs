@@ -1,12 +1,369 @@
-use tauri::{AppHandle, Manager};
+use crate::get_setting_from_store;
+use crate::settings::{
+    validate_overlay_opacity, OverlayAnchor, OverlaySize, OverlayTheme, OverlayVisibility,
+    DEFAULT_OVERLAY_MARGIN_X, DEFAULT_OVERLAY_MARGIN_Y, DEFAULT_OVERLAY_OPACITY,
+    MIN_OVERLAY_OPACITY, OVERLAY_SIZE_KEY,
+};
+use crate::state::AppState;
+use serde::{Deserialize, Serialize};
+use std::sync::atomic::Ordering;
+use tauri::{AppHandle, Emitter, Manager, Monitor, PhysicalPosition, WebviewWindow};
+use tauri_plugin_store::StoreExt;
+
+/// Store key for the user's dragged overlay position
+const CUSTOM_POSITION_KEY: &str = "overlay_custom_position";
+
+/// Store key for the monitor the overlay is placed on (unset = follow the main window)
+const OVERLAY_MONITOR_KEY: &str = "overlay_monitor";
+
+/// Delay before hiding the overlay after a transcription completes (`WhenActive`)
+const OVERLAY_HIDE_DELAY_MS: u64 = 1000;
+
+/// Delay before hiding after an error, long enough for the error state to be seen
+const OVERLAY_ERROR_HIDE_DELAY_MS: u64 = 3000;
+
+/// Logical-pixel rectangle describing a monitor's area
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct ScreenRect {
+    pub x: f64,
+    pub y: f64,
+    pub width: f64,
+    pub height: f64,
+}
+
+/// Logical top-left position of the overlay after the user dragged it
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct OverlayPosition {
+    pub x: f64,
+    pub y: f64,
+}
+
+impl ScreenRect {
+    /// Check if a logical point lies within this rectangle
+    pub fn contains(&self, x: f64, y: f64) -> bool {
+        x >= self.x && x < self.x + self.width && y >= self.y && y < self.y + self.height
+    }
+}
+
+/// Overlay placement settings (anchor and margins from the anchored edges)
+#[derive(Debug, Clone, Copy)]
+pub struct OverlayPlacement {
+    pub anchor: OverlayAnchor,
+    pub margin_x: f64,
+    pub margin_y: f64,
+}
+
+impl OverlayPlacement {
+    /// Read overlay placement from the store, with defaults for missing values
+    pub fn from_store(app: &AppHandle) -> Self {
+        Self {
+            anchor: get_setting_from_store(app, "overlay_anchor", OverlayAnchor::default()),
+            margin_x: get_setting_from_store(app, "overlay_margin_x", DEFAULT_OVERLAY_MARGIN_X),
+            margin_y: get_setting_from_store(app, "overlay_margin_y", DEFAULT_OVERLAY_MARGIN_Y),
+        }
+    }
+}
+
+/// Compute the overlay's top-left logical position on `screen`, clamped so the
+/// overlay stays fully visible
+pub fn compute_overlay_position(
+    screen: ScreenRect,
+    overlay_width: f64,
+    overlay_height: f64,
+    placement: OverlayPlacement,
+) -> (f64, f64) {
+    let left = screen.x + placement.margin_x;
+    let right = screen.x + screen.width - overlay_width - placement.margin_x;
+    let top = screen.y + placement.margin_y;
+    let bottom = screen.y + screen.height - overlay_height - placement.margin_y;
+
+    let (x, y) = match placement.anchor {
+        OverlayAnchor::TopLeft => (left, top),
+        OverlayAnchor::TopRight => (right, top),
+        OverlayAnchor::BottomLeft => (left, bottom),
+        OverlayAnchor::BottomRight => (right, bottom),
+        OverlayAnchor::Center => (
+            screen.x + (screen.width - overlay_width) / 2.0,
+            screen.y + (screen.height - overlay_height) / 2.0,
+        ),
+    };
+
+    // Clamp to the screen (max() last so an oversized overlay pins to the top-left)
+    let max_x = screen.x + screen.width - overlay_width;
+    let max_y = screen.y + screen.height - overlay_height;
+    (x.min(max_x).max(screen.x), y.min(max_y).max(screen.y))
+}
+
+/// A connected monitor, as listed for the overlay monitor picker
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct MonitorInfo {
+    /// Identifier passed to `move_overlay_to_monitor`
+    pub id: String,
+    pub name: Option<String>,
+    /// Logical bounds
+    pub x: f64,
+    pub y: f64,
+    pub width: f64,
+    pub height: f64,
+    pub scale_factor: f64,
+    pub is_primary: bool,
+}
+
+/// Identify a monitor by its OS name, or by its position in the monitor list
+/// when the OS doesn't name it
+pub fn monitor_id(name: Option<&str>, index: usize) -> String {
+    match name {
+        Some(name) if !name.is_empty() => name.to_string(),
+        _ => format!("monitor-{}", index),
+    }
+}
+
+/// Connected monitors paired with their IDs
+fn monitors_with_ids(app: &AppHandle) -> Result<Vec<(String, Monitor)>, String> {
+    let monitors = app.available_monitors().map_err(|e| e.to_string())?;
+    Ok(monitors
+        .into_iter()
+        .enumerate()
+        .map(|(index, monitor)| {
+            (
+                monitor_id(monitor.name().map(String::as_str), index),
+                monitor,
+            )
+        })
+        .collect())
+}
+
+/// Find a connected monitor by ID
+fn find_monitor(app: &AppHandle, id: &str) -> Option<Monitor> {
+    monitors_with_ids(app)
+        .ok()?
+        .into_iter()
+        .find(|(monitor_id, _)| monitor_id == id)
+        .map(|(_, monitor)| monitor)
+}
+
+/// Get the monitor chosen for the overlay, else the one the main window is on,
+/// falling back to the primary monitor
+fn target_monitor(app: &AppHandle) -> Option<Monitor> {
+    let chosen: Option<String> = get_setting_from_store(app, OVERLAY_MONITOR_KEY, None);
+    chosen
+        .and_then(|id| find_monitor(app, &id))
+        .or_else(|| {
+            app.get_webview_window("main")
+                .and_then(|window| window.current_monitor().ok().flatten())
+        })
+        .or_else(|| app.primary_monitor().ok().flatten())
+}
+
+/// Forget the chosen overlay monitor if it is no longer connected, so the overlay
+/// falls back to the primary monitor. Called on startup.
+pub fn validate_overlay_monitor(app: &AppHandle) {
+    let Some(id) = get_setting_from_store::<Option<String>>(app, OVERLAY_MONITOR_KEY, None) else {
+        return;
+    };
+    if find_monitor(app, &id).is_some() {
+        return;
+    }
+    log::info!(
+        "Overlay monitor '{}' is not connected, using the primary monitor",
+        id
+    );
+    if let Ok(store) = app.store("settings.json") {
+        store.delete(OVERLAY_MONITOR_KEY);
+        let _ = store.save();
+    }
+}
+
+/// Convert a monitor's physical bounds to a logical rectangle
+fn monitor_rect(monitor: &Monitor) -> ScreenRect {
+    let scale = monitor.scale_factor();
+    let position = monitor.position().to_logical::<f64>(scale);
+    let size = monitor.size().to_logical::<f64>(scale);
+    ScreenRect {
+        x: position.x,
+        y: position.y,
+        width: size.width,
+        height: size.height,
+    }
+}
+
+/// Move the overlay window according to `placement`
+pub fn position_overlay(
+    app: &AppHandle,
+    window: &WebviewWindow,
+    placement: OverlayPlacement,
+) -> Result<(), String> {
+    app.state::<AppState>()
+        .overlay_dragging
+        .store(false, Ordering::SeqCst);
+    let monitor = target_monitor(app).ok_or("No monitor available")?;
+    let scale = window.scale_factor().map_err(|e| e.to_string())?;
+    let size = window
+        .outer_size()
+        .map_err(|e| e.to_string())?
+        .to_logical::<f64>(scale);
+
+    let (x, y) =
+        compute_overlay_position(monitor_rect(&monitor), size.width, size.height, placement);
+    window
+        .set_position(tauri::Position::Logical(tauri::LogicalPosition { x, y }))
+        .map_err(|e| e.to_string())
+}
+
+/// Place the overlay at the user's dragged position if it is still on a connected
+/// monitor, otherwise at the configured anchor
+pub fn restore_overlay_position(app: &AppHandle, window: &WebviewWindow) -> Result<(), String> {
+    let custom: Option<OverlayPosition> = get_setting_from_store(app, CUSTOM_POSITION_KEY, None);
+
+    if let Some(position) = custom {
+        let monitors = app.available_monitors().map_err(|e| e.to_string())?;
+        if monitors
+            .iter()
+            .any(|monitor| monitor_rect(monitor).contains(position.x, position.y))
+        {
+            return window
+                .set_position(tauri::Position::Logical(tauri::LogicalPosition {
+                    x: position.x,
+                    y: position.y,
+                }))
+                .map_err(|e| e.to_string());
+        }
+        log::info!("Saved overlay position is off-screen, using anchor placement");
+    }
+
+    position_overlay(app, window, OverlayPlacement::from_store(app))
+}
+
+/// Persist the overlay position when it was moved by a user drag
+pub fn handle_overlay_moved(
+    app: &AppHandle,
+    window: &WebviewWindow,
+    position: PhysicalPosition<i32>,
+) {
+    if !app
+        .state::<AppState>()
+        .overlay_dragging
+        .load(Ordering::SeqCst)
+    {
+        return;
+    }
+
+    let scale = window.scale_factor().unwrap_or(1.0);
+    let logical = position.to_logical::<f64>(scale);
+    let position = OverlayPosition {
+        x: logical.x,
+        y: logical.y,
+    };
+
+    match (app.store("settings.json"), serde_json::to_value(position)) {
+        (Ok(store), Ok(value)) => store.set(CUSTOM_POSITION_KEY, value),
+        _ => log::warn!("Failed to persist overlay position"),
+    }
+}
+
+/// Start a user drag of the overlay window; the resulting position is remembered
+#[tauri::command]
+pub async fn start_overlay_drag(app: AppHandle) -> Result<(), String> {
+    let window = app
+        .get_webview_window("overlay")
+        .ok_or("Overlay window not found")?;
+    app.state::<AppState>()
+        .overlay_dragging
+        .store(true, Ordering::SeqCst);
+    window.start_dragging().map_err(|e| e.to_string())
+}
+
+/// Finish a user drag of the overlay window: remember where it ended up and stop
+/// persisting its Moved events, so later programmatic moves aren't saved
+#[tauri::command]
+pub async fn end_overlay_drag(app: AppHandle) -> Result<(), String> {
+    let window = app
+        .get_webview_window("overlay")
+        .ok_or("Overlay window not found")?;
+    // The last Moved event may not have arrived yet
+    let position = window.outer_position().map_err(|e| e.to_string())?;
+    handle_overlay_moved(&app, &window, position);
+    app.state::<AppState>()
+        .overlay_dragging
+        .store(false, Ordering::SeqCst);
+    Ok(())
+}
+
+/// Forget the dragged overlay position and move it back to the configured anchor
+#[tauri::command]
+pub async fn reset_overlay_position(app: AppHandle) -> Result<(), String> {
+    let store = app.store("settings.json").map_err(|e| e.to_string())?;
+    store.delete(CUSTOM_POSITION_KEY);
+    store.save().map_err(|e| e.to_string())?;
+
+    if let Some(window) = app.get_webview_window("overlay") {
+        position_overlay(&app, &window, OverlayPlacement::from_store(&app))?;
+    }
+    Ok(())
+}
+
+/// Reposition the overlay immediately and persist the placement to the store
+#[tauri::command]
+pub async fn set_overlay_position(
+    app: AppHandle,
+    anchor: OverlayAnchor,
+    margin_x: f64,
+    margin_y: f64,
+) -> Result<(), String> {
+    let placement = OverlayPlacement {
+        anchor,
+        margin_x: margin_x.max(0.0),
+        margin_y: margin_y.max(0.0),
+    };
+
+    let store = app.store("settings.json").map_err(|e| e.to_string())?;
+    store.set(
+        "overlay_anchor",
+        serde_json::to_value(placement.anchor).map_err(|e| e.to_string())?,
+    );
+    store.set("overlay_margin_x", placement.margin_x);
+    store.set("overlay_margin_y", placement.margin_y);
+    // An explicit anchor replaces any dragged position
+    store.delete(CUSTOM_POSITION_KEY);
+    store.save().map_err(|e| e.to_string())?;
+
+    if let Some(window) = app.get_webview_window("overlay") {
+        position_overlay(&app, &window, placement)?;
+    }
+    Ok(())
+}
+
+/// Smallest overlay side, so the window can't become invisible
+pub const MIN_OVERLAY_SIZE: f64 = 32.0;
+
+/// Largest overlay width and height, so a runaway webview can't cover the screen
+pub const MAX_OVERLAY_WIDTH: f64 = 640.0;
+pub const MAX_OVERLAY_HEIGHT: f64 = 320.0;
+
+/// Clamp requested overlay dimensions to `MIN_OVERLAY_SIZE` and the maximums.
+/// Non-finite values fall back to the minimum.
+pub fn clamp_overlay_size(width: f64, height: f64) -> (f64, f64) {
+    let clamp = |value: f64, max: f64| {
+        if value.is_finite() {
+            value.clamp(MIN_OVERLAY_SIZE, max)
+        } else {
+            MIN_OVERLAY_SIZE
+        }
+    };
+    (
+        clamp(width, MAX_OVERLAY_WIDTH),
+        clamp(height, MAX_OVERLAY_HEIGHT),
+    )
+}
 
 #[tauri::command]
 pub async fn resize_overlay(app: AppHandle, width: f64, height: f64) -> Result<(), String> {
-    // Enforce minimum dimensions to prevent invisible window
-    let min_size = 48.0;
-    let width = width.max(min_size);
-    let height = height.max(min_size);
+    let (width, height) = clamp_overlay_size(width, height);
+    resize_overlay_window(&app, width, height)
+}
 
+/// Resize the overlay window, keeping its center where it is
+fn resize_overlay_window(app: &AppHandle, width: f64, height: f64) -> Result<(), String> {
     if let Some(window) = app.get_webview_window("overlay") {
         // Get current center point from current position and size
         // This allows the overlay to be dragged and maintain its new position
@@ -26,8 +383,11 @@ pub async fn resize_overlay(app: AppHandle, width: f64, height: f64) -> Result<(
             .set_size(tauri::Size::Logical(tauri::LogicalSize { width, height }))
             .map_err(|e| e.to_string())?;
 
-        // Reposition to keep center fixed
+        // Reposition to keep center fixed (not a user drag, so don't persist it)
         if let Some((cx, cy)) = center {
+            app.state::<AppState>()
+                .overlay_dragging
+                .store(false, Ordering::SeqCst);
             let x = cx - width / 2.0;
             let y = cy - height / 2.0;
             window
@@ -37,3 +397,227 @@ pub async fn resize_overlay(app: AppHandle, width: f64, height: f64) -> Result<(
     }
     Ok(())
 }
+
+/// List connected monitors with their logical bounds
+#[tauri::command]
+pub async fn list_monitors(app: AppHandle) -> Result<Vec<MonitorInfo>, String> {
+    let primary_position = app
+        .primary_monitor()
+        .map_err(|e| e.to_string())?
+        .map(|monitor| *monitor.position());
+
+    Ok(monitors_with_ids(&app)?
+        .into_iter()
+        .map(|(id, monitor)| {
+            let rect = monitor_rect(&monitor);
+            MonitorInfo {
+                id,
+                name: monitor.name().cloned(),
+                x: rect.x,
+                y: rect.y,
+                width: rect.width,
+                height: rect.height,
+                scale_factor: monitor.scale_factor(),
+                is_primary: primary_position == Some(*monitor.position()),
+            }
+        })
+        .collect())
+}
+
+/// Place the overlay on the given monitor at the configured anchor, and keep it
+/// there. Replaces any dragged position.
+#[tauri::command]
+pub async fn move_overlay_to_monitor(app: AppHandle, id: String) -> Result<(), String> {
+    if find_monitor(&app, &id).is_none() {
+        return Err(format!("Monitor '{}' is not connected", id));
+    }
+
+    let store = app.store("settings.json").map_err(|e| e.to_string())?;
+    store.set(OVERLAY_MONITOR_KEY, id);
+    store.delete(CUSTOM_POSITION_KEY);
+    store.save().map_err(|e| e.to_string())?;
+
+    if let Some(window) = app.get_webview_window("overlay") {
+        position_overlay(&app, &window, OverlayPlacement::from_store(&app))?;
+    }
+    Ok(())
+}
+
+/// Read the overlay visibility setting
+pub fn overlay_visibility(app: &AppHandle) -> OverlayVisibility {
+    get_setting_from_store(app, "overlay_visibility", OverlayVisibility::default())
+}
+
+/// Show or hide the overlay window
+fn set_overlay_visible(app: &AppHandle, visible: bool) {
+    let Some(window) = app.get_webview_window("overlay") else {
+        return;
+    };
+    let result = if visible {
+        window.show()
+    } else {
+        window.hide()
+    };
+    if let Err(e) = result {
+        log::warn!("Failed to update overlay visibility: {}", e);
+    }
+}
+
+/// Show or hide the overlay to match the visibility setting while nothing is happening
+pub fn apply_overlay_visibility(app: &AppHandle) {
+    let visible = match overlay_visibility(app) {
+        OverlayVisibility::Always => true,
+        OverlayVisibility::WhenActive => {
+            app.state::<AppState>().is_recording.load(Ordering::SeqCst)
+        }
+        OverlayVisibility::Never => false,
+    };
+    set_overlay_visible(app, visible);
+}
+
+/// Recording or transcription started: show the overlay in `WhenActive` mode and
+/// cancel any pending auto-hide
+pub fn handle_overlay_activity_started(app: &AppHandle) {
+    app.state::<AppState>()
+        .overlay_activity_generation
+        .fetch_add(1, Ordering::SeqCst);
+    if overlay_visibility(app) == OverlayVisibility::WhenActive {
+        set_overlay_visible(app, true);
+    }
+}
+
+/// Transcription finished, failed or was cancelled: in `WhenActive` mode hide the
+/// overlay after a delay, unless new activity starts first
+pub fn handle_overlay_activity_ended(app: &AppHandle, failed: bool) {
+    if overlay_visibility(app) != OverlayVisibility::WhenActive {
+        return;
+    }
+
+    let generation = app
+        .state::<AppState>()
+        .overlay_activity_generation
+        .load(Ordering::SeqCst);
+    let delay_ms = if failed {
+        OVERLAY_ERROR_HIDE_DELAY_MS
+    } else {
+        OVERLAY_HIDE_DELAY_MS
+    };
+    let app = app.clone();
+    std::thread::spawn(move || {
+        std::thread::sleep(std::time::Duration::from_millis(delay_ms));
+
+        let state = app.state::<AppState>();
+        if state.overlay_activity_generation.load(Ordering::SeqCst) != generation
+            || state.is_recording.load(Ordering::SeqCst)
+        {
+            return;
+        }
+        // The setting may have changed while waiting
+        if overlay_visibility(&app) == OverlayVisibility::WhenActive {
+            set_overlay_visible(&app, false);
+        }
+    });
+}
+
+/// Set when the overlay is shown and apply it immediately
+#[tauri::command]
+pub async fn set_overlay_visibility(
+    app: AppHandle,
+    visibility: OverlayVisibility,
+) -> Result<(), String> {
+    let value = serde_json::to_value(visibility).map_err(|e| e.to_string())?;
+    let store = app.store("settings.json").map_err(|e| e.to_string())?;
+    store.set("overlay_visibility", value);
+    store.save().map_err(|e| e.to_string())?;
+
+    apply_overlay_visibility(&app);
+    Ok(())
+}
+
+/// Overlay look sent to the overlay webview. `theme` is always resolved to
+/// `Light` or `Dark`.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize)]
+pub struct OverlayAppearance {
+    pub opacity: f64,
+    pub theme: OverlayTheme,
+    pub size: OverlaySize,
+    /// Scale for the overlay's content, from `size`
+    pub scale: f64,
+}
+
+/// Resolve `Auto` to the OS appearance, falling back to dark when it is unknown
+pub fn resolve_overlay_theme(theme: OverlayTheme, system: Option<tauri::Theme>) -> OverlayTheme {
+    match theme {
+        OverlayTheme::Auto => match system {
+            Some(tauri::Theme::Light) => OverlayTheme::Light,
+            _ => OverlayTheme::Dark,
+        },
+        theme => theme,
+    }
+}
+
+/// Read the overlay appearance from the store and resolve it for display
+pub fn overlay_appearance(app: &AppHandle) -> OverlayAppearance {
+    let opacity: f64 = get_setting_from_store(app, "overlay_opacity", DEFAULT_OVERLAY_OPACITY);
+    let theme = get_setting_from_store(app, "overlay_theme", OverlayTheme::default());
+    let system = app
+        .get_webview_window("overlay")
+        .and_then(|window| window.theme().ok());
+    let size = overlay_size(app);
+    OverlayAppearance {
+        opacity: opacity.clamp(MIN_OVERLAY_OPACITY, 1.0),
+        theme: resolve_overlay_theme(theme, system),
+        size,
+        scale: size.scale(),
+    }
+}
+
+/// The stored overlay size preset
+pub fn overlay_size(app: &AppHandle) -> OverlaySize {
+    get_setting_from_store(app, OVERLAY_SIZE_KEY, OverlaySize::default())
+}
+
+/// Set the overlay size preset and apply it immediately: the window shrinks or
+/// grows to the preset's idle size, and the overlay rescales its content (which
+/// then resizes the window to fit)
+#[tauri::command]
+pub async fn set_overlay_size_preset(app: AppHandle, preset: OverlaySize) -> Result<(), String> {
+    let value = serde_json::to_value(preset).map_err(|e| e.to_string())?;
+    let store = app.store("settings.json").map_err(|e| e.to_string())?;
+    store.set(OVERLAY_SIZE_KEY, value);
+    store.save().map_err(|e| e.to_string())?;
+    log::info!("Overlay size set to {:?}", preset);
+
+    resize_overlay_window(&app, preset.idle_size(), preset.idle_size())?;
+    emit_overlay_appearance(&app);
+    Ok(())
+}
+
+/// Send the current appearance to the overlay webview
+pub fn emit_overlay_appearance(app: &AppHandle) {
+    let _ = app.emit("overlay-appearance-changed", overlay_appearance(app));
+}
+
+/// Get the overlay appearance with `Auto` resolved to the OS theme
+#[tauri::command]
+pub async fn get_overlay_appearance(app: AppHandle) -> OverlayAppearance {
+    overlay_appearance(&app)
+}
+
+/// Set the overlay opacity and theme and apply them immediately
+#[tauri::command]
+pub async fn set_overlay_appearance(
+    app: AppHandle,
+    opacity: f64,
+    theme: OverlayTheme,
+) -> Result<(), String> {
+    validate_overlay_opacity(opacity)?;
+    let value = serde_json::to_value(theme).map_err(|e| e.to_string())?;
+    let store = app.store("settings.json").map_err(|e| e.to_string())?;
+    store.set("overlay_opacity", opacity);
+    store.set("overlay_theme", value);
+    store.save().map_err(|e| e.to_string())?;
+
+    emit_overlay_appearance(&app);
+    Ok(())
+}
@@ -0,0 +1,76 @@
+use crate::permissions::{self, Permission, PermissionState, PermissionStatus};
+use serde::Serialize;
+use tauri::{AppHandle, Emitter};
+use tauri_plugin_opener::OpenerExt;
+
+/// Payload of the `permission-denied` event
+#[derive(Debug, Clone, Serialize)]
+struct PermissionDenied {
+    permission: Permission,
+}
+
+/// Open the system settings pane for `permission`, if the platform has one
+fn open_permission_settings(app: &AppHandle, permission: Permission) -> Result<(), String> {
+    let Some(url) = permissions::settings_url(permission) else {
+        return Ok(());
+    };
+    log::info!("Opening permission settings: {}", url);
+    app.opener()
+        .open_url(url, None::<&str>)
+        .map_err(|e| format!("Failed to open system settings: {}", e))
+}
+
+/// Check microphone access before a recording starts. If it is missing, emit
+/// `permission-denied` and ask the user for it (the OS prompt on macOS if they
+/// haven't been asked yet, otherwise the settings pane). Returns false if the
+/// recording should not start.
+pub fn ensure_microphone_access(app: &AppHandle) -> bool {
+    let state = permissions::check().microphone;
+    if !state.needs_attention() {
+        return true;
+    }
+
+    log::warn!("Microphone access is {:?}, not starting recording", state);
+    let _ = app.emit(
+        "permission-denied",
+        PermissionDenied {
+            permission: Permission::Microphone,
+        },
+    );
+    if state == PermissionState::NotDetermined && permissions::prompt_microphone_access() {
+        return false;
+    }
+    if let Err(e) = open_permission_settings(app, Permission::Microphone) {
+        log::warn!("{}", e);
+    }
+    false
+}
+
+/// Report whether Accessibility and microphone access are granted
+#[tauri::command]
+pub async fn check_permissions() -> PermissionStatus {
+    permissions::check()
+}
+
+/// Open the system settings pane for `permission`, or for the first missing
+/// permission if none is given. Does nothing if there is no pane to open.
+#[tauri::command]
+pub async fn request_permissions(
+    app: AppHandle,
+    permission: Option<Permission>,
+) -> Result<(), String> {
+    let permission = permission.or_else(|| {
+        let status = permissions::check();
+        if status.accessibility.needs_attention() {
+            Some(Permission::Accessibility)
+        } else if status.microphone.needs_attention() {
+            Some(Permission::Microphone)
+        } else {
+            None
+        }
+    });
+    match permission {
+        Some(permission) => open_permission_settings(&app, permission),
+        None => Ok(()),
+    }
+}
@@ -0,0 +1,99 @@
+use crate::commands::settings::register_shortcuts;
+use crate::profiles::{
+    is_profile_key, ProfileSummary, Profiles, SettingsMap, ACTIVE_PROFILE_KEY, PROFILES_KEY,
+};
+use std::collections::BTreeMap;
+use std::sync::Arc;
+use tauri::{AppHandle, Emitter, Wry};
+use tauri_plugin_store::{Store, StoreExt};
+
+/// Open the settings store
+fn settings_store(app: &AppHandle) -> Result<Arc<Store<Wry>>, String> {
+    app.store("settings.json").map_err(|e| e.to_string())
+}
+
+/// Read the profiles and active profile name from the store
+fn load_profiles(store: &Store<Wry>) -> Profiles {
+    let snapshots: BTreeMap<String, SettingsMap> = store
+        .get(PROFILES_KEY)
+        .and_then(|v| serde_json::from_value(v).ok())
+        .unwrap_or_default();
+    let active = store
+        .get(ACTIVE_PROFILE_KEY)
+        .and_then(|v| v.as_str().map(String::from));
+    Profiles::new(active, snapshots)
+}
+
+/// Write the profiles back to the store and persist it
+fn save_profiles(store: &Store<Wry>, profiles: &Profiles) -> Result<(), String> {
+    let snapshots = serde_json::to_value(&profiles.snapshots).map_err(|e| e.to_string())?;
+    store.set(PROFILES_KEY, snapshots);
+    store.set(ACTIVE_PROFILE_KEY, profiles.active.clone());
+    store.save().map_err(|e| e.to_string())
+}
+
+/// The current top-level settings, excluding profile bookkeeping
+fn live_settings(store: &Store<Wry>) -> SettingsMap {
+    store
+        .entries()
+        .into_iter()
+        .filter(|(key, _)| !is_profile_key(key))
+        .collect()
+}
+
+/// List all profiles, marking the active one
+#[tauri::command]
+pub async fn list_profiles(app: AppHandle) -> Result<Vec<ProfileSummary>, String> {
+    let store = settings_store(&app)?;
+    Ok(load_profiles(&store).summaries())
+}
+
+/// Create a profile initialized from the current settings (does not switch to it)
+#[tauri::command]
+pub async fn create_profile(app: AppHandle, name: String) -> Result<ProfileSummary, String> {
+    let store = settings_store(&app)?;
+    let mut profiles = load_profiles(&store);
+    let name = profiles.create(&name, &live_settings(&store))?;
+    save_profiles(&store, &profiles)?;
+    log::info!("Created settings profile '{}'", name);
+    Ok(ProfileSummary {
+        name,
+        is_active: false,
+    })
+}
+
+/// Switch to another profile: its hotkeys, server URL and behavior settings replace
+/// the current ones, and shortcuts are re-registered
+#[tauri::command]
+pub async fn switch_profile(app: AppHandle, name: String) -> Result<(), String> {
+    let store = settings_store(&app)?;
+    let mut profiles = load_profiles(&store);
+    let next = profiles.switch(&name, live_settings(&store))?;
+
+    // Settings the target profile doesn't have fall back to their defaults
+    for key in store.keys() {
+        if !is_profile_key(&key) && !next.contains_key(&key) {
+            store.delete(&key);
+        }
+    }
+    for (key, value) in next {
+        store.set(key, value);
+    }
+    save_profiles(&store, &profiles)?;
+    log::info!("Switched to settings profile '{}'", profiles.active);
+
+    register_shortcuts(app.clone()).await?;
+    let _ = app.emit("settings-changed", ());
+    Ok(())
+}
+
+/// Delete a profile. The active profile can't be deleted.
+#[tauri::command]
+pub async fn delete_profile(app: AppHandle, name: String) -> Result<(), String> {
+    let store = settings_store(&app)?;
+    let mut profiles = load_profiles(&store);
+    profiles.delete(&name)?;
+    save_profiles(&store, &profiles)?;
+    log::info!("Deleted settings profile '{}'", name.trim());
+    Ok(())
+}
@@ -0,0 +1,74 @@
+use crate::get_setting_from_store;
+use crate::history::HistoryStorage;
+use crate::input_processing::{preview_levels, InputLevelPreview, InputProcessing};
+use crate::recordings::{
+    convert_audio, decode_pcm16le, parse_captured_format, AudioFormat, RecordingStorage,
+    SavedRecording, CHANNELS_HEADER, DEFAULT_MAX_SAVED_RECORDINGS, SAMPLE_RATE_HEADER,
+};
+use tauri::ipc::{InvokeBody, Request};
+use tauri::{AppHandle, Manager, State};
+
+/// Save a captured recording as WAV if `save_recordings` is enabled.
+/// The body is raw little-endian 16-bit PCM, interleaved when there are several
+/// channels, with its format in the `x-sample-rate` and `x-channels` headers (sent
+/// as binary because a JSON array of samples is several MB per minute of audio).
+/// The samples are resampled and downmixed to the configured `sample_rate` and
+/// `channels`, then the input gain and noise gate are applied, so the file matches
+/// what the server hears.
+/// Returns None when saving is disabled or incognito mode is on.
+#[tauri::command]
+pub async fn save_recording(
+    app: AppHandle,
+    request: Request<'_>,
+    recordings: State<'_, RecordingStorage>,
+) -> Result<Option<SavedRecording>, String> {
+    if !get_setting_from_store(&app, "save_recordings", false)
+        || app.state::<HistoryStorage>().is_incognito()
+    {
+        return Ok(None);
+    }
+    let header = |name: &str| {
+        request
+            .headers()
+            .get(name)
+            .and_then(|value| value.to_str().ok())
+    };
+    let captured = parse_captured_format(header(SAMPLE_RATE_HEADER), header(CHANNELS_HEADER))?;
+    let InvokeBody::Raw(bytes) = request.body() else {
+        return Err("Recording must be sent as raw PCM bytes".to_string());
+    };
+    let samples = decode_pcm16le(bytes)?;
+
+    let target = AudioFormat::from_store(&app);
+    let mut samples = convert_audio(&samples, captured, target);
+    InputProcessing::from_store(&app).apply(&mut samples);
+    let recording = recordings.save_wav(&samples, target)?;
+    log::info!("Saved recording to {}", recording.path);
+
+    let max_saved: usize =
+        get_setting_from_store(&app, "max_saved_recordings", DEFAULT_MAX_SAVED_RECORDINGS);
+    let removed = recordings.cleanup(max_saved)?;
+    if removed > 0 {
+        log::info!("Removed {} old recording(s)", removed);
+    }
+
+    Ok(Some(recording))
+}
+
+/// Get saved recordings, newest first
+#[tauri::command]
+pub async fn get_saved_recordings(
+    recordings: State<'_, RecordingStorage>,
+) -> Result<Vec<SavedRecording>, String> {
+    recordings.list()
+}
+
+/// Measure a block of captured mono samples before and after the configured
+/// input gain and noise gate, for calibrating them against a live mic
+#[tauri::command]
+pub async fn preview_input_level(
+    app: AppHandle,
+    samples: Vec<i16>,
+) -> Result<InputLevelPreview, String> {
+    Ok(preview_levels(&samples, InputProcessing::from_store(&app)))
+}
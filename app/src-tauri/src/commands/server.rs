@@ -0,0 +1,235 @@
+use std::collections::BTreeMap;
+use std::io::{Read, Write};
+use std::net::{TcpStream, ToSocketAddrs};
+use std::time::{Duration, Instant};
+use tauri::{AppHandle, Url};
+use tauri_plugin_store::StoreExt;
+
+/// Timeout for the server health check (connect, write and read each)
+const HEALTH_CHECK_TIMEOUT: Duration = Duration::from_secs(5);
+
+/// Store keys for server authentication. Excluded from settings exports.
+pub const SERVER_API_KEY_KEY: &str = "server_api_key";
+pub const SERVER_AUTH_HEADER_KEY: &str = "server_auth_header";
+pub const SERVER_HEADERS_KEY: &str = "server_headers";
+
+/// Settings that may hold credentials
+pub const SERVER_SECRET_KEYS: [&str; 2] = [SERVER_API_KEY_KEY, SERVER_HEADERS_KEY];
+
+/// Header the API key is sent in when none is configured
+pub const DEFAULT_AUTH_HEADER: &str = "Authorization";
+
+/// Check that `name` is a valid HTTP header name (an RFC 9110 token)
+pub fn validate_header_name(name: &str) -> Result<(), String> {
+    let is_token_char = |c: char| c.is_ascii_alphanumeric() || "!#$%&'*+-.^_`|~".contains(c);
+    if name.is_empty() || !name.chars().all(is_token_char) {
+        return Err(format!("Invalid header name '{}'", name));
+    }
+    Ok(())
+}
+
+/// Check that a header value can be sent. The value itself is never included in
+/// the error, since it may be a secret.
+fn validate_header_value(name: &str, value: &str) -> Result<(), String> {
+    if value.chars().any(|c| c.is_control() && c != '\t') {
+        return Err(format!(
+            "Value of header '{}' contains control characters",
+            name
+        ));
+    }
+    Ok(())
+}
+
+/// Build the headers sent with every request to the server.
+///
+/// `headers` are sent as given. The API key is sent in `auth_header`
+/// (default `Authorization`, replacing any header of the same name): for
+/// `Authorization` a bare key is sent as `Bearer <key>` and a key that already
+/// names a scheme (`Token abc`) is sent unchanged; other headers get the raw key.
+pub fn server_request_headers(
+    api_key: Option<&str>,
+    auth_header: Option<&str>,
+    headers: &BTreeMap<String, String>,
+) -> Result<Vec<(String, String)>, String> {
+    let mut result = Vec::with_capacity(headers.len() + 1);
+    for (name, value) in headers {
+        validate_header_name(name)?;
+        validate_header_value(name, value)?;
+        result.push((name.clone(), value.clone()));
+    }
+
+    let Some(api_key) = api_key.map(str::trim).filter(|key| !key.is_empty()) else {
+        return Ok(result);
+    };
+    let auth_header = auth_header
+        .map(str::trim)
+        .filter(|name| !name.is_empty())
+        .unwrap_or(DEFAULT_AUTH_HEADER);
+    validate_header_name(auth_header)?;
+    validate_header_value(auth_header, api_key)?;
+
+    let value = if auth_header.eq_ignore_ascii_case(DEFAULT_AUTH_HEADER)
+        && !api_key.contains(char::is_whitespace)
+    {
+        format!("Bearer {}", api_key)
+    } else {
+        api_key.to_string()
+    };
+    result.retain(|(name, _)| !name.eq_ignore_ascii_case(auth_header));
+    result.push((auth_header.to_string(), value));
+    Ok(result)
+}
+
+/// Headers for requests to the configured server
+pub fn server_request_headers_from_store(app: &AppHandle) -> Result<Vec<(String, String)>, String> {
+    let api_key: Option<String> = crate::get_setting_from_store(app, SERVER_API_KEY_KEY, None);
+    let auth_header: Option<String> =
+        crate::get_setting_from_store(app, SERVER_AUTH_HEADER_KEY, None);
+    let headers: BTreeMap<String, String> =
+        crate::get_setting_from_store(app, SERVER_HEADERS_KEY, BTreeMap::new());
+    server_request_headers(api_key.as_deref(), auth_header.as_deref(), &headers)
+}
+
+/// Validate a transcription server URL, returning it normalized (no trailing slash).
+/// The scheme must be http or https and the host must be non-empty.
+pub fn validate_server_url(url: &str) -> Result<String, String> {
+    let trimmed = url.trim();
+    let parsed = Url::parse(trimmed).map_err(|e| format!("Invalid URL '{}': {}", trimmed, e))?;
+
+    if !matches!(parsed.scheme(), "http" | "https") {
+        return Err(format!(
+            "Unsupported scheme '{}': server URL must start with http:// or https://",
+            parsed.scheme()
+        ));
+    }
+
+    if parsed.host_str().is_none_or(str::is_empty) {
+        return Err(format!("Server URL '{}' has no host", trimmed));
+    }
+
+    Ok(trimmed.trim_end_matches('/').to_string())
+}
+
+/// Validate and persist the transcription server URL
+#[tauri::command]
+pub async fn set_server_url(app: AppHandle, url: String) -> Result<String, String> {
+    let url = validate_server_url(&url)?;
+    let store = app.store("settings.json").map_err(|e| e.to_string())?;
+    store.set("server_url", url.clone());
+    store.save().map_err(|e| e.to_string())?;
+    Ok(url)
+}
+
+/// Validate and persist the API key and extra headers sent to the server.
+/// An empty or missing `api_key` removes the stored key.
+#[tauri::command]
+pub async fn set_server_auth(
+    app: AppHandle,
+    api_key: Option<String>,
+    auth_header: Option<String>,
+    headers: BTreeMap<String, String>,
+) -> Result<(), String> {
+    let api_key = api_key.filter(|key| !key.trim().is_empty());
+    let auth_header = auth_header.filter(|name| !name.trim().is_empty());
+    server_request_headers(api_key.as_deref(), auth_header.as_deref(), &headers)?;
+
+    let store = app.store("settings.json").map_err(|e| e.to_string())?;
+    match api_key {
+        Some(key) => store.set(SERVER_API_KEY_KEY, key.trim()),
+        None => {
+            store.delete(SERVER_API_KEY_KEY);
+        }
+    }
+    match auth_header {
+        Some(name) => store.set(SERVER_AUTH_HEADER_KEY, name.trim()),
+        None => {
+            store.delete(SERVER_AUTH_HEADER_KEY);
+        }
+    }
+    store.set(SERVER_HEADERS_KEY, serde_json::json!(headers));
+    store.save().map_err(|e| e.to_string())?;
+    Ok(())
+}
+
+/// Get the headers (including the API key) to attach to requests to the server
+#[tauri::command]
+pub async fn get_server_request_headers(
+    app: AppHandle,
+) -> Result<BTreeMap<String, String>, String> {
+    Ok(server_request_headers_from_store(&app)?
+        .into_iter()
+        .collect())
+}
+
+/// Check that the configured server is reachable, returning the latency in milliseconds
+#[tauri::command]
+pub async fn test_server_connection(app: AppHandle) -> Result<u64, String> {
+    let headers = server_request_headers_from_store(&app)?;
+    let url = super::text::get_server_url(app).await?;
+    let url = validate_server_url(&url)?;
+    let parsed = Url::parse(&url).map_err(|e| e.to_string())?;
+
+    tauri::async_runtime::spawn_blocking(move || check_health(&parsed, &headers))
+        .await
+        .map_err(|e| e.to_string())?
+}
+
+/// Request the server's /health endpoint and time the round trip.
+/// For https only the TCP connection is checked, since there is no TLS client here.
+fn check_health(url: &Url, headers: &[(String, String)]) -> Result<u64, String> {
+    let host = url.host_str().ok_or("Server URL has no host")?;
+    let port = url
+        .port_or_known_default()
+        .ok_or("Server URL has no port")?;
+    let addr = (host, port)
+        .to_socket_addrs()
+        .map_err(|e| format!("Failed to resolve {}: {}", host, e))?
+        .next()
+        .ok_or_else(|| format!("No address found for {}", host))?;
+
+    let start = Instant::now();
+    let mut stream = TcpStream::connect_timeout(&addr, HEALTH_CHECK_TIMEOUT)
+        .map_err(|e| format!("Failed to connect to {}: {}", addr, e))?;
+
+    if url.scheme() == "https" {
+        return Ok(start.elapsed().as_millis() as u64);
+    }
+
+    stream
+        .set_read_timeout(Some(HEALTH_CHECK_TIMEOUT))
+        .and_then(|_| stream.set_write_timeout(Some(HEALTH_CHECK_TIMEOUT)))
+        .map_err(|e| e.to_string())?;
+
+    let path = format!("{}/health", url.path().trim_end_matches('/'));
+    let extra_headers: String = headers
+        .iter()
+        .map(|(name, value)| format!("{}: {}\r\n", name, value))
+        .collect();
+    let request = format!(
+        "GET {} HTTP/1.1\r\nHost: {}\r\n{}Connection: close\r\n\r\n",
+        path, host, extra_headers
+    );
+    stream
+        .write_all(request.as_bytes())
+        .map_err(|e| format!("Failed to send health check: {}", e))?;
+
+    // Only the status line is needed
+    let mut buffer = [0u8; 64];
+    let read = stream
+        .read(&mut buffer)
+        .map_err(|e| format!("No response from server: {}", e))?;
+    let latency = start.elapsed().as_millis() as u64;
+
+    let response = String::from_utf8_lossy(&buffer[..read]);
+    let status = response
+        .split_whitespace()
+        .nth(1)
+        .and_then(|code| code.parse::<u16>().ok())
+        .ok_or("Invalid response from server")?;
+
+    if (200..300).contains(&status) {
+        Ok(latency)
+    } else {
+        Err(format!("Server health check returned status {}", status))
+    }
+}
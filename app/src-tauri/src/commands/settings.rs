@@ -1,10 +1,40 @@
-use crate::settings::HotkeyConfig;
-use tauri::AppHandle;
+use crate::commands::history::notify_history_changed;
+use crate::commands::overlay::{
+    apply_overlay_visibility, emit_overlay_appearance, restore_overlay_position,
+};
+use crate::commands::server::SERVER_SECRET_KEYS;
+use crate::commands::window::apply_main_window_always_on_top;
+use crate::control_server::CONTROL_SERVER_TOKEN_KEY;
+use crate::history::{HistoryChange, HistoryStorage, HISTORY_ENCRYPTION_KEY, INCOGNITO_KEY};
+use crate::logging::LOG_LEVEL_KEY;
+use crate::migrations::{migrate_settings, read_version, SETTINGS_VERSION_KEY};
+use crate::profiles::is_profile_key;
+use crate::settings::{
+    normalize_language, normalize_setting, EffectiveHotkey, HotkeyConfig, HotkeyRegistration,
+    LogLevel, RejectedSetting, SettingsImportResult,
+};
+use crate::text_processing::{
+    normalize_vocabulary, rules_from_store, validate_rules, validate_timestamp_format,
+    vocabulary_from_store, ReplacementRule, CUSTOM_VOCABULARY_KEY, TEXT_REPLACEMENTS_KEY,
+    TIMESTAMP_FORMAT_KEY,
+};
+use crate::voice_commands::{
+    builtin_commands, commands_from_store, validate_commands, VoiceCommand, VOICE_COMMANDS_KEY,
+};
+use crate::webhook::TRANSCRIPTION_WEBHOOK_SECRET_KEY;
+use tauri::{AppHandle, Emitter, Manager};
 
 #[cfg(desktop)]
-use tauri_plugin_global_shortcut::{GlobalShortcutExt, Shortcut};
-
+use crate::configured_hotkey;
+#[cfg(desktop)]
+use crate::settings::HotkeyAction;
+#[cfg(desktop)]
+use crate::state::AppState;
+#[cfg(desktop)]
+use std::sync::atomic::Ordering;
 #[cfg(desktop)]
+use tauri_plugin_global_shortcut::GlobalShortcutExt;
+
 use tauri_plugin_store::StoreExt;
 
 /// Temporarily unregister all global shortcuts.
@@ -17,6 +47,10 @@ pub async fn unregister_shortcuts(app: AppHandle) -> Result<(), String> {
     shortcut_manager
         .unregister_all()
         .map_err(|e| format!("Failed to unregister shortcuts: {}", e))?;
+    // The cancel hotkey (if registered for a recording) went with them
+    app.state::<AppState>()
+        .cancel_shortcut_registered
+        .store(false, Ordering::SeqCst);
     Ok(())
 }
 
@@ -27,75 +61,389 @@ pub async fn unregister_shortcuts(_app: AppHandle) -> Result<(), String> {
     Ok(())
 }
 
-/// Helper to read a setting from the store with a default fallback
-#[cfg(desktop)]
-fn get_setting_from_store<T: serde::de::DeserializeOwned>(
-    app: &AppHandle,
-    key: &str,
-    default: T,
-) -> T {
-    app.store("settings.json")
-        .ok()
-        .and_then(|store| store.get(key))
-        .and_then(|v| serde_json::from_value(v).ok())
-        .unwrap_or(default)
-}
-
 /// Re-register global shortcuts with the current settings from the store.
 /// Called from frontend after hotkey settings are changed.
-/// Falls back to defaults if stored values are invalid.
+/// Falls back to defaults if stored values are invalid. Each hotkey is registered
+/// independently; the per-hotkey results let the UI flag the conflicting field.
 #[cfg(desktop)]
 #[tauri::command]
-pub async fn register_shortcuts(app: AppHandle) -> Result<(), String> {
-    // Read hotkeys from store with defaults
-    let toggle_hotkey: HotkeyConfig =
-        get_setting_from_store(&app, "toggle_hotkey", HotkeyConfig::default_toggle());
-    let hold_hotkey: HotkeyConfig =
-        get_setting_from_store(&app, "hold_hotkey", HotkeyConfig::default_hold());
-    let paste_last_hotkey: HotkeyConfig = get_setting_from_store(
-        &app,
-        "paste_last_hotkey",
-        HotkeyConfig::default_paste_last(),
-    );
-
-    // Convert to shortcuts with validation (fall back to defaults if invalid)
-    let toggle_shortcut = toggle_hotkey.to_shortcut_or_default(HotkeyConfig::default_toggle);
-    let hold_shortcut = hold_hotkey.to_shortcut_or_default(HotkeyConfig::default_hold);
-    let paste_last_shortcut =
-        paste_last_hotkey.to_shortcut_or_default(HotkeyConfig::default_paste_last);
-
-    log::info!(
-        "Re-registering shortcuts - Toggle: {}, Hold: {}, PasteLast: {}",
-        toggle_hotkey.to_shortcut_string(),
-        hold_hotkey.to_shortcut_string(),
-        paste_last_hotkey.to_shortcut_string()
-    );
+pub async fn register_shortcuts(app: AppHandle) -> Result<Vec<HotkeyRegistration>, String> {
+    // Reject ambiguous bindings before touching the current registrations
+    let hotkeys: Vec<(HotkeyAction, HotkeyConfig)> = HotkeyAction::ALL
+        .into_iter()
+        .filter_map(|action| Some((action, configured_hotkey(&app, action)?)))
+        .collect();
+    if let Some((first, second)) = crate::find_duplicate_hotkeys(&hotkeys) {
+        let shortcut = hotkeys
+            .iter()
+            .find(|(action, _)| *action == first)
+            .map(|(_, hotkey)| hotkey.to_shortcut_string())
+            .unwrap_or_default();
+        return Err(format!(
+            "The {} and {} hotkeys are both set to {}",
+            first.label(),
+            second.label(),
+            shortcut
+        ));
+    }
 
-    // Get the global shortcut manager
-    let shortcut_manager = app.global_shortcut();
+    log::info!("Re-registering shortcuts");
 
     // Unregister all existing shortcuts
-    shortcut_manager
+    app.global_shortcut()
         .unregister_all()
         .map_err(|e| format!("Failed to unregister shortcuts: {}", e))?;
+    app.state::<AppState>()
+        .cancel_shortcut_registered
+        .store(false, Ordering::SeqCst);
 
-    // Collect shortcuts to register
-    let shortcuts: Vec<Shortcut> = vec![toggle_shortcut, hold_shortcut, paste_last_shortcut];
+    Ok(crate::register_hotkeys(&app))
+}
 
-    // Register new shortcuts with handler
-    shortcut_manager
-        .on_shortcuts(shortcuts, |app, shortcut, event| {
-            crate::handle_shortcut_event(app, shortcut, &event);
-        })
-        .map_err(|e| format!("Failed to register shortcuts: {}", e))?;
+// Stub for non-desktop platforms
+#[cfg(not(desktop))]
+#[tauri::command]
+pub async fn register_shortcuts(_app: AppHandle) -> Result<Vec<HotkeyRegistration>, String> {
+    Ok(Vec::new())
+}
 
-    log::info!("Shortcuts re-registered successfully");
+/// Unregister all global hotkeys until `resume_hotkeys` (persists across restarts)
+#[cfg(desktop)]
+#[tauri::command]
+pub async fn suspend_hotkeys(app: AppHandle) -> Result<(), String> {
+    crate::set_hotkeys_suspended(&app, true).map(drop)
+}
+
+/// Re-register the global hotkeys after `suspend_hotkeys`
+#[cfg(desktop)]
+#[tauri::command]
+pub async fn resume_hotkeys(app: AppHandle) -> Result<Vec<HotkeyRegistration>, String> {
+    crate::set_hotkeys_suspended(&app, false)
+}
+
+// Stubs for non-desktop platforms
+#[cfg(not(desktop))]
+#[tauri::command]
+pub async fn suspend_hotkeys(_app: AppHandle) -> Result<(), String> {
     Ok(())
 }
 
+#[cfg(not(desktop))]
+#[tauri::command]
+pub async fn resume_hotkeys(_app: AppHandle) -> Result<Vec<HotkeyRegistration>, String> {
+    Ok(Vec::new())
+}
+
+/// Get whether global hotkeys are suspended
+#[tauri::command]
+pub fn get_hotkeys_suspended(app: AppHandle) -> bool {
+    app.state::<crate::state::AppState>()
+        .hotkeys_suspended
+        .load(std::sync::atomic::Ordering::SeqCst)
+}
+
+/// Get, for each action, the configured combo and the normalized strings
+/// `handle_shortcut_event` compares, to debug hotkeys that don't trigger
+#[cfg(desktop)]
+#[tauri::command]
+pub fn get_effective_hotkeys(app: AppHandle) -> Vec<EffectiveHotkey> {
+    HotkeyAction::ALL
+        .into_iter()
+        .map(|action| {
+            crate::effective_hotkey(
+                action,
+                crate::stored_hotkey(&app, action).as_ref(),
+                crate::hotkey_enabled(&app, action),
+            )
+        })
+        .collect()
+}
+
+// Stub for non-desktop platforms
+#[cfg(not(desktop))]
+#[tauri::command]
+pub fn get_effective_hotkeys(_app: AppHandle) -> Vec<EffectiveHotkey> {
+    Vec::new()
+}
+
+/// Check whether a hotkey combo is free by briefly registering it.
+/// Combos already registered by this app count as available.
+#[cfg(desktop)]
+#[tauri::command]
+pub async fn check_hotkey_available(app: AppHandle, config: HotkeyConfig) -> Result<bool, String> {
+    let shortcut = config.to_shortcut()?;
+    let shortcut_manager = app.global_shortcut();
+    if shortcut_manager.is_registered(shortcut) {
+        return Ok(true);
+    }
+
+    match shortcut_manager.register(shortcut) {
+        Ok(()) => {
+            if let Err(e) = shortcut_manager.unregister(shortcut) {
+                log::warn!("Failed to release test registration of {}: {}", shortcut, e);
+            }
+            Ok(true)
+        }
+        Err(e) => {
+            log::info!(
+                "Hotkey {} is unavailable: {}",
+                config.to_shortcut_string(),
+                e
+            );
+            Ok(false)
+        }
+    }
+}
+
 // Stub for non-desktop platforms
 #[cfg(not(desktop))]
 #[tauri::command]
-pub async fn register_shortcuts(_app: AppHandle) -> Result<(), String> {
+pub async fn check_hotkey_available(
+    _app: AppHandle,
+    _config: HotkeyConfig,
+) -> Result<bool, String> {
+    Ok(false)
+}
+
+/// Set the transcription language sent to the server.
+/// Unknown codes fall back to "auto"; returns the code that was actually stored.
+#[tauri::command]
+pub async fn set_language(app: AppHandle, language: String) -> Result<String, String> {
+    let normalized = normalize_language(&language);
+    if normalized != language {
+        log::info!(
+            "Normalized transcription language '{}' to '{}'",
+            language,
+            normalized
+        );
+    }
+
+    let store = app.store("settings.json").map_err(|e| e.to_string())?;
+    store.set("language", normalized.clone());
+    store.save().map_err(|e| e.to_string())?;
+    Ok(normalized)
+}
+
+/// Replace the find/replace rules applied to transcriptions.
+/// Rejects the whole list if any rule is invalid.
+#[tauri::command]
+pub async fn set_text_replacements(
+    app: AppHandle,
+    rules: Vec<ReplacementRule>,
+) -> Result<(), String> {
+    validate_rules(&rules)?;
+
+    let value = serde_json::to_value(&rules).map_err(|e| e.to_string())?;
+    let store = app.store("settings.json").map_err(|e| e.to_string())?;
+    store.set(TEXT_REPLACEMENTS_KEY, value);
+    store.save().map_err(|e| e.to_string())?;
     Ok(())
 }
+
+/// Get the find/replace rules applied to transcriptions
+#[tauri::command]
+pub async fn get_text_replacements(app: AppHandle) -> Result<Vec<ReplacementRule>, String> {
+    Ok(rules_from_store(&app))
+}
+
+/// Replace the custom vocabulary used to correct the spelling of names and jargon.
+/// Returns the saved list (trimmed and de-duplicated).
+#[tauri::command]
+pub async fn set_custom_vocabulary(
+    app: AppHandle,
+    terms: Vec<String>,
+) -> Result<Vec<String>, String> {
+    let terms = normalize_vocabulary(terms)?;
+
+    let store = app.store("settings.json").map_err(|e| e.to_string())?;
+    store.set(CUSTOM_VOCABULARY_KEY, serde_json::json!(terms));
+    store.save().map_err(|e| e.to_string())?;
+    Ok(terms)
+}
+
+/// Get the custom vocabulary
+#[tauri::command]
+pub async fn get_custom_vocabulary(app: AppHandle) -> Result<Vec<String>, String> {
+    Ok(vocabulary_from_store(&app))
+}
+
+/// Set the strftime format of the time prefixed to dictations when
+/// `prepend_timestamp` is on, e.g. `[%H:%M] `
+#[tauri::command]
+pub async fn set_timestamp_format(app: AppHandle, format: String) -> Result<(), String> {
+    validate_timestamp_format(&format)?;
+
+    let store = app.store("settings.json").map_err(|e| e.to_string())?;
+    store.set(TIMESTAMP_FORMAT_KEY, serde_json::json!(format));
+    store.save().map_err(|e| e.to_string())
+}
+
+/// Change which log records are kept, immediately and on future launches
+#[tauri::command]
+pub async fn set_log_level(app: AppHandle, level: LogLevel) -> Result<(), String> {
+    let store = app.store("settings.json").map_err(|e| e.to_string())?;
+    store.set(LOG_LEVEL_KEY, serde_json::json!(level));
+    store.save().map_err(|e| e.to_string())?;
+
+    crate::logging::set_level(Some(level.filter()));
+    log::info!("Log level set to {:?}", level);
+    Ok(())
+}
+
+/// Get the most verbose level currently logged
+#[tauri::command]
+pub async fn get_log_level() -> Result<LogLevel, String> {
+    Ok(LogLevel::from_filter(log::max_level()))
+}
+
+/// Replace the user-defined voice commands.
+/// Rejects the whole list if any command is invalid.
+#[tauri::command]
+pub async fn set_voice_commands(app: AppHandle, commands: Vec<VoiceCommand>) -> Result<(), String> {
+    validate_commands(&commands)?;
+
+    let value = serde_json::to_value(&commands).map_err(|e| e.to_string())?;
+    let store = app.store("settings.json").map_err(|e| e.to_string())?;
+    store.set(VOICE_COMMANDS_KEY, value);
+    store.save().map_err(|e| e.to_string())?;
+    Ok(())
+}
+
+/// Get the user-defined voice commands
+#[tauri::command]
+pub async fn get_voice_commands(app: AppHandle) -> Result<Vec<VoiceCommand>, String> {
+    Ok(commands_from_store(&app))
+}
+
+/// Get the built-in voice commands (user-defined phrases override these)
+#[tauri::command]
+pub fn get_builtin_voice_commands() -> Vec<VoiceCommand> {
+    builtin_commands()
+}
+
+/// Other settings holding credentials, left out of exports like the server's
+const SECRET_KEYS: [&str; 2] = [CONTROL_SERVER_TOKEN_KEY, TRANSCRIPTION_WEBHOOK_SECRET_KEY];
+
+/// Write every stored setting except credentials to `path` as a JSON object.
+/// Returns the number of settings written.
+#[tauri::command]
+pub async fn export_settings(app: AppHandle, path: String) -> Result<usize, String> {
+    let store = app.store("settings.json").map_err(|e| e.to_string())?;
+    // Server credentials stay on this machine
+    let settings: serde_json::Map<String, serde_json::Value> = store
+        .entries()
+        .into_iter()
+        .filter(|(key, _)| {
+            !SERVER_SECRET_KEYS.contains(&key.as_str())
+                && !SECRET_KEYS.contains(&key.as_str())
+                // Describes this machine's history file, not a preference
+                && key != HISTORY_ENCRYPTION_KEY
+        })
+        .collect();
+
+    let json = serde_json::to_string_pretty(&settings)
+        .map_err(|e| format!("Failed to serialize settings: {}", e))?;
+    std::fs::write(&path, json).map_err(|e| format!("Failed to write {}: {}", path, e))?;
+    Ok(settings.len())
+}
+
+/// Load settings from a JSON file written by `export_settings`, upgrading files
+/// exported by older versions first. Known settings with valid values are applied; unknown keys and type mismatches are
+/// skipped and reported. Settings with a live effect (hotkeys, incognito, the overlay,
+/// ...) take effect immediately.
+#[tauri::command]
+pub async fn import_settings(app: AppHandle, path: String) -> Result<SettingsImportResult, String> {
+    let contents =
+        std::fs::read_to_string(&path).map_err(|e| format!("Failed to read {}: {}", path, e))?;
+    let mut settings: serde_json::Map<String, serde_json::Value> = serde_json::from_str(&contents)
+        .map_err(|e| format!("Settings file must be a JSON object: {}", e))?;
+    let version = read_version(settings.remove(SETTINGS_VERSION_KEY).as_ref());
+    migrate_settings("Imported settings", &mut settings, version);
+
+    let store = app.store("settings.json").map_err(|e| e.to_string())?;
+    let mut result = SettingsImportResult::default();
+    for (key, value) in settings {
+        match normalize_setting(&key, value) {
+            Ok(value) => {
+                store.set(key.clone(), value);
+                result.applied.push(key);
+            }
+            Err(reason) => {
+                log::warn!("Skipping imported setting '{}': {}", key, reason);
+                result.rejected.push(RejectedSetting { key, reason });
+            }
+        }
+    }
+    store.save().map_err(|e| e.to_string())?;
+    log::info!(
+        "Imported {} setting(s), rejected {}",
+        result.applied.len(),
+        result.rejected.len()
+    );
+
+    apply_live_settings(&app).await?;
+    Ok(result)
+}
+
+/// Bring everything that acts on a setting outside the store (hotkey registration
+/// and suspension, incognito mode, the overlay, the main window, the control server
+/// and the log level) in line with the store, after many settings changed at once
+async fn apply_live_settings(app: &AppHandle) -> Result<(), String> {
+    #[cfg(desktop)]
+    {
+        let suspended = crate::get_setting_from_store(app, crate::HOTKEYS_SUSPENDED_KEY, false);
+        if get_hotkeys_suspended(app.clone()) != suspended {
+            crate::set_hotkeys_suspended(app, suspended)?;
+        }
+    }
+    let incognito = crate::get_setting_from_store(app, INCOGNITO_KEY, false);
+    if app.state::<HistoryStorage>().is_incognito() != incognito {
+        crate::commands::history::set_incognito_mode(app, incognito)?;
+    }
+    register_shortcuts(app.clone()).await?;
+    if let Some(window) = app.get_webview_window("overlay") {
+        restore_overlay_position(app, &window)?;
+    }
+    apply_overlay_visibility(app);
+    emit_overlay_appearance(app);
+    apply_main_window_always_on_top(app)?;
+    crate::control_server::apply(app)?;
+    let log_level: Option<LogLevel> = crate::get_setting_from_store(app, LOG_LEVEL_KEY, None);
+    crate::logging::set_level(log_level.map(LogLevel::filter));
+    Ok(())
+}
+
+/// Reset every setting to its default by removing it from the store, then apply the
+/// defaults to hotkeys, incognito, the overlay and the rest. Settings profiles and
+/// the history encryption are kept, and so is the dictation history unless
+/// `clear_history` is true. Emits `settings-reset`.
+#[tauri::command]
+pub async fn reset_settings(app: AppHandle, clear_history: bool) -> Result<(), String> {
+    let store = app.store("settings.json").map_err(|e| e.to_string())?;
+    for key in store.keys() {
+        if !is_profile_key(&key) && key != HISTORY_ENCRYPTION_KEY {
+            store.delete(&key);
+        }
+    }
+    store.save().map_err(|e| e.to_string())?;
+    log::info!("Reset all settings to defaults");
+
+    if clear_history {
+        app.state::<HistoryStorage>().clear(true)?;
+        notify_history_changed(&app, HistoryChange::Reset);
+        log::info!("Cleared dictation history");
+    }
+
+    // Ends a hotkey suspension and incognito mode, both off by default
+    apply_live_settings(&app).await?;
+
+    let _ = app.emit("settings-reset", ());
+    let _ = app.emit("settings-changed", ());
+    Ok(())
+}
+
+/// Parse a shortcut string (e.g. "ctrl+alt+Space" or "super+super") into a hotkey config
+#[tauri::command]
+pub fn parse_hotkey(shortcut: String) -> Result<HotkeyConfig, String> {
+    HotkeyConfig::from_shortcut_string(&shortcut)
+}
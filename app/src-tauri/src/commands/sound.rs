@@ -0,0 +1,9 @@
+use crate::audio::{play_sound, SoundSettings, SoundType};
+use tauri::AppHandle;
+
+/// Play the configured start or stop sound so the settings UI can preview it.
+/// Plays even when `sound_enabled` is off.
+#[tauri::command]
+pub fn preview_sound(app: AppHandle, which: SoundType) {
+    play_sound(which, SoundSettings::from_store(&app));
+}
@@ -0,0 +1,113 @@
+use crate::commands::history::notify_history_changed;
+use crate::history::{HistoryChange, HistoryStorage, HISTORY_STORAGE_PATH_KEY};
+use crate::logging::LOG_FILE_NAME;
+use std::path::PathBuf;
+use tauri::{AppHandle, Manager, State};
+use tauri_plugin_opener::OpenerExt;
+use tauri_plugin_store::StoreExt;
+
+/// Directory holding settings.json, history.json and saved recordings
+fn data_dir(app: &AppHandle) -> Result<PathBuf, String> {
+    app.path()
+        .app_data_dir()
+        .map_err(|e| format!("Failed to get app data directory: {}", e))
+}
+
+/// Get the path of the app data directory
+#[tauri::command]
+pub async fn get_data_dir_path(app: AppHandle) -> Result<String, String> {
+    Ok(data_dir(&app)?.to_string_lossy().into_owned())
+}
+
+/// Open the app data directory in the OS file manager
+#[tauri::command]
+pub async fn open_data_dir(app: AppHandle) -> Result<(), String> {
+    let dir = data_dir(&app)?;
+    // Nothing may have been saved yet
+    std::fs::create_dir_all(&dir)
+        .map_err(|e| format!("Failed to create {}: {}", dir.display(), e))?;
+    app.opener()
+        .open_path(dir.to_string_lossy(), None::<&str>)
+        .map_err(|e| format!("Failed to open {}: {}", dir.display(), e))
+}
+
+/// Get the directory the history file is currently kept in. This is the app data
+/// directory unless a custom location is set and was accessible at startup.
+#[tauri::command]
+pub async fn get_history_storage_path(
+    history: State<'_, HistoryStorage>,
+) -> Result<String, String> {
+    let file_path = history.file_path()?;
+    Ok(file_path
+        .parent()
+        .unwrap_or(&file_path)
+        .to_string_lossy()
+        .into_owned())
+}
+
+/// Keep history in `path` (or the app data directory when `None`). The directory
+/// must be writable. With `migrate`, the current entries move along; otherwise any
+/// history already in the new location is loaded. Returns the directory now in use.
+#[tauri::command]
+pub async fn set_history_storage_path(
+    app: AppHandle,
+    path: Option<String>,
+    migrate: bool,
+    history: State<'_, HistoryStorage>,
+) -> Result<String, String> {
+    let path = path.filter(|p| !p.trim().is_empty());
+    let dir = match &path {
+        Some(p) => PathBuf::from(p.trim()),
+        None => data_dir(&app)?,
+    };
+    if !dir.is_absolute() {
+        return Err(format!(
+            "History location must be an absolute path: {}",
+            dir.display()
+        ));
+    }
+
+    let entries = history.relocate(&dir, migrate)?;
+    let store = app.store("settings.json").map_err(|e| e.to_string())?;
+    match &path {
+        Some(_) => store.set(HISTORY_STORAGE_PATH_KEY, dir.to_string_lossy().into_owned()),
+        None => {
+            store.delete(HISTORY_STORAGE_PATH_KEY);
+        }
+    }
+    store.save().map_err(|e| e.to_string())?;
+    notify_history_changed(&app, HistoryChange::Reset);
+    log::info!(
+        "History now stored in {} ({} entries{})",
+        dir.display(),
+        entries,
+        if migrate { ", migrated" } else { "" }
+    );
+    Ok(dir.to_string_lossy().into_owned())
+}
+
+/// Current log file (older logs are rotated to `.1`, `.2`, ... beside it)
+fn log_path(app: &AppHandle) -> Result<PathBuf, String> {
+    app.path()
+        .app_log_dir()
+        .map(|dir| dir.join(LOG_FILE_NAME))
+        .map_err(|e| format!("Failed to get app log directory: {}", e))
+}
+
+/// Get the path of the current log file
+#[tauri::command]
+pub async fn get_log_path(app: AppHandle) -> Result<String, String> {
+    Ok(log_path(&app)?.to_string_lossy().into_owned())
+}
+
+/// Reveal the current log file in the OS file manager
+#[tauri::command]
+pub async fn open_logs(app: AppHandle) -> Result<(), String> {
+    let path = log_path(&app)?;
+    if !path.exists() {
+        return Err(format!("No log file at {}", path.display()));
+    }
+    app.opener()
+        .reveal_item_in_dir(&path)
+        .map_err(|e| format!("Failed to open {}: {}", path.display(), e))
+}
@@ -1,10 +1,25 @@
+use crate::audio::{play_sound_if_enabled, SoundType};
+use crate::settings::{untypeable_char, InsertionMethod};
+use crate::state::AppState;
+use crate::target_app::{
+    focus_target_app, list_running_apps as running_apps, RunningApp, TargetAppUnavailable,
+    FOCUS_SETTLE_DELAY_MS, TARGET_APP_KEY,
+};
+use crate::text_processing::{
+    apply_rules, apply_vocabulary, format_for_insertion, rules_from_store,
+    timestamp_prefix_from_store, vocabulary_from_store,
+};
+use crate::voice_commands::{commands_from_store, match_command, VoiceAction};
 use arboard::Clipboard;
 use enigo::{Direction, Enigo, Key, Keyboard, Settings};
+use serde::Serialize;
+use std::sync::atomic::Ordering;
 use std::sync::mpsc;
 use std::thread;
 use std::time::Duration;
-use tauri::AppHandle;
+use tauri::{AppHandle, Emitter, Manager};
 use tauri_plugin_store::StoreExt;
+use unicode_segmentation::UnicodeSegmentation;
 
 /// Delay after clipboard operations to ensure system stability
 const CLIPBOARD_STABILIZATION_DELAY_MS: u64 = 50;
@@ -12,8 +27,14 @@ const CLIPBOARD_STABILIZATION_DELAY_MS: u64 = 50;
 /// Delay between keyboard key press and release events
 const KEY_EVENT_DELAY_MS: u64 = 50;
 
-/// Delay before restoring previous clipboard content
-const CLIPBOARD_RESTORE_DELAY_MS: u64 = 100;
+/// Default pause after each typed character in `Type` mode (0 = type all at once)
+pub const DEFAULT_TYPE_DELAY_MS: u64 = 0;
+
+/// How long after an insertion `undo_last_insertion` may remove it
+pub const UNDO_WINDOW_MS: u64 = 10_000;
+
+/// Default delay before restoring previous clipboard content
+pub const DEFAULT_CLIPBOARD_RESTORE_DELAY_MS: u64 = 120;
 
 /// Default server URL when not configured
 const DEFAULT_SERVER_URL: &str = "http://127.0.0.1:8765";
@@ -28,15 +49,202 @@ pub async fn get_server_url(app: AppHandle) -> Result<String, String> {
     Ok(url)
 }
 
+/// Settings that control how text is inserted into the focused application
+#[derive(Debug, Clone, Copy)]
+pub struct InsertionSettings {
+    pub method: InsertionMethod,
+    pub clipboard_restore_delay_ms: u64,
+    pub auto_capitalize_first: bool,
+    pub append_trailing_space: bool,
+    pub type_delay_ms: u64,
+}
+
+impl InsertionSettings {
+    /// Read insertion settings from the store, with defaults for missing values
+    pub fn from_store(app: &AppHandle) -> Self {
+        let store = app.store("settings.json").ok();
+        let get = |key: &str| store.as_ref().and_then(|store| store.get(key));
+
+        Self {
+            method: get("insertion_method")
+                .and_then(|v| serde_json::from_value(v).ok())
+                .unwrap_or_default(),
+            clipboard_restore_delay_ms: get("clipboard_restore_delay_ms")
+                .and_then(|v| v.as_u64())
+                .unwrap_or(DEFAULT_CLIPBOARD_RESTORE_DELAY_MS),
+            auto_capitalize_first: get("auto_capitalize_first")
+                .and_then(|v| v.as_bool())
+                .unwrap_or(false),
+            append_trailing_space: get("append_trailing_space")
+                .and_then(|v| v.as_bool())
+                .unwrap_or(false),
+            type_delay_ms: get("type_delay_ms")
+                .and_then(|v| v.as_u64())
+                .unwrap_or(DEFAULT_TYPE_DELAY_MS),
+        }
+    }
+}
+
+/// Payload of the "dry-run-result" event: what `type_text` would have done
+#[derive(Debug, Clone, Serialize)]
+pub struct DryRunResult {
+    /// Text as it would have been inserted, after all post-processing
+    pub text: String,
+    /// Voice command that would have run instead of inserting, if any
+    pub voice_action: Option<VoiceAction>,
+}
+
+/// Insert a transcription into the focused application. With `dry_run` on,
+/// nothing is inserted and a "dry-run-result" event is emitted instead.
 #[tauri::command]
 pub async fn type_text(app: AppHandle, text: String) -> Result<(), String> {
-    // macOS HIToolbox APIs (used by enigo) must run on the main thread
+    // Nothing to insert (background noise only); leave the clipboard alone
+    if text.trim().is_empty() {
+        return Ok(());
+    }
+    let settings = InsertionSettings::from_store(&app);
+    let state = app.state::<AppState>();
+
+    // A transcription that is exactly a command phrase performs the action instead
+    let voice_action = if crate::get_setting_from_store(&app, "voice_commands_enabled", false) {
+        match_command(&text, &commands_from_store(&app))
+    } else {
+        None
+    };
+    let text = apply_vocabulary(&text, &vocabulary_from_store(&app));
+    let text = apply_rules(&text, &rules_from_store(&app));
+    let text = match timestamp_prefix_from_store(&app, false) {
+        Some(prefix) => format!("{}{}", prefix, text),
+        None => text,
+    };
+
+    if crate::get_setting_from_store(&app, "dry_run", false) {
+        let text = format_for_insertion(
+            &text,
+            settings.auto_capitalize_first,
+            settings.append_trailing_space,
+        );
+        log::info!("Dry run, not inserting {} chars", text.chars().count());
+        let _ = app.emit("dry-run-result", DryRunResult { text, voice_action });
+        return Ok(());
+    }
+
+    let last_inserted_chars = state.last_inserted_chars.load(Ordering::SeqCst);
+    focus_configured_target(&app);
+
+    let result = run_on_main_thread(&app, move || match &voice_action {
+        Some(action) => {
+            log::info!("Voice command recognized: {:?}", action);
+            perform_voice_action(action, settings, last_inserted_chars)
+        }
+        None => type_text_blocking(&text, settings),
+    });
+    match result {
+        Ok(inserted_chars) => {
+            state.record_insertion(inserted_chars, crate::now_millis());
+            play_sound_if_enabled(&app, SoundType::TranscriptionComplete);
+            Ok(())
+        }
+        Err(e) => {
+            play_sound_if_enabled(&app, SoundType::Error);
+            Err(e)
+        }
+    }
+}
+
+/// Type or paste `text` exactly as given, skipping recording, transcription and
+/// post-processing. `method` overrides the configured insertion method.
+#[tauri::command]
+pub async fn insert_text(
+    app: AppHandle,
+    text: String,
+    method: Option<InsertionMethod>,
+) -> Result<(), String> {
+    if text.is_empty() {
+        return Ok(());
+    }
+    let configured = InsertionSettings::from_store(&app);
+    let settings = InsertionSettings {
+        method: method.unwrap_or(configured.method),
+        auto_capitalize_first: false,
+        append_trailing_space: false,
+        ..configured
+    };
+
+    let inserted_chars = run_on_main_thread(&app, move || type_text_blocking(&text, settings))?;
+    app.state::<AppState>()
+        .record_insertion(inserted_chars, crate::now_millis());
+    Ok(())
+}
+
+/// Raise the `target_app`, if one is set, so the insertion lands there.
+/// If it can't be focused the text goes to the current focus and a
+/// "target-app-unavailable" event is emitted.
+fn focus_configured_target(app: &AppHandle) {
+    let Some(target) = crate::get_setting_from_store::<Option<String>>(app, TARGET_APP_KEY, None)
+        .filter(|target| !target.trim().is_empty())
+    else {
+        return;
+    };
+
+    match focus_target_app(&target) {
+        Ok(focused) => {
+            log::info!("Focused target app {} ({})", focused.name, focused.id);
+            thread::sleep(Duration::from_millis(FOCUS_SETTLE_DELAY_MS));
+        }
+        Err(reason) => {
+            log::warn!(
+                "Could not focus target app {}, inserting into the current focus: {}",
+                target,
+                reason
+            );
+            let _ = app.emit(
+                "target-app-unavailable",
+                TargetAppUnavailable { target, reason },
+            );
+        }
+    }
+}
+
+/// List applications with a window, for choosing a `target_app`
+#[tauri::command]
+pub async fn list_running_apps() -> Result<Vec<RunningApp>, String> {
+    running_apps()
+}
+
+/// Delete the text inserted by the most recent dictation by sending that many
+/// backspaces. Only allowed within UNDO_WINDOW_MS of the insertion, and only once:
+/// keystrokes made since then can't be detected, so a short window keeps the
+/// backspaces from eating unrelated text. Returns the number of characters deleted.
+#[tauri::command]
+pub async fn undo_last_insertion(app: AppHandle) -> Result<usize, String> {
+    let chars = app
+        .state::<AppState>()
+        .take_undoable_insertion(crate::now_millis(), UNDO_WINDOW_MS)
+        .ok_or_else(|| {
+            format!(
+                "Nothing to undo: the last insertion was already undone or is older than {} seconds",
+                UNDO_WINDOW_MS / 1000
+            )
+        })?;
+
+    log::info!("Undoing last insertion ({} characters)", chars);
+    run_on_main_thread(&app, move || {
+        press_key(Key::Backspace, chars).map(|_| chars)
+    })
+}
+
+/// Run an insertion on the main thread and wait for its result.
+/// macOS HIToolbox APIs (used by enigo) must run on the main thread.
+pub fn run_on_main_thread<F>(app: &AppHandle, insert: F) -> Result<usize, String>
+where
+    F: FnOnce() -> Result<usize, String> + Send + 'static,
+{
     // Use a channel to get the result back from the main thread
-    let (tx, rx) = mpsc::channel::<Result<(), String>>();
+    let (tx, rx) = mpsc::channel::<Result<usize, String>>();
 
     app.run_on_main_thread(move || {
-        let result = type_text_blocking(&text);
-        let _ = tx.send(result);
+        let _ = tx.send(insert());
     })
     .map_err(|e| e.to_string())?;
 
@@ -44,12 +252,141 @@ pub async fn type_text(app: AppHandle, text: String) -> Result<(), String> {
     rx.recv().map_err(|e| e.to_string())?
 }
 
-/// Type text using clipboard and paste. Used internally by shortcut handlers.
-pub fn type_text_blocking(text: &str) -> Result<(), String> {
+/// Insert text using the given settings. Used internally by shortcut handlers.
+/// Returns the number of characters inserted, counted as `backspace_count` does.
+pub fn type_text_blocking(text: &str, settings: InsertionSettings) -> Result<usize, String> {
+    let text = &format_for_insertion(
+        text,
+        settings.auto_capitalize_first,
+        settings.append_trailing_space,
+    );
+    let method = settings.method.resolve(text);
+    if settings.method == InsertionMethod::Type && method == InsertionMethod::Paste {
+        log::info!(
+            "Text contains a character that can't be typed ({:?}), pasting instead",
+            untypeable_char(text).unwrap_or_default()
+        );
+    }
+    match method {
+        InsertionMethod::Type => type_keystrokes(text, settings.type_delay_ms),
+        InsertionMethod::Paste | InsertionMethod::Auto => {
+            paste_text(text, settings.clipboard_restore_delay_ms)
+        }
+    }?;
+    Ok(backspace_count(text))
+}
+
+/// Number of Backspace presses that remove `text` again: one per grapheme
+/// cluster, so a multi-codepoint emoji or a letter with combining marks counts once
+pub fn backspace_count(text: &str) -> usize {
+    typing_chunks(text).len()
+}
+
+/// Perform a voice command action. `last_inserted_chars` is the length of the
+/// previous insertion (for `DeleteLast`). Returns the number of characters now
+/// considered "last inserted".
+pub fn perform_voice_action(
+    action: &VoiceAction,
+    settings: InsertionSettings,
+    last_inserted_chars: usize,
+) -> Result<usize, String> {
+    match action {
+        VoiceAction::NewLine => press_key(Key::Return, 1).map(|_| 1),
+        VoiceAction::NewParagraph => press_key(Key::Return, 2).map(|_| 2),
+        VoiceAction::Tab => press_key(Key::Tab, 1).map(|_| 1),
+        VoiceAction::Backspace { count } => press_key(Key::Backspace, *count as usize).map(|_| 0),
+        VoiceAction::DeleteLast => press_key(Key::Backspace, last_inserted_chars).map(|_| 0),
+        VoiceAction::Text { text } => type_text_blocking(text, settings),
+    }
+}
+
+/// Click `key` the given number of times
+fn press_key(key: Key, times: usize) -> Result<(), String> {
+    let mut enigo = Enigo::new(&Settings::default()).map_err(|e| e.to_string())?;
+    for _ in 0..times {
+        enigo
+            .key(key, Direction::Click)
+            .map_err(|e| e.to_string())?;
+    }
+    Ok(())
+}
+
+/// Split text into the units typed one at a time when a typing delay is set.
+/// Grapheme clusters keep combining marks and multi-codepoint emoji together.
+pub fn typing_chunks(text: &str) -> Vec<&str> {
+    text.graphemes(true).collect()
+}
+
+/// Type text by simulating individual keystrokes. With a non-zero `delay_ms` the
+/// text is typed one character at a time, pausing after each, for applications
+/// that drop characters when input arrives too quickly.
+fn type_keystrokes(text: &str, delay_ms: u64) -> Result<(), String> {
+    let mut enigo = Enigo::new(&Settings::default()).map_err(|e| e.to_string())?;
+    if delay_ms == 0 {
+        return enigo.text(text).map_err(|e| {
+            format!(
+                "{} (if characters are being dropped, set type_delay_ms above 0 to type more slowly)",
+                e
+            )
+        });
+    }
+
+    let chunks = typing_chunks(text);
+    log::debug!(
+        "Typing {} characters with a {}ms delay",
+        chunks.len(),
+        delay_ms
+    );
+    for (index, chunk) in chunks.iter().enumerate() {
+        enigo.text(chunk).map_err(|e| {
+            format!(
+                "Typing stopped after {} of {} characters: {} (a higher type_delay_ms, currently {}ms, is more reliable but slower)",
+                index,
+                chunks.len(),
+                e,
+                delay_ms
+            )
+        })?;
+        thread::sleep(Duration::from_millis(delay_ms));
+    }
+    Ok(())
+}
+
+/// Insert text using clipboard and paste
+fn paste_text(text: &str, clipboard_restore_delay_ms: u64) -> Result<(), String> {
+    with_clipboard_text(text, clipboard_restore_delay_ms, || {
+        // Simulate Ctrl+V / Cmd+V
+        let mut enigo = Enigo::new(&Settings::default()).map_err(|e| e.to_string())?;
+
+        #[cfg(target_os = "macos")]
+        let modifier = Key::Meta;
+        #[cfg(not(target_os = "macos"))]
+        let modifier = Key::Control;
+
+        enigo
+            .key(modifier, Direction::Press)
+            .map_err(|e| e.to_string())?;
+        thread::sleep(Duration::from_millis(KEY_EVENT_DELAY_MS));
+        enigo
+            .key(Key::Unicode('v'), Direction::Click)
+            .map_err(|e| e.to_string())?;
+        thread::sleep(Duration::from_millis(KEY_EVENT_DELAY_MS));
+        enigo
+            .key(modifier, Direction::Release)
+            .map_err(|e| e.to_string())
+    })
+}
+
+/// Put `text` on the clipboard, run `paste`, then restore the previous clipboard text
+/// after `restore_delay_ms` so the paste has time to complete.
+fn with_clipboard_text<F>(text: &str, restore_delay_ms: u64, paste: F) -> Result<(), String>
+where
+    F: FnOnce() -> Result<(), String>,
+{
     let mut clipboard = Clipboard::new().map_err(|e| e.to_string())?;
 
     // Save previous clipboard content
-    let previous = clipboard.get_text().unwrap_or_default();
+    let previous = capture_clipboard_text(&mut clipboard);
 
     // Set new text
     clipboard.set_text(text).map_err(|e| e.to_string())?;
@@ -57,29 +394,43 @@ pub fn type_text_blocking(text: &str) -> Result<(), String> {
     // Small delay for clipboard to stabilize
     thread::sleep(Duration::from_millis(CLIPBOARD_STABILIZATION_DELAY_MS));
 
-    // Simulate Ctrl+V / Cmd+V
-    let mut enigo = Enigo::new(&Settings::default()).map_err(|e| e.to_string())?;
+    let result = paste();
 
-    #[cfg(target_os = "macos")]
-    let modifier = Key::Meta;
-    #[cfg(not(target_os = "macos"))]
-    let modifier = Key::Control;
-
-    enigo
-        .key(modifier, Direction::Press)
-        .map_err(|e| e.to_string())?;
-    thread::sleep(Duration::from_millis(KEY_EVENT_DELAY_MS));
-    enigo
-        .key(Key::Unicode('v'), Direction::Click)
-        .map_err(|e| e.to_string())?;
-    thread::sleep(Duration::from_millis(KEY_EVENT_DELAY_MS));
-    enigo
-        .key(modifier, Direction::Release)
-        .map_err(|e| e.to_string())?;
-
-    // Restore previous clipboard after a delay
-    thread::sleep(Duration::from_millis(CLIPBOARD_RESTORE_DELAY_MS));
-    let _ = clipboard.set_text(&previous);
+    // Restore previous clipboard after a delay (even if the paste failed)
+    if let Some(previous) = previous {
+        thread::sleep(Duration::from_millis(restore_delay_ms));
+        if let Err(e) = clipboard.set_text(&previous) {
+            log::warn!("Failed to restore clipboard: {}", e);
+        }
+    }
 
-    Ok(())
+    result
+}
+
+/// Read the current clipboard text. Returns None when the clipboard holds an image,
+/// so it is left alone rather than clobbered.
+fn capture_clipboard_text(clipboard: &mut Clipboard) -> Option<String> {
+    let text = clipboard.get_text();
+    if let Err(e) = &text {
+        log::debug!("Clipboard has no text content: {}", e);
+    }
+    let restore = clipboard_text_to_restore(text.ok(), || clipboard.get_image().is_ok());
+    if restore.is_none() {
+        log::warn!("Clipboard holds an image, it will not be restored after paste");
+    }
+    restore
+}
+
+/// Decide what to put back on the clipboard after a paste, given its previous text
+/// (None if it had none). An image is left alone; an empty or non-text clipboard is
+/// restored as empty text so the pasted transcription doesn't linger.
+pub fn clipboard_text_to_restore(
+    text: Option<String>,
+    holds_image: impl FnOnce() -> bool,
+) -> Option<String> {
+    match text {
+        Some(text) => Some(text),
+        None if holds_image() => None,
+        None => Some(String::new()),
+    }
 }
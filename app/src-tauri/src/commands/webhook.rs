@@ -0,0 +1,45 @@
+use crate::webhook::{
+    validate_webhook_secret, validate_webhook_url, TRANSCRIPTION_WEBHOOK_SECRET_KEY,
+    TRANSCRIPTION_WEBHOOK_URL_KEY,
+};
+use tauri::AppHandle;
+use tauri_plugin_store::StoreExt;
+
+/// Validate and persist the transcription webhook. An empty or missing `url` turns
+/// the webhook off, and an empty or missing `secret` removes the stored secret.
+/// Returns the URL as saved.
+#[tauri::command]
+pub async fn set_transcription_webhook(
+    app: AppHandle,
+    url: Option<String>,
+    secret: Option<String>,
+) -> Result<Option<String>, String> {
+    let url = url
+        .filter(|url| !url.trim().is_empty())
+        .map(|url| validate_webhook_url(&url))
+        .transpose()?;
+    let secret = secret.filter(|secret| !secret.trim().is_empty());
+    if let Some(secret) = &secret {
+        validate_webhook_secret(secret.trim())?;
+    }
+
+    let store = app.store("settings.json").map_err(|e| e.to_string())?;
+    match &url {
+        Some(url) => store.set(TRANSCRIPTION_WEBHOOK_URL_KEY, url.clone()),
+        None => {
+            store.delete(TRANSCRIPTION_WEBHOOK_URL_KEY);
+        }
+    }
+    match secret {
+        Some(secret) => store.set(TRANSCRIPTION_WEBHOOK_SECRET_KEY, secret.trim()),
+        None => {
+            store.delete(TRANSCRIPTION_WEBHOOK_SECRET_KEY);
+        }
+    }
+    store.save().map_err(|e| e.to_string())?;
+    match &url {
+        Some(url) => log::info!("Transcription webhook set to {}", url),
+        None => log::info!("Transcription webhook turned off"),
+    }
+    Ok(url)
+}
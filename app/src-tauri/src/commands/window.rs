@@ -0,0 +1,44 @@
+use crate::get_setting_from_store;
+use tauri::{AppHandle, Manager, WebviewWindow};
+use tauri_plugin_store::StoreExt;
+
+/// Store key for keeping the main window above other windows
+const ALWAYS_ON_TOP_KEY: &str = "main_window_always_on_top";
+
+/// Apply the stored always-on-top setting to the main window
+pub fn apply_main_window_always_on_top(app: &AppHandle) -> Result<(), String> {
+    let Some(window) = app.get_webview_window("main") else {
+        return Ok(());
+    };
+    let always_on_top: bool = get_setting_from_store(app, ALWAYS_ON_TOP_KEY, false);
+    window
+        .set_always_on_top(always_on_top)
+        .map_err(|e| e.to_string())
+}
+
+/// Hide the main window when `close_to_tray` is enabled, otherwise quit the app.
+/// The tray "Show Window" item and icon click bring a hidden window back.
+pub fn handle_main_window_close(window: &WebviewWindow) {
+    let app = window.app_handle();
+    if get_setting_from_store(app, "close_to_tray", false) {
+        if let Err(e) = window.hide() {
+            log::warn!("Failed to hide main window: {}", e);
+        }
+    } else {
+        crate::quit_app(app);
+    }
+}
+
+/// Keep the main window above other windows (independent of the overlay) and
+/// persist the choice
+#[tauri::command]
+pub async fn set_main_window_always_on_top(
+    app: AppHandle,
+    always_on_top: bool,
+) -> Result<(), String> {
+    let store = app.store("settings.json").map_err(|e| e.to_string())?;
+    store.set(ALWAYS_ON_TOP_KEY, always_on_top);
+    store.save().map_err(|e| e.to_string())?;
+
+    apply_main_window_always_on_top(&app)
+}
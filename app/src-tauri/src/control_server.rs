@@ -0,0 +1,405 @@
+//! Optional local HTTP API for driving the app from scripts and devices such as a
+//! Stream Deck, without global hotkeys.
+//!
+//! Off by default. When enabled it listens on 127.0.0.1 only (`control_server_port`),
+//! and every request must send the token as `Authorization: Bearer <token>`.
+//! Responses are JSON; errors are `{"error": "..."}`.
+//!
+//! - `POST /recording/start`, `/recording/stop`, `/recording/toggle`, `/recording/cancel`
+//!   run the action like the hotkeys do and return the recording state
+//! - `POST /paste-last` types the most recent transcription
+//! - `GET /recording` returns the recording state
+//! - `GET /history?limit=N` returns the newest entries (default 20)
+
+use crate::history::HistoryStorage;
+use crate::state::AppState;
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value};
+use std::io::{Read, Write};
+use std::net::{Ipv4Addr, SocketAddr, TcpListener, TcpStream};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex};
+use std::thread::JoinHandle;
+use std::time::Duration;
+use tauri::{AppHandle, Manager};
+use tauri_plugin_store::StoreExt;
+
+/// Store key for whether the control server runs
+pub const CONTROL_SERVER_ENABLED_KEY: &str = "control_server_enabled";
+
+/// Store key for the port the control server listens on
+pub const CONTROL_SERVER_PORT_KEY: &str = "control_server_port";
+
+/// Store key for the token requests must carry. Excluded from settings exports.
+pub const CONTROL_SERVER_TOKEN_KEY: &str = "control_server_token";
+
+/// Default port, next to the transcription server's 8765
+pub const DEFAULT_CONTROL_SERVER_PORT: u16 = 8766;
+
+/// Entries returned by `GET /history` without a limit
+pub const DEFAULT_HISTORY_LIMIT: usize = 20;
+
+/// Largest request head accepted; requests have no body
+const MAX_REQUEST_HEAD_BYTES: usize = 8192;
+
+/// Timeout for reading a request and writing its response
+const REQUEST_TIMEOUT: Duration = Duration::from_secs(5);
+
+/// A recording action requested from outside the hotkeys
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum RemoteAction {
+    Start,
+    Stop,
+    Toggle,
+    Cancel,
+    PasteLast,
+}
+
+/// What a request asks for
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ControlRoute {
+    Action(RemoteAction),
+    RecordingState,
+    History { limit: usize },
+}
+
+/// The parts of a request the API looks at
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ControlRequest {
+    pub method: String,
+    pub target: String,
+    /// Bearer token from the Authorization header
+    pub token: Option<String>,
+}
+
+/// Parse a request head (request line and headers, without the blank line)
+pub fn parse_request(head: &str) -> Result<ControlRequest, String> {
+    let mut lines = head.lines();
+    let request_line = lines.next().ok_or("Empty request")?;
+    let mut parts = request_line.split_whitespace();
+    let (Some(method), Some(target), Some(version)) = (parts.next(), parts.next(), parts.next())
+    else {
+        return Err(format!("Malformed request line '{}'", request_line));
+    };
+    if !version.starts_with("HTTP/1.") {
+        return Err(format!("Unsupported protocol '{}'", version));
+    }
+
+    let token = lines
+        .filter_map(|line| line.split_once(':'))
+        .find(|(name, _)| name.trim().eq_ignore_ascii_case("authorization"))
+        .and_then(|(_, value)| {
+            let (scheme, token) = value.trim().split_once(' ')?;
+            scheme
+                .eq_ignore_ascii_case("bearer")
+                .then(|| token.trim().to_string())
+        });
+
+    Ok(ControlRequest {
+        method: method.to_string(),
+        target: target.to_string(),
+        token,
+    })
+}
+
+/// Match a request to an endpoint, or the HTTP status and message to reply with
+pub fn route(method: &str, target: &str) -> Result<ControlRoute, (u16, String)> {
+    let (path, query) = target.split_once('?').unwrap_or((target, ""));
+    let expected_method = match path {
+        "/recording" | "/history" => "GET",
+        "/recording/start" | "/recording/stop" | "/recording/toggle" | "/recording/cancel"
+        | "/paste-last" => "POST",
+        _ => return Err((404, format!("Unknown endpoint '{}'", path))),
+    };
+    if method != expected_method {
+        return Err((
+            405,
+            format!("{} requires {}, not {}", path, expected_method, method),
+        ));
+    }
+
+    Ok(match path {
+        "/recording/start" => ControlRoute::Action(RemoteAction::Start),
+        "/recording/stop" => ControlRoute::Action(RemoteAction::Stop),
+        "/recording/toggle" => ControlRoute::Action(RemoteAction::Toggle),
+        "/recording/cancel" => ControlRoute::Action(RemoteAction::Cancel),
+        "/paste-last" => ControlRoute::Action(RemoteAction::PasteLast),
+        "/recording" => ControlRoute::RecordingState,
+        _ => {
+            let limit = query
+                .split('&')
+                .find_map(|pair| pair.strip_prefix("limit="))
+                .map(|limit| {
+                    limit
+                        .parse::<usize>()
+                        .map_err(|_| (400, format!("Invalid limit '{}'", limit)))
+                })
+                .transpose()?
+                .unwrap_or(DEFAULT_HISTORY_LIMIT);
+            ControlRoute::History { limit }
+        }
+    })
+}
+
+/// Compare tokens in time independent of where they differ
+pub fn token_matches(expected: &str, given: Option<&str>) -> bool {
+    let Some(given) = given else {
+        return false;
+    };
+    if expected.is_empty() || expected.len() != given.len() {
+        return false;
+    }
+    expected
+        .bytes()
+        .zip(given.bytes())
+        .fold(0u8, |diff, (a, b)| diff | (a ^ b))
+        == 0
+}
+
+/// Generate a new random token
+pub fn generate_token() -> String {
+    uuid::Uuid::new_v4().simple().to_string()
+}
+
+/// Control server settings and whether it is currently listening
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct ControlServerStatus {
+    pub enabled: bool,
+    pub port: u16,
+    /// Port it is listening on, `None` when stopped
+    pub listening_port: Option<u16>,
+    pub token: Option<String>,
+}
+
+struct RunningServer {
+    port: u16,
+    shutdown: Arc<AtomicBool>,
+    thread: JoinHandle<()>,
+}
+
+/// The running control server, if any
+#[derive(Default)]
+pub struct ControlServer {
+    running: Mutex<Option<RunningServer>>,
+}
+
+impl ControlServer {
+    /// Port the server is listening on, `None` when stopped
+    pub fn listening_port(&self) -> Option<u16> {
+        self.running
+            .lock()
+            .ok()
+            .and_then(|running| running.as_ref().map(|server| server.port))
+    }
+
+    /// Listen on 127.0.0.1:`port`, replacing any running server
+    pub fn start(&self, app: &AppHandle, port: u16) -> Result<(), String> {
+        let mut running = self
+            .running
+            .lock()
+            .map_err(|e| format!("Failed to lock control server: {}", e))?;
+        if let Some(server) = running.take() {
+            shut_down(server, true);
+        }
+
+        let listener = TcpListener::bind((Ipv4Addr::LOCALHOST, port))
+            .map_err(|e| format!("Failed to listen on 127.0.0.1:{}: {}", port, e))?;
+        let shutdown = Arc::new(AtomicBool::new(false));
+        let thread = {
+            let app = app.clone();
+            let shutdown = shutdown.clone();
+            std::thread::Builder::new()
+                .name("control-server".to_string())
+                .spawn(move || accept_loop(&app, listener, &shutdown))
+                .map_err(|e| format!("Failed to start control server: {}", e))?
+        };
+        log::info!("Control server listening on 127.0.0.1:{}", port);
+        *running = Some(RunningServer {
+            port,
+            shutdown,
+            thread,
+        });
+        Ok(())
+    }
+
+    /// Stop listening. With `wait`, block until the request in progress (if any)
+    /// finishes. Don't wait on the main thread: a request may be waiting on it.
+    pub fn stop(&self, wait: bool) {
+        let server = match self.running.lock() {
+            Ok(mut running) => running.take(),
+            Err(e) => {
+                log::warn!("Failed to lock control server: {}", e);
+                return;
+            }
+        };
+        if let Some(server) = server {
+            shut_down(server, wait);
+        }
+    }
+}
+
+fn shut_down(server: RunningServer, wait: bool) {
+    server.shutdown.store(true, Ordering::SeqCst);
+    // Wake the blocking accept so the loop sees the flag
+    let _ = TcpStream::connect_timeout(
+        &SocketAddr::from((Ipv4Addr::LOCALHOST, server.port)),
+        REQUEST_TIMEOUT,
+    );
+    if wait && server.thread.join().is_err() {
+        log::warn!("Control server thread panicked");
+    }
+    log::info!("Control server on port {} stopped", server.port);
+}
+
+fn accept_loop(app: &AppHandle, listener: TcpListener, shutdown: &AtomicBool) {
+    for stream in listener.incoming() {
+        if shutdown.load(Ordering::SeqCst) {
+            break;
+        }
+        match stream {
+            // One request at a time, so actions run in the order they arrive
+            Ok(stream) => handle_connection(app, stream),
+            Err(e) => log::warn!("Control server failed to accept a connection: {}", e),
+        }
+    }
+}
+
+fn handle_connection(app: &AppHandle, mut stream: TcpStream) {
+    if let Err(e) = stream
+        .set_read_timeout(Some(REQUEST_TIMEOUT))
+        .and_then(|_| stream.set_write_timeout(Some(REQUEST_TIMEOUT)))
+    {
+        log::warn!("Control server failed to set timeouts: {}", e);
+        return;
+    }
+
+    let (status, body) = match read_request_head(&mut stream)
+        .and_then(|head| parse_request(&head).map_err(|e| (400, e)))
+    {
+        Ok(request) => respond_to(app, &request),
+        Err((status, message)) => (status, json!({ "error": message })),
+    };
+
+    if let Err(e) = write_response(&mut stream, status, &body) {
+        log::warn!("Control server failed to respond: {}", e);
+    }
+}
+
+fn read_request_head(stream: &mut TcpStream) -> Result<String, (u16, String)> {
+    let mut head = Vec::new();
+    let mut buffer = [0u8; 1024];
+    loop {
+        let read = stream
+            .read(&mut buffer)
+            .map_err(|e| (408, format!("Failed to read request: {}", e)))?;
+        if read == 0 {
+            return Err((400, "Incomplete request".to_string()));
+        }
+        head.extend_from_slice(&buffer[..read]);
+        if let Some(end) = head.windows(4).position(|window| window == b"\r\n\r\n") {
+            head.truncate(end);
+            return String::from_utf8(head).map_err(|_| (400, "Request is not UTF-8".to_string()));
+        }
+        if head.len() > MAX_REQUEST_HEAD_BYTES {
+            return Err((431, "Request headers too large".to_string()));
+        }
+    }
+}
+
+fn respond_to(app: &AppHandle, request: &ControlRequest) -> (u16, Value) {
+    let token: Option<String> = crate::get_setting_from_store(app, CONTROL_SERVER_TOKEN_KEY, None);
+    if !token_matches(token.as_deref().unwrap_or(""), request.token.as_deref()) {
+        log::warn!(
+            "Control server rejected {} {}: missing or wrong token",
+            request.method,
+            request.target
+        );
+        return (401, json!({ "error": "Missing or wrong token" }));
+    }
+
+    let route = match route(&request.method, &request.target) {
+        Ok(route) => route,
+        Err((status, message)) => return (status, json!({ "error": message })),
+    };
+    log::info!("Control server: {} {}", request.method, request.target);
+
+    match route {
+        ControlRoute::Action(action) => {
+            crate::run_remote_action(app, action, "Control API");
+            (200, json!(app.state::<AppState>().recording_state()))
+        }
+        ControlRoute::RecordingState => (200, json!(app.state::<AppState>().recording_state())),
+        ControlRoute::History { limit } => {
+            match app.state::<HistoryStorage>().get_all(Some(limit)) {
+                Ok(entries) => (200, json!(entries)),
+                Err(e) => (500, json!({ "error": e })),
+            }
+        }
+    }
+}
+
+fn write_response(stream: &mut TcpStream, status: u16, body: &Value) -> std::io::Result<()> {
+    let reason = match status {
+        200 => "OK",
+        400 => "Bad Request",
+        401 => "Unauthorized",
+        404 => "Not Found",
+        405 => "Method Not Allowed",
+        408 => "Request Timeout",
+        431 => "Request Header Fields Too Large",
+        _ => "Internal Server Error",
+    };
+    let body = body.to_string();
+    let response = format!(
+        "HTTP/1.1 {} {}\r\nContent-Type: application/json\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{}",
+        status,
+        reason,
+        body.len(),
+        body
+    );
+    stream.write_all(response.as_bytes())?;
+    stream.flush()
+}
+
+/// The stored token, creating and saving one if there is none yet
+pub fn ensure_token(app: &AppHandle) -> Result<String, String> {
+    let token: Option<String> = crate::get_setting_from_store(app, CONTROL_SERVER_TOKEN_KEY, None);
+    if let Some(token) = token.filter(|token| !token.is_empty()) {
+        return Ok(token);
+    }
+    let token = generate_token();
+    let store = app.store("settings.json").map_err(|e| e.to_string())?;
+    store.set(CONTROL_SERVER_TOKEN_KEY, token.clone());
+    store.save().map_err(|e| e.to_string())?;
+    Ok(token)
+}
+
+/// Current settings and state of the control server
+pub fn status(app: &AppHandle) -> ControlServerStatus {
+    ControlServerStatus {
+        enabled: crate::get_setting_from_store(app, CONTROL_SERVER_ENABLED_KEY, false),
+        port: crate::get_setting_from_store(
+            app,
+            CONTROL_SERVER_PORT_KEY,
+            DEFAULT_CONTROL_SERVER_PORT,
+        ),
+        listening_port: app.state::<ControlServer>().listening_port(),
+        token: crate::get_setting_from_store(app, CONTROL_SERVER_TOKEN_KEY, None),
+    }
+}
+
+/// Start, restart or stop the server to match the stored settings
+pub fn apply(app: &AppHandle) -> Result<(), String> {
+    let server = app.state::<ControlServer>();
+    let settings = status(app);
+    if !settings.enabled {
+        server.stop(true);
+        return Ok(());
+    }
+    ensure_token(app)?;
+    if settings.listening_port != Some(settings.port) {
+        server.start(app, settings.port)?;
+    }
+    Ok(())
+}
@@ -1,9 +1,14 @@
+use crate::history_crypto::{EncryptedHistory, HistoryKey, KeySource};
+use crate::migrations::{migrate_history, HISTORY_SCHEMA_VERSION};
 use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
 use std::fs;
-use std::path::PathBuf;
-use std::sync::RwLock;
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Mutex, RwLock, RwLockReadGuard, RwLockWriteGuard};
 use uuid::Uuid;
+use zeroize::Zeroizing;
 
 /// A single dictation history entry
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -11,34 +16,346 @@ pub struct HistoryEntry {
     pub id: String,
     pub timestamp: DateTime<Utc>,
     pub text: String,
+    /// Pinned entries are kept by `clear` unless explicitly included
+    #[serde(default)]
+    pub pinned: bool,
+    /// Language the server reported for the transcription, if known
+    #[serde(default)]
+    pub language: Option<String>,
+    /// Number of characters in `text` (backfilled on load for older entries)
+    #[serde(default)]
+    pub char_count: usize,
+    /// Number of whitespace-separated words in `text` (backfilled on load for older entries)
+    #[serde(default)]
+    pub word_count: usize,
+    /// Length of the recording, if the client reported it
+    #[serde(default)]
+    pub audio_duration_ms: Option<u64>,
+    /// When the text was last changed with `HistoryStorage::update`
+    #[serde(default)]
+    pub edited_at: Option<DateTime<Utc>>,
+    /// User-assigned labels, matched case-insensitively
+    #[serde(default)]
+    pub tags: Vec<String>,
+    /// When a chunk was last appended with `HistoryStorage::append_to_latest`
+    #[serde(default)]
+    pub appended_at: Option<DateTime<Utc>>,
+    /// Added in incognito mode: kept in memory for this session, never written to disk
+    #[serde(default)]
+    pub incognito: bool,
 }
 
 impl HistoryEntry {
-    pub fn new(text: String) -> Self {
-        Self {
+    pub fn new(text: String, language: Option<String>, audio_duration_ms: Option<u64>) -> Self {
+        let mut entry = Self {
             id: Uuid::new_v4().to_string(),
             timestamp: Utc::now(),
             text,
+            pinned: false,
+            language,
+            char_count: 0,
+            word_count: 0,
+            audio_duration_ms,
+            edited_at: None,
+            tags: Vec::new(),
+            appended_at: None,
+            incognito: false,
+        };
+        entry.fill_counts();
+        entry
+    }
+
+    /// Whether the entry carries `tag`, ignoring case
+    pub fn has_tag(&self, tag: &str) -> bool {
+        self.tags.iter().any(|t| t.eq_ignore_ascii_case(tag))
+    }
+
+    /// Compute `char_count` and `word_count` from `text`
+    fn fill_counts(&mut self) {
+        self.char_count = self.text.chars().count();
+        self.word_count = self.text.split_whitespace().count();
+    }
+}
+
+/// Aggregate statistics over all history entries
+#[derive(Debug, Clone, Serialize, PartialEq)]
+pub struct HistoryStats {
+    pub total_entries: usize,
+    pub total_words: usize,
+    pub total_chars: usize,
+    /// Sum of durations for entries that recorded one
+    pub total_audio_duration_ms: u64,
+    pub average_words: f64,
+    pub average_chars: f64,
+}
+
+/// File format for `HistoryStorage::export`
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum ExportFormat {
+    /// Array of full entries (ids, timestamps, pin state, language)
+    Json,
+    /// Entry text only, separated by blank lines
+    Text,
+}
+
+/// Default maximum number of entries kept in history (0 = unlimited)
+pub const DEFAULT_HISTORY_MAX_ENTRIES: usize = 1000;
+
+/// Default maximum age of history entries in days (0 = unlimited)
+pub const DEFAULT_HISTORY_MAX_AGE_DAYS: u32 = 0;
+
+/// Default window in append mode: a transcription completing within this long
+/// after the previous one is appended to its entry
+pub const DEFAULT_APPEND_WINDOW_MS: u64 = 10_000;
+
+/// Size and age limits applied by `HistoryStorage::prune`. Pinned entries are never pruned.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct HistoryLimits {
+    /// Maximum number of entries (0 = unlimited)
+    pub max_entries: usize,
+    /// Maximum entry age in days (0 = unlimited)
+    pub max_age_days: u32,
+}
+
+impl Default for HistoryLimits {
+    fn default() -> Self {
+        Self {
+            max_entries: DEFAULT_HISTORY_MAX_ENTRIES,
+            max_age_days: DEFAULT_HISTORY_MAX_AGE_DAYS,
         }
     }
 }
 
+/// Remove unpinned entries (newest first) that exceed `limits` as of `now`.
+/// Returns the number of entries removed.
+fn prune_entries(
+    entries: &mut Vec<HistoryEntry>,
+    limits: &HistoryLimits,
+    now: DateTime<Utc>,
+) -> usize {
+    let initial_len = entries.len();
+
+    if limits.max_age_days > 0 {
+        let cutoff = now - chrono::Duration::days(i64::from(limits.max_age_days));
+        entries.retain(|e| e.pinned || e.timestamp >= cutoff);
+    }
+
+    // Drop the oldest unpinned entries first
+    if limits.max_entries > 0 {
+        while entries.len() > limits.max_entries {
+            match entries.iter().rposition(|e| !e.pinned) {
+                Some(index) => {
+                    entries.remove(index);
+                }
+                None => break,
+            }
+        }
+    }
+
+    initial_len - entries.len()
+}
+
+/// Fill in counts for entries saved before they were recorded
+fn backfill_counts(entries: &mut [HistoryEntry]) {
+    for entry in entries
+        .iter_mut()
+        .filter(|e| e.char_count == 0 && !e.text.is_empty())
+    {
+        entry.fill_counts();
+    }
+}
+
+/// Where the previous save is kept while the history file is replaced
+fn backup_path(file_path: &Path) -> PathBuf {
+    file_path.with_extension("json.bak")
+}
+
+/// Replace `file_path` with `content` so a crash at any point leaves either the old
+/// or the new file intact: write and flush a temp file, move the current file to
+/// the backup, then rename the temp file into place. If the last step never runs,
+/// loading falls back to the backup.
+fn write_atomically(file_path: &Path, content: &[u8]) -> std::io::Result<()> {
+    let temp_path = file_path.with_extension("json.tmp");
+    let mut file = fs::File::create(&temp_path)?;
+    file.write_all(content)?;
+    file.sync_all()?;
+    drop(file);
+
+    if file_path.exists() {
+        fs::rename(file_path, backup_path(file_path))?;
+    }
+    fs::rename(&temp_path, file_path)
+}
+
+/// Store key for incognito mode, in which new entries are never written to disk
+pub const INCOGNITO_KEY: &str = "incognito";
+
+/// Store key for a directory to keep the history file in instead of the app data directory
+pub const HISTORY_STORAGE_PATH_KEY: &str = "history_storage_path";
+
+/// Store key for the key source the history file is encrypted with (absent when
+/// it is plaintext), so a missing encrypted file isn't replaced by a plaintext one
+pub const HISTORY_ENCRYPTION_KEY: &str = "history_encryption";
+
+/// Check that `dir` exists (creating it if needed) and that files can be written in it
+pub fn check_writable(dir: &Path) -> Result<(), String> {
+    fs::create_dir_all(dir).map_err(|e| format!("Failed to create {}: {}", dir.display(), e))?;
+    let probe = dir.join(".tambourine-write-test");
+    fs::write(&probe, b"").map_err(|e| format!("{} is not writable: {}", dir.display(), e))?;
+    let _ = fs::remove_file(&probe);
+    Ok(())
+}
+
+/// Directory to keep history in: `custom` if it is set and writable, otherwise
+/// `default` (with a warning when the custom directory is unusable)
+pub fn resolve_history_dir(custom: Option<&Path>, default: PathBuf) -> PathBuf {
+    match custom {
+        Some(dir) => match check_writable(dir) {
+            Ok(()) => dir.to_path_buf(),
+            Err(e) => {
+                log::warn!(
+                    "Custom history location is unavailable ({}); using {}",
+                    e,
+                    default.display()
+                );
+                default
+            }
+        },
+        None => default,
+    }
+}
+
+/// Payload of the "history-changed" event, emitted after every change to the
+/// history so windows can update their lists without refetching them
+#[derive(Debug, Clone, Serialize)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum HistoryChange {
+    /// A new entry, now the newest
+    Added {
+        entry: HistoryEntry,
+    },
+    /// An entry's text, tags or pin changed (including text appended in append mode)
+    Updated {
+        entry: HistoryEntry,
+    },
+    Deleted {
+        id: String,
+    },
+    /// Any number of entries changed (clear, import, prune, unlock, new location):
+    /// reload the list
+    Reset,
+}
+
+/// Outcome of `HistoryStorage::import`
+#[derive(Debug, Clone, Copy, Serialize, PartialEq, Eq)]
+pub struct ImportResult {
+    pub imported: usize,
+    pub skipped: usize,
+}
+
+/// Borrowed form of `HistoryData` holding only the entries that are written to disk
+#[derive(Serialize)]
+struct PersistedHistory<'a> {
+    version: u32,
+    entries: Vec<&'a HistoryEntry>,
+}
+
+/// Serialize `data` for the history file, leaving out incognito entries
+fn persisted_json(data: &HistoryData) -> Result<String, String> {
+    let persisted = PersistedHistory {
+        version: data.version,
+        entries: data.entries.iter().filter(|e| !e.incognito).collect(),
+    };
+    serde_json::to_string_pretty(&persisted)
+        .map_err(|e| format!("Failed to serialize history: {}", e))
+}
+
 /// Storage for dictation history entries
-#[derive(Debug, Serialize, Deserialize, Default)]
+#[derive(Debug, Serialize, Deserialize)]
 struct HistoryData {
+    /// Schema version, upgraded on load by `migrations::migrate_history`
+    #[serde(default)]
+    version: u32,
     entries: Vec<HistoryEntry>,
 }
 
-/// Manages loading and saving of dictation history
+impl Default for HistoryData {
+    fn default() -> Self {
+        Self {
+            version: HISTORY_SCHEMA_VERSION,
+            entries: Vec::new(),
+        }
+    }
+}
+
+/// Manages loading and saving of dictation history.
+///
+/// Safe to share between threads: every change is made under a single write lock
+/// (so readers only see whole entries), and `save` is serialized so a slower save
+/// of an older snapshot can't overwrite a newer one on disk.
+///
+/// In incognito mode new entries are marked `incognito` and only kept in memory,
+/// so they are gone after quitting. With encryption enabled the file is encrypted
+/// (see `history_crypto`); if its key is unavailable the history is locked and
+/// every read and write fails rather than falling back to plaintext.
 pub struct HistoryStorage {
     data: RwLock<HistoryData>,
-    file_path: PathBuf,
+    /// Held while snapshotting and writing the file
+    save_lock: Mutex<()>,
+    /// Changed by `relocate`
+    file_path: RwLock<PathBuf>,
+    incognito: AtomicBool,
+    encryption: RwLock<Encryption>,
+}
+
+/// How the history file is stored
+enum Encryption {
+    /// Plain JSON
+    Off,
+    /// Encrypted with this key
+    On(HistoryKey),
+    /// The file is encrypted and its key isn't available, so the history can't be
+    /// read or written until `unlock`
+    Locked(KeySource),
+}
+
+/// Whether and how the history file is encrypted
+#[derive(Debug, Clone, Copy, Serialize, PartialEq, Eq)]
+pub struct HistoryEncryptionStatus {
+    /// None for a plaintext history
+    pub key_source: Option<KeySource>,
+    /// The key is unavailable, so the history can't be used
+    pub locked: bool,
+}
+
+/// Why a history file couldn't be loaded
+enum LoadError {
+    /// Encrypted, and the key is missing or wrong. The file is left untouched.
+    Locked(KeySource),
+    Unreadable(String),
+}
+
+const LOCKED_ERROR: &str = "History is encrypted and locked";
+
+/// Read the header of an encrypted history file in `dir` (or of its backup if the
+/// file is missing), or None if it isn't encrypted
+pub fn read_encrypted_history(dir: &Path) -> Option<EncryptedHistory> {
+    let file_path = dir.join("history.json");
+    [file_path.clone(), backup_path(&file_path)]
+        .iter()
+        .find_map(|path| fs::read_to_string(path).ok())
+        .and_then(|content| EncryptedHistory::parse(&content))
 }
 
 impl HistoryStorage {
-    /// Create a new history storage with the given app data directory
-    pub fn new(app_data_dir: PathBuf) -> Self {
-        let file_path = app_data_dir.join("history.json");
+    /// Create a new history storage keeping its file in `dir`. If the file is
+    /// encrypted, the storage is locked until `unlock` is called with its key.
+    /// `expected` is the key source the history was last encrypted with: if no
+    /// encrypted file (or backup) can be read, the storage still starts locked
+    /// rather than falling back to a plaintext history.
+    pub fn new(dir: PathBuf, expected: Option<KeySource>) -> Self {
+        let file_path = dir.join("history.json");
 
         // Ensure the directory exists
         if let Some(parent) = file_path.parent() {
@@ -46,63 +363,360 @@ impl HistoryStorage {
         }
 
         // Load existing history or use empty
-        let data = Self::load_from_file(&file_path).unwrap_or_default();
+        let (data, encryption) = match (Self::load_or_recover(&file_path, None), expected) {
+            (Ok(data), None) => (data, Encryption::Off),
+            (Ok(_), Some(source)) => {
+                log::warn!(
+                    "Encrypted history {} is missing or unreadable, keeping it locked",
+                    file_path.display()
+                );
+                (HistoryData::default(), Encryption::Locked(source))
+            }
+            (Err(source), _) => (HistoryData::default(), Encryption::Locked(source)),
+        };
 
         Self {
             data: RwLock::new(data),
-            file_path,
+            save_lock: Mutex::new(()),
+            file_path: RwLock::new(file_path),
+            incognito: AtomicBool::new(false),
+            encryption: RwLock::new(encryption),
         }
     }
 
-    /// Load history from the JSON file
-    fn load_from_file(file_path: &PathBuf) -> Option<HistoryData> {
-        let content = fs::read_to_string(file_path).ok()?;
-        serde_json::from_str(&content).ok()
+    /// Read access to the entries. Fails while the history is locked.
+    fn read_data(&self) -> Result<RwLockReadGuard<'_, HistoryData>, String> {
+        if self.encryption_status()?.locked {
+            return Err(LOCKED_ERROR.to_string());
+        }
+        self.data
+            .read()
+            .map_err(|e| format!("Failed to read history: {}", e))
     }
 
-    /// Save current history to disk
-    fn save(&self) -> Result<(), String> {
-        let data = self
+    /// Write access to the entries. Fails while the history is locked.
+    fn write_data(&self) -> Result<RwLockWriteGuard<'_, HistoryData>, String> {
+        if self.encryption_status()?.locked {
+            return Err(LOCKED_ERROR.to_string());
+        }
+        self.data
+            .write()
+            .map_err(|e| format!("Failed to write history: {}", e))
+    }
+
+    /// Whether the history file is encrypted, and whether it is locked
+    pub fn encryption_status(&self) -> Result<HistoryEncryptionStatus, String> {
+        let encryption = self
+            .encryption
+            .read()
+            .map_err(|e| format!("Failed to read history encryption: {}", e))?;
+        Ok(match &*encryption {
+            Encryption::Off => HistoryEncryptionStatus {
+                key_source: None,
+                locked: false,
+            },
+            Encryption::On(key) => HistoryEncryptionStatus {
+                key_source: Some(key.source()),
+                locked: false,
+            },
+            Encryption::Locked(source) => HistoryEncryptionStatus {
+                key_source: Some(*source),
+                locked: true,
+            },
+        })
+    }
+
+    /// Load a locked history with `key`. Returns the number of entries loaded, or
+    /// an error (leaving the history locked) if the key doesn't open the file.
+    pub fn unlock(&self, key: HistoryKey) -> Result<usize, String> {
+        let _save_guard = self
+            .save_lock
+            .lock()
+            .map_err(|e| format!("Failed to lock history file: {}", e))?;
+        let mut encryption = self
+            .encryption
+            .write()
+            .map_err(|e| format!("Failed to write history encryption: {}", e))?;
+        if !matches!(*encryption, Encryption::Locked(_)) {
+            return Err("History is not locked".to_string());
+        }
+        let data = Self::load_or_recover(&self.file_path()?, Some(&key))
+            .map_err(|_| "Wrong key or passphrase for the history".to_string())?;
+        let count = data.entries.len();
+        *self
             .data
+            .write()
+            .map_err(|e| format!("Failed to write history: {}", e))? = data;
+        *encryption = Encryption::On(key);
+        Ok(count)
+    }
+
+    /// Encrypt the history file with `key`, or store it as plaintext when `None`,
+    /// and rewrite it right away. The backup of the previous form is removed so
+    /// no plaintext copy is left behind (deleted files are not securely erased).
+    pub fn set_encryption(&self, key: Option<HistoryKey>) -> Result<(), String> {
+        {
+            let mut encryption = self
+                .encryption
+                .write()
+                .map_err(|e| format!("Failed to write history encryption: {}", e))?;
+            if matches!(*encryption, Encryption::Locked(_)) {
+                return Err(LOCKED_ERROR.to_string());
+            }
+            *encryption = key.map_or(Encryption::Off, Encryption::On);
+        }
+        self.save()?;
+        self.remove_backup()?;
+        Ok(())
+    }
+
+    /// Serialize `data` for the history file, encrypting it if enabled
+    fn encode(&self, data: &HistoryData) -> Result<String, String> {
+        let json = Zeroizing::new(persisted_json(data)?);
+        let encryption = self
+            .encryption
+            .read()
+            .map_err(|e| format!("Failed to read history encryption: {}", e))?;
+        match &*encryption {
+            Encryption::Off => Ok(json.to_string()),
+            Encryption::On(key) => serde_json::to_string_pretty(&key.encrypt(json.as_bytes())?)
+                .map_err(|e| format!("Failed to serialize history: {}", e)),
+            Encryption::Locked(_) => Err(LOCKED_ERROR.to_string()),
+        }
+    }
+
+    /// The key in use, if the history is encrypted
+    fn current_key(&self) -> Result<Option<HistoryKey>, String> {
+        let encryption = self
+            .encryption
+            .read()
+            .map_err(|e| format!("Failed to read history encryption: {}", e))?;
+        match &*encryption {
+            Encryption::Off => Ok(None),
+            Encryption::On(key) => Ok(Some(key.clone())),
+            Encryption::Locked(_) => Err(LOCKED_ERROR.to_string()),
+        }
+    }
+
+    /// Whether new entries are kept in memory only
+    pub fn is_incognito(&self) -> bool {
+        self.incognito.load(Ordering::SeqCst)
+    }
+
+    /// Turn incognito mode on or off. Entries added while it was on stay in memory
+    /// (and off disk) for the rest of the session either way.
+    pub fn set_incognito(&self, incognito: bool) {
+        self.incognito.store(incognito, Ordering::SeqCst);
+    }
+
+    /// Path of the history file currently in use
+    pub fn file_path(&self) -> Result<PathBuf, String> {
+        self.file_path
             .read()
-            .map_err(|e| format!("Failed to read history: {}", e))?;
+            .map(|path| path.clone())
+            .map_err(|e| format!("Failed to read history location: {}", e))
+    }
+
+    /// Keep history in `dir` from now on. With `migrate`, the current entries are
+    /// written there (an existing history file in `dir` becomes its backup);
+    /// otherwise whatever history `dir` already holds is loaded, which must be
+    /// readable with the current key. The old file is left in place. Returns the
+    /// number of entries now in history.
+    pub fn relocate(&self, dir: &Path, migrate: bool) -> Result<usize, String> {
+        check_writable(dir)?;
+        let new_path = dir.join("history.json");
+
+        let _save_guard = self
+            .save_lock
+            .lock()
+            .map_err(|e| format!("Failed to lock history file: {}", e))?;
+        let mut data = self.write_data()?;
+        if migrate {
+            let content = self.encode(&data)?;
+            write_atomically(&new_path, content.as_bytes())
+                .map_err(|e| format!("Failed to write history file: {}", e))?;
+        } else {
+            *data =
+                Self::load_or_recover(&new_path, self.current_key()?.as_ref()).map_err(|_| {
+                    format!("History in {} is encrypted with another key", dir.display())
+                })?;
+        }
+        *self
+            .file_path
+            .write()
+            .map_err(|e| format!("Failed to write history location: {}", e))? = new_path;
+        Ok(data.entries.len())
+    }
+
+    /// Load history from the file, decrypting it with `key` if it is encrypted and
+    /// upgrading it from older schema versions
+    fn load_from_file(
+        file_path: &Path,
+        key: Option<&HistoryKey>,
+    ) -> Result<HistoryData, LoadError> {
+        let content =
+            fs::read_to_string(file_path).map_err(|e| LoadError::Unreadable(e.to_string()))?;
+        let plaintext = match EncryptedHistory::parse(&content) {
+            Some(envelope) => {
+                let key = key.ok_or(LoadError::Locked(envelope.key_source))?;
+                let plaintext = key.decrypt(&envelope).map_err(|e| {
+                    log::warn!("Failed to decrypt {}: {}", file_path.display(), e);
+                    LoadError::Locked(envelope.key_source)
+                })?;
+                Zeroizing::new(
+                    String::from_utf8(plaintext.to_vec())
+                        .map_err(|e| LoadError::Unreadable(e.to_string()))?,
+                )
+            }
+            None => Zeroizing::new(content),
+        };
+        let mut value: serde_json::Value =
+            serde_json::from_str(&plaintext).map_err(|e| LoadError::Unreadable(e.to_string()))?;
+        migrate_history(&mut value).map_err(LoadError::Unreadable)?;
+        let mut data: HistoryData =
+            serde_json::from_value(value).map_err(|e| LoadError::Unreadable(e.to_string()))?;
+        backfill_counts(&mut data.entries);
+        Ok(data)
+    }
+
+    /// Load the history file, falling back to the backup of the previous save if it
+    /// is missing or unreadable. A corrupt file is set aside (not overwritten by the
+    /// next save) so it can still be inspected. An encrypted file that can't be
+    /// opened is left alone and its key source returned instead.
+    fn load_or_recover(
+        file_path: &Path,
+        key: Option<&HistoryKey>,
+    ) -> Result<HistoryData, KeySource> {
+        let error = match Self::load_from_file(file_path, key) {
+            Ok(data) => return Ok(data),
+            Err(LoadError::Locked(source)) => return Err(source),
+            Err(LoadError::Unreadable(e)) => e,
+        };
+        if file_path.exists() {
+            log::error!(
+                "History file {} is unreadable: {}",
+                file_path.display(),
+                error
+            );
+            let corrupt_path = file_path.with_extension("json.corrupt");
+            if let Err(e) = fs::rename(file_path, &corrupt_path) {
+                log::warn!("Failed to set aside corrupt history file: {}", e);
+            }
+        }
+
+        let backup_path = backup_path(file_path);
+        match Self::load_from_file(&backup_path, key) {
+            Ok(data) => {
+                log::warn!(
+                    "Recovered {} history entries from {}",
+                    data.entries.len(),
+                    backup_path.display()
+                );
+                Ok(data)
+            }
+            Err(LoadError::Locked(source)) => Err(source),
+            // First run, or nothing recoverable
+            Err(LoadError::Unreadable(_)) => Ok(HistoryData::default()),
+        }
+    }
+
+    /// Delete the backup of the previous save
+    fn remove_backup(&self) -> Result<(), String> {
+        if let Err(e) = fs::remove_file(backup_path(&self.file_path()?)) {
+            if e.kind() != std::io::ErrorKind::NotFound {
+                log::warn!("Failed to remove history backup: {}", e);
+            }
+        }
+        Ok(())
+    }
+
+    /// Save current history to disk
+    fn save(&self) -> Result<(), String> {
+        // Snapshot and write under one lock, so saves land on disk in the order
+        // their snapshots were taken
+        let _save_guard = self
+            .save_lock
+            .lock()
+            .map_err(|e| format!("Failed to lock history file: {}", e))?;
 
-        let content = serde_json::to_string_pretty(&*data)
-            .map_err(|e| format!("Failed to serialize history: {}", e))?;
+        let content = {
+            let data = self.read_data()?;
+            self.encode(&data)?
+        };
 
-        fs::write(&self.file_path, content)
+        write_atomically(&self.file_path()?, content.as_bytes())
             .map_err(|e| format!("Failed to write history file: {}", e))?;
 
         Ok(())
     }
 
-    /// Add a new entry to the history
-    pub fn add_entry(&self, text: String) -> Result<HistoryEntry, String> {
-        let entry = HistoryEntry::new(text);
+    /// Add a new entry to the history (in memory only in incognito mode)
+    pub fn add_entry(
+        &self,
+        text: String,
+        language: Option<String>,
+        audio_duration_ms: Option<u64>,
+    ) -> Result<HistoryEntry, String> {
+        let mut entry = HistoryEntry::new(text, language, audio_duration_ms);
+        entry.incognito = self.is_incognito();
         {
-            let mut data = self
-                .data
-                .write()
-                .map_err(|e| format!("Failed to write history: {}", e))?;
+            let mut data = self.write_data()?;
 
             // Add to the beginning (newest first)
             data.entries.insert(0, entry.clone());
+        }
+        if !entry.incognito {
+            self.save()?;
+        }
+        Ok(entry)
+    }
+
+    /// Append `text` (separated by a space) to the newest entry if its last chunk
+    /// arrived within `window_ms` before `now`. Pinned entries are never extended.
+    /// Returns `None` without changing anything when there is no entry to append to.
+    pub fn append_to_latest(
+        &self,
+        text: &str,
+        audio_duration_ms: Option<u64>,
+        window_ms: u64,
+        now: DateTime<Utc>,
+    ) -> Result<Option<HistoryEntry>, String> {
+        let window = chrono::Duration::milliseconds(i64::try_from(window_ms).unwrap_or(i64::MAX));
+        let entry = {
+            let mut data = self.write_data()?;
 
-            // Limit to 500 entries
-            if data.entries.len() > 500 {
-                data.entries.truncate(500);
+            let Some(latest) = data.entries.first_mut() else {
+                return Ok(None);
+            };
+            let last_chunk_at = latest.appended_at.unwrap_or(latest.timestamp);
+            // Incognito dictation must not end up in a saved entry
+            if latest.pinned
+                || (self.is_incognito() && !latest.incognito)
+                || last_chunk_at > now
+                || now - last_chunk_at > window
+            {
+                return Ok(None);
             }
+
+            latest.text = format!("{} {}", latest.text.trim_end(), text.trim_start());
+            latest.fill_counts();
+            latest.audio_duration_ms = match (latest.audio_duration_ms, audio_duration_ms) {
+                (Some(a), Some(b)) => Some(a + b),
+                (a, b) => a.or(b),
+            };
+            latest.appended_at = Some(now);
+            latest.clone()
+        };
+
+        if !entry.incognito {
+            self.save()?;
         }
-        self.save()?;
-        Ok(entry)
+        Ok(Some(entry))
     }
 
     /// Get all history entries (newest first), optionally limited
     pub fn get_all(&self, limit: Option<usize>) -> Result<Vec<HistoryEntry>, String> {
-        let data = self
-            .data
-            .read()
-            .map_err(|e| format!("Failed to read history: {}", e))?;
+        let data = self.read_data()?;
 
         let entries = match limit {
             Some(n) => data.entries.iter().take(n).cloned().collect(),
@@ -112,13 +726,238 @@ impl HistoryStorage {
         Ok(entries)
     }
 
+    /// Get a single entry by ID
+    pub fn get(&self, id: &str) -> Result<Option<HistoryEntry>, String> {
+        let data = self.read_data()?;
+
+        Ok(data.entries.iter().find(|e| e.id == id).cloned())
+    }
+
+    /// Compute totals and averages over all entries
+    pub fn stats(&self) -> Result<HistoryStats, String> {
+        let data = self.read_data()?;
+
+        let total_entries = data.entries.len();
+        let total_words = data.entries.iter().map(|e| e.word_count).sum();
+        let total_chars = data.entries.iter().map(|e| e.char_count).sum();
+        let total_audio_duration_ms = data
+            .entries
+            .iter()
+            .filter_map(|e| e.audio_duration_ms)
+            .sum();
+        let average = |total: usize| {
+            if total_entries == 0 {
+                0.0
+            } else {
+                total as f64 / total_entries as f64
+            }
+        };
+
+        Ok(HistoryStats {
+            total_entries,
+            total_words,
+            total_chars,
+            total_audio_duration_ms,
+            average_words: average(total_words),
+            average_chars: average(total_chars),
+        })
+    }
+
+    /// Get history entries with pinned entries first, each group newest first
+    pub fn get_all_pinned_first(&self, limit: Option<usize>) -> Result<Vec<HistoryEntry>, String> {
+        let mut entries = self.get_all(None)?;
+        // Stable sort keeps recency order within the pinned and unpinned groups
+        entries.sort_by_key(|e| !e.pinned);
+        if let Some(n) = limit {
+            entries.truncate(n);
+        }
+        Ok(entries)
+    }
+
+    /// Get the entry for a paste slot: the `slot`th (1-based) newest entry, or the
+    /// `slot`th in `get_all_pinned_first` order when `pinned_first` is set
+    pub fn get_slot(
+        &self,
+        slot: usize,
+        pinned_first: bool,
+    ) -> Result<Option<HistoryEntry>, String> {
+        let Some(index) = slot.checked_sub(1) else {
+            return Ok(None);
+        };
+        let entries = if pinned_first {
+            self.get_all_pinned_first(Some(slot))?
+        } else {
+            self.get_all(Some(slot))?
+        };
+        Ok(entries.into_iter().nth(index))
+    }
+
+    /// Search entries (newest first) whose text contains every whitespace-separated
+    /// token of the query, case-insensitively. An empty query matches everything.
+    pub fn search(&self, query: &str, limit: Option<usize>) -> Result<Vec<HistoryEntry>, String> {
+        let tokens: Vec<String> = query.split_whitespace().map(str::to_lowercase).collect();
+
+        let data = self.read_data()?;
+
+        let matches = data.entries.iter().filter(|entry| {
+            let text = entry.text.to_lowercase();
+            tokens.iter().all(|token| text.contains(token.as_str()))
+        });
+
+        let entries = match limit {
+            Some(n) => matches.take(n).cloned().collect(),
+            None => matches.cloned().collect(),
+        };
+
+        Ok(entries)
+    }
+
+    /// Get entries with `start <= timestamp <= end`, newest first, capped at `limit`
+    pub fn get_range(
+        &self,
+        start: DateTime<Utc>,
+        end: DateTime<Utc>,
+        limit: Option<usize>,
+    ) -> Result<Vec<HistoryEntry>, String> {
+        if start > end {
+            return Err(format!(
+                "Range start {} is after its end {}",
+                start.to_rfc3339(),
+                end.to_rfc3339()
+            ));
+        }
+
+        let data = self.read_data()?;
+
+        let mut entries: Vec<HistoryEntry> = data
+            .entries
+            .iter()
+            .filter(|e| e.timestamp >= start && e.timestamp <= end)
+            .cloned()
+            .collect();
+        // Imported entries are not guaranteed to be in timestamp order
+        entries.sort_by_key(|e| std::cmp::Reverse(e.timestamp));
+        if let Some(n) = limit {
+            entries.truncate(n);
+        }
+
+        Ok(entries)
+    }
+
+    /// Export all entries to `path`, newest first unless `oldest_first` is set.
+    /// Returns the number of entries written.
+    pub fn export(
+        &self,
+        format: ExportFormat,
+        path: &Path,
+        oldest_first: bool,
+    ) -> Result<usize, String> {
+        // Snapshot so the lock isn't held while serializing and writing the file
+        let mut entries = self.get_all(None)?;
+        if oldest_first {
+            entries.reverse();
+        }
+
+        let content = match format {
+            ExportFormat::Json => serde_json::to_string_pretty(&entries)
+                .map_err(|e| format!("Failed to serialize history: {}", e))?,
+            ExportFormat::Text => entries
+                .iter()
+                .map(|entry| entry.text.as_str())
+                .collect::<Vec<_>>()
+                .join("\n\n"),
+        };
+
+        fs::write(path, content)
+            .map_err(|e| format!("Failed to write export to {}: {}", path.display(), e))?;
+
+        Ok(entries.len())
+    }
+
+    /// Merge entries from a JSON file written by `export`.
+    ///
+    /// With `dedupe`, entries whose text and timestamp match an existing entry
+    /// are skipped. The whole file is validated before anything is merged.
+    pub fn import(&self, path: &Path, dedupe: bool) -> Result<ImportResult, String> {
+        let content = fs::read_to_string(path)
+            .map_err(|e| format!("Failed to read {}: {}", path.display(), e))?;
+        let mut incoming: Vec<HistoryEntry> = serde_json::from_str(&content)
+            .map_err(|e| format!("Invalid history export {}: {}", path.display(), e))?;
+        backfill_counts(&mut incoming);
+
+        let mut result = ImportResult {
+            imported: 0,
+            skipped: 0,
+        };
+        {
+            let mut data = self.write_data()?;
+
+            for mut entry in incoming {
+                let is_duplicate = data
+                    .entries
+                    .iter()
+                    .any(|e| e.text == entry.text && e.timestamp == entry.timestamp);
+                if dedupe && is_duplicate {
+                    result.skipped += 1;
+                    continue;
+                }
+
+                // Keep IDs unique so delete/pin still target a single entry
+                if data.entries.iter().any(|e| e.id == entry.id) {
+                    entry.id = Uuid::new_v4().to_string();
+                }
+                data.entries.push(entry);
+                result.imported += 1;
+            }
+
+            // Stable sort keeps insertion order for entries with equal timestamps
+            data.entries.sort_by_key(|e| std::cmp::Reverse(e.timestamp));
+        }
+
+        if result.imported > 0 {
+            self.save()?;
+        }
+
+        Ok(result)
+    }
+
+    /// Remove unpinned entries beyond the size/age `limits`.
+    /// Returns the number of entries removed.
+    pub fn prune(&self, limits: &HistoryLimits) -> Result<usize, String> {
+        let removed = {
+            let mut data = self.write_data()?;
+            prune_entries(&mut data.entries, limits, Utc::now())
+        };
+
+        if removed > 0 {
+            self.save()?;
+        }
+
+        Ok(removed)
+    }
+
+    /// Delete unpinned entries from before `cutoff`. Returns the number removed.
+    pub fn clear_before(&self, cutoff: DateTime<Utc>) -> Result<usize, String> {
+        let removed = {
+            let mut data = self.write_data()?;
+            let initial_len = data.entries.len();
+            data.entries.retain(|e| e.pinned || e.timestamp >= cutoff);
+            initial_len - data.entries.len()
+        };
+
+        if removed > 0 {
+            self.save()?;
+            // The backup still holds the removed entries
+            self.remove_backup()?;
+        }
+
+        Ok(removed)
+    }
+
     /// Delete an entry by ID
     pub fn delete(&self, id: &str) -> Result<bool, String> {
         let deleted = {
-            let mut data = self
-                .data
-                .write()
-                .map_err(|e| format!("Failed to write history: {}", e))?;
+            let mut data = self.write_data()?;
 
             let initial_len = data.entries.len();
             data.entries.retain(|e| e.id != id);
@@ -132,15 +971,113 @@ impl HistoryStorage {
         Ok(deleted)
     }
 
-    /// Clear all history
-    pub fn clear(&self) -> Result<(), String> {
+    /// Apply `change` to the entry with the given ID, save, and return the result.
+    /// Errors if no entry has the given ID.
+    fn modify(
+        &self,
+        id: &str,
+        change: impl FnOnce(&mut HistoryEntry),
+    ) -> Result<HistoryEntry, String> {
+        let entry = {
+            let mut data = self.write_data()?;
+
+            // Look up by ID under the write lock so entries added meanwhile can't shift it
+            let entry = data
+                .entries
+                .iter_mut()
+                .find(|e| e.id == id)
+                .ok_or_else(|| format!("History entry not found: {}", id))?;
+            change(entry);
+            entry.clone()
+        };
+
+        if !entry.incognito {
+            self.save()?;
+        }
+        Ok(entry)
+    }
+
+    /// Replace an entry's text, keeping its timestamp and setting `edited_at`.
+    /// Errors if no entry has the given ID.
+    pub fn update(&self, id: &str, text: String) -> Result<HistoryEntry, String> {
+        self.modify(id, |entry| {
+            if entry.text != text {
+                entry.text = text;
+                entry.fill_counts();
+                entry.edited_at = Some(Utc::now());
+            }
+        })
+    }
+
+    /// Add a tag to an entry. Surrounding whitespace is trimmed and a tag the
+    /// entry already has (in any case) is not added again.
+    pub fn add_tag(&self, id: &str, tag: &str) -> Result<HistoryEntry, String> {
+        let tag = tag.trim();
+        if tag.is_empty() {
+            return Err("Tag cannot be empty".to_string());
+        }
+        self.modify(id, |entry| {
+            if !entry.has_tag(tag) {
+                entry.tags.push(tag.to_string());
+            }
+        })
+    }
+
+    /// Remove a tag (matched case-insensitively) from an entry
+    pub fn remove_tag(&self, id: &str, tag: &str) -> Result<HistoryEntry, String> {
+        let tag = tag.trim();
+        self.modify(id, |entry| {
+            entry.tags.retain(|t| !t.eq_ignore_ascii_case(tag));
+        })
+    }
+
+    /// Get entries (newest first) carrying `tag`, ignoring case
+    pub fn get_by_tag(&self, tag: &str) -> Result<Vec<HistoryEntry>, String> {
+        let tag = tag.trim();
+        let data = self.read_data()?;
+
+        Ok(data
+            .entries
+            .iter()
+            .filter(|e| e.has_tag(tag))
+            .cloned()
+            .collect())
+    }
+
+    /// Pin or unpin an entry by ID
+    pub fn set_pinned(&self, id: &str, pinned: bool) -> Result<bool, String> {
+        let updated = {
+            let mut data = self.write_data()?;
+
+            match data.entries.iter_mut().find(|e| e.id == id) {
+                Some(entry) => {
+                    entry.pinned = pinned;
+                    true
+                }
+                None => false,
+            }
+        };
+
+        if updated {
+            self.save()?;
+        }
+
+        Ok(updated)
+    }
+
+    /// Clear history, keeping pinned entries unless `include_pinned` is set
+    pub fn clear(&self, include_pinned: bool) -> Result<(), String> {
         {
-            let mut data = self
-                .data
-                .write()
-                .map_err(|e| format!("Failed to write history: {}", e))?;
-            data.entries.clear();
+            let mut data = self.write_data()?;
+            if include_pinned {
+                data.entries.clear();
+            } else {
+                data.entries.retain(|e| e.pinned);
+            }
         }
-        self.save()
+        self.save()?;
+        // The backup still holds the cleared entries
+        self.remove_backup()?;
+        Ok(())
     }
 }
@@ -0,0 +1,335 @@
+//! Optional encryption at rest for the history file.
+//!
+//! Crypto choices, kept deliberately small so they are easy to audit:
+//! - Cipher: XChaCha20-Poly1305 (RustCrypto `chacha20poly1305`), an AEAD, so a
+//!   modified or truncated file fails to decrypt instead of yielding garbage. Its
+//!   192-bit nonce is safe to pick at random, and a fresh one is used for every save.
+//! - Keychain keys: 256 random bits from the OS RNG, stored base64-encoded in the
+//!   OS credential store (macOS Keychain, Windows Credential Manager, Secret Service
+//!   on Linux) under `KEYCHAIN_SERVICE` / `KEYCHAIN_ACCOUNT`.
+//! - Passphrase keys: Argon2id over the passphrase with a random 128-bit salt. The
+//!   salt and cost parameters are stored in the file so they can be raised later
+//!   without breaking older files. The passphrase itself is never stored.
+//! - The envelope's fixed fields are bound to the ciphertext as associated data.
+//!
+//! Without the key the history stays locked: nothing is read or written, and the
+//! encrypted file is never replaced by plaintext.
+
+use argon2::{Algorithm, Argon2, Params, Version};
+use base64::engine::general_purpose::STANDARD as BASE64;
+use base64::Engine;
+use chacha20poly1305::aead::rand_core::RngCore;
+use chacha20poly1305::aead::{Aead, AeadCore, KeyInit, OsRng, Payload};
+use chacha20poly1305::{XChaCha20Poly1305, XNonce};
+use serde::{Deserialize, Serialize};
+use zeroize::Zeroizing;
+
+/// Value of `EncryptedHistory::format`, used to recognize encrypted history files
+pub const ENCRYPTED_HISTORY_FORMAT: &str = "tambourine-encrypted-history";
+
+/// Version of the envelope layout and algorithms
+pub const ENCRYPTION_VERSION: u32 = 1;
+
+/// Cipher named in the envelope
+pub const CIPHER: &str = "XChaCha20-Poly1305";
+
+/// OS credential store service holding the keychain key
+pub const KEYCHAIN_SERVICE: &str = "Tambourine";
+
+/// OS credential store account holding the keychain key
+pub const KEYCHAIN_ACCOUNT: &str = "history-encryption-key";
+
+/// Shortest passphrase accepted for a new key
+pub const MIN_PASSPHRASE_CHARS: usize = 8;
+
+const KEY_LEN: usize = 32;
+const SALT_LEN: usize = 16;
+
+/// Where the key for an encrypted history comes from
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum KeySource {
+    /// A random key kept in the OS credential store
+    Keychain,
+    /// A key derived from a passphrase entered each session
+    Passphrase,
+}
+
+/// Argon2id parameters for passphrase keys
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+pub struct KdfParams {
+    /// Memory in KiB
+    pub m_cost: u32,
+    pub t_cost: u32,
+    pub p_cost: u32,
+}
+
+impl Default for KdfParams {
+    /// The argon2 crate's defaults, which follow the OWASP recommendation
+    /// (19 MiB, 2 passes, 1 lane)
+    fn default() -> Self {
+        Self {
+            m_cost: Params::DEFAULT_M_COST,
+            t_cost: Params::DEFAULT_T_COST,
+            p_cost: Params::DEFAULT_P_COST,
+        }
+    }
+}
+
+/// Salt and parameters a passphrase key was derived with
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+pub struct KdfInfo {
+    /// Always "argon2id"
+    pub algorithm: String,
+    /// Base64
+    pub salt: String,
+    #[serde(flatten)]
+    pub params: KdfParams,
+}
+
+/// An encrypted history file
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+pub struct EncryptedHistory {
+    pub format: String,
+    pub version: u32,
+    pub cipher: String,
+    pub key_source: KeySource,
+    /// Set for passphrase keys
+    #[serde(default)]
+    pub kdf: Option<KdfInfo>,
+    /// Base64
+    pub nonce: String,
+    /// Base64 ciphertext of the plaintext history JSON, including the Poly1305 tag
+    pub ciphertext: String,
+}
+
+impl EncryptedHistory {
+    /// Parse `content` if it is an encrypted history file
+    pub fn parse(content: &str) -> Option<Self> {
+        serde_json::from_str::<Self>(content)
+            .ok()
+            .filter(|envelope| envelope.format == ENCRYPTED_HISTORY_FORMAT)
+    }
+
+    /// Associated data binding the header fields to the ciphertext
+    fn associated_data(&self) -> String {
+        let kdf = self
+            .kdf
+            .as_ref()
+            .map(|kdf| {
+                format!(
+                    "{}:{}:{}:{}:{}",
+                    kdf.algorithm,
+                    kdf.salt,
+                    kdf.params.m_cost,
+                    kdf.params.t_cost,
+                    kdf.params.p_cost
+                )
+            })
+            .unwrap_or_default();
+        format!(
+            "{}/{}/{}/{:?}/{}",
+            self.format, self.version, self.cipher, self.key_source, kdf
+        )
+    }
+}
+
+/// A 256-bit history key, wiped from memory when dropped
+#[derive(Clone)]
+pub struct HistoryKey {
+    key: Zeroizing<[u8; KEY_LEN]>,
+    source: KeySource,
+    /// Set for passphrase keys
+    kdf: Option<KdfInfo>,
+}
+
+impl std::fmt::Debug for HistoryKey {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        // Never print the key itself
+        f.debug_struct("HistoryKey")
+            .field("source", &self.source)
+            .finish_non_exhaustive()
+    }
+}
+
+impl HistoryKey {
+    pub fn source(&self) -> KeySource {
+        self.source
+    }
+
+    /// A new random key for the OS credential store
+    pub fn generate() -> Self {
+        let mut key = Zeroizing::new([0u8; KEY_LEN]);
+        OsRng.fill_bytes(key.as_mut());
+        Self {
+            key,
+            source: KeySource::Keychain,
+            kdf: None,
+        }
+    }
+
+    /// Derive a key from `passphrase` with a fresh random salt
+    pub fn new_from_passphrase(passphrase: &str, params: KdfParams) -> Result<Self, String> {
+        if passphrase.chars().count() < MIN_PASSPHRASE_CHARS {
+            return Err(format!(
+                "Passphrase must be at least {} characters",
+                MIN_PASSPHRASE_CHARS
+            ));
+        }
+        let mut salt = [0u8; SALT_LEN];
+        OsRng.fill_bytes(&mut salt);
+        Self::derive(
+            passphrase,
+            KdfInfo {
+                algorithm: "argon2id".to_string(),
+                salt: BASE64.encode(salt),
+                params,
+            },
+        )
+    }
+
+    /// Derive the key for an existing file from `passphrase` and the file's salt
+    pub fn from_passphrase(passphrase: &str, envelope: &EncryptedHistory) -> Result<Self, String> {
+        match &envelope.kdf {
+            Some(kdf) if envelope.key_source == KeySource::Passphrase => {
+                Self::derive(passphrase, kdf.clone())
+            }
+            _ => Err("History is not encrypted with a passphrase".to_string()),
+        }
+    }
+
+    fn derive(passphrase: &str, kdf: KdfInfo) -> Result<Self, String> {
+        if kdf.algorithm != "argon2id" {
+            return Err(format!("Unsupported key derivation: {}", kdf.algorithm));
+        }
+        let salt = BASE64
+            .decode(&kdf.salt)
+            .map_err(|e| format!("Invalid key derivation salt: {}", e))?;
+        let params = Params::new(
+            kdf.params.m_cost,
+            kdf.params.t_cost,
+            kdf.params.p_cost,
+            Some(KEY_LEN),
+        )
+        .map_err(|e| format!("Invalid key derivation parameters: {}", e))?;
+        let mut key = Zeroizing::new([0u8; KEY_LEN]);
+        Argon2::new(Algorithm::Argon2id, Version::V0x13, params)
+            .hash_password_into(passphrase.as_bytes(), &salt, key.as_mut())
+            .map_err(|e| format!("Failed to derive history key: {}", e))?;
+        Ok(Self {
+            key,
+            source: KeySource::Passphrase,
+            kdf: Some(kdf),
+        })
+    }
+
+    /// Encode a keychain key for the credential store
+    fn to_base64(&self) -> Zeroizing<String> {
+        Zeroizing::new(BASE64.encode(self.key.as_ref()))
+    }
+
+    /// Decode a keychain key read from the credential store
+    fn from_base64(encoded: &str) -> Result<Self, String> {
+        let bytes = Zeroizing::new(
+            BASE64
+                .decode(encoded.trim())
+                .map_err(|e| format!("Invalid history key in keychain: {}", e))?,
+        );
+        let key: [u8; KEY_LEN] = bytes
+            .as_slice()
+            .try_into()
+            .map_err(|_| "History key in keychain has the wrong length".to_string())?;
+        Ok(Self {
+            key: Zeroizing::new(key),
+            source: KeySource::Keychain,
+            kdf: None,
+        })
+    }
+
+    fn cipher(&self) -> XChaCha20Poly1305 {
+        XChaCha20Poly1305::new(self.key.as_ref().into())
+    }
+
+    /// Encrypt `plaintext` with a fresh nonce
+    pub fn encrypt(&self, plaintext: &[u8]) -> Result<EncryptedHistory, String> {
+        let nonce = XChaCha20Poly1305::generate_nonce(&mut OsRng);
+        let mut envelope = EncryptedHistory {
+            format: ENCRYPTED_HISTORY_FORMAT.to_string(),
+            version: ENCRYPTION_VERSION,
+            cipher: CIPHER.to_string(),
+            key_source: self.source,
+            kdf: self.kdf.clone(),
+            nonce: BASE64.encode(nonce),
+            ciphertext: String::new(),
+        };
+        let aad = envelope.associated_data();
+        let ciphertext = self
+            .cipher()
+            .encrypt(
+                &nonce,
+                Payload {
+                    msg: plaintext,
+                    aad: aad.as_bytes(),
+                },
+            )
+            .map_err(|_| "Failed to encrypt history".to_string())?;
+        envelope.ciphertext = BASE64.encode(ciphertext);
+        Ok(envelope)
+    }
+
+    /// Decrypt `envelope`. Fails for the wrong key and for any tampering.
+    pub fn decrypt(&self, envelope: &EncryptedHistory) -> Result<Zeroizing<Vec<u8>>, String> {
+        if envelope.version != ENCRYPTION_VERSION || envelope.cipher != CIPHER {
+            return Err(format!(
+                "Unsupported history encryption: {} version {}",
+                envelope.cipher, envelope.version
+            ));
+        }
+        let nonce = BASE64
+            .decode(&envelope.nonce)
+            .map_err(|e| format!("Invalid history nonce: {}", e))?;
+        if nonce.len() != 24 {
+            return Err("Invalid history nonce length".to_string());
+        }
+        let ciphertext = BASE64
+            .decode(&envelope.ciphertext)
+            .map_err(|e| format!("Invalid history ciphertext: {}", e))?;
+        let aad = envelope.associated_data();
+        self.cipher()
+            .decrypt(
+                XNonce::from_slice(&nonce),
+                Payload {
+                    msg: &ciphertext,
+                    aad: aad.as_bytes(),
+                },
+            )
+            .map(Zeroizing::new)
+            .map_err(|_| "Wrong key or damaged history file".to_string())
+    }
+}
+
+/// Read the history key from the OS credential store, creating and storing a new
+/// one when `create` is set and there is none yet
+#[cfg(desktop)]
+pub fn keychain_key(create: bool) -> Result<HistoryKey, String> {
+    let entry = keyring::Entry::new(KEYCHAIN_SERVICE, KEYCHAIN_ACCOUNT)
+        .map_err(|e| format!("Failed to open keychain: {}", e))?;
+    match entry.get_password() {
+        Ok(encoded) => HistoryKey::from_base64(&Zeroizing::new(encoded)),
+        Err(keyring::Error::NoEntry) if create => {
+            let key = HistoryKey::generate();
+            entry
+                .set_password(&key.to_base64())
+                .map_err(|e| format!("Failed to store history key in keychain: {}", e))?;
+            log::info!("Stored a new history key in the keychain");
+            Ok(key)
+        }
+        Err(keyring::Error::NoEntry) => Err("No history key in the keychain".to_string()),
+        Err(e) => Err(format!("Failed to read history key from keychain: {}", e)),
+    }
+}
+
+#[cfg(not(desktop))]
+pub fn keychain_key(_create: bool) -> Result<HistoryKey, String> {
+    Err("Keychain is not available on this platform".to_string())
+}
@@ -0,0 +1,160 @@
+//! Input gain and noise gate for captured microphone audio.
+//!
+//! The server applies the same processing to the live stream (see
+//! `server/processors/input_gain.py`); this copy is used for saved recordings
+//! and for previewing levels while calibrating.
+
+use serde::Serialize;
+use tauri::AppHandle;
+
+/// Store key for the linear multiplier applied to captured samples
+pub const INPUT_GAIN_KEY: &str = "input_gain";
+
+/// Store key for the RMS level (0.0-1.0 of full scale) below which audio is silenced
+pub const NOISE_GATE_THRESHOLD_KEY: &str = "noise_gate_threshold";
+
+/// Default input gain (unchanged)
+pub const DEFAULT_INPUT_GAIN: f64 = 1.0;
+
+/// Highest allowed input gain. Boosting further mostly amplifies noise and
+/// clips speech, which degrades transcription more than a quiet signal does.
+pub const MAX_INPUT_GAIN: f64 = 8.0;
+
+/// Default noise gate threshold (gate off)
+pub const DEFAULT_NOISE_GATE_THRESHOLD: f64 = 0.0;
+
+/// Samples per gated block: 10ms at 16kHz. Gating whole blocks by their RMS
+/// avoids chopping up individual waveform cycles.
+pub const GATE_BLOCK_SAMPLES: usize = 160;
+
+/// Check that an input gain is within 0.0..=MAX_INPUT_GAIN
+pub fn validate_input_gain(gain: f64) -> Result<(), String> {
+    if (0.0..=MAX_INPUT_GAIN).contains(&gain) {
+        Ok(())
+    } else {
+        Err(format!(
+            "Input gain must be between 0.0 and {}, got {}",
+            MAX_INPUT_GAIN, gain
+        ))
+    }
+}
+
+/// Check that a noise gate threshold is within 0.0..=1.0
+pub fn validate_noise_gate_threshold(threshold: f64) -> Result<(), String> {
+    if (0.0..=1.0).contains(&threshold) {
+        Ok(())
+    } else {
+        Err(format!(
+            "Noise gate threshold must be between 0.0 and 1.0, got {}",
+            threshold
+        ))
+    }
+}
+
+/// Peak and RMS level of a block of samples, as fractions of full scale
+#[derive(Debug, Clone, Copy, Default, Serialize, PartialEq)]
+pub struct InputLevels {
+    pub peak: f64,
+    pub rms: f64,
+}
+
+impl InputLevels {
+    /// Measure `samples` (silence for an empty slice)
+    pub fn measure(samples: &[i16]) -> Self {
+        if samples.is_empty() {
+            return Self::default();
+        }
+        let full_scale = f64::from(i16::MAX);
+        let peak = samples
+            .iter()
+            .map(|&s| f64::from(s).abs())
+            .fold(0.0, f64::max);
+        let sum_of_squares: f64 = samples.iter().map(|&s| f64::from(s).powi(2)).sum();
+        let rms = (sum_of_squares / samples.len() as f64).sqrt();
+        Self {
+            peak: (peak / full_scale).min(1.0),
+            rms: (rms / full_scale).min(1.0),
+        }
+    }
+}
+
+/// Gain and noise gate settings
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct InputProcessing {
+    pub gain: f64,
+    pub gate_threshold: f64,
+}
+
+impl Default for InputProcessing {
+    fn default() -> Self {
+        Self {
+            gain: DEFAULT_INPUT_GAIN,
+            gate_threshold: DEFAULT_NOISE_GATE_THRESHOLD,
+        }
+    }
+}
+
+impl InputProcessing {
+    /// Read the gain and threshold from the store, clamping them to their valid ranges
+    pub fn from_store(app: &AppHandle) -> Self {
+        let gain: f64 = crate::get_setting_from_store(app, INPUT_GAIN_KEY, DEFAULT_INPUT_GAIN);
+        let gate_threshold: f64 = crate::get_setting_from_store(
+            app,
+            NOISE_GATE_THRESHOLD_KEY,
+            DEFAULT_NOISE_GATE_THRESHOLD,
+        );
+        Self {
+            gain: if gain.is_nan() {
+                DEFAULT_INPUT_GAIN
+            } else {
+                gain.clamp(0.0, MAX_INPUT_GAIN)
+            },
+            gate_threshold: if gate_threshold.is_nan() {
+                DEFAULT_NOISE_GATE_THRESHOLD
+            } else {
+                gate_threshold.clamp(0.0, 1.0)
+            },
+        }
+    }
+
+    /// Apply gain (saturating at full scale instead of wrapping), then silence
+    /// each GATE_BLOCK_SAMPLES block whose RMS is below the gate threshold
+    pub fn apply(&self, samples: &mut [i16]) {
+        if self.gain != 1.0 {
+            for sample in samples.iter_mut() {
+                *sample = (f64::from(*sample) * self.gain)
+                    .round()
+                    .clamp(f64::from(i16::MIN), f64::from(i16::MAX))
+                    as i16;
+            }
+        }
+        if self.gate_threshold > 0.0 {
+            for block in samples.chunks_mut(GATE_BLOCK_SAMPLES) {
+                if InputLevels::measure(block).rms < self.gate_threshold {
+                    block.fill(0);
+                }
+            }
+        }
+    }
+}
+
+/// Levels of a captured block before and after gain and gating
+#[derive(Debug, Clone, Copy, Serialize, PartialEq)]
+pub struct InputLevelPreview {
+    pub input: InputLevels,
+    pub processed: InputLevels,
+    /// The processed signal reached full scale, so the gain is too high
+    pub clipped: bool,
+}
+
+/// Measure `samples` as captured and as the server would receive them
+pub fn preview_levels(samples: &[i16], processing: InputProcessing) -> InputLevelPreview {
+    let mut processed = samples.to_vec();
+    processing.apply(&mut processed);
+    let processed_levels = InputLevels::measure(&processed);
+    InputLevelPreview {
+        input: InputLevels::measure(samples),
+        processed: processed_levels,
+        clipped: processed_levels.peak >= 1.0 && processing.gain > 1.0,
+    }
+}
@@ -1,27 +1,45 @@
 use std::sync::atomic::Ordering;
-use tauri::{
-    menu::{Menu, MenuItem},
-    tray::{MouseButton, MouseButtonState, TrayIconBuilder, TrayIconEvent},
-    AppHandle, Emitter, Manager,
-};
+use tauri::{AppHandle, Emitter, Listener, Manager};
 use tauri_utils::config::BackgroundThrottlingPolicy;
 
 mod audio;
 mod audio_mute;
+#[cfg(desktop)]
+mod cli;
 mod commands;
+mod control_server;
 mod history;
+mod history_crypto;
+mod input_processing;
+mod logging;
+mod migrations;
+mod permissions;
+mod profiles;
+mod recordings;
 mod settings;
 mod state;
+mod target_app;
+mod text_processing;
+mod tray;
+mod vad;
+mod voice_commands;
+mod webhook;
 
 #[cfg(test)]
 mod tests;
 
 use audio_mute::AudioMuteManager;
+use control_server::{ControlServer, RemoteAction};
 use history::HistoryStorage;
-use settings::HotkeyConfig;
-use state::AppState;
+use recordings::RecordingStorage;
+use settings::{
+    ActivationMode, EffectiveHotkey, HotkeyAction, HotkeyConfig, HotkeyRegistration, MuteMode,
+    PasteSlotSource, DEFAULT_DOUBLE_TAP_WINDOW_MS, DEFAULT_DUCK_LEVEL, DEFAULT_HOLD_START_DELAY_MS,
+    DEFAULT_HOLD_STOP_GRACE_MS, DEFAULT_MIN_RECORDING_MS, DEFAULT_TOGGLE_DEBOUNCE_MS,
+    MAX_HOLD_START_DELAY_MS,
+};
+use state::{AppState, RecordingSource, RecordingState};
 
-#[cfg(desktop)]
 use tauri_plugin_store::StoreExt;
 
 #[cfg(desktop)]
@@ -48,9 +66,123 @@ pub(crate) fn normalize_shortcut_string(s: &str) -> String {
         .replace("win", "super")
 }
 
-/// Helper to read a setting from the store with a default fallback
+/// Whether `action`'s hotkey is enabled (the default)
+#[cfg(desktop)]
+pub(crate) fn hotkey_enabled(app: &AppHandle, action: HotkeyAction) -> bool {
+    get_setting_from_store(app, action.enabled_key(), true)
+}
+
+/// The hotkey stored for `action` (or its default), `None` if the action is unbound
+#[cfg(desktop)]
+pub(crate) fn stored_hotkey(app: &AppHandle, action: HotkeyAction) -> Option<HotkeyConfig> {
+    match action.default_hotkey() {
+        Some(default) => Some(get_setting_from_store(app, action.store_key(), default)),
+        None => get_setting_from_store(app, action.store_key(), None),
+    }
+}
+
+/// The hotkey bound to `action`, or `None` if the action is unbound or disabled
+#[cfg(desktop)]
+pub(crate) fn configured_hotkey(app: &AppHandle, action: HotkeyAction) -> Option<HotkeyConfig> {
+    if !hotkey_enabled(app, action) {
+        return None;
+    }
+    stored_hotkey(app, action)
+}
+
+/// Describe how `handle_shortcut_event` matches `action`'s hotkey: invalid hotkeys
+/// fall back to the default (if any), modifier-only ones match nothing, and an event is recognized when the OS's
+/// string for the shortcut normalizes to the same value as the configured combo.
+#[cfg(desktop)]
+pub(crate) fn effective_hotkey(
+    action: HotkeyAction,
+    hotkey: Option<&HotkeyConfig>,
+    enabled: bool,
+) -> EffectiveHotkey {
+    let error = hotkey.and_then(|hotkey| hotkey.to_shortcut().err());
+    let effective = match (hotkey, &error) {
+        (Some(hotkey), Some(_)) if hotkey.is_modifier_only() => None,
+        (Some(_), Some(_)) => action.default_hotkey(),
+        (hotkey, _) => hotkey.cloned(),
+    };
+    let normalized = effective
+        .as_ref()
+        .map(|hotkey| normalize_shortcut_string(&hotkey.to_shortcut_string()));
+    let event_string = effective
+        .as_ref()
+        .and_then(|hotkey| hotkey.to_shortcut().ok())
+        .map(|shortcut| normalize_shortcut_string(&shortcut.to_string()));
+    EffectiveHotkey {
+        action,
+        enabled,
+        configured: hotkey.map(HotkeyConfig::to_shortcut_string),
+        parsed: hotkey.is_some() && error.is_none(),
+        error,
+        matches: normalized.is_some() && normalized == event_string,
+        normalized,
+        event_string,
+    }
+}
+
+/// Find two actions bound to the same combo, so `handle_shortcut_event` can tell them apart.
+/// Spellings are normalized first, so "cmd+A" and "super+A" collide.
+#[cfg(desktop)]
+pub(crate) fn find_duplicate_hotkeys(
+    hotkeys: &[(HotkeyAction, HotkeyConfig)],
+) -> Option<(HotkeyAction, HotkeyAction)> {
+    let normalized: Vec<(HotkeyAction, String)> = hotkeys
+        .iter()
+        .map(|(action, hotkey)| {
+            // Parsed shortcuts print modifiers in a canonical order
+            let shortcut_str = hotkey
+                .to_shortcut()
+                .map(|shortcut| shortcut.to_string())
+                .unwrap_or_else(|_| hotkey.to_shortcut_string());
+            (*action, normalize_shortcut_string(&shortcut_str))
+        })
+        .collect();
+
+    normalized
+        .iter()
+        .enumerate()
+        .find_map(|(index, (action, shortcut_str))| {
+            normalized[index + 1..]
+                .iter()
+                .find(|(_, other_str)| other_str == shortcut_str)
+                .map(|(other, _)| (*action, *other))
+        })
+}
+
+/// Check if a release at `now_ms` completes a double-tap started by the release at `last_release_ms`
+#[cfg(desktop)]
+pub(crate) fn is_double_tap(last_release_ms: u64, now_ms: u64, window_ms: u64) -> bool {
+    last_release_ms != 0 && now_ms >= last_release_ms && now_ms - last_release_ms <= window_ms
+}
+
+/// Check if a release at `released_ms` came too soon after the press at `pressed_ms`
+/// to be deliberate (keyboard bounce)
+#[cfg(desktop)]
+pub(crate) fn is_key_bounce(pressed_ms: u64, released_ms: u64, debounce_ms: u64) -> bool {
+    released_ms >= pressed_ms && released_ms - pressed_ms < debounce_ms
+}
+
+/// Check if a hold released after `held_ms` was too short to be a real recording
+/// (an accidental tap). Such recordings are discarded silently.
 #[cfg(desktop)]
-fn get_setting_from_store<T: serde::de::DeserializeOwned>(
+pub(crate) fn is_accidental_hold(held_ms: u64, min_recording_ms: u64) -> bool {
+    held_ms < min_recording_ms
+}
+
+/// Current Unix time in milliseconds
+pub(crate) fn now_millis() -> u64 {
+    std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .map(|d| d.as_millis() as u64)
+        .unwrap_or(0)
+}
+
+/// Helper to read a setting from the store with a default fallback
+pub(crate) fn get_setting_from_store<T: serde::de::DeserializeOwned>(
     app: &AppHandle,
     key: &str,
     default: T,
@@ -70,25 +202,294 @@ fn start_recording(
     sound_enabled: bool,
     audio_mute_manager: &Option<tauri::State<'_, AudioMuteManager>>,
     auto_mute_audio: bool,
-    source: &str,
+    source: RecordingSource,
 ) {
-    state.is_recording.store(true, Ordering::SeqCst);
-    log::info!("{}: starting recording", source);
+    if !state.try_set_recording(true) {
+        log::info!("{:?}: already recording, ignoring start", source);
+        return;
+    }
+    // Bail out before the start sound and muting if the microphone is unavailable
+    if !commands::permissions::ensure_microphone_access(app) {
+        state.try_set_recording(false);
+        return;
+    }
+    let generation = state.recording_generation.fetch_add(1, Ordering::SeqCst) + 1;
+    if let Ok(mut guard) = state.recording_session.lock() {
+        *guard = Some((source, now_millis()));
+    }
+    log::info!("{:?}: starting recording", source);
     // Play sound BEFORE muting so it's audible
-    if sound_enabled {
-        audio::play_sound(audio::SoundType::RecordingStart);
+    let sound_settings = audio::SoundSettings::from_store(app);
+    if sound_enabled && sound_settings.is_audible() {
+        audio::play_sound(audio::SoundType::RecordingStart, sound_settings);
         // Brief delay to let sound play before muting
         std::thread::sleep(std::time::Duration::from_millis(150));
     }
     // Mute system audio if enabled
     if auto_mute_audio {
         if let Some(manager) = audio_mute_manager {
-            if let Err(e) = manager.mute() {
+            let mute_exclusions: Vec<String> =
+                get_setting_from_store(app, "mute_exclusions", Vec::new());
+            let mute_mode: MuteMode = get_setting_from_store(app, "mute_mode", MuteMode::default());
+            let duck_level: u8 = get_setting_from_store(app, "duck_level", DEFAULT_DUCK_LEVEL);
+            if let Err(e) = manager.mute(&mute_exclusions, mute_mode, duck_level) {
                 log::warn!("Failed to mute audio: {}", e);
             }
         }
     }
-    let _ = app.emit("recording-start", ());
+    set_cancel_shortcut_active(app, state, true);
+    tray::set_recording_indicator(app, true);
+    // Hold mode: let the start sound finish before capture begins so it isn't recorded
+    let hold_start_delay_ms: u64 = if source == RecordingSource::Hold {
+        get_setting_from_store(app, "hold_start_delay_ms", DEFAULT_HOLD_START_DELAY_MS)
+            .min(MAX_HOLD_START_DELAY_MS)
+    } else {
+        0
+    };
+    if hold_start_delay_ms > 0 {
+        spawn_hold_start_delay(app.clone(), generation, hold_start_delay_ms);
+    } else {
+        let _ = app.emit("recording-start", ());
+    }
+
+    // Arm silence detection for hands-free stop (opt-in)
+    let detector = get_setting_from_store(app, "vad_enabled", false).then(|| {
+        vad::SilenceDetector::new(
+            get_setting_from_store(app, "vad_threshold", vad::DEFAULT_VAD_THRESHOLD),
+            get_setting_from_store(app, "silence_timeout_ms", vad::DEFAULT_SILENCE_TIMEOUT_MS),
+        )
+    });
+    if let Ok(mut guard) = state.silence_detector.lock() {
+        *guard = detector;
+    }
+
+    spawn_elapsed_ticker(app.clone(), generation);
+
+    // Arm the auto-stop timer (0 = no limit)
+    let max_recording_secs: u64 = get_setting_from_store(app, "max_recording_secs", 0);
+    if max_recording_secs > 0 {
+        spawn_recording_timeout(app.clone(), generation, max_recording_secs);
+    }
+}
+
+/// Begin capture once the hold start delay has elapsed, unless the session
+/// identified by `generation` was stopped or cancelled in the meantime
+#[cfg(desktop)]
+fn spawn_hold_start_delay(app: AppHandle, generation: u64, hold_start_delay_ms: u64) {
+    std::thread::spawn(move || {
+        std::thread::sleep(std::time::Duration::from_millis(hold_start_delay_ms));
+
+        let state = app.state::<AppState>();
+        if state.recording_generation.load(Ordering::SeqCst) != generation {
+            return;
+        }
+        let _ = app.emit("recording-start", ());
+    });
+}
+
+/// Stop the hold-mode recording after the release grace period, unless the session
+/// identified by `generation` already ended or the hold key was pressed again
+/// (in which case the recording simply continues until the next release)
+#[cfg(desktop)]
+fn spawn_hold_stop_grace(app: AppHandle, generation: u64, hold_stop_grace_ms: u64) {
+    std::thread::spawn(move || {
+        std::thread::sleep(std::time::Duration::from_millis(hold_stop_grace_ms));
+
+        let state = app.state::<AppState>();
+        if state.recording_generation.load(Ordering::SeqCst) != generation
+            || state.ptt_key_held.load(Ordering::SeqCst)
+        {
+            return;
+        }
+
+        let sound_enabled: bool = get_setting_from_store(&app, "sound_enabled", true);
+        let auto_mute_audio: bool = get_setting_from_store(&app, "auto_mute_audio", false);
+        let audio_mute_manager = app.try_state::<AudioMuteManager>();
+        stop_recording(
+            &app,
+            &state,
+            sound_enabled,
+            &audio_mute_manager,
+            auto_mute_audio,
+            "Hold",
+        );
+    });
+}
+
+/// Emit `recording-elapsed` (whole seconds) once per second until the session
+/// identified by `generation` ends
+#[cfg(desktop)]
+fn spawn_elapsed_ticker(app: AppHandle, generation: u64) {
+    std::thread::spawn(move || {
+        let started = std::time::Instant::now();
+        let mut elapsed_secs: u64 = 0;
+        loop {
+            // Sleep until the next whole second so the count doesn't drift
+            let next_tick = started + std::time::Duration::from_secs(elapsed_secs + 1);
+            std::thread::sleep(next_tick.saturating_duration_since(std::time::Instant::now()));
+
+            let state = app.state::<AppState>();
+            if state.recording_generation.load(Ordering::SeqCst) != generation
+                || !state.is_recording.load(Ordering::SeqCst)
+            {
+                return;
+            }
+
+            elapsed_secs += 1;
+            let _ = app.emit("recording-elapsed", elapsed_secs);
+        }
+    });
+}
+
+/// Stop the recording after `max_recording_secs` unless the session identified by
+/// `generation` has already been stopped (or replaced by a newer one)
+#[cfg(desktop)]
+fn spawn_recording_timeout(app: AppHandle, generation: u64, max_recording_secs: u64) {
+    std::thread::spawn(move || {
+        std::thread::sleep(std::time::Duration::from_secs(max_recording_secs));
+
+        let state = app.state::<AppState>();
+        if state.recording_generation.load(Ordering::SeqCst) != generation
+            || !state.is_recording.load(Ordering::SeqCst)
+        {
+            return;
+        }
+
+        log::info!(
+            "Recording exceeded {}s limit, stopping automatically",
+            max_recording_secs
+        );
+        let sound_enabled: bool = get_setting_from_store(&app, "sound_enabled", true);
+        let auto_mute_audio: bool = get_setting_from_store(&app, "auto_mute_audio", false);
+        let audio_mute_manager = app.try_state::<AudioMuteManager>();
+        stop_recording(
+            &app,
+            &state,
+            sound_enabled,
+            &audio_mute_manager,
+            auto_mute_audio,
+            "Timeout",
+        );
+        let _ = app.emit("recording-timeout", max_recording_secs);
+    });
+}
+
+/// Payload of the `recording-level` event emitted by the overlay
+#[cfg(desktop)]
+#[derive(serde::Deserialize)]
+struct RecordingLevel {
+    level: f32,
+}
+
+/// Payload of the `transcription-queue-changed` event emitted by the overlay
+#[derive(serde::Deserialize)]
+struct TranscriptionQueue {
+    depth: usize,
+}
+
+/// What went wrong in a failed transcription
+#[cfg(desktop)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub(crate) enum TranscriptionErrorKind {
+    /// Lost or couldn't reach the server
+    Network,
+    /// No response from the server in time
+    Timeout,
+    /// The server reported an error
+    Server,
+    /// The server's response couldn't be understood
+    Parse,
+}
+
+/// Payload of the `transcription-error` event emitted by the overlay
+#[cfg(desktop)]
+#[derive(Debug, Clone, PartialEq, serde::Serialize, serde::Deserialize)]
+pub(crate) struct TranscriptionError {
+    pub kind: TranscriptionErrorKind,
+    pub message: String,
+    /// Whether the overlay will retry (e.g. reconnect to the server)
+    pub will_retry: bool,
+}
+
+/// End a recording that failed to transcribe and always restore system audio,
+/// even if auto mute was switched off mid-recording.
+/// An error for a queued transcription leaves a newer in-progress recording alone,
+/// unless the connection was lost.
+#[cfg(desktop)]
+fn handle_transcription_error(app: &AppHandle, error: &TranscriptionError) {
+    log::warn!(
+        "Transcription failed ({:?}, retry: {}): {}",
+        error.kind,
+        error.will_retry,
+        error.message
+    );
+    let state = app.state::<AppState>();
+    let queued = state.transcription_queue_depth.load(Ordering::SeqCst) > 0;
+    // The error sound replaces the deferred stop sound, so only one cue plays
+    finish_stop_sound(app, false);
+    audio::play_sound_if_enabled(app, audio::SoundType::Error);
+    if error.kind == TranscriptionErrorKind::Network {
+        // Every in-flight transcription is lost with the connection
+        state.clear_stop_sounds();
+    }
+
+    let audio_mute_manager = app.try_state::<AudioMuteManager>();
+    if (error.kind == TranscriptionErrorKind::Network || !queued) && state.try_set_recording(false)
+    {
+        end_recording_session(app, &state, &audio_mute_manager, false);
+    }
+    if state.is_recording.load(Ordering::SeqCst) {
+        return;
+    }
+    if let Some(manager) = audio_mute_manager {
+        if let Err(e) = manager.unmute() {
+            log::warn!("Failed to unmute audio: {}", e);
+        }
+    }
+}
+
+/// Feed a mic level sample into the silence detector, emitting `speech-detected` /
+/// `silence-detected` transitions and stopping the recording once silence lasts
+/// for the full `silence_timeout_ms` window
+#[cfg(desktop)]
+fn handle_recording_level(app: &AppHandle, level: f32) {
+    let state = app.state::<AppState>();
+    if !state.is_recording.load(Ordering::SeqCst) {
+        return;
+    }
+
+    let vad_event = match state.silence_detector.lock() {
+        Ok(mut guard) => match guard.as_mut() {
+            Some(detector) => detector.update(level, now_millis()),
+            None => return,
+        },
+        Err(_) => return,
+    };
+
+    match vad_event {
+        Some(vad::VadEvent::SpeechDetected) => {
+            let _ = app.emit("speech-detected", ());
+        }
+        Some(vad::VadEvent::SilenceDetected) => {
+            let _ = app.emit("silence-detected", ());
+        }
+        Some(vad::VadEvent::SilenceTimeout) => {
+            log::info!("Sustained silence detected, stopping recording");
+            let sound_enabled: bool = get_setting_from_store(app, "sound_enabled", true);
+            let auto_mute_audio: bool = get_setting_from_store(app, "auto_mute_audio", false);
+            let audio_mute_manager = app.try_state::<AudioMuteManager>();
+            stop_recording(
+                app,
+                &state,
+                sound_enabled,
+                &audio_mute_manager,
+                auto_mute_audio,
+                "VAD",
+            );
+        }
+        None => {}
+    }
 }
 
 /// Stop recording with sound and audio unmute handling
@@ -101,8 +502,75 @@ fn stop_recording(
     auto_mute_audio: bool,
     source: &str,
 ) {
-    state.is_recording.store(false, Ordering::SeqCst);
+    if !state.try_set_recording(false) {
+        log::info!("{}: not recording, ignoring stop", source);
+        return;
+    }
     log::info!("{}: stopping recording", source);
+    end_recording_session(app, state, audio_mute_manager, auto_mute_audio);
+    // Played by finish_stop_sound once we know whether anything was transcribed
+    state.queue_stop_sound(sound_enabled);
+    let _ = app.emit("recording-stop", ());
+}
+
+/// Play the stop sound deferred by `stop_recording` for the oldest pending
+/// transcription, if any. `play` is false when the outcome plays its own sound
+/// (completion or error), and for empty transcriptions unless `play_sound_on_empty`
+/// is set. Either way the queued sound is consumed, so each outcome has one cue.
+#[cfg(desktop)]
+fn finish_stop_sound(app: &AppHandle, play: bool) {
+    let state = app.state::<AppState>();
+    if state.take_stop_sound() && play {
+        audio::play_sound(
+            audio::SoundType::RecordingStop,
+            audio::SoundSettings::from_store(app),
+        );
+    }
+}
+
+/// Discard the in-progress recording without transcribing it.
+/// No-op if not currently recording.
+#[cfg(desktop)]
+fn cancel_recording(
+    app: &AppHandle,
+    state: &AppState,
+    sound_enabled: bool,
+    audio_mute_manager: &Option<tauri::State<'_, AudioMuteManager>>,
+    auto_mute_audio: bool,
+    source: &str,
+) {
+    if !state.try_set_recording(false) {
+        return;
+    }
+    log::info!("{}: cancelling recording", source);
+    end_recording_session(app, state, audio_mute_manager, auto_mute_audio);
+    if sound_enabled {
+        audio::play_sound(
+            audio::SoundType::RecordingCancel,
+            audio::SoundSettings::from_store(app),
+        );
+    }
+    let _ = app.emit("recording-cancelled", ());
+}
+
+/// Tear down per-session state shared by stop and cancel
+#[cfg(desktop)]
+fn end_recording_session(
+    app: &AppHandle,
+    state: &AppState,
+    audio_mute_manager: &Option<tauri::State<'_, AudioMuteManager>>,
+    auto_mute_audio: bool,
+) {
+    // Invalidate any pending auto-stop timer for this session
+    state.recording_generation.fetch_add(1, Ordering::SeqCst);
+    if let Ok(mut guard) = state.silence_detector.lock() {
+        *guard = None;
+    }
+    if let Ok(mut guard) = state.recording_session.lock() {
+        *guard = None;
+    }
+    set_cancel_shortcut_active(app, state, false);
+    tray::set_recording_indicator(app, false);
     // Unmute system audio if it was muted
     if auto_mute_audio {
         if let Some(manager) = audio_mute_manager {
@@ -111,10 +579,74 @@ fn stop_recording(
             }
         }
     }
-    if sound_enabled {
-        audio::play_sound(audio::SoundType::RecordingStop);
+}
+
+/// Get the configured cancel hotkey as a shortcut (falls back to Escape)
+#[cfg(desktop)]
+fn cancel_shortcut(app: &AppHandle) -> Shortcut {
+    let cancel_hotkey: HotkeyConfig =
+        get_setting_from_store(app, "cancel_hotkey", HotkeyConfig::default_cancel());
+    cancel_hotkey.to_shortcut_or_default(HotkeyConfig::default_cancel)
+}
+
+/// Register the cancel hotkey only while recording, so its key (Escape by default)
+/// isn't swallowed globally the rest of the time
+#[cfg(desktop)]
+fn set_cancel_shortcut_active(app: &AppHandle, state: &AppState, active: bool) {
+    use tauri_plugin_global_shortcut::GlobalShortcutExt;
+
+    let shortcut = cancel_shortcut(app);
+    let shortcut_manager = app.global_shortcut();
+
+    if active {
+        // Don't take over a recording hotkey that happens to use the same keys
+        if shortcut_manager.is_registered(shortcut) {
+            log::warn!(
+                "Cancel hotkey {} is already in use, not registering",
+                shortcut
+            );
+            return;
+        }
+        match shortcut_manager.on_shortcut(shortcut, |app, shortcut, event| {
+            handle_shortcut_event(app, shortcut, &event);
+        }) {
+            Ok(()) => state
+                .cancel_shortcut_registered
+                .store(true, Ordering::SeqCst),
+            Err(e) => log::warn!("Failed to register cancel hotkey: {}", e),
+        }
+    } else if state
+        .cancel_shortcut_registered
+        .swap(false, Ordering::SeqCst)
+    {
+        if let Err(e) = shortcut_manager.unregister(shortcut) {
+            log::warn!("Failed to unregister cancel hotkey: {}", e);
+        }
+    }
+}
+
+/// Decide whether a toggle key release should flip recording state, based on the activation mode
+#[cfg(desktop)]
+fn should_activate_toggle(app: &AppHandle, state: &AppState) -> bool {
+    let toggle_activation: ActivationMode =
+        get_setting_from_store(app, "toggle_activation", ActivationMode::default());
+
+    match toggle_activation {
+        ActivationMode::Single => true,
+        ActivationMode::DoubleTap => {
+            let window_ms: u64 =
+                get_setting_from_store(app, "double_tap_window_ms", DEFAULT_DOUBLE_TAP_WINDOW_MS);
+            let now = now_millis();
+            let last_release = state.last_toggle_release_ms.swap(now, Ordering::SeqCst);
+            if is_double_tap(last_release, now, window_ms) {
+                // Reset so a third tap starts a new sequence instead of toggling again
+                state.last_toggle_release_ms.store(0, Ordering::SeqCst);
+                true
+            } else {
+                false
+            }
+        }
     }
-    let _ = app.emit("recording-stop", ());
 }
 
 /// Handle a shortcut event - public so it can be called from commands/settings.rs
@@ -157,22 +689,58 @@ pub fn handle_shortcut_event(app: &AppHandle, shortcut: &Shortcut, event: &Short
             .unwrap_or_else(|_| HotkeyConfig::default_paste_last().to_shortcut_string()),
     );
 
+    // Unbound unless configured
+    let show_window_shortcut_str = configured_hotkey(app, HotkeyAction::ShowWindow)
+        .filter(|hotkey| hotkey.to_shortcut().is_ok())
+        .map(|hotkey| normalize_shortcut_string(&hotkey.to_shortcut_string()));
+
+    // Extra paste slots are unbound unless configured
+    let paste_slot = [HotkeyAction::PasteSlot2, HotkeyAction::PasteSlot3]
+        .into_iter()
+        .find(|&action| {
+            configured_hotkey(app, action)
+                .filter(|hotkey| hotkey.to_shortcut().is_ok())
+                .is_some_and(|hotkey| {
+                    normalize_shortcut_string(&hotkey.to_shortcut_string()) == shortcut_str
+                })
+        })
+        .and_then(HotkeyAction::paste_slot);
+
+    let cancel_shortcut_str = normalize_shortcut_string(&cancel_shortcut(app).to_string());
+
     // Get audio mute manager if available
     let audio_mute_manager = app.try_state::<AudioMuteManager>();
 
-    // Compare normalized strings directly
-    let is_toggle = shortcut_str == toggle_shortcut_str;
-    let is_hold = shortcut_str == hold_shortcut_str;
-    let is_paste_last = shortcut_str == paste_last_shortcut_str;
+    // Compare normalized strings directly. Disabled actions never match, so a stale
+    // registration can't trigger them and their combo can be reused by another action.
+    let is_toggle =
+        shortcut_str == toggle_shortcut_str && hotkey_enabled(app, HotkeyAction::Toggle);
+    let is_hold = shortcut_str == hold_shortcut_str && hotkey_enabled(app, HotkeyAction::Hold);
+    let is_paste_last =
+        shortcut_str == paste_last_shortcut_str && hotkey_enabled(app, HotkeyAction::PasteLast);
+    let is_show_window = show_window_shortcut_str.as_deref() == Some(shortcut_str.as_str());
+    let is_cancel = shortcut_str == cancel_shortcut_str;
 
     if is_toggle {
         // Toggle mode: action happens on key release (debounced)
         match event.state {
             ShortcutState::Pressed => {
-                state.toggle_key_held.swap(true, Ordering::SeqCst);
+                // Time the first press only, so OS key repeat doesn't extend the debounce
+                if !state.toggle_key_held.swap(true, Ordering::SeqCst) {
+                    state
+                        .toggle_pressed_at_ms
+                        .store(now_millis(), Ordering::SeqCst);
+                }
             }
             ShortcutState::Released => {
-                if state.toggle_key_held.swap(false, Ordering::SeqCst) {
+                let debounce_ms: u64 =
+                    get_setting_from_store(app, "toggle_debounce_ms", DEFAULT_TOGGLE_DEBOUNCE_MS);
+                let pressed_ms = state.toggle_pressed_at_ms.load(Ordering::SeqCst);
+                // Only releases count towards a double-tap, so OS key repeat can't skew timing
+                let was_held = state.toggle_key_held.swap(false, Ordering::SeqCst);
+                if was_held && is_key_bounce(pressed_ms, now_millis(), debounce_ms) {
+                    log::debug!("Toggle: ignoring release within {}ms of press", debounce_ms);
+                } else if was_held && should_activate_toggle(app, &state) {
                     if state.is_recording.load(Ordering::SeqCst) {
                         stop_recording(
                             app,
@@ -189,7 +757,7 @@ pub fn handle_shortcut_event(app: &AppHandle, shortcut: &Shortcut, event: &Short
                             sound_enabled,
                             &audio_mute_manager,
                             auto_mute_audio,
-                            "Toggle",
+                            RecordingSource::Toggle,
                         );
                     }
                 }
@@ -206,20 +774,53 @@ pub fn handle_shortcut_event(app: &AppHandle, shortcut: &Shortcut, event: &Short
                         sound_enabled,
                         &audio_mute_manager,
                         auto_mute_audio,
-                        "Hold",
+                        RecordingSource::Hold,
                     );
                 }
             }
             ShortcutState::Released => {
-                if state.ptt_key_held.swap(false, Ordering::SeqCst) {
-                    stop_recording(
+                // Skip if the recording was already ended by the auto-stop timeout
+                if state.ptt_key_held.swap(false, Ordering::SeqCst)
+                    && state.is_recording.load(Ordering::SeqCst)
+                {
+                    let min_recording_ms: u64 =
+                        get_setting_from_store(app, "min_recording_ms", DEFAULT_MIN_RECORDING_MS);
+                    let hold_stop_grace_ms: u64 = get_setting_from_store(
                         app,
-                        &state,
-                        sound_enabled,
-                        &audio_mute_manager,
-                        auto_mute_audio,
-                        "Hold",
+                        "hold_stop_grace_ms",
+                        DEFAULT_HOLD_STOP_GRACE_MS,
                     );
+                    let held_ms = state.recording_elapsed_ms(now_millis()).unwrap_or(u64::MAX);
+                    if is_accidental_hold(held_ms, min_recording_ms) {
+                        // Accidental tap: discard instead of sending an empty transcription.
+                        // No cancel sound, so a mistaken tap only plays the start cue.
+                        log::debug!(
+                            "Hold: released after {}ms (minimum {}ms), discarding recording",
+                            held_ms,
+                            min_recording_ms
+                        );
+                        cancel_recording(
+                            app,
+                            &state,
+                            false,
+                            &audio_mute_manager,
+                            auto_mute_audio,
+                            "Hold",
+                        );
+                    } else if hold_stop_grace_ms > 0 {
+                        // Keep capturing briefly so trailing words aren't cut off
+                        let generation = state.recording_generation.load(Ordering::SeqCst);
+                        spawn_hold_stop_grace(app.clone(), generation, hold_stop_grace_ms);
+                    } else {
+                        stop_recording(
+                            app,
+                            &state,
+                            sound_enabled,
+                            &audio_mute_manager,
+                            auto_mute_audio,
+                            "Hold",
+                        );
+                    }
                 }
             }
         }
@@ -234,41 +835,197 @@ pub fn handle_shortcut_event(app: &AppHandle, shortcut: &Shortcut, event: &Short
                 if state.paste_key_held.swap(false, Ordering::SeqCst) {
                     // Key released - do the paste
                     log::info!("PasteLast: pasting last transcription");
-                    let history_storage = app.state::<HistoryStorage>();
-
-                    if let Ok(entries) = history_storage.get_all(Some(1)) {
-                        if let Some(entry) = entries.first() {
-                            if let Err(e) = commands::text::type_text_blocking(&entry.text) {
-                                log::error!("Failed to paste last transcription: {}", e);
-                            }
-                        } else {
-                            log::info!("PasteLast: no history entries available");
-                        }
-                    }
+                    paste_history_slot(app, &state, 1);
+                }
+            }
+        }
+    } else if let Some(slot) = paste_slot {
+        // Paste slot 2/3: same hold-to-paste behavior as PasteLast
+        let key_held = &state.paste_slot_key_held[slot - 2];
+        match event.state {
+            ShortcutState::Pressed => {
+                key_held.swap(true, Ordering::SeqCst);
+            }
+            ShortcutState::Released => {
+                if key_held.swap(false, Ordering::SeqCst) {
+                    log::info!("PasteSlot{}: pasting history slot {}", slot, slot);
+                    paste_history_slot(app, &state, slot);
                 }
             }
         }
+    } else if is_show_window {
+        // Show window: act on release so OS key repeat doesn't flicker the window
+        if event.state == ShortcutState::Released {
+            toggle_main_window(app);
+        }
+    } else if is_cancel {
+        // Cancel: discard the recording on press (no-op when not recording)
+        if event.state == ShortcutState::Pressed {
+            cancel_recording(
+                app,
+                &state,
+                sound_enabled,
+                &audio_mute_manager,
+                auto_mute_audio,
+                "Cancel",
+            );
+        }
     } else {
         log::warn!("Unknown shortcut: {}", shortcut_str);
     }
 }
 
+/// Type the history entry for a paste slot (1 = last transcription).
+/// Slot 1 is always the most recent entry; the extra slots follow `paste_slot_source`.
+/// Typing runs on the main thread, since this is also called from the control
+/// server and second-instance threads.
+#[cfg(desktop)]
+fn paste_history_slot(app: &AppHandle, state: &AppState, slot: usize) {
+    let source = if slot == 1 {
+        PasteSlotSource::Recent
+    } else {
+        get_setting_from_store(app, "paste_slot_source", PasteSlotSource::default())
+    };
+    let history_storage = app.state::<HistoryStorage>();
+    match history_storage.get_slot(slot, source == PasteSlotSource::Pinned) {
+        Ok(Some(entry)) => {
+            let settings = commands::text::InsertionSettings::from_store(app);
+            match commands::text::run_on_main_thread(app, move || {
+                commands::text::type_text_blocking(&entry.text, settings)
+            }) {
+                Ok(inserted_chars) => state.record_insertion(inserted_chars, now_millis()),
+                Err(e) => log::error!("Failed to paste history slot {}: {}", slot, e),
+            }
+        }
+        Ok(None) => log::info!("Paste slot {}: no history entry available", slot),
+        Err(e) => log::error!("Failed to read history for paste slot {}: {}", slot, e),
+    }
+}
+
+/// Run a recording action requested from outside the hotkeys (`source` names the
+/// requester in the log), with the same sound, mute and paste handling as the hotkeys
+#[cfg(desktop)]
+pub(crate) fn run_remote_action(app: &AppHandle, action: RemoteAction, source: &str) {
+    let state = app.state::<AppState>();
+    let sound_enabled: bool = get_setting_from_store(app, "sound_enabled", true);
+    let auto_mute_audio: bool = get_setting_from_store(app, "auto_mute_audio", false);
+    let audio_mute_manager = app.try_state::<AudioMuteManager>();
+
+    let start = |state: &AppState| {
+        start_recording(
+            app,
+            state,
+            sound_enabled,
+            &audio_mute_manager,
+            auto_mute_audio,
+            RecordingSource::Toggle,
+        )
+    };
+    let stop = |state: &AppState| {
+        stop_recording(
+            app,
+            state,
+            sound_enabled,
+            &audio_mute_manager,
+            auto_mute_audio,
+            source,
+        )
+    };
+    match action {
+        RemoteAction::Start => start(&state),
+        RemoteAction::Stop => stop(&state),
+        RemoteAction::Toggle => {
+            if state.is_recording.load(Ordering::SeqCst) {
+                stop(&state);
+            } else {
+                start(&state);
+            }
+        }
+        RemoteAction::Cancel => cancel_recording(
+            app,
+            &state,
+            sound_enabled,
+            &audio_mute_manager,
+            auto_mute_audio,
+            source,
+        ),
+        RemoteAction::PasteLast => {
+            log::info!("{}: pasting last transcription", source);
+            paste_history_slot(app, &state, 1);
+        }
+    }
+}
+
+#[cfg(not(desktop))]
+pub(crate) fn run_remote_action(_app: &AppHandle, action: RemoteAction, source: &str) {
+    log::warn!("{}: {:?} is not supported on this platform", source, action);
+}
+
 /// Check if audio mute is supported on this platform
 #[tauri::command]
 fn is_audio_mute_supported() -> bool {
     audio_mute::is_supported()
 }
 
+/// Check if muting individual applications (mute exclusions) is supported on this platform
+#[tauri::command]
+fn is_per_app_mute_supported() -> bool {
+    audio_mute::is_per_app_supported()
+}
+
+/// Get whether a recording is in progress, what started it and when
+#[tauri::command]
+fn get_recording_state(state: tauri::State<'_, AppState>) -> RecordingState {
+    state.recording_state()
+}
+
+/// Get the hotkeys that failed to register (e.g. combos owned by another application)
+#[tauri::command]
+fn get_shortcut_registration_failures(
+    state: tauri::State<'_, AppState>,
+) -> Vec<HotkeyRegistration> {
+    state.hotkey_registration_failures()
+}
+
+/// Get how many stopped recordings are still awaiting transcription
+#[tauri::command]
+fn get_transcription_queue_depth(state: tauri::State<'_, AppState>) -> usize {
+    state.transcription_queue_depth.load(Ordering::SeqCst)
+}
+
 #[cfg_attr(mobile, tauri::mobile_entry_point)]
 pub fn run() {
     // Initialize logger
-    env_logger::Builder::from_env(env_logger::Env::default().default_filter_or("info")).init();
+    logging::init();
 
     let mut builder = tauri::Builder::default();
 
     #[cfg(desktop)]
     {
-        builder = builder.plugin(build_global_shortcut_plugin());
+        let args: Vec<String> = std::env::args().collect();
+        match cli::parse_cli_action(&args) {
+            Ok(Some(action)) => log::info!(
+                "No running instance to send {:?} to, starting normally",
+                action
+            ),
+            Ok(None) => {}
+            Err(e) => log::warn!("Ignoring command line: {}", e),
+        }
+        // Must be the first plugin, so a second launch exits before setting anything up
+        builder = builder.plugin(tauri_plugin_single_instance::init(|app, args, _cwd| {
+            handle_second_instance(app, &args);
+        }));
+    }
+
+    #[cfg(desktop)]
+    {
+        builder =
+            builder
+                .plugin(build_global_shortcut_plugin())
+                .plugin(tauri_plugin_autostart::init(
+                    tauri_plugin_autostart::MacosLauncher::LaunchAgent,
+                    None,
+                ));
     }
 
     #[cfg(target_os = "macos")]
@@ -280,47 +1037,273 @@ pub fn run() {
         .plugin(tauri_plugin_opener::init())
         .plugin(tauri_plugin_store::Builder::new().build())
         .manage(AppState::default())
+        .manage(ControlServer::default())
         .invoke_handler(tauri::generate_handler![
             commands::text::type_text,
+            commands::text::insert_text,
+            commands::text::list_running_apps,
+            commands::text::undo_last_insertion,
             commands::text::get_server_url,
+            commands::server::set_server_url,
+            commands::server::test_server_connection,
+            commands::server::set_server_auth,
+            commands::server::get_server_request_headers,
+            commands::webhook::set_transcription_webhook,
             commands::settings::register_shortcuts,
+            commands::settings::get_effective_hotkeys,
+            commands::settings::suspend_hotkeys,
+            commands::settings::resume_hotkeys,
+            commands::settings::get_hotkeys_suspended,
             commands::settings::unregister_shortcuts,
+            commands::settings::set_language,
+            commands::settings::parse_hotkey,
+            commands::settings::check_hotkey_available,
+            commands::settings::set_text_replacements,
+            commands::settings::get_text_replacements,
+            commands::settings::set_custom_vocabulary,
+            commands::settings::set_timestamp_format,
+            commands::settings::get_custom_vocabulary,
+            commands::settings::set_voice_commands,
+            commands::settings::get_voice_commands,
+            commands::settings::get_builtin_voice_commands,
+            commands::settings::export_settings,
+            commands::settings::import_settings,
+            commands::settings::reset_settings,
+            commands::settings::set_log_level,
+            commands::settings::get_log_level,
+            commands::profiles::list_profiles,
+            commands::profiles::create_profile,
+            commands::profiles::switch_profile,
+            commands::profiles::delete_profile,
+            commands::sound::preview_sound,
+            commands::storage::get_data_dir_path,
+            commands::storage::open_data_dir,
+            commands::storage::get_history_storage_path,
+            commands::storage::set_history_storage_path,
+            commands::storage::get_log_path,
+            commands::storage::open_logs,
             is_audio_mute_supported,
+            is_per_app_mute_supported,
+            get_recording_state,
+            get_transcription_queue_depth,
+            get_shortcut_registration_failures,
             commands::history::add_history_entry,
             commands::history::get_history,
+            commands::history::get_history_stats,
+            commands::history::set_incognito,
+            commands::history::get_incognito,
+            commands::history::get_history_encryption,
+            commands::history::set_history_encryption,
+            commands::history::unlock_history,
+            commands::history::search_history,
+            commands::history::get_history_range,
             commands::history::delete_history_entry,
+            commands::history::update_history_entry,
+            commands::history::add_history_tag,
+            commands::history::remove_history_tag,
+            commands::history::get_history_by_tag,
+            commands::history::set_history_pinned,
             commands::history::clear_history,
+            commands::history::clear_history_before,
+            commands::history::export_history,
+            commands::history::import_history,
+            commands::history::prune_history,
+            commands::history::copy_history_entry,
+            commands::history::paste_history_entry,
+            commands::recordings::save_recording,
+            commands::recordings::preview_input_level,
+            commands::recordings::get_saved_recordings,
             commands::overlay::resize_overlay,
+            commands::overlay::set_overlay_size_preset,
+            commands::overlay::set_overlay_position,
+            commands::overlay::start_overlay_drag,
+            commands::overlay::end_overlay_drag,
+            commands::overlay::reset_overlay_position,
+            commands::overlay::list_monitors,
+            commands::overlay::move_overlay_to_monitor,
+            commands::overlay::set_overlay_visibility,
+            commands::overlay::get_overlay_appearance,
+            commands::window::set_main_window_always_on_top,
+            commands::control::get_control_server,
+            commands::control::set_control_server,
+            commands::control::regenerate_control_server_token,
+            commands::autostart::get_autostart,
+            commands::autostart::set_autostart,
+            commands::permissions::check_permissions,
+            commands::permissions::request_permissions,
+            commands::overlay::set_overlay_appearance,
         ])
         .setup(|app| {
+            // Keep a log file alongside stderr
+            match app.path().app_log_dir() {
+                Ok(log_dir) => match logging::attach_log_file(&log_dir) {
+                    Ok(path) => log::info!("Logging to {}", path.display()),
+                    Err(e) => log::warn!("Failed to open log file in {}: {}", log_dir.display(), e),
+                },
+                Err(e) => log::warn!("Failed to get app log directory: {}", e),
+            }
+            if !logging::env_filter_set() {
+                let log_level: Option<settings::LogLevel> =
+                    get_setting_from_store(app.handle(), logging::LOG_LEVEL_KEY, None);
+                if let Some(level) = log_level {
+                    logging::set_level(Some(level.filter()));
+                    log::info!("Log level set to {:?}", level);
+                }
+            }
+
+            // Upgrade settings saved by older versions before anything reads them
+            if let Err(e) = migrations::migrate_settings_store(app.handle()) {
+                log::warn!("Failed to migrate settings: {}", e);
+            }
+
             // Initialize history storage
             let app_data_dir = app
                 .path()
                 .app_data_dir()
                 .expect("Failed to get app data directory");
 
-            let history_storage = HistoryStorage::new(app_data_dir);
+            let custom_history_dir: Option<std::path::PathBuf> =
+                get_setting_from_store(app.handle(), history::HISTORY_STORAGE_PATH_KEY, None);
+            let history_dir =
+                history::resolve_history_dir(custom_history_dir.as_deref(), app_data_dir.clone());
+            let history_storage = HistoryStorage::new(
+                history_dir,
+                get_setting_from_store(app.handle(), history::HISTORY_ENCRYPTION_KEY, None),
+            );
+            // Encrypted history opens with its keychain key. Passphrase-encrypted
+            // history (or one whose key is missing) stays locked until unlock_history.
+            if let Ok(status) = history_storage.encryption_status() {
+                if status.locked {
+                    let unlocked = match status.key_source {
+                        Some(history_crypto::KeySource::Keychain) => {
+                            history_crypto::keychain_key(false)
+                                .and_then(|key| history_storage.unlock(key))
+                        }
+                        _ => Err("a passphrase is required".to_string()),
+                    };
+                    if let Err(e) = unlocked {
+                        log::warn!("History is encrypted and locked: {}", e);
+                    }
+                }
+            }
+            history_storage.set_incognito(get_setting_from_store(
+                app.handle(),
+                history::INCOGNITO_KEY,
+                false,
+            ));
             app.manage(history_storage);
 
+            // Initialize storage for saved audio recordings (opt-in via save_recordings)
+            app.manage(RecordingStorage::new(app_data_dir));
+
             // Initialize audio mute manager (may be None on unsupported platforms)
             if let Some(audio_mute_manager) = AudioMuteManager::new() {
                 app.manage(audio_mute_manager);
             }
 
-            // Register shortcuts from store (now that store plugin is available)
+            // Register shortcuts from store (now that store plugin is available),
+            // unless they were suspended when the app last ran
+            #[cfg(desktop)]
+            {
+                if get_setting_from_store(app.handle(), HOTKEYS_SUSPENDED_KEY, false) {
+                    app.state::<AppState>()
+                        .hotkeys_suspended
+                        .store(true, Ordering::SeqCst);
+                }
+                register_hotkeys(app.handle());
+            }
+
+            // Run silence detection on the mic levels sampled by the overlay
+            #[cfg(desktop)]
+            {
+                let app_handle = app.handle().clone();
+                app.listen("recording-level", move |event| {
+                    match serde_json::from_str::<RecordingLevel>(event.payload()) {
+                        Ok(payload) => handle_recording_level(&app_handle, payload.level),
+                        Err(e) => log::warn!("Invalid recording-level payload: {}", e),
+                    }
+                });
+            }
+
             #[cfg(desktop)]
             {
-                register_initial_shortcuts(app.handle())?;
+                let app_handle = app.handle().clone();
+                app.listen(
+                    "transcription-error",
+                    move |event| match serde_json::from_str::<TranscriptionError>(event.payload()) {
+                        Ok(error) => handle_transcription_error(&app_handle, &error),
+                        Err(e) => log::warn!("Invalid transcription-error payload: {}", e),
+                    },
+                );
+            }
+
+            // Pending transcription count, tracked by the overlay
+            {
+                let app_handle = app.handle().clone();
+                app.listen(
+                    "transcription-queue-changed",
+                    move |event| match serde_json::from_str::<TranscriptionQueue>(event.payload()) {
+                        Ok(queue) => app_handle
+                            .state::<AppState>()
+                            .transcription_queue_depth
+                            .store(queue.depth, Ordering::SeqCst),
+                        Err(e) => log::warn!("Invalid transcription-queue-changed payload: {}", e),
+                    },
+                );
+            }
+
+            // Deferred stop sound: after a low-confidence transcription, optionally after
+            // an empty one. A completed transcription is typed by type_text, whose
+            // completion (or error) sound replaces it, except in dry runs where nothing
+            // is typed.
+            #[cfg(desktop)]
+            {
+                let app_handle = app.handle().clone();
+                app.listen("transcription-complete", move |_| {
+                    let dry_run = get_setting_from_store(&app_handle, "dry_run", false);
+                    finish_stop_sound(&app_handle, dry_run);
+                });
+                let app_handle = app.handle().clone();
+                app.listen("transcription-low-confidence", move |_| {
+                    finish_stop_sound(&app_handle, true);
+                });
+                let app_handle = app.handle().clone();
+                app.listen("transcription-empty", move |_| {
+                    let play_sound_on_empty =
+                        get_setting_from_store(&app_handle, "play_sound_on_empty", false);
+                    finish_stop_sound(&app_handle, play_sound_on_empty);
+                });
+            }
+
+            // Show/hide the overlay around recording activity (overlay_visibility)
+            for event in ["recording-start", "transcribing"] {
+                let app_handle = app.handle().clone();
+                app.listen(event, move |_| {
+                    commands::overlay::handle_overlay_activity_started(&app_handle);
+                });
+            }
+            for (event, failed) in [
+                ("transcription-complete", false),
+                ("transcription-empty", false),
+                ("transcription-low-confidence", false),
+                ("recording-cancelled", false),
+                ("transcription-error", true),
+            ] {
+                let app_handle = app.handle().clone();
+                app.listen(event, move |_| {
+                    commands::overlay::handle_overlay_activity_ended(&app_handle, failed);
+                });
             }
 
-            // Create overlay window
+            // Create overlay window at the idle size of the stored size preset
+            let overlay_side = commands::overlay::overlay_size(app.handle()).idle_size();
             let overlay = tauri::WebviewWindowBuilder::new(
                 app,
                 "overlay",
                 tauri::WebviewUrl::App("overlay.html".into()),
             )
             .title("Voice Overlay")
-            .inner_size(48.0, 48.0)
+            .inner_size(overlay_side, overlay_side)
             .decorations(false)
             .transparent(true)
             .shadow(false)
@@ -330,7 +1313,10 @@ pub fn run() {
             .focused(false)
             .focusable(false)
             .accept_first_mouse(true)
-            .visible(true)
+            .visible(
+                commands::overlay::overlay_visibility(app.handle())
+                    == settings::OverlayVisibility::Always,
+            )
             .visible_on_all_workspaces(true)
             .background_throttling(BackgroundThrottlingPolicy::Disabled)
             .build()?;
@@ -363,81 +1349,105 @@ pub fn run() {
                 }
             }
 
-            // Position bottom-right
-            if let Ok(Some(monitor)) = overlay.current_monitor() {
-                let size = monitor.size();
-                let scale = monitor.scale_factor();
-                let x = (size.width as f64 / scale) as i32 - 150;
-                let y = (size.height as f64 / scale) as i32 - 100;
-                let _ = overlay.set_position(tauri::Position::Logical(tauri::LogicalPosition {
-                    x: x as f64,
-                    y: y as f64,
-                }));
+            if let Err(e) = commands::window::apply_main_window_always_on_top(app.handle()) {
+                log::warn!("Failed to restore main window always-on-top: {}", e);
             }
 
-            // Setup system tray
-            setup_tray(app.handle())?;
+            // Closing the main window hides it to the tray (close_to_tray) or quits
+            if let Some(main_window) = app.get_webview_window("main") {
+                let window = main_window.clone();
+                main_window.on_window_event(move |event| {
+                    if let tauri::WindowEvent::CloseRequested { api, .. } = event {
+                        api.prevent_close();
+                        commands::window::handle_main_window_close(&window);
+                    }
+                });
+            }
 
-            Ok(())
-        })
-        .run(tauri::generate_context!())
-        .expect("error while running tauri application");
-}
-
-fn setup_tray(app: &AppHandle) -> Result<(), Box<dyn std::error::Error>> {
-    let show_item = MenuItem::with_id(app, "show", "Show Window", true, None::<&str>)?;
-    let quit_item = MenuItem::with_id(app, "quit", "Quit", true, None::<&str>)?;
-    let menu = Menu::with_items(app, &[&show_item, &quit_item])?;
-
-    // Load the template icon for macOS menu bar
-    // The @2x version is automatically used for retina displays
-    let icon_bytes = include_bytes!("../icons/tray-iconTemplate@2x.png");
-    let icon = tauri::image::Image::from_bytes(icon_bytes)?;
-
-    let _tray = TrayIconBuilder::new()
-        .icon(icon)
-        .icon_as_template(true)
-        .menu(&menu)
-        .show_menu_on_left_click(false)
-        .on_menu_event(|app, event| match event.id.as_ref() {
-            "show" => {
-                if let Some(window) = app.get_webview_window("main") {
-                    let _ = window.show();
-                    let _ = window.set_focus();
-                }
+            // Restore the dragged position, or place according to the configured anchor
+            // (on the chosen monitor, if it is still connected)
+            commands::overlay::validate_overlay_monitor(app.handle());
+            if let Err(e) = commands::overlay::restore_overlay_position(app.handle(), &overlay) {
+                log::warn!("Failed to position overlay: {}", e);
             }
-            "quit" => {
-                // Emit disconnect request to frontend before exiting
-                if let Some(window) = app.get_webview_window("overlay") {
-                    let _ = window.emit("request-disconnect", ());
+
+            // Remember where the user drags the overlay, and follow OS theme changes
+            let overlay_window = overlay.clone();
+            overlay.on_window_event(move |event| match event {
+                tauri::WindowEvent::Moved(position) => {
+                    commands::overlay::handle_overlay_moved(
+                        overlay_window.app_handle(),
+                        &overlay_window,
+                        *position,
+                    );
                 }
-                // Give frontend time to disconnect gracefully
-                std::thread::sleep(std::time::Duration::from_millis(500));
-                app.exit(0);
-            }
-            _ => {}
-        })
-        .on_tray_icon_event(|tray, event| {
-            if let TrayIconEvent::Click {
-                button: MouseButton::Left,
-                button_state: MouseButtonState::Up,
-                ..
-            } = event
-            {
-                let app = tray.app_handle();
-                if let Some(window) = app.get_webview_window("main") {
-                    if window.is_visible().unwrap_or(false) {
-                        let _ = window.hide();
-                    } else {
-                        let _ = window.show();
-                        let _ = window.set_focus();
-                    }
+                tauri::WindowEvent::ThemeChanged(_) => {
+                    commands::overlay::emit_overlay_appearance(overlay_window.app_handle());
                 }
+                _ => {}
+            });
+
+            // Setup system tray
+            tray::setup_tray(app.handle())?;
+
+            // Local control API for scripts (opt-in via control_server_enabled)
+            if let Err(e) = control_server::apply(app.handle()) {
+                log::warn!("Failed to start control server: {}", e);
             }
+
+            Ok(())
         })
-        .build(app)?;
+        .build(tauri::generate_context!())
+        .expect("error while building tauri application")
+        .run(|app, event| {
+            if let tauri::RunEvent::Exit = event {
+                // Runs on the main thread, which a request in progress may be waiting on
+                app.state::<ControlServer>().stop(false);
+            }
+        });
+}
 
-    Ok(())
+/// Exit the app, giving the overlay a moment to disconnect from the server first
+pub(crate) fn quit_app(app: &AppHandle) {
+    // Emit disconnect request to frontend before exiting
+    if let Some(window) = app.get_webview_window("overlay") {
+        let _ = window.emit("request-disconnect", ());
+    }
+    // Give frontend time to disconnect gracefully
+    std::thread::sleep(std::time::Duration::from_millis(500));
+    app.exit(0);
+}
+
+/// Handle a second launch: run the action its flags request (see cli.rs),
+/// or bring up the main window when there is none
+#[cfg(desktop)]
+fn handle_second_instance(app: &AppHandle, args: &[String]) {
+    match cli::parse_cli_action(args) {
+        Ok(Some(action)) => run_remote_action(app, action, "CLI"),
+        Ok(None) => {
+            if let Some(window) = app.get_webview_window("main") {
+                let _ = window.unminimize();
+                let _ = window.show();
+                let _ = window.set_focus();
+            }
+        }
+        Err(e) => log::warn!("Ignoring command line from second instance: {}", e),
+    }
+}
+
+/// Show and focus the main window, or hide it if it is already visible and focused
+#[cfg(desktop)]
+fn toggle_main_window(app: &AppHandle) {
+    let Some(window) = app.get_webview_window("main") else {
+        return;
+    };
+    if window.is_visible().unwrap_or(false) && window.is_focused().unwrap_or(false) {
+        let _ = window.hide();
+    } else {
+        let _ = window.unminimize();
+        let _ = window.show();
+        let _ = window.set_focus();
+    }
 }
 
 #[cfg(desktop)]
@@ -446,39 +1456,120 @@ fn build_global_shortcut_plugin() -> tauri::plugin::TauriPlugin<tauri::Wry> {
     tauri_plugin_global_shortcut::Builder::new().build()
 }
 
-/// Register shortcuts from store settings (called from setup() after store plugin is available)
+/// Store key remembering that hotkeys were suspended, so a restart doesn't
+/// silently re-enable them
+pub(crate) const HOTKEYS_SUSPENDED_KEY: &str = "hotkeys_suspended";
+
+/// Suspend (unregister) or resume (re-register) all global hotkeys, e.g. while
+/// gaming or using another dictation tool. The state is persisted and shown in the
+/// tray. Returns the registrations made when resuming.
 #[cfg(desktop)]
-fn register_initial_shortcuts(app: &AppHandle) -> Result<(), Box<dyn std::error::Error>> {
+pub(crate) fn set_hotkeys_suspended(
+    app: &AppHandle,
+    suspended: bool,
+) -> Result<Vec<HotkeyRegistration>, String> {
     use tauri_plugin_global_shortcut::GlobalShortcutExt;
 
-    // Read hotkeys from store with defaults
-    let toggle_hotkey: HotkeyConfig =
-        get_setting_from_store(app, "toggle_hotkey", HotkeyConfig::default_toggle());
-    let hold_hotkey: HotkeyConfig =
-        get_setting_from_store(app, "hold_hotkey", HotkeyConfig::default_hold());
-    let paste_last_hotkey: HotkeyConfig =
-        get_setting_from_store(app, "paste_last_hotkey", HotkeyConfig::default_paste_last());
+    let state = app.state::<AppState>();
+    app.global_shortcut()
+        .unregister_all()
+        .map_err(|e| format!("Failed to unregister shortcuts: {}", e))?;
+    state
+        .cancel_shortcut_registered
+        .store(false, Ordering::SeqCst);
+    state.hotkeys_suspended.store(suspended, Ordering::SeqCst);
+    let registrations = if suspended {
+        log::info!("Hotkeys suspended");
+        Vec::new()
+    } else {
+        log::info!("Hotkeys resumed");
+        register_hotkeys(app)
+    };
 
-    // Convert to shortcuts with validation (fall back to defaults if invalid)
-    let toggle_shortcut = toggle_hotkey.to_shortcut_or_default(HotkeyConfig::default_toggle);
-    let hold_shortcut = hold_hotkey.to_shortcut_or_default(HotkeyConfig::default_hold);
-    let paste_last_shortcut =
-        paste_last_hotkey.to_shortcut_or_default(HotkeyConfig::default_paste_last);
-
-    log::info!(
-        "Registering shortcuts - Toggle: {}, Hold: {}, PasteLast: {}",
-        toggle_hotkey.to_shortcut_string(),
-        hold_hotkey.to_shortcut_string(),
-        paste_last_hotkey.to_shortcut_string()
-    );
+    let store = app.store("settings.json").map_err(|e| e.to_string())?;
+    store.set(HOTKEYS_SUSPENDED_KEY, suspended);
+    store.save().map_err(|e| e.to_string())?;
 
-    let shortcuts: Vec<Shortcut> = vec![toggle_shortcut, hold_shortcut, paste_last_shortcut];
+    tray::refresh_tray_status(app);
+    let _ = app.emit("hotkeys-suspended-changed", suspended);
+    Ok(registrations)
+}
 
-    app.global_shortcut()
-        .on_shortcuts(shortcuts, |app, shortcut, event| {
-            handle_shortcut_event(app, shortcut, &event);
-        })?;
+/// Register the configured hotkeys from the store one at a time, so a combo owned
+/// by another application doesn't stop the others from working. Unbound and
+/// disabled actions are skipped, and nothing is registered while hotkeys are
+/// suspended. Assumes no hotkeys are currently registered.
+/// Failures are never fatal: they are stored in `AppState` and emitted as
+/// "shortcut-registration-failed" so the user can pick a different combo.
+#[cfg(desktop)]
+pub(crate) fn register_hotkeys(app: &AppHandle) -> Vec<HotkeyRegistration> {
+    use tauri_plugin_global_shortcut::GlobalShortcutExt;
+
+    if app
+        .state::<AppState>()
+        .hotkeys_suspended
+        .load(Ordering::SeqCst)
+    {
+        log::info!("Hotkeys are suspended, not registering them");
+        return Vec::new();
+    }
+
+    let shortcut_manager = app.global_shortcut();
+    let registrations: Vec<HotkeyRegistration> = HotkeyAction::ALL
+        .into_iter()
+        .filter_map(|action| {
+            let hotkey = configured_hotkey(app, action)?;
+            let shortcut_str = hotkey.to_shortcut_string();
+
+            // Fall back to the default if the stored value is invalid. Modifier-only
+            // bindings are reported as failed instead, so the user sees why.
+            let shortcut = match (hotkey.to_shortcut(), action.default_hotkey()) {
+                (Ok(shortcut), _) => shortcut,
+                (Err(e), Some(default)) if !hotkey.is_modifier_only() => {
+                    log::warn!("{}, using default hotkey", e);
+                    default.to_shortcut().expect("Default hotkey must be valid")
+                }
+                (Err(e), _) => {
+                    log::warn!("Invalid {:?} hotkey {}: {}", action, shortcut_str, e);
+                    return Some(HotkeyRegistration {
+                        action,
+                        shortcut: shortcut_str,
+                        registered: false,
+                        error: Some(e),
+                    });
+                }
+            };
 
-    log::info!("Shortcuts registered successfully");
-    Ok(())
+            let error = shortcut_manager
+                .on_shortcut(shortcut, |app, shortcut, event| {
+                    handle_shortcut_event(app, shortcut, &event);
+                })
+                .err()
+                .map(|e| e.to_string());
+            match &error {
+                None => log::info!("Registered {:?} hotkey: {}", action, shortcut_str),
+                Some(e) => log::warn!(
+                    "Failed to register {:?} hotkey {}: {}",
+                    action,
+                    shortcut_str,
+                    e
+                ),
+            }
+
+            Some(HotkeyRegistration {
+                action,
+                shortcut: shortcut_str,
+                registered: error.is_none(),
+                error,
+            })
+        })
+        .collect();
+
+    let failures = app
+        .state::<AppState>()
+        .set_hotkey_registration_failures(&registrations);
+    if !failures.is_empty() {
+        let _ = app.emit("shortcut-registration-failed", &failures);
+    }
+    registrations
 }
@@ -0,0 +1,202 @@
+//! Log output: stderr plus a size-rotated file in the app log directory, so logs
+//! survive when the app runs detached from a terminal.
+
+use std::fs::{File, OpenOptions};
+use std::io::{self, Write};
+use std::path::{Path, PathBuf};
+use std::sync::{Mutex, RwLock};
+
+/// Name of the current log file in the app log directory
+pub const LOG_FILE_NAME: &str = "tambourine.log";
+
+/// Size at which the log file is rotated
+pub const MAX_LOG_FILE_BYTES: u64 = 5 * 1024 * 1024;
+
+/// Rotated files kept (`tambourine.log.1` is the newest), capping total log size at
+/// (MAX_ROTATED_LOG_FILES + 1) * MAX_LOG_FILE_BYTES
+pub const MAX_ROTATED_LOG_FILES: usize = 3;
+
+/// Store key for the level chosen with `set_log_level`
+pub const LOG_LEVEL_KEY: &str = "log_level";
+
+/// Logger that records are forwarded to; replaced when the level changes
+static LOGGER: RwLock<Option<env_logger::Logger>> = RwLock::new(None);
+
+/// Log file records are also written to, once `attach_log_file` has run
+static LOG_FILE: Mutex<Option<RotatingFile>> = Mutex::new(None);
+
+/// Path of the `index`th rotated copy of `path` (`app.log` -> `app.log.2`)
+pub fn rotated_path(path: &Path, index: usize) -> PathBuf {
+    let mut name = path.as_os_str().to_owned();
+    name.push(format!(".{}", index));
+    PathBuf::from(name)
+}
+
+/// Append-only file that moves itself aside once it reaches `max_bytes`
+pub struct RotatingFile {
+    path: PathBuf,
+    file: File,
+    size: u64,
+    max_bytes: u64,
+    max_rotated: usize,
+}
+
+impl RotatingFile {
+    /// Open (or create) `path` for appending
+    pub fn open(path: PathBuf, max_bytes: u64, max_rotated: usize) -> io::Result<Self> {
+        let file = OpenOptions::new().create(true).append(true).open(&path)?;
+        let size = file.metadata()?.len();
+        Ok(Self {
+            path,
+            file,
+            size,
+            max_bytes,
+            max_rotated,
+        })
+    }
+
+    /// Shift `path.N` to `path.N+1` (dropping the oldest), move `path` to `path.1`
+    /// and start a new empty file
+    fn rotate(&mut self) -> io::Result<()> {
+        self.file.flush()?;
+        if self.max_rotated == 0 {
+            self.file = File::create(&self.path)?;
+            self.size = 0;
+            return Ok(());
+        }
+        let _ = std::fs::remove_file(rotated_path(&self.path, self.max_rotated));
+        for index in (1..self.max_rotated).rev() {
+            let from = rotated_path(&self.path, index);
+            if from.exists() {
+                std::fs::rename(&from, rotated_path(&self.path, index + 1))?;
+            }
+        }
+        std::fs::rename(&self.path, rotated_path(&self.path, 1))?;
+        self.file = OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(&self.path)?;
+        self.size = 0;
+        Ok(())
+    }
+}
+
+impl Write for RotatingFile {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        if self.size > 0 && self.size + buf.len() as u64 > self.max_bytes {
+            self.rotate()?;
+        }
+        self.file.write_all(buf)?;
+        self.size += buf.len() as u64;
+        Ok(buf.len())
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        self.file.flush()
+    }
+}
+
+/// env_logger target writing each record to stderr and the log file
+struct LogWriter;
+
+impl Write for LogWriter {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        let _ = io::stderr().write_all(buf);
+        if let Ok(mut guard) = LOG_FILE.lock() {
+            if let Some(file) = guard.as_mut() {
+                let _ = file.write_all(buf);
+            }
+        }
+        Ok(buf.len())
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        let _ = io::stderr().flush();
+        if let Ok(mut guard) = LOG_FILE.lock() {
+            if let Some(file) = guard.as_mut() {
+                let _ = file.flush();
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Global logger delegating to the current env_logger, so the level can change
+/// at runtime (env_logger's own filter is fixed once built)
+struct AppLogger;
+
+static APP_LOGGER: AppLogger = AppLogger;
+
+impl log::Log for AppLogger {
+    fn enabled(&self, metadata: &log::Metadata) -> bool {
+        LOGGER
+            .read()
+            .ok()
+            .and_then(|guard| guard.as_ref().map(|logger| logger.enabled(metadata)))
+            .unwrap_or(false)
+    }
+
+    fn log(&self, record: &log::Record) {
+        if let Ok(guard) = LOGGER.read() {
+            if let Some(logger) = guard.as_ref() {
+                logger.log(record);
+            }
+        }
+    }
+
+    fn flush(&self) {
+        if let Ok(guard) = LOGGER.read() {
+            if let Some(logger) = guard.as_ref() {
+                logger.flush();
+            }
+        }
+    }
+}
+
+/// Build an env_logger for `level`, or following RUST_LOG (default info) if None
+fn build_logger(level: Option<log::LevelFilter>) -> env_logger::Logger {
+    let mut builder = match level {
+        Some(level) => {
+            let mut builder = env_logger::Builder::new();
+            builder.filter_level(level);
+            builder
+        }
+        None => env_logger::Builder::from_env(env_logger::Env::default().default_filter_or("info")),
+    };
+    builder
+        .target(env_logger::Target::Pipe(Box::new(LogWriter)))
+        .build()
+}
+
+/// Install the logger. Filtering follows RUST_LOG, defaulting to info.
+pub fn init() {
+    set_level(None);
+    if log::set_logger(&APP_LOGGER).is_err() {
+        eprintln!("A logger was already installed");
+    }
+}
+
+/// Whether RUST_LOG was given, in which case it wins over the saved level at startup
+pub fn env_filter_set() -> bool {
+    std::env::var_os(env_logger::DEFAULT_FILTER_ENV).is_some()
+}
+
+/// Log everything at `level` and above, or go back to RUST_LOG (default info) if None
+pub fn set_level(level: Option<log::LevelFilter>) {
+    let logger = build_logger(level);
+    log::set_max_level(logger.filter());
+    if let Ok(mut guard) = LOGGER.write() {
+        *guard = Some(logger);
+    }
+}
+
+/// Start writing log records to LOG_FILE_NAME in `dir`, returning its path
+pub fn attach_log_file(dir: &Path) -> io::Result<PathBuf> {
+    std::fs::create_dir_all(dir)?;
+    let path = dir.join(LOG_FILE_NAME);
+    let file = RotatingFile::open(path.clone(), MAX_LOG_FILE_BYTES, MAX_ROTATED_LOG_FILES)?;
+    if let Ok(mut guard) = LOG_FILE.lock() {
+        *guard = Some(file);
+    }
+    Ok(path)
+}
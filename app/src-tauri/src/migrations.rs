@@ -0,0 +1,210 @@
+//! Schema versions and upgrades for the history file and the settings store.
+//!
+//! Both are loaded as plain JSON first and upgraded one version at a time before
+//! being deserialized, so a change in shape fills in or renames values instead of
+//! failing to load and falling back to defaults. Each applied step is logged.
+
+use crate::profiles::{SettingsMap, PROFILES_KEY};
+use crate::settings::{HotkeyAction, HotkeyConfig};
+use serde_json::Value;
+
+/// Current version of the history file (the `version` field in `history.json`)
+pub const HISTORY_SCHEMA_VERSION: u32 = 1;
+
+/// Current version of the settings store
+pub const SETTINGS_SCHEMA_VERSION: u32 = 1;
+
+/// Store key holding the version the settings were last migrated to
+pub const SETTINGS_VERSION_KEY: &str = "settings_version";
+
+/// One upgrade step, from `to_version - 1` to `to_version`
+struct Migration {
+    to_version: u32,
+    description: &'static str,
+    apply: fn(&mut SettingsMap),
+}
+
+const HISTORY_MIGRATIONS: &[Migration] = &[Migration {
+    to_version: 1,
+    description: "fill in missing entry ids, timestamps and text",
+    apply: history_v1,
+}];
+
+const SETTINGS_MIGRATIONS: &[Migration] = &[Migration {
+    to_version: 1,
+    description: "convert hotkeys stored as shortcut strings to hotkey configs",
+    apply: settings_v1,
+}];
+
+/// Apply every step newer than `from` to `data`, logging each one.
+/// Returns the version `data` ends up at.
+fn run_migrations(
+    name: &str,
+    data: &mut SettingsMap,
+    from: u32,
+    migrations: &[Migration],
+    current: u32,
+) -> u32 {
+    if from > current {
+        log::warn!(
+            "{} schema version {} is newer than this app supports ({}); loading as is",
+            name,
+            from,
+            current
+        );
+        return from;
+    }
+    let mut version = from;
+    for migration in migrations.iter().filter(|m| m.to_version > from) {
+        (migration.apply)(data);
+        version = migration.to_version;
+        log::info!(
+            "Migrated {} to schema version {}: {}",
+            name,
+            version,
+            migration.description
+        );
+    }
+    version
+}
+
+/// Read a schema version, treating a missing or malformed one as 0 (unversioned)
+pub fn read_version(value: Option<&Value>) -> u32 {
+    value
+        .and_then(Value::as_u64)
+        .and_then(|v| u32::try_from(v).ok())
+        .unwrap_or(0)
+}
+
+/// Upgrade a parsed `history.json` to `HISTORY_SCHEMA_VERSION` and stamp its version.
+/// Returns whether any migration was applied.
+pub fn migrate_history(data: &mut Value) -> Result<bool, String> {
+    let Value::Object(map) = data else {
+        return Err("History file is not a JSON object".to_string());
+    };
+    let from = read_version(map.get("version"));
+    let version = run_migrations(
+        "History",
+        map,
+        from,
+        HISTORY_MIGRATIONS,
+        HISTORY_SCHEMA_VERSION,
+    );
+    map.insert("version".to_string(), Value::from(version));
+    Ok(version != from)
+}
+
+/// Entries are required to have an id, timestamp and text. Give entries written
+/// without one a fresh id, the Unix epoch and empty text rather than dropping the
+/// whole file.
+fn history_v1(data: &mut SettingsMap) {
+    let entries = data
+        .entry("entries")
+        .or_insert_with(|| Value::Array(Vec::new()));
+    let Value::Array(entries) = entries else {
+        return;
+    };
+    for entry in entries.iter_mut().filter_map(Value::as_object_mut) {
+        if !entry.get("id").is_some_and(Value::is_string) {
+            entry.insert(
+                "id".to_string(),
+                Value::from(uuid::Uuid::new_v4().to_string()),
+            );
+        }
+        if !entry.get("timestamp").is_some_and(Value::is_string) {
+            entry.insert(
+                "timestamp".to_string(),
+                Value::from(chrono::DateTime::UNIX_EPOCH.to_rfc3339()),
+            );
+        }
+        if !entry.get("text").is_some_and(Value::is_string) {
+            entry.insert("text".to_string(), Value::from(""));
+        }
+    }
+}
+
+/// Upgrade a settings map (the live settings or a profile snapshot, named `name`
+/// in the log) from `from` to `SETTINGS_SCHEMA_VERSION`. Returns the version reached.
+pub fn migrate_settings(name: &str, settings: &mut SettingsMap, from: u32) -> u32 {
+    run_migrations(
+        name,
+        settings,
+        from,
+        SETTINGS_MIGRATIONS,
+        SETTINGS_SCHEMA_VERSION,
+    )
+}
+
+/// Hotkeys are stored as `HotkeyConfig` objects; turn any saved as a shortcut
+/// string (e.g. "ctrl+alt+Space") into one
+fn settings_v1(settings: &mut SettingsMap) {
+    for action in HotkeyAction::ALL {
+        let Some(Value::String(shortcut)) = settings.get(action.store_key()).cloned() else {
+            continue;
+        };
+        match HotkeyConfig::from_shortcut_string(&shortcut)
+            .and_then(|config| serde_json::to_value(config).map_err(|e| e.to_string()))
+        {
+            Ok(value) => {
+                settings.insert(action.store_key().to_string(), value);
+            }
+            Err(e) => {
+                log::warn!(
+                    "Dropping unparseable {} '{}': {}",
+                    action.store_key(),
+                    shortcut,
+                    e
+                );
+                settings.remove(action.store_key());
+            }
+        }
+    }
+}
+
+/// Migrate the settings store and every profile snapshot in it, then record the
+/// new version. Returns whether anything was migrated.
+pub fn migrate_settings_store(app: &tauri::AppHandle) -> Result<bool, String> {
+    use tauri_plugin_store::StoreExt;
+
+    let store = app.store("settings.json").map_err(|e| e.to_string())?;
+    let from = read_version(store.get(SETTINGS_VERSION_KEY).as_ref());
+    if from >= SETTINGS_SCHEMA_VERSION {
+        if from > SETTINGS_SCHEMA_VERSION {
+            log::warn!(
+                "Settings schema version {} is newer than this app supports ({})",
+                from,
+                SETTINGS_SCHEMA_VERSION
+            );
+        }
+        return Ok(false);
+    }
+
+    let mut live: SettingsMap = store
+        .entries()
+        .into_iter()
+        .filter(|(key, _)| !crate::profiles::is_profile_key(key))
+        .collect();
+    let before = live.clone();
+    let version = migrate_settings("Settings", &mut live, from);
+    for key in before.keys().filter(|key| !live.contains_key(*key)) {
+        store.delete(key);
+    }
+    for (key, value) in live {
+        if before.get(&key) != Some(&value) {
+            store.set(key, value);
+        }
+    }
+
+    if let Some(Value::Object(mut profiles)) = store.get(PROFILES_KEY) {
+        for (name, snapshot) in profiles.iter_mut() {
+            if let Value::Object(snapshot) = snapshot {
+                migrate_settings(&format!("Profile '{}'", name), snapshot, from);
+            }
+        }
+        store.set(PROFILES_KEY, Value::Object(profiles));
+    }
+
+    store.set(SETTINGS_VERSION_KEY, version);
+    store.save().map_err(|e| e.to_string())?;
+    Ok(true)
+}
@@ -0,0 +1,199 @@
+//! Best-effort detection of the OS permissions dictation depends on.
+//!
+//! Typing text needs Accessibility access on macOS; recording needs microphone
+//! access on macOS and Windows. Where a permission can't be determined, or the
+//! platform has no such permission, that is reported instead of guessing.
+
+use serde::{Deserialize, Serialize};
+
+/// State of a single OS permission
+#[derive(Debug, Clone, Copy, Serialize, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum PermissionState {
+    Granted,
+    Denied,
+    /// The user hasn't been asked yet (macOS microphone)
+    #[cfg_attr(not(target_os = "macos"), allow(dead_code))]
+    NotDetermined,
+    /// The platform has the permission but it couldn't be read
+    Unknown,
+    /// The platform has no such permission
+    NotApplicable,
+}
+
+impl PermissionState {
+    /// Check if this permission may stop the app from working
+    pub fn needs_attention(self) -> bool {
+        matches!(self, Self::Denied | Self::NotDetermined)
+    }
+}
+
+/// Permissions needed for dictation
+#[derive(Debug, Clone, Copy, Serialize, PartialEq, Eq)]
+pub struct PermissionStatus {
+    /// Needed to type or paste into other applications
+    pub accessibility: PermissionState,
+    /// Needed to record audio
+    pub microphone: PermissionState,
+}
+
+/// A permission whose settings pane can be opened
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum Permission {
+    Accessibility,
+    Microphone,
+}
+
+/// Parse the `Value` line of `reg query ...\ConsentStore\microphone /v Value`
+#[cfg_attr(not(target_os = "windows"), allow(dead_code))] // Only called on Windows; tested everywhere
+pub fn parse_consent_value(output: &str) -> PermissionState {
+    output
+        .lines()
+        .find(|line| line.trim_start().starts_with("Value") && line.contains("REG_SZ"))
+        .and_then(|line| line.split_whitespace().last())
+        .map(|value| match value {
+            "Allow" => PermissionState::Granted,
+            "Deny" => PermissionState::Denied,
+            _ => PermissionState::Unknown,
+        })
+        .unwrap_or(PermissionState::Unknown)
+}
+
+#[cfg(target_os = "macos")]
+mod platform {
+    use super::{Permission, PermissionState};
+    use block2::RcBlock;
+    use objc2::runtime::Bool;
+    use objc2_av_foundation::{AVAuthorizationStatus, AVCaptureDevice, AVMediaTypeAudio};
+
+    #[link(name = "ApplicationServices", kind = "framework")]
+    extern "C" {
+        fn AXIsProcessTrusted() -> bool;
+    }
+
+    pub fn accessibility() -> PermissionState {
+        if unsafe { AXIsProcessTrusted() } {
+            PermissionState::Granted
+        } else {
+            PermissionState::Denied
+        }
+    }
+
+    pub fn microphone() -> PermissionState {
+        let Some(media_type) = (unsafe { AVMediaTypeAudio }) else {
+            return PermissionState::Unknown;
+        };
+        match unsafe { AVCaptureDevice::authorizationStatusForMediaType(media_type) } {
+            AVAuthorizationStatus::Authorized => PermissionState::Granted,
+            AVAuthorizationStatus::Denied | AVAuthorizationStatus::Restricted => {
+                PermissionState::Denied
+            }
+            AVAuthorizationStatus::NotDetermined => PermissionState::NotDetermined,
+            _ => PermissionState::Unknown,
+        }
+    }
+
+    pub fn prompt_microphone() -> bool {
+        let Some(media_type) = (unsafe { AVMediaTypeAudio }) else {
+            return false;
+        };
+        let handler = RcBlock::new(|granted: Bool| {
+            log::info!("Microphone access granted: {}", granted.as_bool());
+        });
+        unsafe {
+            AVCaptureDevice::requestAccessForMediaType_completionHandler(media_type, &handler)
+        };
+        true
+    }
+
+    pub fn settings_url(permission: Permission) -> Option<&'static str> {
+        Some(match permission {
+            Permission::Accessibility => {
+                "x-apple.systempreferences:com.apple.preference.security?Privacy_Accessibility"
+            }
+            Permission::Microphone => {
+                "x-apple.systempreferences:com.apple.preference.security?Privacy_Microphone"
+            }
+        })
+    }
+}
+
+#[cfg(target_os = "windows")]
+mod platform {
+    use super::{parse_consent_value, Permission, PermissionState};
+    use std::process::Command;
+
+    const MICROPHONE_CONSENT_KEY: &str = r"HKCU\Software\Microsoft\Windows\CurrentVersion\CapabilityAccessManager\ConsentStore\microphone";
+
+    pub fn accessibility() -> PermissionState {
+        PermissionState::NotApplicable
+    }
+
+    pub fn microphone() -> PermissionState {
+        match Command::new("reg")
+            .args(["query", MICROPHONE_CONSENT_KEY, "/v", "Value"])
+            .output()
+        {
+            Ok(output) if output.status.success() => {
+                parse_consent_value(&String::from_utf8_lossy(&output.stdout))
+            }
+            Ok(_) => PermissionState::Unknown,
+            Err(e) => {
+                log::warn!("Failed to query microphone consent: {}", e);
+                PermissionState::Unknown
+            }
+        }
+    }
+
+    pub fn prompt_microphone() -> bool {
+        false
+    }
+
+    pub fn settings_url(permission: Permission) -> Option<&'static str> {
+        match permission {
+            Permission::Accessibility => None,
+            Permission::Microphone => Some("ms-settings:privacy-microphone"),
+        }
+    }
+}
+
+#[cfg(not(any(target_os = "macos", target_os = "windows")))]
+mod platform {
+    use super::{Permission, PermissionState};
+
+    pub fn accessibility() -> PermissionState {
+        PermissionState::NotApplicable
+    }
+
+    pub fn microphone() -> PermissionState {
+        PermissionState::NotApplicable
+    }
+
+    pub fn prompt_microphone() -> bool {
+        false
+    }
+
+    pub fn settings_url(_permission: Permission) -> Option<&'static str> {
+        None
+    }
+}
+
+/// Read the current permission states
+pub fn check() -> PermissionStatus {
+    PermissionStatus {
+        accessibility: platform::accessibility(),
+        microphone: platform::microphone(),
+    }
+}
+
+/// Show the OS prompt asking for microphone access. Only possible while the user
+/// hasn't been asked yet, and only on macOS; returns false if no prompt was shown.
+pub fn prompt_microphone_access() -> bool {
+    platform::prompt_microphone()
+}
+
+/// System settings URL where `permission` is granted, if the platform has one
+pub fn settings_url(permission: Permission) -> Option<&'static str> {
+    platform::settings_url(permission)
+}
@@ -0,0 +1,116 @@
+//! Named settings profiles.
+//!
+//! The live settings stay at the top level of the store, so the rest of the app
+//! reads them as usual. Each profile keeps a snapshot of those settings; switching
+//! saves the live settings into the active profile and loads the target's snapshot.
+
+use serde::Serialize;
+use std::collections::BTreeMap;
+
+/// Store key holding every profile's settings snapshot
+pub const PROFILES_KEY: &str = "profiles";
+
+/// Store key holding the name of the active profile
+pub const ACTIVE_PROFILE_KEY: &str = "active_profile";
+
+/// Name of the profile used before any profile has been created
+pub const DEFAULT_PROFILE_NAME: &str = "Default";
+
+/// A flat map of setting keys to values, as stored in `settings.json`
+pub type SettingsMap = serde_json::Map<String, serde_json::Value>;
+
+/// Check if a store key holds bookkeeping (profiles or the settings schema version)
+/// rather than a setting
+pub fn is_profile_key(key: &str) -> bool {
+    key == PROFILES_KEY
+        || key == ACTIVE_PROFILE_KEY
+        || key == crate::migrations::SETTINGS_VERSION_KEY
+}
+
+/// A profile as shown in the settings UI
+#[derive(Debug, Clone, Serialize, PartialEq, Eq)]
+pub struct ProfileSummary {
+    pub name: String,
+    pub is_active: bool,
+}
+
+/// All profiles and which one is active
+#[derive(Debug, Clone, PartialEq)]
+pub struct Profiles {
+    pub active: String,
+    /// Settings snapshot per profile. The active profile's snapshot may be stale;
+    /// its current values are the live settings.
+    pub snapshots: BTreeMap<String, SettingsMap>,
+}
+
+impl Profiles {
+    /// Build from stored values, ensuring the active profile exists
+    pub fn new(active: Option<String>, mut snapshots: BTreeMap<String, SettingsMap>) -> Self {
+        let active = active
+            .filter(|name| !name.trim().is_empty())
+            .unwrap_or_else(|| DEFAULT_PROFILE_NAME.to_string());
+        snapshots.entry(active.clone()).or_default();
+        Self { active, snapshots }
+    }
+
+    /// Profiles sorted by name
+    pub fn summaries(&self) -> Vec<ProfileSummary> {
+        self.snapshots
+            .keys()
+            .map(|name| ProfileSummary {
+                name: name.clone(),
+                is_active: *name == self.active,
+            })
+            .collect()
+    }
+
+    /// Create a profile starting from the live settings. Returns the trimmed name.
+    pub fn create(&mut self, name: &str, live: &SettingsMap) -> Result<String, String> {
+        let name = normalize_profile_name(name)?;
+        if self.snapshots.contains_key(&name) {
+            return Err(format!("Profile '{}' already exists", name));
+        }
+        self.snapshots.insert(name.clone(), live.clone());
+        Ok(name)
+    }
+
+    /// Make `name` the active profile. The live settings are saved into the
+    /// currently active profile, and the target profile's settings are returned
+    /// to become the new live settings.
+    pub fn switch(&mut self, name: &str, live: SettingsMap) -> Result<SettingsMap, String> {
+        let name = normalize_profile_name(name)?;
+        let target = self
+            .snapshots
+            .get(&name)
+            .cloned()
+            .ok_or_else(|| format!("Profile '{}' not found", name))?;
+
+        self.snapshots.insert(self.active.clone(), live);
+        self.active = name;
+        Ok(target)
+    }
+
+    /// Delete a profile other than the active one
+    pub fn delete(&mut self, name: &str) -> Result<(), String> {
+        let name = normalize_profile_name(name)?;
+        if name == self.active {
+            return Err(format!(
+                "Cannot delete the active profile '{}'; switch to another profile first",
+                name
+            ));
+        }
+        self.snapshots
+            .remove(&name)
+            .map(drop)
+            .ok_or_else(|| format!("Profile '{}' not found", name))
+    }
+}
+
+/// Trim a profile name, rejecting empty names
+fn normalize_profile_name(name: &str) -> Result<String, String> {
+    let name = name.trim();
+    if name.is_empty() {
+        return Err("Profile name cannot be empty".to_string());
+    }
+    Ok(name.to_string())
+}
@@ -0,0 +1,316 @@
+use chrono::{DateTime, Utc};
+use serde::Serialize;
+use std::fs;
+use std::path::PathBuf;
+use tauri::AppHandle;
+
+/// Default number of saved recordings to keep on disk
+pub const DEFAULT_MAX_SAVED_RECORDINGS: usize = 20;
+
+/// A recording saved to disk as a WAV file
+#[derive(Debug, Clone, Serialize)]
+pub struct SavedRecording {
+    pub file_name: String,
+    pub path: String,
+    pub created_at: DateTime<Utc>,
+    pub size_bytes: u64,
+}
+
+/// Default sample rate recordings are converted to; most speech models expect 16kHz
+pub const DEFAULT_SAMPLE_RATE: u32 = 16_000;
+
+/// Default channel count recordings are converted to (mono)
+pub const DEFAULT_CHANNELS: u16 = 1;
+
+/// Lowest supported target sample rate (telephone quality)
+pub const MIN_SAMPLE_RATE: u32 = 8_000;
+
+/// Highest supported target sample rate
+pub const MAX_SAMPLE_RATE: u32 = 48_000;
+
+/// Highest supported channel count (stereo)
+pub const MAX_CHANNELS: u16 = 2;
+
+/// Header carrying the sample rate of a raw `save_recording` body
+pub const SAMPLE_RATE_HEADER: &str = "x-sample-rate";
+
+/// Header carrying the channel count of a raw `save_recording` body
+pub const CHANNELS_HEADER: &str = "x-channels";
+
+/// Decode raw little-endian 16-bit PCM, as sent by the overlay
+pub fn decode_pcm16le(bytes: &[u8]) -> Result<Vec<i16>, String> {
+    if !bytes.len().is_multiple_of(2) {
+        return Err(format!(
+            "Recording has an odd number of bytes ({}), expected 16-bit samples",
+            bytes.len()
+        ));
+    }
+    Ok(bytes
+        .chunks_exact(2)
+        .map(|pair| i16::from_le_bytes([pair[0], pair[1]]))
+        .collect())
+}
+
+/// Parse the format of captured audio from the `save_recording` headers.
+/// The sample rate is required; the channel count defaults to mono.
+pub fn parse_captured_format(
+    sample_rate: Option<&str>,
+    channels: Option<&str>,
+) -> Result<AudioFormat, String> {
+    let sample_rate = sample_rate
+        .ok_or_else(|| format!("Missing {} header", SAMPLE_RATE_HEADER))?
+        .trim()
+        .parse::<u32>()
+        .map_err(|e| format!("Invalid {} header: {}", SAMPLE_RATE_HEADER, e))?;
+    if sample_rate == 0 {
+        return Err("Recording sample rate must be positive".to_string());
+    }
+    let channels = match channels {
+        Some(value) => value
+            .trim()
+            .parse::<u16>()
+            .map_err(|e| format!("Invalid {} header: {}", CHANNELS_HEADER, e))?,
+        None => 1,
+    };
+    Ok(AudioFormat {
+        sample_rate,
+        channels: channels.max(1),
+    })
+}
+
+/// Sample rate and channel count of interleaved 16-bit PCM
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct AudioFormat {
+    pub sample_rate: u32,
+    pub channels: u16,
+}
+
+impl Default for AudioFormat {
+    fn default() -> Self {
+        Self {
+            sample_rate: DEFAULT_SAMPLE_RATE,
+            channels: DEFAULT_CHANNELS,
+        }
+    }
+}
+
+impl AudioFormat {
+    /// Read the target `sample_rate` and `channels` from the store, ignoring
+    /// out-of-range values
+    pub fn from_store(app: &AppHandle) -> Self {
+        let format = Self {
+            sample_rate: crate::get_setting_from_store(app, "sample_rate", DEFAULT_SAMPLE_RATE),
+            channels: crate::get_setting_from_store(app, "channels", DEFAULT_CHANNELS),
+        };
+        match validate_audio_format(format) {
+            Ok(()) => format,
+            Err(e) => {
+                log::warn!("{}, using the default audio format", e);
+                Self::default()
+            }
+        }
+    }
+}
+
+/// Check that a target format is within MIN_SAMPLE_RATE..=MAX_SAMPLE_RATE and 1..=MAX_CHANNELS
+pub fn validate_audio_format(format: AudioFormat) -> Result<(), String> {
+    if !(MIN_SAMPLE_RATE..=MAX_SAMPLE_RATE).contains(&format.sample_rate) {
+        return Err(format!(
+            "Sample rate must be between {} and {} Hz, got {}",
+            MIN_SAMPLE_RATE, MAX_SAMPLE_RATE, format.sample_rate
+        ));
+    }
+    if !(1..=MAX_CHANNELS).contains(&format.channels) {
+        return Err(format!(
+            "Channel count must be between 1 and {}, got {}",
+            MAX_CHANNELS, format.channels
+        ));
+    }
+    Ok(())
+}
+
+/// Resample one channel. Downsampling averages each output sample's span of
+/// input (so content above the new Nyquist frequency is attenuated rather than
+/// aliased); upsampling interpolates linearly. Duration, and so pitch, is kept.
+fn resample_channel(input: &[f32], from_rate: u32, to_rate: u32) -> Vec<f32> {
+    if from_rate == to_rate || input.is_empty() {
+        return input.to_vec();
+    }
+    let ratio = f64::from(from_rate) / f64::from(to_rate);
+    let output_len = (input.len() as f64 / ratio).round() as usize;
+
+    (0..output_len)
+        .map(|i| {
+            let start = i as f64 * ratio;
+            if ratio > 1.0 {
+                let end = start + ratio;
+                let mut sum = 0.0;
+                let mut weight = 0.0;
+                let mut j = start.floor() as usize;
+                while j < input.len() && (j as f64) < end {
+                    let overlap = end.min(j as f64 + 1.0) - start.max(j as f64);
+                    sum += f64::from(input[j]) * overlap;
+                    weight += overlap;
+                    j += 1;
+                }
+                if weight > 0.0 {
+                    (sum / weight) as f32
+                } else {
+                    0.0
+                }
+            } else {
+                let j = (start.floor() as usize).min(input.len() - 1);
+                let next = input[(j + 1).min(input.len() - 1)];
+                let frac = (start - j as f64) as f32;
+                input[j] + (next - input[j]) * frac
+            }
+        })
+        .collect()
+}
+
+/// Convert interleaved samples from one format to another. Downmixing to mono
+/// averages the channels; upmixing copies the last source channel. A trailing
+/// partial frame is dropped.
+pub fn convert_audio(samples: &[i16], from: AudioFormat, to: AudioFormat) -> Vec<i16> {
+    if from == to {
+        return samples.to_vec();
+    }
+    let from_channels = usize::from(from.channels.max(1));
+    let to_channels = usize::from(to.channels.max(1));
+    let frames = samples.chunks_exact(from_channels);
+
+    let mut channels: Vec<Vec<f32>> = vec![Vec::with_capacity(frames.len()); to_channels];
+    for frame in frames {
+        if to_channels == 1 {
+            let sum: f32 = frame.iter().map(|&s| f32::from(s)).sum();
+            channels[0].push(sum / from_channels as f32);
+        } else {
+            for (c, channel) in channels.iter_mut().enumerate() {
+                channel.push(f32::from(frame[c.min(from_channels - 1)]));
+            }
+        }
+    }
+
+    let channels: Vec<Vec<f32>> = channels
+        .iter()
+        .map(|channel| resample_channel(channel, from.sample_rate, to.sample_rate))
+        .collect();
+    let output_frames = channels[0].len();
+
+    let mut output = Vec::with_capacity(output_frames * to_channels);
+    for i in 0..output_frames {
+        for channel in &channels {
+            output.push(
+                channel[i]
+                    .round()
+                    .clamp(f32::from(i16::MIN), f32::from(i16::MAX)) as i16,
+            );
+        }
+    }
+    output
+}
+
+/// Encode interleaved 16-bit PCM samples as a WAV file
+pub fn encode_wav(samples: &[i16], format: AudioFormat) -> Vec<u8> {
+    const BITS_PER_SAMPLE: u16 = 16;
+    let AudioFormat {
+        sample_rate,
+        channels,
+    } = format;
+    let block_align = channels * BITS_PER_SAMPLE / 8;
+    let byte_rate = sample_rate * block_align as u32;
+    let data_len = (samples.len() * 2) as u32;
+
+    let mut wav = Vec::with_capacity(44 + data_len as usize);
+    wav.extend_from_slice(b"RIFF");
+    wav.extend_from_slice(&(36 + data_len).to_le_bytes());
+    wav.extend_from_slice(b"WAVE");
+    wav.extend_from_slice(b"fmt ");
+    wav.extend_from_slice(&16u32.to_le_bytes());
+    wav.extend_from_slice(&1u16.to_le_bytes()); // PCM
+    wav.extend_from_slice(&channels.to_le_bytes());
+    wav.extend_from_slice(&sample_rate.to_le_bytes());
+    wav.extend_from_slice(&byte_rate.to_le_bytes());
+    wav.extend_from_slice(&block_align.to_le_bytes());
+    wav.extend_from_slice(&BITS_PER_SAMPLE.to_le_bytes());
+    wav.extend_from_slice(b"data");
+    wav.extend_from_slice(&data_len.to_le_bytes());
+    for sample in samples {
+        wav.extend_from_slice(&sample.to_le_bytes());
+    }
+    wav
+}
+
+/// Manages saved audio recordings in the app data directory
+pub struct RecordingStorage {
+    dir: PathBuf,
+}
+
+impl RecordingStorage {
+    /// Create a new recording storage with the given app data directory
+    pub fn new(app_data_dir: PathBuf) -> Self {
+        Self {
+            dir: app_data_dir.join("recordings"),
+        }
+    }
+
+    /// Save samples as a timestamped WAV file
+    pub fn save_wav(&self, samples: &[i16], format: AudioFormat) -> Result<SavedRecording, String> {
+        fs::create_dir_all(&self.dir)
+            .map_err(|e| format!("Failed to create recordings directory: {}", e))?;
+
+        let created_at = Utc::now();
+        // Timestamped names sort chronologically
+        let file_name = format!("recording-{}.wav", created_at.format("%Y%m%d-%H%M%S-%3f"));
+        let path = self.dir.join(&file_name);
+        let wav = encode_wav(samples, format);
+
+        fs::write(&path, &wav).map_err(|e| format!("Failed to write recording: {}", e))?;
+
+        Ok(SavedRecording {
+            file_name,
+            path: path.to_string_lossy().to_string(),
+            created_at,
+            size_bytes: wav.len() as u64,
+        })
+    }
+
+    /// List saved recordings, newest first
+    pub fn list(&self) -> Result<Vec<SavedRecording>, String> {
+        let entries = match fs::read_dir(&self.dir) {
+            Ok(entries) => entries,
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(Vec::new()),
+            Err(e) => return Err(format!("Failed to read recordings directory: {}", e)),
+        };
+
+        let mut recordings: Vec<SavedRecording> = entries
+            .filter_map(|entry| entry.ok())
+            .filter(|entry| entry.path().extension().is_some_and(|ext| ext == "wav"))
+            .filter_map(|entry| {
+                let metadata = entry.metadata().ok()?;
+                Some(SavedRecording {
+                    file_name: entry.file_name().to_string_lossy().to_string(),
+                    path: entry.path().to_string_lossy().to_string(),
+                    created_at: metadata.modified().ok()?.into(),
+                    size_bytes: metadata.len(),
+                })
+            })
+            .collect();
+
+        recordings.sort_by(|a, b| b.file_name.cmp(&a.file_name));
+        Ok(recordings)
+    }
+
+    /// Delete all but the newest `keep` recordings, returning how many were removed
+    pub fn cleanup(&self, keep: usize) -> Result<usize, String> {
+        let stale = self.list()?.into_iter().skip(keep);
+        let mut removed = 0;
+        for recording in stale {
+            match fs::remove_file(&recording.path) {
+                Ok(()) => removed += 1,
+                Err(e) => log::warn!("Failed to delete recording {}: {}", recording.file_name, e),
+            }
+        }
+        Ok(removed)
+    }
+}
@@ -1,4 +1,26 @@
+use crate::commands::overlay::OverlayPosition;
+use crate::commands::server::{server_request_headers, validate_header_name, validate_server_url};
+use crate::control_server::{
+    CONTROL_SERVER_ENABLED_KEY, CONTROL_SERVER_PORT_KEY, CONTROL_SERVER_TOKEN_KEY,
+};
+use crate::history::HISTORY_STORAGE_PATH_KEY;
+use crate::input_processing::{
+    validate_input_gain, validate_noise_gate_threshold, INPUT_GAIN_KEY, NOISE_GATE_THRESHOLD_KEY,
+};
+use crate::profiles::{SettingsMap, ACTIVE_PROFILE_KEY, PROFILES_KEY};
+use crate::recordings::{validate_audio_format, AudioFormat};
+use crate::target_app::TARGET_APP_KEY;
+use crate::text_processing::{
+    normalize_vocabulary, validate_rules, validate_timestamp_format, ReplacementRule,
+    TIMESTAMP_FORMAT_KEY,
+};
+use crate::voice_commands::{validate_commands, VoiceCommand};
+use crate::webhook::{
+    validate_webhook_secret, validate_webhook_url, TRANSCRIPTION_WEBHOOK_SECRET_KEY,
+    TRANSCRIPTION_WEBHOOK_URL_KEY,
+};
 use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
 use std::str::FromStr;
 
 #[cfg(desktop)]
@@ -20,15 +42,277 @@ pub const DEFAULT_HOLD_KEY: &str = "Backquote";
 /// Default key for paste last transcription (Ctrl+Alt+.)
 pub const DEFAULT_PASTE_LAST_KEY: &str = "Period";
 
+/// Default key for cancelling an in-progress recording (Escape, only registered while recording)
+pub const DEFAULT_CANCEL_KEY: &str = "Escape";
+
+/// Default window for a double-tap of the toggle hotkey
+pub const DEFAULT_DOUBLE_TAP_WINDOW_MS: u64 = 400;
+
+/// Default minimum time the toggle hotkey must be held. Some keyboards report a
+/// spurious press/release pair a few milliseconds apart, which would otherwise
+/// stop a recording right after it started.
+pub const DEFAULT_TOGGLE_DEBOUNCE_MS: u64 = 50;
+
+/// Default delay between a hold-hotkey press and the start of capture
+pub const DEFAULT_HOLD_START_DELAY_MS: u64 = 100;
+
+/// Longest accepted delay between a hold-hotkey press and the start of capture
+pub const MAX_HOLD_START_DELAY_MS: u64 = 1000;
+
+/// Default time capture continues after the hold hotkey is released
+pub const DEFAULT_HOLD_STOP_GRACE_MS: u64 = 300;
+
+/// Default minimum hold duration; shorter presses are discarded as accidental taps
+pub const DEFAULT_MIN_RECORDING_MS: u64 = 300;
+
+/// In `Auto` insertion mode, text longer than this many characters is pasted instead of typed
+pub const AUTO_PASTE_THRESHOLD_CHARS: usize = 40;
+
+/// Default horizontal distance between the overlay and the screen edge
+pub const DEFAULT_OVERLAY_MARGIN_X: f64 = 100.0;
+
+/// Default vertical distance between the overlay and the screen edge
+pub const DEFAULT_OVERLAY_MARGIN_Y: f64 = 50.0;
+
+/// Default overlay opacity (fully opaque)
+pub const DEFAULT_OVERLAY_OPACITY: f64 = 1.0;
+
+/// Lowest overlay opacity, so the overlay can't be made invisible by accident
+pub const MIN_OVERLAY_OPACITY: f64 = 0.1;
+
+/// Store key for the overlay size preset
+pub const OVERLAY_SIZE_KEY: &str = "overlay_size";
+
+/// Default volume (percent of full scale) system audio is lowered to in `Duck` mute mode
+pub const DEFAULT_DUCK_LEVEL: u8 = 20;
+
+/// Default notification sound volume (0.0 = silent, 1.0 = full)
+pub const DEFAULT_SOUND_VOLUME: f32 = 1.0;
+
+/// Transcription language used when none (or an unknown one) is configured
+pub const DEFAULT_LANGUAGE: &str = "auto";
+
+/// ISO 639-1 language codes accepted for transcription ("auto" = let the server detect)
+pub const SUPPORTED_LANGUAGES: &[&str] = &[
+    "auto", "ar", "bg", "ca", "cs", "da", "de", "el", "en", "es", "et", "fi", "fr", "he", "hi",
+    "hr", "hu", "id", "it", "ja", "ko", "lt", "lv", "ms", "nl", "no", "pl", "pt", "ro", "ru", "sk",
+    "sl", "sr", "sv", "th", "tr", "uk", "vi", "zh",
+];
+
+/// Normalize a language code to a supported ISO 639-1 code.
+/// Region suffixes are dropped ("de-AT" -> "de"); unknown codes fall back to "auto".
+pub fn normalize_language(code: &str) -> String {
+    let base = code
+        .trim()
+        .split(['-', '_'])
+        .next()
+        .unwrap_or_default()
+        .to_lowercase();
+    if SUPPORTED_LANGUAGES.contains(&base.as_str()) {
+        base
+    } else {
+        DEFAULT_LANGUAGE.to_string()
+    }
+}
+
 // ============================================================================
 
-/// Configuration for a hotkey combination
+/// Screen corner (or center) the overlay is placed relative to
+#[derive(Debug, Clone, Copy, Default, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum OverlayAnchor {
+    TopLeft,
+    TopRight,
+    BottomLeft,
+    #[default]
+    BottomRight,
+    Center,
+}
+
+/// When the overlay window is shown
+#[derive(Debug, Clone, Copy, Default, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum OverlayVisibility {
+    /// Always visible
+    #[default]
+    Always,
+    /// Shown while recording or transcribing, hidden shortly after
+    WhenActive,
+    /// Never shown (recording still works)
+    Never,
+}
+
+/// Color scheme of the overlay
+#[derive(Debug, Clone, Copy, Default, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum OverlayTheme {
+    Light,
+    #[default]
+    Dark,
+    /// Follow the OS appearance (dark if it can't be detected)
+    Auto,
+}
+
+/// Size preset of the overlay. Its content is scaled and the window follows.
+#[derive(Debug, Clone, Copy, Default, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum OverlaySize {
+    Small,
+    #[default]
+    Medium,
+    Large,
+}
+
+impl OverlaySize {
+    /// Scale applied to the overlay's content (`Medium` is the original size)
+    pub fn scale(self) -> f64 {
+        match self {
+            OverlaySize::Small => 0.75,
+            OverlaySize::Medium => 1.0,
+            OverlaySize::Large => 1.5,
+        }
+    }
+
+    /// Side of the idle overlay in logical pixels: 36, 48 or 72
+    pub fn idle_size(self) -> f64 {
+        48.0 * self.scale()
+    }
+}
+
+/// How transcribed text is inserted into the focused application
+#[derive(Debug, Clone, Copy, Default, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum InsertionMethod {
+    /// Simulate individual keystrokes
+    Type,
+    /// Write to the clipboard and simulate Cmd/Ctrl+V
+    #[default]
+    Paste,
+    /// Paste long text, type short snippets
+    Auto,
+}
+
+impl InsertionMethod {
+    /// Resolve `Auto` into a concrete method for the given text. Text containing a
+    /// character that can't be typed is always pasted, so it isn't inserted garbled.
+    pub fn resolve(self, text: &str) -> Self {
+        match self {
+            Self::Type | Self::Auto if untypeable_char(text).is_some() => Self::Paste,
+            Self::Auto if text.chars().count() > AUTO_PASTE_THRESHOLD_CHARS => Self::Paste,
+            Self::Auto => Self::Type,
+            method => method,
+        }
+    }
+}
+
+/// First character keystroke simulation can't reproduce: control characters other
+/// than newline, carriage return and tab, and Unicode noncharacters. Letters from any
+/// script, punctuation and emoji are all typed as Unicode text.
+pub fn untypeable_char(text: &str) -> Option<char> {
+    text.chars().find(|&c| {
+        let is_noncharacter = matches!(c, '\u{FDD0}'..='\u{FDEF}') || (c as u32) & 0xFFFE == 0xFFFE;
+        (c.is_control() && !matches!(c, '\n' | '\r' | '\t')) || is_noncharacter
+    })
+}
+
+/// How the toggle hotkey activates recording
+#[derive(Debug, Clone, Copy, Default, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum ActivationMode {
+    /// A single press/release flips recording state
+    #[default]
+    Single,
+    /// Two releases within the double-tap window are required
+    DoubleTap,
+}
+
+/// How system audio is silenced while recording (when auto-mute is enabled)
+#[derive(Debug, Clone, Copy, Default, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum MuteMode {
+    /// Fully mute system audio
+    #[default]
+    Mute,
+    /// Lower the master volume to `duck_level` percent
+    Duck,
+}
+
+/// Which history entries the extra paste slot hotkeys refer to
+#[derive(Debug, Clone, Copy, Default, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum PasteSlotSource {
+    /// Slot N pastes the Nth most recent entry
+    #[default]
+    Recent,
+    /// Slot N pastes the Nth entry with pinned entries first, so pinned phrases
+    /// stay on the same slot as new dictations come in
+    Pinned,
+}
+
+/// Verbosity set from the UI with `set_log_level`
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum LogLevel {
+    Trace,
+    Debug,
+    Info,
+    Warn,
+    Error,
+}
+
+impl LogLevel {
+    pub fn filter(self) -> log::LevelFilter {
+        match self {
+            LogLevel::Trace => log::LevelFilter::Trace,
+            LogLevel::Debug => log::LevelFilter::Debug,
+            LogLevel::Info => log::LevelFilter::Info,
+            LogLevel::Warn => log::LevelFilter::Warn,
+            LogLevel::Error => log::LevelFilter::Error,
+        }
+    }
+
+    /// The most verbose level `filter` lets through (`Off` maps to `Error`)
+    pub fn from_filter(filter: log::LevelFilter) -> Self {
+        match filter {
+            log::LevelFilter::Trace => LogLevel::Trace,
+            log::LevelFilter::Debug => LogLevel::Debug,
+            log::LevelFilter::Info => LogLevel::Info,
+            log::LevelFilter::Warn => LogLevel::Warn,
+            log::LevelFilter::Error | log::LevelFilter::Off => LogLevel::Error,
+        }
+    }
+}
+
+/// Modifier names recognized when parsing shortcut strings
+const MODIFIER_NAMES: &[&str] = &[
+    "ctrl", "control", "alt", "option", "shift", "super", "cmd", "command", "meta", "win",
+];
+
+fn is_modifier_name(part: &str) -> bool {
+    MODIFIER_NAMES.contains(&part.to_lowercase().as_str())
+}
+
+fn is_zero(n: &u8) -> bool {
+    *n == 0
+}
+
+/// Configuration for a hotkey combination.
+///
+/// Two forms are supported:
+/// - Combo: `modifiers` + `key`, e.g. `{ modifiers: ["ctrl", "alt"], key: "Space" }` ("ctrl+alt+Space")
+/// - Modifier-only tap: a single modifier, empty `key` and `modifier_taps > 0`,
+///   e.g. `{ modifiers: ["super"], key: "", modifier_taps: 2 }` ("super+super", shown as "⌘⌘").
+///   These parse and display, but the global shortcut plugin can't detect them, so
+///   they are rejected when saved or registered (see `check_registrable`).
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
 pub struct HotkeyConfig {
     /// Modifier keys (e.g., ["ctrl", "alt"])
     pub modifiers: Vec<String>,
-    /// The main key (e.g., "Space")
+    /// The main key (e.g., "Space"), empty for modifier-only bindings
     pub key: String,
+    /// Number of taps of the modifier for modifier-only bindings (0 for combos)
+    #[serde(default, skip_serializing_if = "is_zero")]
+    pub modifier_taps: u8,
 }
 
 impl Default for HotkeyConfig {
@@ -39,6 +323,7 @@ impl Default for HotkeyConfig {
                 .map(|s| s.to_string())
                 .collect(),
             key: DEFAULT_TOGGLE_KEY.to_string(),
+            modifier_taps: 0,
         }
     }
 }
@@ -52,6 +337,7 @@ impl HotkeyConfig {
                 .map(|s| s.to_string())
                 .collect(),
             key: DEFAULT_TOGGLE_KEY.to_string(),
+            modifier_taps: 0,
         }
     }
 
@@ -63,6 +349,7 @@ impl HotkeyConfig {
                 .map(|s| s.to_string())
                 .collect(),
             key: DEFAULT_HOLD_KEY.to_string(),
+            modifier_taps: 0,
         }
     }
 
@@ -74,20 +361,94 @@ impl HotkeyConfig {
                 .map(|s| s.to_string())
                 .collect(),
             key: DEFAULT_PASTE_LAST_KEY.to_string(),
+            modifier_taps: 0,
+        }
+    }
+
+    /// Create default cancel hotkey config (no modifiers)
+    pub fn default_cancel() -> Self {
+        Self {
+            modifiers: Vec::new(),
+            key: DEFAULT_CANCEL_KEY.to_string(),
+            modifier_taps: 0,
+        }
+    }
+
+    /// Check if this is a modifier-only tap binding (e.g. double-tap Command)
+    pub fn is_modifier_only(&self) -> bool {
+        self.key.is_empty() && self.modifier_taps > 0
+    }
+
+    /// Check that the hotkey can be registered as a global shortcut.
+    /// Modifier-only bindings are rejected with an explanation instead of being
+    /// silently replaced by the default.
+    pub fn check_registrable(&self) -> Result<(), String> {
+        if self.is_modifier_only() {
+            return Err(format!(
+                "Modifier-only hotkey '{}' is not supported: global shortcuts need a main key (e.g. ctrl+alt+Space)",
+                self.to_shortcut_string()
+            ));
         }
+        Ok(())
     }
 
-    /// Convert to shortcut string format like "ctrl+alt+Space"
+    /// Convert to shortcut string format like "ctrl+alt+Space".
+    /// Modifier-only bindings repeat the modifier once per tap, e.g. "super+super".
     /// Note: modifiers must be lowercase for the parser to recognize them
     pub fn to_shortcut_string(&self) -> String {
+        if self.is_modifier_only() {
+            let modifier = self
+                .modifiers
+                .first()
+                .map(|m| m.to_lowercase())
+                .unwrap_or_default();
+            return vec![modifier; self.modifier_taps as usize].join("+");
+        }
+
         let mut parts: Vec<String> = self.modifiers.iter().map(|m| m.to_lowercase()).collect();
         parts.push(self.key.clone());
         parts.join("+")
     }
 
+    /// Parse a shortcut string produced by `to_shortcut_string`.
+    ///
+    /// The last part is the main key and the rest are modifiers. If every part is the
+    /// same modifier ("super+super"), it is parsed as a modifier-only tap binding.
+    pub fn from_shortcut_string(shortcut: &str) -> Result<Self, String> {
+        let parts: Vec<&str> = shortcut.split('+').map(str::trim).collect();
+        if parts.iter().any(|part| part.is_empty()) {
+            return Err(format!("Invalid shortcut '{}': empty key", shortcut));
+        }
+
+        let (key, modifiers) = parts
+            .split_last()
+            .ok_or_else(|| format!("Invalid shortcut '{}'", shortcut))?;
+
+        if is_modifier_name(key) {
+            if !modifiers.iter().all(|m| m.eq_ignore_ascii_case(key)) {
+                return Err(format!("Invalid shortcut '{}': missing main key", shortcut));
+            }
+            let modifier_taps = u8::try_from(parts.len())
+                .map_err(|_| format!("Invalid shortcut '{}': too many taps", shortcut))?;
+            return Ok(Self {
+                modifiers: vec![key.to_lowercase()],
+                key: String::new(),
+                modifier_taps,
+            });
+        }
+
+        Ok(Self {
+            modifiers: modifiers.iter().map(|m| m.to_lowercase()).collect(),
+            key: key.to_string(),
+            modifier_taps: 0,
+        })
+    }
+
     /// Convert to a tauri Shortcut using FromStr parsing
     #[cfg(desktop)]
     pub fn to_shortcut(&self) -> Result<Shortcut, String> {
+        // The global shortcut plugin only registers modifier+key combinations
+        self.check_registrable()?;
         let shortcut_str = self.to_shortcut_string();
         Shortcut::from_str(&shortcut_str)
             .map_err(|e| format!("Failed to parse shortcut '{}': {:?}", shortcut_str, e))
@@ -96,10 +457,333 @@ impl HotkeyConfig {
     /// Convert to a tauri Shortcut, falling back to a default if parsing fails
     #[cfg(desktop)]
     pub fn to_shortcut_or_default(&self, default_fn: fn() -> Self) -> Shortcut {
-        self.to_shortcut().unwrap_or_else(|_| {
+        self.to_shortcut().unwrap_or_else(|e| {
+            log::warn!("{}, using default hotkey", e);
             default_fn()
                 .to_shortcut()
                 .expect("Default hotkey must be valid")
         })
     }
 }
+
+/// Action triggered by one of the configurable global hotkeys
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum HotkeyAction {
+    Toggle,
+    Hold,
+    PasteLast,
+    ShowWindow,
+    #[serde(rename = "paste_slot_2")]
+    PasteSlot2,
+    #[serde(rename = "paste_slot_3")]
+    PasteSlot3,
+}
+
+impl HotkeyAction {
+    pub const ALL: [Self; 6] = [
+        Self::Toggle,
+        Self::Hold,
+        Self::PasteLast,
+        Self::ShowWindow,
+        Self::PasteSlot2,
+        Self::PasteSlot3,
+    ];
+
+    /// Name shown in messages, e.g. "paste last"
+    pub fn label(self) -> &'static str {
+        match self {
+            Self::Toggle => "toggle",
+            Self::Hold => "hold",
+            Self::PasteLast => "paste last",
+            Self::ShowWindow => "show window",
+            Self::PasteSlot2 => "paste slot 2",
+            Self::PasteSlot3 => "paste slot 3",
+        }
+    }
+
+    /// Store key holding this action's hotkey
+    pub fn store_key(self) -> &'static str {
+        match self {
+            Self::Toggle => "toggle_hotkey",
+            Self::Hold => "hold_hotkey",
+            Self::PasteLast => "paste_last_hotkey",
+            Self::ShowWindow => "show_window_hotkey",
+            Self::PasteSlot2 => "paste_slot_2_hotkey",
+            Self::PasteSlot3 => "paste_slot_3_hotkey",
+        }
+    }
+
+    /// Store key of the flag that disables this action's hotkey entirely, freeing
+    /// its combo for other applications (enabled when unset)
+    pub fn enabled_key(self) -> &'static str {
+        match self {
+            Self::Toggle => "toggle_hotkey_enabled",
+            Self::Hold => "hold_hotkey_enabled",
+            Self::PasteLast => "paste_last_hotkey_enabled",
+            Self::ShowWindow => "show_window_hotkey_enabled",
+            Self::PasteSlot2 => "paste_slot_2_hotkey_enabled",
+            Self::PasteSlot3 => "paste_slot_3_hotkey_enabled",
+        }
+    }
+
+    /// Hotkey used when none (or an invalid one) is stored.
+    /// `None` for actions that are unbound until the user configures them.
+    pub fn default_hotkey(self) -> Option<HotkeyConfig> {
+        match self {
+            Self::Toggle => Some(HotkeyConfig::default_toggle()),
+            Self::Hold => Some(HotkeyConfig::default_hold()),
+            Self::PasteLast => Some(HotkeyConfig::default_paste_last()),
+            Self::ShowWindow | Self::PasteSlot2 | Self::PasteSlot3 => None,
+        }
+    }
+
+    /// History slot (1-based) pasted by this action, `None` for non-paste actions
+    pub fn paste_slot(self) -> Option<usize> {
+        match self {
+            Self::PasteLast => Some(1),
+            Self::PasteSlot2 => Some(2),
+            Self::PasteSlot3 => Some(3),
+            Self::Toggle | Self::Hold | Self::ShowWindow => None,
+        }
+    }
+}
+
+/// Outcome of registering one global hotkey
+#[derive(Debug, Clone, Serialize, PartialEq, Eq)]
+pub struct HotkeyRegistration {
+    pub action: HotkeyAction,
+    /// The combo that was registered, e.g. "ctrl+alt+Space"
+    pub shortcut: String,
+    pub registered: bool,
+    /// Why registration failed (typically another application owns the combo)
+    pub error: Option<String>,
+}
+
+/// How one hotkey is matched against shortcut events, for diagnosing combos that
+/// log "Unknown shortcut" instead of triggering their action
+#[derive(Debug, Clone, Serialize, PartialEq, Eq)]
+pub struct EffectiveHotkey {
+    pub action: HotkeyAction,
+    pub enabled: bool,
+    /// The stored combo (or the default), `None` when the action is unbound
+    pub configured: Option<String>,
+    /// Whether `configured` parses as a shortcut
+    pub parsed: bool,
+    pub error: Option<String>,
+    /// Normalized combo that events are compared against. Uses the default when
+    /// `configured` doesn't parse and the action has one.
+    pub normalized: Option<String>,
+    /// Normalized string of the shortcut as the OS reports it in events
+    pub event_string: Option<String>,
+    /// `normalized` equals `event_string`, so events for this hotkey are recognized
+    pub matches: bool,
+}
+
+// ============================================================================
+// SETTINGS EXPORT / IMPORT
+// ============================================================================
+
+/// A setting from an imported file that was not applied
+#[derive(Debug, Clone, Serialize, PartialEq, Eq)]
+pub struct RejectedSetting {
+    pub key: String,
+    pub reason: String,
+}
+
+/// Outcome of a settings import
+#[derive(Debug, Clone, Default, Serialize, PartialEq, Eq)]
+pub struct SettingsImportResult {
+    /// Keys that were written to the store
+    pub applied: Vec<String>,
+    /// Unknown or invalid keys that were skipped
+    pub rejected: Vec<RejectedSetting>,
+}
+
+/// Check that an overlay opacity is within MIN_OVERLAY_OPACITY..=1.0
+pub fn validate_overlay_opacity(opacity: f64) -> Result<(), String> {
+    if (MIN_OVERLAY_OPACITY..=1.0).contains(&opacity) {
+        Ok(())
+    } else {
+        Err(format!(
+            "Overlay opacity must be between {} and 1.0, got {}",
+            MIN_OVERLAY_OPACITY, opacity
+        ))
+    }
+}
+
+/// Shortest allowed server request timeout, so requests can't fail instantly
+pub const MIN_REQUEST_TIMEOUT_MS: u64 = 1000;
+
+/// Check that a server request timeout is at least MIN_REQUEST_TIMEOUT_MS
+pub fn validate_request_timeout_ms(timeout_ms: u64) -> Result<(), String> {
+    if timeout_ms >= MIN_REQUEST_TIMEOUT_MS {
+        Ok(())
+    } else {
+        Err(format!(
+            "Request timeout must be at least {} ms, got {}",
+            MIN_REQUEST_TIMEOUT_MS, timeout_ms
+        ))
+    }
+}
+
+/// Check that a hold start delay is at most MAX_HOLD_START_DELAY_MS
+pub fn validate_hold_start_delay_ms(delay_ms: u64) -> Result<(), String> {
+    if delay_ms <= MAX_HOLD_START_DELAY_MS {
+        Ok(())
+    } else {
+        Err(format!(
+            "Hold start delay must be at most {} ms, got {}",
+            MAX_HOLD_START_DELAY_MS, delay_ms
+        ))
+    }
+}
+
+/// Check that a minimum transcription confidence is within 0.0..=1.0
+pub fn validate_min_confidence(min_confidence: f64) -> Result<(), String> {
+    if (0.0..=1.0).contains(&min_confidence) {
+        Ok(())
+    } else {
+        Err(format!(
+            "Minimum confidence must be between 0.0 and 1.0, got {}",
+            min_confidence
+        ))
+    }
+}
+
+/// Check that `value` deserializes as `T`
+fn check_type<T: serde::de::DeserializeOwned>(value: &serde_json::Value) -> Result<T, String> {
+    T::deserialize(value).map_err(|e| e.to_string())
+}
+
+/// Validate `value` for the setting `key` and return it as it should be stored.
+/// The server URL is normalized the same way `set_server_url` stores it.
+pub fn normalize_setting(key: &str, value: serde_json::Value) -> Result<serde_json::Value, String> {
+    validate_setting(key, &value)?;
+    match key {
+        "server_url" => validate_server_url(&check_type::<String>(&value)?).map(Into::into),
+        _ => Ok(value),
+    }
+}
+
+/// Check that `value` is valid for the setting `key`.
+/// Unknown keys are rejected so typos in an imported file don't silently pile up.
+pub fn validate_setting(key: &str, value: &serde_json::Value) -> Result<(), String> {
+    match key {
+        "toggle_hotkey" | "hold_hotkey" | "paste_last_hotkey" | "cancel_hotkey" => {
+            check_type::<HotkeyConfig>(value)?.check_registrable()
+        }
+        "show_window_hotkey" | "paste_slot_2_hotkey" | "paste_slot_3_hotkey" => {
+            check_type::<Option<HotkeyConfig>>(value)?
+                .map_or(Ok(()), |hotkey| hotkey.check_registrable())
+        }
+        "auto_mute_audio"
+        | "sound_enabled"
+        | "play_sound_on_empty"
+        | "save_recordings"
+        | "vad_enabled"
+        | "streaming_enabled"
+        | "auto_capitalize_first"
+        | "append_trailing_space"
+        | "dry_run"
+        | "append_mode"
+        | "hotkeys_suspended"
+        | "incognito"
+        | "prepend_timestamp"
+        | "timestamp_in_history"
+        | "voice_commands_enabled"
+        | "main_window_always_on_top"
+        | "autostart"
+        | "close_to_tray"
+        | CONTROL_SERVER_ENABLED_KEY => check_type::<bool>(value).map(drop),
+        key if HotkeyAction::ALL.iter().any(|a| a.enabled_key() == key) => {
+            check_type::<bool>(value).map(drop)
+        }
+        "duck_level" => check_type::<u8>(value).map(drop),
+        CONTROL_SERVER_PORT_KEY => match check_type::<u16>(value)? {
+            0 => Err("port must be between 1 and 65535".to_string()),
+            _ => Ok(()),
+        },
+        "sample_rate" => validate_audio_format(AudioFormat {
+            sample_rate: check_type::<u32>(value)?,
+            ..AudioFormat::default()
+        }),
+        "channels" => validate_audio_format(AudioFormat {
+            channels: check_type::<u16>(value)?,
+            ..AudioFormat::default()
+        }),
+        "sound_volume" | "vad_threshold" | "overlay_margin_x" | "overlay_margin_y" => {
+            check_type::<f64>(value).map(drop)
+        }
+        "clipboard_restore_delay_ms"
+        | "type_delay_ms"
+        | "double_tap_window_ms"
+        | "toggle_debounce_ms"
+        | "append_window_ms"
+        | "max_recording_secs"
+        | "max_saved_recordings"
+        | "silence_timeout_ms"
+        | "history_max_entries"
+        | "history_max_age_days"
+        | "hold_stop_grace_ms"
+        | "min_recording_ms" => check_type::<u64>(value).map(drop),
+        "request_timeout_ms" => validate_request_timeout_ms(check_type::<u64>(value)?),
+        "hold_start_delay_ms" => validate_hold_start_delay_ms(check_type::<u64>(value)?),
+        "stt_timeout_seconds" => check_type::<Option<f64>>(value).map(drop),
+        "language" => check_type::<String>(value).map(drop),
+        "server_url" => validate_server_url(&check_type::<String>(value)?).map(drop),
+        "server_api_key" | CONTROL_SERVER_TOKEN_KEY => {
+            check_type::<Option<String>>(value).map(drop)
+        }
+        TRANSCRIPTION_WEBHOOK_URL_KEY => match check_type::<Option<String>>(value)? {
+            Some(url) => validate_webhook_url(&url).map(drop),
+            None => Ok(()),
+        },
+        TRANSCRIPTION_WEBHOOK_SECRET_KEY => match check_type::<Option<String>>(value)? {
+            Some(secret) => validate_webhook_secret(&secret),
+            None => Ok(()),
+        },
+        "server_auth_header" => match check_type::<Option<String>>(value)? {
+            Some(name) => validate_header_name(&name),
+            None => Ok(()),
+        },
+        "server_headers" => {
+            server_request_headers(None, None, &check_type::<BTreeMap<String, String>>(value)?)
+                .map(drop)
+        }
+        "stt_provider"
+        | "llm_provider"
+        | "selected_mic_id"
+        | "custom_start_sound_path"
+        | "custom_stop_sound_path"
+        | "custom_complete_sound_path"
+        | "custom_error_sound_path"
+        | HISTORY_STORAGE_PATH_KEY => check_type::<Option<String>>(value).map(drop),
+        "mute_exclusions" => check_type::<Vec<String>>(value).map(drop),
+        "log_level" => check_type::<LogLevel>(value).map(drop),
+        "mute_mode" => check_type::<MuteMode>(value).map(drop),
+        "paste_slot_source" => check_type::<PasteSlotSource>(value).map(drop),
+        "insertion_method" => check_type::<InsertionMethod>(value).map(drop),
+        "toggle_activation" => check_type::<ActivationMode>(value).map(drop),
+        "overlay_anchor" => check_type::<OverlayAnchor>(value).map(drop),
+        "overlay_visibility" => check_type::<OverlayVisibility>(value).map(drop),
+        "overlay_theme" => check_type::<OverlayTheme>(value).map(drop),
+        OVERLAY_SIZE_KEY => check_type::<OverlaySize>(value).map(drop),
+        "overlay_opacity" => validate_overlay_opacity(check_type::<f64>(value)?),
+        "min_confidence" => validate_min_confidence(check_type::<f64>(value)?),
+        INPUT_GAIN_KEY => validate_input_gain(check_type::<f64>(value)?),
+        NOISE_GATE_THRESHOLD_KEY => validate_noise_gate_threshold(check_type::<f64>(value)?),
+        "overlay_monitor" | TARGET_APP_KEY => check_type::<Option<String>>(value).map(drop),
+        "overlay_custom_position" => check_type::<Option<OverlayPosition>>(value).map(drop),
+        "cleanup_prompt_sections" => {
+            check_type::<Option<serde_json::Map<String, serde_json::Value>>>(value).map(drop)
+        }
+        "text_replacements" => validate_rules(&check_type::<Vec<ReplacementRule>>(value)?),
+        TIMESTAMP_FORMAT_KEY => validate_timestamp_format(&check_type::<String>(value)?),
+        "custom_vocabulary" => normalize_vocabulary(check_type::<Vec<String>>(value)?).map(drop),
+        "voice_commands" => validate_commands(&check_type::<Vec<VoiceCommand>>(value)?),
+        PROFILES_KEY => check_type::<BTreeMap<String, SettingsMap>>(value).map(drop),
+        ACTIVE_PROFILE_KEY => check_type::<String>(value).map(drop),
+        _ => Err("unknown setting".to_string()),
+    }
+}
@@ -1,4 +1,30 @@
-use std::sync::atomic::AtomicBool;
+use crate::settings::HotkeyRegistration;
+use crate::vad::SilenceDetector;
+use serde::Serialize;
+use std::collections::VecDeque;
+use std::sync::atomic::{AtomicBool, AtomicU64, AtomicUsize, Ordering};
+use std::sync::Mutex;
+
+/// Most stopped recordings tracked while awaiting transcription; older ones are
+/// assumed lost
+pub const MAX_PENDING_TRANSCRIPTIONS: usize = 5;
+
+/// What started the current recording
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum RecordingSource {
+    Toggle,
+    Hold,
+}
+
+/// Snapshot of the recording state, for windows that missed the start/stop events
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct RecordingState {
+    pub is_recording: bool,
+    pub source: Option<RecordingSource>,
+    /// Unix millis when the recording started
+    pub started_at_ms: Option<u64>,
+}
 
 #[derive(Default)]
 pub struct AppState {
@@ -8,6 +34,148 @@ pub struct AppState {
     pub ptt_key_held: AtomicBool,
     /// Tracks if paste-last key is currently held down
     pub paste_key_held: AtomicBool,
+    /// Tracks if the paste slot 2 and 3 keys are currently held down
+    pub paste_slot_key_held: [AtomicBool; 2],
     /// Tracks if toggle key is currently held down (for debouncing - action happens on release)
     pub toggle_key_held: AtomicBool,
+    /// Unix millis of the last toggle key press (for debouncing spurious releases)
+    pub toggle_pressed_at_ms: AtomicU64,
+    /// Unix millis of the last toggle key release (for double-tap activation, 0 = none)
+    pub last_toggle_release_ms: AtomicU64,
+    /// Incremented on every recording start/stop so pending auto-stop timers can detect
+    /// that the session they were armed for has already ended
+    pub recording_generation: AtomicU64,
+    /// Set when the user starts dragging the overlay, so its Moved events are persisted.
+    /// Cleared when the drag ends and before programmatic moves (resize, anchor placement).
+    pub overlay_dragging: AtomicBool,
+    /// Incremented whenever overlay activity starts, so a pending auto-hide from the
+    /// previous activity can detect it is stale
+    pub overlay_activity_generation: AtomicU64,
+    /// Set while the cancel hotkey is registered (only during a recording)
+    pub cancel_shortcut_registered: AtomicBool,
+    /// Silence detector for the current recording (Some only when `vad_enabled`)
+    pub silence_detector: Mutex<Option<SilenceDetector>>,
+    /// Characters inserted by the most recent dictation (backspaced by "delete that"
+    /// and `undo_last_insertion`)
+    pub last_inserted_chars: AtomicUsize,
+    /// Unix millis of the most recent insertion
+    pub last_inserted_at_ms: AtomicU64,
+    /// One entry per stopped recording awaiting transcription (oldest first): whether
+    /// its stop sound is still due. The sound plays once the result arrives, so it can
+    /// be skipped for empty transcriptions.
+    pub pending_stop_sounds: Mutex<VecDeque<bool>>,
+    /// Stopped recordings awaiting transcription, as reported by the overlay
+    pub transcription_queue_depth: AtomicUsize,
+    /// Source and start time (Unix millis) of the current recording
+    pub recording_session: Mutex<Option<(RecordingSource, u64)>>,
+    /// Hotkeys that failed to register in the latest registration pass (at startup or
+    /// after a settings change), kept so the UI can show them once it has loaded
+    pub hotkey_registration_failures: Mutex<Vec<HotkeyRegistration>>,
+    /// Set while global hotkeys are suspended with `suspend_hotkeys`
+    pub hotkeys_suspended: AtomicBool,
+}
+
+impl AppState {
+    /// Atomically move `is_recording` to `recording`.
+    /// Returns false (and changes nothing) if it already had that value, so
+    /// overlapping start/stop triggers can't run a transition twice.
+    pub fn try_set_recording(&self, recording: bool) -> bool {
+        self.is_recording
+            .compare_exchange(!recording, recording, Ordering::SeqCst, Ordering::SeqCst)
+            .is_ok()
+    }
+
+    /// Remember the length of an insertion made at `now_ms`, in grapheme clusters
+    /// (the Backspace presses needed to undo it)
+    pub fn record_insertion(&self, chars: usize, now_ms: u64) {
+        self.last_inserted_chars.store(chars, Ordering::SeqCst);
+        self.last_inserted_at_ms.store(now_ms, Ordering::SeqCst);
+    }
+
+    /// Take the length of the last insertion if it happened within `window_ms` of
+    /// `now_ms`. Clears it, so an insertion can only be undone once.
+    pub fn take_undoable_insertion(&self, now_ms: u64, window_ms: u64) -> Option<usize> {
+        let inserted_at_ms = self.last_inserted_at_ms.load(Ordering::SeqCst);
+        if now_ms.saturating_sub(inserted_at_ms) > window_ms {
+            return None;
+        }
+        match self.last_inserted_chars.swap(0, Ordering::SeqCst) {
+            0 => None,
+            chars => Some(chars),
+        }
+    }
+
+    /// Replace the stored registration failures with the failed entries of
+    /// `registrations`, returning them
+    pub fn set_hotkey_registration_failures(
+        &self,
+        registrations: &[HotkeyRegistration],
+    ) -> Vec<HotkeyRegistration> {
+        let failures: Vec<HotkeyRegistration> = registrations
+            .iter()
+            .filter(|registration| !registration.registered)
+            .cloned()
+            .collect();
+        if let Ok(mut stored) = self.hotkey_registration_failures.lock() {
+            stored.clone_from(&failures);
+        }
+        failures
+    }
+
+    /// Hotkeys that failed to register in the latest registration pass
+    pub fn hotkey_registration_failures(&self) -> Vec<HotkeyRegistration> {
+        self.hotkey_registration_failures
+            .lock()
+            .map(|failures| failures.clone())
+            .unwrap_or_default()
+    }
+
+    /// Queue the stop sound for a recording that was just stopped
+    pub fn queue_stop_sound(&self, play: bool) {
+        if let Ok(mut queue) = self.pending_stop_sounds.lock() {
+            if queue.len() == MAX_PENDING_TRANSCRIPTIONS {
+                queue.pop_front();
+            }
+            queue.push_back(play);
+        }
+    }
+
+    /// Take the stop sound of the oldest pending transcription.
+    /// Returns whether it should play (false if nothing was pending).
+    pub fn take_stop_sound(&self) -> bool {
+        self.pending_stop_sounds
+            .lock()
+            .ok()
+            .and_then(|mut queue| queue.pop_front())
+            .unwrap_or(false)
+    }
+
+    /// Forget all pending stop sounds (their transcriptions were lost)
+    pub fn clear_stop_sounds(&self) {
+        if let Ok(mut queue) = self.pending_stop_sounds.lock() {
+            queue.clear();
+        }
+    }
+
+    /// Milliseconds since the current recording started, or None if not recording
+    pub fn recording_elapsed_ms(&self, now_ms: u64) -> Option<u64> {
+        self.recording_state()
+            .started_at_ms
+            .map(|started_at_ms| now_ms.saturating_sub(started_at_ms))
+    }
+
+    /// Current recording state. Session details are only reported while recording.
+    pub fn recording_state(&self) -> RecordingState {
+        let is_recording = self.is_recording.load(Ordering::SeqCst);
+        let session = if is_recording {
+            self.recording_session.lock().ok().and_then(|guard| *guard)
+        } else {
+            None
+        };
+        RecordingState {
+            is_recording,
+            source: session.map(|(source, _)| source),
+            started_at_ms: session.map(|(_, started_at_ms)| started_at_ms),
+        }
+    }
 }
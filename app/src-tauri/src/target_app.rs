@@ -0,0 +1,214 @@
+//! Focusing a chosen application before dictated text is inserted.
+//!
+//! Apps are listed and raised with the platform's own tools: AppleScript on
+//! macOS, `wmctrl` on Linux (X11 only) and PowerShell on Windows.
+
+use serde::Serialize;
+use std::process::Command;
+
+/// Store key holding the app to focus before inserting (unset = current focus)
+pub const TARGET_APP_KEY: &str = "target_app";
+
+/// Time allowed for the window manager to finish raising the target app
+pub const FOCUS_SETTLE_DELAY_MS: u64 = 150;
+
+/// An application with a window that can be focused
+#[derive(Debug, Clone, Serialize, PartialEq, Eq)]
+pub struct RunningApp {
+    /// Platform handle used to focus the app: bundle identifier on macOS,
+    /// window ID on Linux, process ID on Windows
+    pub id: String,
+    /// Application or process name
+    pub name: String,
+    /// Title of the app's window, where the platform reports it
+    pub title: Option<String>,
+}
+
+/// Payload of the "target-app-unavailable" event
+#[derive(Debug, Clone, Serialize)]
+pub struct TargetAppUnavailable {
+    pub target: String,
+    pub reason: String,
+}
+
+/// Find the app a `target_app` setting refers to. An exact (case-insensitive)
+/// match on ID or name wins; otherwise the first window whose title contains
+/// the target is used.
+pub fn find_target_app<'a>(apps: &'a [RunningApp], target: &str) -> Option<&'a RunningApp> {
+    let target = target.trim().to_lowercase();
+    if target.is_empty() {
+        return None;
+    }
+    apps.iter()
+        .find(|app| app.id.to_lowercase() == target || app.name.to_lowercase() == target)
+        .or_else(|| {
+            apps.iter().find(|app| {
+                app.title
+                    .as_ref()
+                    .is_some_and(|title| title.to_lowercase().contains(&target))
+            })
+        })
+}
+
+/// Parse `id<TAB>name[<TAB>title]` lines, as printed by the macOS and Windows scripts
+#[allow(dead_code)] // Used by the macOS and Windows implementations, not Linux
+pub fn parse_app_lines(output: &str) -> Vec<RunningApp> {
+    output
+        .lines()
+        .filter_map(|line| {
+            let mut fields = line.split('\t').map(str::trim);
+            let id = fields
+                .next()
+                .filter(|id| !id.is_empty() && *id != "missing value")?;
+            let name = fields.next().filter(|name| !name.is_empty())?;
+            let title = fields.next().filter(|title| !title.is_empty());
+            Some(RunningApp {
+                id: id.to_string(),
+                name: name.to_string(),
+                title: title.map(str::to_string),
+            })
+        })
+        .collect()
+}
+
+/// Parse `wmctrl -lx` output: window ID, desktop, WM_CLASS, host, then the title
+#[allow(dead_code)] // Used by the Linux implementation, not macOS/Windows
+pub fn parse_wmctrl_list(output: &str) -> Vec<RunningApp> {
+    output
+        .lines()
+        .filter_map(|line| {
+            let mut fields = line.split_whitespace();
+            let id = fields.next()?;
+            let desktop = fields.next()?;
+            let class = fields.next()?;
+            let _host = fields.next()?;
+            // Sticky windows (desktop -1) are panels and docks
+            if desktop == "-1" {
+                return None;
+            }
+            // WM_CLASS is "instance.Class"; the class is the readable app name
+            let name = class.rsplit('.').next().unwrap_or(class);
+            let title = fields.collect::<Vec<_>>().join(" ");
+            Some(RunningApp {
+                id: id.to_string(),
+                name: name.to_string(),
+                title: (!title.is_empty()).then_some(title),
+            })
+        })
+        .collect()
+}
+
+/// Run a program, returning stdout on success
+#[allow(dead_code)] // Unused on platforms without an implementation
+fn run(program: &str, args: &[&str]) -> Result<String, String> {
+    let output = Command::new(program)
+        .args(args)
+        .output()
+        .map_err(|e| format!("Failed to run {}: {}", program, e))?;
+    if !output.status.success() {
+        return Err(format!(
+            "{} failed: {}",
+            program,
+            String::from_utf8_lossy(&output.stderr).trim()
+        ));
+    }
+    Ok(String::from_utf8_lossy(&output.stdout).into_owned())
+}
+
+#[cfg(target_os = "macos")]
+const LIST_APPS_SCRIPT: &str = r#"set out to ""
+tell application "System Events"
+    repeat with p in (every application process whose background only is false)
+        set out to out & (bundle identifier of p as text) & tab & (name of p) & linefeed
+    end repeat
+end tell
+return out"#;
+
+#[cfg(target_os = "windows")]
+const LIST_APPS_SCRIPT: &str = "Get-Process | Where-Object { $_.MainWindowTitle } | \
+    ForEach-Object { \"$($_.Id)`t$($_.ProcessName)`t$($_.MainWindowTitle)\" }";
+
+/// List applications that have a window to focus
+pub fn list_running_apps() -> Result<Vec<RunningApp>, String> {
+    #[cfg(target_os = "macos")]
+    {
+        run("osascript", &["-e", LIST_APPS_SCRIPT]).map(|output| parse_app_lines(&output))
+    }
+
+    #[cfg(target_os = "linux")]
+    {
+        run("wmctrl", &["-lx"]).map(|output| parse_wmctrl_list(&output))
+    }
+
+    #[cfg(target_os = "windows")]
+    {
+        run(
+            "powershell",
+            &[
+                "-NoProfile",
+                "-NonInteractive",
+                "-Command",
+                LIST_APPS_SCRIPT,
+            ],
+        )
+        .map(|output| parse_app_lines(&output))
+    }
+
+    #[cfg(not(any(target_os = "macos", target_os = "linux", target_os = "windows")))]
+    {
+        Err("Listing applications is not supported on this platform".to_string())
+    }
+}
+
+/// Raise and focus `app`
+pub fn focus_app(app: &RunningApp) -> Result<(), String> {
+    #[cfg(target_os = "macos")]
+    {
+        let script = format!(
+            "tell application id \"{}\" to activate",
+            app.id.replace('"', "")
+        );
+        run("osascript", &["-e", &script]).map(drop)
+    }
+
+    #[cfg(target_os = "linux")]
+    {
+        run("wmctrl", &["-i", "-a", &app.id]).map(drop)
+    }
+
+    #[cfg(target_os = "windows")]
+    {
+        let pid: u32 = app
+            .id
+            .parse()
+            .map_err(|_| format!("Invalid process ID: {}", app.id))?;
+        let script = format!("(New-Object -ComObject WScript.Shell).AppActivate({})", pid);
+        let output = run(
+            "powershell",
+            &["-NoProfile", "-NonInteractive", "-Command", &script],
+        )?;
+        if output.trim().eq_ignore_ascii_case("true") {
+            Ok(())
+        } else {
+            Err(format!("Windows refused to focus {}", app.name))
+        }
+    }
+
+    #[cfg(not(any(target_os = "macos", target_os = "linux", target_os = "windows")))]
+    {
+        Err(format!(
+            "Focusing {} is not supported on this platform",
+            app.name
+        ))
+    }
+}
+
+/// Focus the app named by `target`. Errors if it isn't running or can't be raised.
+pub fn focus_target_app(target: &str) -> Result<RunningApp, String> {
+    let apps = list_running_apps()?;
+    let app = find_target_app(&apps, target)
+        .ok_or_else(|| format!("{} is not running", target))?
+        .clone();
+    focus_app(&app)?;
+    Ok(app)
+}
@@ -0,0 +1,110 @@
+use crate::audio_mute::{
+    app_matches_exclusion, duck_target, parse_pactl_channel_volumes, parse_pactl_mute,
+    restore_target, SavedVolume,
+};
+
+#[test]
+fn test_app_matches_exclusion_ignores_case() {
+    assert!(app_matches_exclusion("spotify.exe", "Spotify.exe"));
+}
+
+#[test]
+fn test_app_matches_exclusion_ignores_exe_suffix() {
+    assert!(app_matches_exclusion("spotify.exe", "spotify"));
+    assert!(app_matches_exclusion("zoom", "Zoom.EXE"));
+}
+
+#[test]
+fn test_app_matches_exclusion_trims_whitespace() {
+    assert!(app_matches_exclusion("discord.exe", "  discord "));
+}
+
+#[test]
+fn test_app_matches_exclusion_rejects_different_app() {
+    assert!(!app_matches_exclusion("chrome.exe", "spotify"));
+    assert!(!app_matches_exclusion("spotify.exe", "spot"));
+}
+
+#[test]
+fn test_duck_target_lowers_to_level() {
+    assert_eq!(duck_target(0.8, 20), Some(0.2));
+}
+
+#[test]
+fn test_duck_target_never_raises_volume() {
+    assert_eq!(duck_target(0.1, 20), None);
+    assert_eq!(duck_target(0.2, 20), None);
+}
+
+#[test]
+fn test_duck_target_clamps_level_above_100() {
+    assert_eq!(duck_target(0.5, 250), None);
+}
+
+#[test]
+fn test_restore_target_restores_prior_volume() {
+    let prior = SavedVolume::Master(0.8);
+    assert_eq!(restore_target(&prior, 0.2, 0.2), Some(&prior));
+}
+
+#[test]
+fn test_restore_target_keeps_volume_changed_mid_recording() {
+    assert_eq!(restore_target(&SavedVolume::Master(0.8), 0.2, 0.5), None);
+}
+
+#[test]
+fn test_restore_target_skips_when_nothing_was_ducked() {
+    assert_eq!(restore_target(&SavedVolume::Master(0.1), 0.1, 0.1), None);
+}
+
+#[test]
+fn test_restore_target_keeps_amplified_and_balanced_volume() {
+    // 120% on the left, 110% on the right: restored as is, not clamped or evened out
+    let prior = SavedVolume::Sink {
+        name: "alsa_output.usb".to_string(),
+        channels: vec![78643, 72090],
+    };
+    assert_eq!(restore_target(&prior, 0.2, 0.2), Some(&prior));
+}
+
+#[test]
+fn test_saved_sink_level_uses_loudest_channel() {
+    let saved = SavedVolume::Sink {
+        name: "sink".to_string(),
+        channels: vec![32768, 65536],
+    };
+    assert_eq!(saved.level(), 1.0);
+    assert_eq!(SavedVolume::Master(0.4).level(), 0.4);
+}
+
+#[test]
+fn test_parse_pactl_mute() {
+    assert_eq!(parse_pactl_mute("Mute: yes\n"), Some(true));
+    assert_eq!(parse_pactl_mute("Mute: no\n"), Some(false));
+    assert_eq!(parse_pactl_mute("Connection failure"), None);
+}
+
+#[test]
+fn test_parse_pactl_channel_volumes() {
+    let output = "Volume: front-left: 42597 /  65% / -11.23 dB,   \
+                  front-right: 39321 /  60% / -13.31 dB\n        balance -0.08\n";
+    assert_eq!(
+        parse_pactl_channel_volumes(output),
+        Some(vec![42597, 39321])
+    );
+}
+
+#[test]
+fn test_parse_pactl_channel_volumes_keeps_amplified_levels() {
+    let output = "Volume: mono: 78643 / 120% / 4.75 dB\n";
+    assert_eq!(parse_pactl_channel_volumes(output), Some(vec![78643]));
+}
+
+#[test]
+fn test_parse_pactl_channel_volumes_rejects_garbage() {
+    assert_eq!(parse_pactl_channel_volumes("No such entity"), None);
+    assert_eq!(
+        parse_pactl_channel_volumes("Volume: front-left: loud"),
+        None
+    );
+}
@@ -0,0 +1,34 @@
+use crate::audio::{sound_gain, SoundType};
+
+#[test]
+fn test_sound_gain_full_volume_is_base_gain() {
+    assert_eq!(sound_gain(1.0), 0.3);
+}
+
+#[test]
+fn test_sound_gain_scales_with_volume() {
+    assert!((sound_gain(0.5) - 0.15).abs() < f32::EPSILON);
+}
+
+#[test]
+fn test_sound_gain_zero_volume_is_silent() {
+    assert_eq!(sound_gain(0.0), 0.0);
+}
+
+#[test]
+fn test_sound_gain_clamps_out_of_range_volume() {
+    assert_eq!(sound_gain(4.0), 0.3);
+    assert_eq!(sound_gain(-1.0), 0.0);
+}
+
+#[test]
+fn test_sound_type_deserializes_result_sounds() {
+    assert!(matches!(
+        serde_json::from_value::<SoundType>(serde_json::json!("complete")),
+        Ok(SoundType::TranscriptionComplete)
+    ));
+    assert!(matches!(
+        serde_json::from_value::<SoundType>(serde_json::json!("error")),
+        Ok(SoundType::Error)
+    ));
+}
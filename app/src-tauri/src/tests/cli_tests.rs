@@ -0,0 +1,47 @@
+use crate::cli::parse_cli_action;
+use crate::control_server::RemoteAction;
+
+fn args(args: &[&str]) -> Vec<String> {
+    std::iter::once("tambourine")
+        .chain(args.iter().copied())
+        .map(String::from)
+        .collect()
+}
+
+#[test]
+fn test_parse_cli_action_maps_each_flag() {
+    for (flag, action) in [
+        ("--toggle", RemoteAction::Toggle),
+        ("--start", RemoteAction::Start),
+        ("--stop", RemoteAction::Stop),
+        ("--cancel", RemoteAction::Cancel),
+        ("--paste-last", RemoteAction::PasteLast),
+    ] {
+        assert_eq!(parse_cli_action(&args(&[flag])), Ok(Some(action)));
+    }
+}
+
+#[test]
+fn test_parse_cli_action_without_flags_is_none() {
+    assert_eq!(parse_cli_action(&args(&[])), Ok(None));
+    assert_eq!(parse_cli_action(&[]), Ok(None));
+}
+
+#[test]
+fn test_parse_cli_action_ignores_other_arguments() {
+    assert_eq!(
+        parse_cli_action(&args(&["--autostarted", "--toggle", "-psn_0_12345"])),
+        Ok(Some(RemoteAction::Toggle))
+    );
+}
+
+#[test]
+fn test_parse_cli_action_ignores_the_program_name() {
+    assert_eq!(parse_cli_action(&["--toggle".to_string()]), Ok(None));
+}
+
+#[test]
+fn test_parse_cli_action_rejects_several_actions() {
+    let error = parse_cli_action(&args(&["--start", "--stop"])).unwrap_err();
+    assert!(error.contains("--start") && error.contains("--stop"));
+}
@@ -0,0 +1,88 @@
+use crate::control_server::{
+    parse_request, route, token_matches, ControlRoute, RemoteAction, DEFAULT_HISTORY_LIMIT,
+};
+
+// Tests for parse_request()
+#[test]
+fn test_parse_request_reads_method_target_and_bearer_token() {
+    let request = parse_request(
+        "POST /recording/toggle HTTP/1.1\r\nHost: localhost\r\nAuthorization: Bearer abc123",
+    )
+    .unwrap();
+    assert_eq!(request.method, "POST");
+    assert_eq!(request.target, "/recording/toggle");
+    assert_eq!(request.token.as_deref(), Some("abc123"));
+}
+
+#[test]
+fn test_parse_request_header_names_and_scheme_are_case_insensitive() {
+    let request = parse_request("GET /recording HTTP/1.0\r\nauthorization: bearer abc123").unwrap();
+    assert_eq!(request.token.as_deref(), Some("abc123"));
+}
+
+#[test]
+fn test_parse_request_ignores_other_auth_schemes() {
+    let request = parse_request("GET /recording HTTP/1.1\r\nAuthorization: Basic abc123").unwrap();
+    assert_eq!(request.token, None);
+    let request = parse_request("GET /recording HTTP/1.1").unwrap();
+    assert_eq!(request.token, None);
+}
+
+#[test]
+fn test_parse_request_rejects_malformed_request_lines() {
+    assert!(parse_request("").is_err());
+    assert!(parse_request("GET /recording").is_err());
+    assert!(parse_request("GET /recording SPDY/3").is_err());
+}
+
+// Tests for route()
+#[test]
+fn test_route_maps_action_endpoints() {
+    for (path, action) in [
+        ("/recording/start", RemoteAction::Start),
+        ("/recording/stop", RemoteAction::Stop),
+        ("/recording/toggle", RemoteAction::Toggle),
+        ("/recording/cancel", RemoteAction::Cancel),
+        ("/paste-last", RemoteAction::PasteLast),
+    ] {
+        assert_eq!(route("POST", path), Ok(ControlRoute::Action(action)));
+    }
+    assert_eq!(route("GET", "/recording"), Ok(ControlRoute::RecordingState));
+}
+
+#[test]
+fn test_route_reads_history_limit() {
+    assert_eq!(
+        route("GET", "/history"),
+        Ok(ControlRoute::History {
+            limit: DEFAULT_HISTORY_LIMIT
+        })
+    );
+    assert_eq!(
+        route("GET", "/history?limit=5"),
+        Ok(ControlRoute::History { limit: 5 })
+    );
+    assert_eq!(route("GET", "/history?limit=five").unwrap_err().0, 400);
+}
+
+#[test]
+fn test_route_rejects_unknown_paths_and_wrong_methods() {
+    assert_eq!(route("GET", "/settings").unwrap_err().0, 404);
+    assert_eq!(route("GET", "/recording/start").unwrap_err().0, 405);
+    assert_eq!(route("POST", "/history").unwrap_err().0, 405);
+}
+
+// Tests for token_matches()
+#[test]
+fn test_token_matches_requires_the_exact_token() {
+    assert!(token_matches("secret-token", Some("secret-token")));
+    assert!(!token_matches("secret-token", Some("secret-tokeN")));
+    assert!(!token_matches("secret-token", Some("secret")));
+    assert!(!token_matches("secret-token", None));
+}
+
+#[test]
+fn test_token_matches_never_accepts_an_empty_token() {
+    assert!(!token_matches("", Some("")));
+    assert!(!token_matches("", None));
+}
@@ -0,0 +1,74 @@
+use crate::history_crypto::{EncryptedHistory, HistoryKey, KdfParams, KeySource};
+
+/// Cheap Argon2 parameters so tests run quickly
+const TEST_PARAMS: KdfParams = KdfParams {
+    m_cost: 64,
+    t_cost: 1,
+    p_cost: 1,
+};
+
+#[test]
+fn test_encrypt_round_trip() {
+    let key = HistoryKey::generate();
+    let envelope = key.encrypt(b"{\"entries\":[]}").unwrap();
+    assert_eq!(envelope.key_source, KeySource::Keychain);
+    assert!(!envelope.ciphertext.contains("entries"));
+    assert_eq!(
+        key.decrypt(&envelope).unwrap().as_slice(),
+        b"{\"entries\":[]}"
+    );
+}
+
+#[test]
+fn test_each_encryption_uses_a_fresh_nonce() {
+    let key = HistoryKey::generate();
+    let first = key.encrypt(b"same").unwrap();
+    let second = key.encrypt(b"same").unwrap();
+    assert_ne!(first.nonce, second.nonce);
+    assert_ne!(first.ciphertext, second.ciphertext);
+}
+
+#[test]
+fn test_decrypt_fails_with_wrong_key() {
+    let envelope = HistoryKey::generate().encrypt(b"secret").unwrap();
+    assert!(HistoryKey::generate().decrypt(&envelope).is_err());
+}
+
+#[test]
+fn test_decrypt_detects_tampered_header() {
+    let key = HistoryKey::generate();
+    let mut envelope = key.encrypt(b"secret").unwrap();
+    envelope.key_source = KeySource::Passphrase;
+    assert!(key.decrypt(&envelope).is_err());
+}
+
+#[test]
+fn test_passphrase_key_round_trip() {
+    let key = HistoryKey::new_from_passphrase("correct horse battery", TEST_PARAMS).unwrap();
+    let envelope = key.encrypt(b"secret").unwrap();
+    assert_eq!(envelope.key_source, KeySource::Passphrase);
+    assert_eq!(envelope.kdf.as_ref().unwrap().params, TEST_PARAMS);
+
+    // The same passphrase and stored salt give the same key
+    let again = HistoryKey::from_passphrase("correct horse battery", &envelope).unwrap();
+    assert_eq!(again.decrypt(&envelope).unwrap().as_slice(), b"secret");
+
+    let wrong = HistoryKey::from_passphrase("wrong horse battery", &envelope).unwrap();
+    assert!(wrong.decrypt(&envelope).is_err());
+}
+
+#[test]
+fn test_short_passphrase_is_rejected() {
+    assert!(HistoryKey::new_from_passphrase("short", TEST_PARAMS).is_err());
+}
+
+#[test]
+fn test_parse_recognizes_only_encrypted_files() {
+    let envelope = HistoryKey::generate().encrypt(b"secret").unwrap();
+    let json = serde_json::to_string(&envelope).unwrap();
+    assert_eq!(EncryptedHistory::parse(&json), Some(envelope));
+    assert_eq!(
+        EncryptedHistory::parse(r#"{"version":1,"entries":[]}"#),
+        None
+    );
+}
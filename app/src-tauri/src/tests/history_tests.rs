@@ -0,0 +1,1017 @@
+use crate::history::{ExportFormat, HistoryChange, HistoryEntry, HistoryLimits, HistoryStorage};
+use crate::history_crypto::{HistoryKey, KeySource};
+use std::path::PathBuf;
+
+/// Create a history storage backed by a fresh temporary directory
+fn temp_storage() -> (HistoryStorage, PathBuf) {
+    let dir = std::env::temp_dir().join(format!("tambourine-test-{}", uuid::Uuid::new_v4()));
+    (HistoryStorage::new(dir.clone(), None), dir)
+}
+
+// Tests for HistoryStorage::search()
+#[test]
+fn test_search_is_case_insensitive() {
+    let (storage, dir) = temp_storage();
+    storage
+        .add_entry("Hello World".to_string(), None, None)
+        .unwrap();
+    storage
+        .add_entry("Goodbye".to_string(), None, None)
+        .unwrap();
+
+    let results = storage.search("hello", None).unwrap();
+    assert_eq!(results.len(), 1);
+    assert_eq!(results[0].text, "Hello World");
+    let _ = std::fs::remove_dir_all(dir);
+}
+
+#[test]
+fn test_search_requires_all_tokens() {
+    let (storage, dir) = temp_storage();
+    storage
+        .add_entry("send the quarterly report".to_string(), None, None)
+        .unwrap();
+    storage
+        .add_entry("send the invoice".to_string(), None, None)
+        .unwrap();
+
+    let results = storage.search("send  report", None).unwrap();
+    assert_eq!(results.len(), 1);
+    assert_eq!(results[0].text, "send the quarterly report");
+    let _ = std::fs::remove_dir_all(dir);
+}
+
+#[test]
+fn test_search_returns_newest_first_with_limit() {
+    let (storage, dir) = temp_storage();
+    storage
+        .add_entry("note one".to_string(), None, None)
+        .unwrap();
+    storage
+        .add_entry("note two".to_string(), None, None)
+        .unwrap();
+    storage
+        .add_entry("note three".to_string(), None, None)
+        .unwrap();
+
+    let results = storage.search("note", Some(2)).unwrap();
+    let texts: Vec<&str> = results.iter().map(|e| e.text.as_str()).collect();
+    assert_eq!(texts, vec!["note three", "note two"]);
+    let _ = std::fs::remove_dir_all(dir);
+}
+
+#[test]
+fn test_search_empty_query_matches_everything() {
+    let (storage, dir) = temp_storage();
+    storage.add_entry("a".to_string(), None, None).unwrap();
+    storage.add_entry("b".to_string(), None, None).unwrap();
+
+    assert_eq!(storage.search("   ", None).unwrap().len(), 2);
+    let _ = std::fs::remove_dir_all(dir);
+}
+
+// Tests for pinning
+#[test]
+fn test_clear_keeps_pinned_entries_by_default() {
+    let (storage, dir) = temp_storage();
+    let pinned = storage
+        .add_entry("keep me".to_string(), None, None)
+        .unwrap();
+    storage
+        .add_entry("drop me".to_string(), None, None)
+        .unwrap();
+    assert!(storage.set_pinned(&pinned.id, true).unwrap());
+
+    storage.clear(false).unwrap();
+    let entries = storage.get_all(None).unwrap();
+    assert_eq!(entries.len(), 1);
+    assert_eq!(entries[0].text, "keep me");
+
+    storage.clear(true).unwrap();
+    assert!(storage.get_all(None).unwrap().is_empty());
+    let _ = std::fs::remove_dir_all(dir);
+}
+
+#[test]
+fn test_get_range_returns_matching_entries_newest_first() {
+    let (storage, dir) = temp_storage();
+    let path = dir.join("import.json");
+    std::fs::write(
+        &path,
+        r#"[{"id":"a","timestamp":"2024-01-01T09:00:00Z","text":"before"},
+            {"id":"b","timestamp":"2024-01-02T09:00:00Z","text":"first"},
+            {"id":"c","timestamp":"2024-01-02T17:00:00Z","text":"second"},
+            {"id":"d","timestamp":"2024-01-03T09:00:00Z","text":"after"}]"#,
+    )
+    .unwrap();
+    storage.import(&path, true).unwrap();
+
+    let start = "2024-01-02T00:00:00Z".parse().unwrap();
+    let end = "2024-01-02T17:00:00Z".parse().unwrap();
+    let texts = |entries: Vec<HistoryEntry>| -> Vec<String> {
+        entries.into_iter().map(|e| e.text).collect()
+    };
+    assert_eq!(
+        texts(storage.get_range(start, end, None).unwrap()),
+        vec!["second", "first"]
+    );
+    assert_eq!(
+        texts(storage.get_range(start, end, Some(1)).unwrap()),
+        vec!["second"]
+    );
+
+    let empty_start = "2025-01-01T00:00:00Z".parse().unwrap();
+    let empty_end = "2025-02-01T00:00:00Z".parse().unwrap();
+    assert!(storage
+        .get_range(empty_start, empty_end, None)
+        .unwrap()
+        .is_empty());
+
+    assert!(storage.get_range(end, start, None).is_err());
+    let _ = std::fs::remove_dir_all(dir);
+}
+
+#[test]
+fn test_history_change_serializes_with_kind_tag() {
+    let (storage, dir) = temp_storage();
+    let entry = storage.add_entry("hello".to_string(), None, None).unwrap();
+
+    let added = serde_json::to_value(HistoryChange::Added {
+        entry: entry.clone(),
+    })
+    .unwrap();
+    assert_eq!(added["kind"], "added");
+    assert_eq!(added["entry"]["id"], entry.id.as_str());
+    assert_eq!(added["entry"]["text"], "hello");
+
+    let deleted = serde_json::to_value(HistoryChange::Deleted {
+        id: entry.id.clone(),
+    })
+    .unwrap();
+    assert_eq!(
+        deleted,
+        serde_json::json!({ "kind": "deleted", "id": entry.id })
+    );
+
+    assert_eq!(
+        serde_json::to_value(HistoryChange::Reset).unwrap(),
+        serde_json::json!({ "kind": "reset" })
+    );
+    let _ = std::fs::remove_dir_all(dir);
+}
+
+#[test]
+fn test_clear_before_removes_older_unpinned_entries() {
+    let (storage, dir) = temp_storage();
+    storage.add_entry("recent".to_string(), None, None).unwrap();
+    let path = dir.join("import.json");
+    std::fs::write(
+        &path,
+        r#"[{"id":"a","timestamp":"2020-01-01T00:00:00Z","text":"stale"},
+            {"id":"b","timestamp":"2020-06-01T00:00:00Z","text":"kept","pinned":true},
+            {"id":"c","timestamp":"2023-01-01T00:00:00Z","text":"newer"}]"#,
+    )
+    .unwrap();
+    storage.import(&path, true).unwrap();
+
+    let cutoff = "2022-01-01T00:00:00Z".parse().unwrap();
+    assert_eq!(storage.clear_before(cutoff).unwrap(), 1);
+    let texts: Vec<String> = storage
+        .get_all(None)
+        .unwrap()
+        .into_iter()
+        .map(|e| e.text)
+        .collect();
+    assert_eq!(texts, vec!["recent", "newer", "kept"]);
+
+    // Nothing left to remove
+    assert_eq!(storage.clear_before(cutoff).unwrap(), 0);
+    let _ = std::fs::remove_dir_all(dir);
+}
+
+#[test]
+fn test_get_all_pinned_first_orders_pinned_entries_on_top() {
+    let (storage, dir) = temp_storage();
+    let oldest = storage.add_entry("oldest".to_string(), None, None).unwrap();
+    storage.add_entry("middle".to_string(), None, None).unwrap();
+    storage.add_entry("newest".to_string(), None, None).unwrap();
+    storage.set_pinned(&oldest.id, true).unwrap();
+
+    let texts: Vec<String> = storage
+        .get_all_pinned_first(None)
+        .unwrap()
+        .into_iter()
+        .map(|e| e.text)
+        .collect();
+    assert_eq!(texts, vec!["oldest", "newest", "middle"]);
+    // Plain get_all is still newest first
+    assert_eq!(storage.get_all(Some(1)).unwrap()[0].text, "newest");
+    let _ = std::fs::remove_dir_all(dir);
+}
+
+#[test]
+fn test_get_slot_counts_from_newest() {
+    let (storage, dir) = temp_storage();
+    storage.add_entry("oldest".to_string(), None, None).unwrap();
+    storage.add_entry("middle".to_string(), None, None).unwrap();
+    storage.add_entry("newest".to_string(), None, None).unwrap();
+
+    assert_eq!(storage.get_slot(1, false).unwrap().unwrap().text, "newest");
+    assert_eq!(storage.get_slot(3, false).unwrap().unwrap().text, "oldest");
+    assert!(storage.get_slot(4, false).unwrap().is_none());
+    assert!(storage.get_slot(0, false).unwrap().is_none());
+    let _ = std::fs::remove_dir_all(dir);
+}
+
+#[test]
+fn test_get_slot_pinned_first_keeps_pinned_entries_in_place() {
+    let (storage, dir) = temp_storage();
+    let first = storage.add_entry("first".to_string(), None, None).unwrap();
+    let second = storage.add_entry("second".to_string(), None, None).unwrap();
+    storage.set_pinned(&first.id, true).unwrap();
+    storage.set_pinned(&second.id, true).unwrap();
+    storage.add_entry("newest".to_string(), None, None).unwrap();
+
+    assert_eq!(storage.get_slot(2, true).unwrap().unwrap().text, "first");
+    assert_eq!(storage.get_slot(3, true).unwrap().unwrap().text, "newest");
+    assert_eq!(storage.get_slot(2, false).unwrap().unwrap().text, "second");
+    let _ = std::fs::remove_dir_all(dir);
+}
+
+#[test]
+fn test_set_pinned_unknown_id_returns_false() {
+    let (storage, dir) = temp_storage();
+    assert!(!storage.set_pinned("missing", true).unwrap());
+    let _ = std::fs::remove_dir_all(dir);
+}
+
+#[test]
+fn test_entry_without_pinned_field_deserializes_unpinned() {
+    let json = r#"{"id":"1","timestamp":"2024-01-01T00:00:00Z","text":"hi"}"#;
+    let entry: HistoryEntry = serde_json::from_str(json).unwrap();
+    assert!(!entry.pinned);
+}
+
+// Tests for HistoryStorage::export()
+#[test]
+fn test_export_json_round_trips_entries() {
+    let (storage, dir) = temp_storage();
+    storage.add_entry("first".to_string(), None, None).unwrap();
+    storage
+        .add_entry("second".to_string(), Some("en".to_string()), None)
+        .unwrap();
+
+    let path = dir.join("export.json");
+    assert_eq!(storage.export(ExportFormat::Json, &path, false).unwrap(), 2);
+
+    let content = std::fs::read_to_string(&path).unwrap();
+    let entries: Vec<HistoryEntry> = serde_json::from_str(&content).unwrap();
+    assert_eq!(entries[0].text, "second");
+    assert_eq!(entries[0].language.as_deref(), Some("en"));
+    assert_eq!(entries[1].text, "first");
+    let _ = std::fs::remove_dir_all(dir);
+}
+
+#[test]
+fn test_export_text_oldest_first() {
+    let (storage, dir) = temp_storage();
+    storage.add_entry("first".to_string(), None, None).unwrap();
+    storage.add_entry("second".to_string(), None, None).unwrap();
+
+    let path = dir.join("export.txt");
+    assert_eq!(storage.export(ExportFormat::Text, &path, true).unwrap(), 2);
+    assert_eq!(std::fs::read_to_string(&path).unwrap(), "first\n\nsecond");
+    let _ = std::fs::remove_dir_all(dir);
+}
+
+#[test]
+fn test_export_to_missing_directory_fails() {
+    let (storage, dir) = temp_storage();
+    let path = dir.join("missing").join("export.json");
+    let err = storage
+        .export(ExportFormat::Json, &path, false)
+        .unwrap_err();
+    assert!(err.contains("Failed to write export"));
+    let _ = std::fs::remove_dir_all(dir);
+}
+
+// Tests for HistoryStorage::import()
+#[test]
+fn test_import_with_dedupe_skips_existing_entries() {
+    let (storage, dir) = temp_storage();
+    storage.add_entry("kept".to_string(), None, None).unwrap();
+    let path = dir.join("export.json");
+    storage.export(ExportFormat::Json, &path, false).unwrap();
+
+    let result = storage.import(&path, true).unwrap();
+    assert_eq!(result.imported, 0);
+    assert_eq!(result.skipped, 1);
+    assert_eq!(storage.get_all(None).unwrap().len(), 1);
+    let _ = std::fs::remove_dir_all(dir);
+}
+
+#[test]
+fn test_import_without_dedupe_assigns_fresh_ids() {
+    let (storage, dir) = temp_storage();
+    storage.add_entry("copied".to_string(), None, None).unwrap();
+    let path = dir.join("export.json");
+    storage.export(ExportFormat::Json, &path, false).unwrap();
+
+    let result = storage.import(&path, false).unwrap();
+    assert_eq!(result.imported, 1);
+    let entries = storage.get_all(None).unwrap();
+    assert_eq!(entries.len(), 2);
+    assert_ne!(entries[0].id, entries[1].id);
+    let _ = std::fs::remove_dir_all(dir);
+}
+
+#[test]
+fn test_import_merges_by_timestamp() {
+    let (storage, dir) = temp_storage();
+    storage.add_entry("recent".to_string(), None, None).unwrap();
+    let path = dir.join("import.json");
+    std::fs::write(
+        &path,
+        r#"[{"id":"old","timestamp":"2020-01-01T00:00:00Z","text":"older"}]"#,
+    )
+    .unwrap();
+
+    storage.import(&path, true).unwrap();
+    let texts: Vec<String> = storage
+        .get_all(None)
+        .unwrap()
+        .into_iter()
+        .map(|e| e.text)
+        .collect();
+    assert_eq!(texts, vec!["recent", "older"]);
+    let _ = std::fs::remove_dir_all(dir);
+}
+
+#[test]
+fn test_import_rejects_malformed_file() {
+    let (storage, dir) = temp_storage();
+    storage
+        .add_entry("untouched".to_string(), None, None)
+        .unwrap();
+    let path = dir.join("bad.json");
+    std::fs::write(&path, r#"[{"text":"missing fields"}]"#).unwrap();
+
+    let err = storage.import(&path, true).unwrap_err();
+    assert!(err.contains("Invalid history export"));
+    assert_eq!(storage.get_all(None).unwrap().len(), 1);
+    let _ = std::fs::remove_dir_all(dir);
+}
+
+// Tests for HistoryStorage::prune()
+#[test]
+fn test_prune_drops_oldest_unpinned_entries_over_max() {
+    let (storage, dir) = temp_storage();
+    let oldest = storage.add_entry("oldest".to_string(), None, None).unwrap();
+    storage.add_entry("middle".to_string(), None, None).unwrap();
+    storage.add_entry("newest".to_string(), None, None).unwrap();
+    storage.set_pinned(&oldest.id, true).unwrap();
+
+    let limits = HistoryLimits {
+        max_entries: 2,
+        max_age_days: 0,
+    };
+    assert_eq!(storage.prune(&limits).unwrap(), 1);
+    let texts: Vec<String> = storage
+        .get_all(None)
+        .unwrap()
+        .into_iter()
+        .map(|e| e.text)
+        .collect();
+    assert_eq!(texts, vec!["newest", "oldest"]);
+    let _ = std::fs::remove_dir_all(dir);
+}
+
+#[test]
+fn test_prune_drops_unpinned_entries_older_than_max_age() {
+    let (storage, dir) = temp_storage();
+    storage.add_entry("recent".to_string(), None, None).unwrap();
+    let path = dir.join("import.json");
+    std::fs::write(
+        &path,
+        r#"[{"id":"a","timestamp":"2020-01-01T00:00:00Z","text":"stale"},
+            {"id":"b","timestamp":"2020-01-01T00:00:00Z","text":"kept","pinned":true}]"#,
+    )
+    .unwrap();
+    storage.import(&path, true).unwrap();
+
+    let limits = HistoryLimits {
+        max_entries: 0,
+        max_age_days: 30,
+    };
+    assert_eq!(storage.prune(&limits).unwrap(), 1);
+    let texts: Vec<String> = storage
+        .get_all(None)
+        .unwrap()
+        .into_iter()
+        .map(|e| e.text)
+        .collect();
+    assert_eq!(texts, vec!["recent", "kept"]);
+    let _ = std::fs::remove_dir_all(dir);
+}
+
+#[test]
+fn test_prune_with_default_limits_keeps_recent_entries() {
+    let (storage, dir) = temp_storage();
+    storage.add_entry("one".to_string(), None, None).unwrap();
+    storage.add_entry("two".to_string(), None, None).unwrap();
+
+    assert_eq!(storage.prune(&HistoryLimits::default()).unwrap(), 0);
+    assert_eq!(storage.get_all(None).unwrap().len(), 2);
+    let _ = std::fs::remove_dir_all(dir);
+}
+
+// Tests for HistoryStorage::get()
+#[test]
+fn test_get_returns_entry_by_id() {
+    let (storage, dir) = temp_storage();
+    let entry = storage
+        .add_entry("find me".to_string(), None, None)
+        .unwrap();
+    storage.add_entry("other".to_string(), None, None).unwrap();
+
+    assert_eq!(storage.get(&entry.id).unwrap().unwrap().text, "find me");
+    assert!(storage.get("missing").unwrap().is_none());
+    let _ = std::fs::remove_dir_all(dir);
+}
+
+// Tests for HistoryStorage::update()
+#[test]
+fn test_update_replaces_text_and_recounts() {
+    let (storage, dir) = temp_storage();
+    let entry = storage
+        .add_entry("helo world".to_string(), None, None)
+        .unwrap();
+
+    let updated = storage
+        .update(&entry.id, "hello there big world".to_string())
+        .unwrap();
+    assert_eq!(updated.text, "hello there big world");
+    assert_eq!(updated.word_count, 4);
+    assert_eq!(updated.char_count, 21);
+    assert_eq!(updated.timestamp, entry.timestamp);
+    assert!(updated.edited_at.is_some());
+
+    // Persisted to disk
+    let reloaded = HistoryStorage::new(dir.clone(), None);
+    assert_eq!(
+        reloaded.get(&entry.id).unwrap().unwrap().text,
+        "hello there big world"
+    );
+    let _ = std::fs::remove_dir_all(dir);
+}
+
+#[test]
+fn test_update_with_same_text_keeps_edited_at_unset() {
+    let (storage, dir) = temp_storage();
+    let entry = storage.add_entry("same".to_string(), None, None).unwrap();
+
+    let updated = storage.update(&entry.id, "same".to_string()).unwrap();
+    assert!(updated.edited_at.is_none());
+    let _ = std::fs::remove_dir_all(dir);
+}
+
+#[test]
+fn test_update_unknown_id_errors() {
+    let (storage, dir) = temp_storage();
+    assert!(storage.update("missing", "text".to_string()).is_err());
+    let _ = std::fs::remove_dir_all(dir);
+}
+
+#[test]
+fn test_update_survives_concurrent_adds() {
+    let (storage, dir) = temp_storage();
+    let entry = storage
+        .add_entry("original".to_string(), None, None)
+        .unwrap();
+
+    std::thread::scope(|scope| {
+        scope.spawn(|| {
+            for i in 0..20 {
+                storage.add_entry(format!("new {}", i), None, None).unwrap();
+            }
+        });
+        scope.spawn(|| {
+            storage.update(&entry.id, "edited".to_string()).unwrap();
+        });
+    });
+
+    let entries = storage.get_all(None).unwrap();
+    assert_eq!(entries.len(), 21);
+    assert_eq!(storage.get(&entry.id).unwrap().unwrap().text, "edited");
+    let _ = std::fs::remove_dir_all(dir);
+}
+
+// Tests for history tags
+#[test]
+fn test_add_tag_trims_and_ignores_duplicates() {
+    let (storage, dir) = temp_storage();
+    let entry = storage.add_entry("note".to_string(), None, None).unwrap();
+
+    storage.add_tag(&entry.id, " work ").unwrap();
+    let tagged = storage.add_tag(&entry.id, "Work").unwrap();
+    assert_eq!(tagged.tags, vec!["work".to_string()]);
+    assert!(storage.add_tag(&entry.id, "  ").is_err());
+    assert!(storage.add_tag("missing", "work").is_err());
+    let _ = std::fs::remove_dir_all(dir);
+}
+
+#[test]
+fn test_remove_tag_ignores_case() {
+    let (storage, dir) = temp_storage();
+    let entry = storage.add_entry("note".to_string(), None, None).unwrap();
+    storage.add_tag(&entry.id, "Work").unwrap();
+    storage.add_tag(&entry.id, "ideas").unwrap();
+
+    let entry = storage.remove_tag(&entry.id, "work").unwrap();
+    assert_eq!(entry.tags, vec!["ideas".to_string()]);
+    let _ = std::fs::remove_dir_all(dir);
+}
+
+#[test]
+fn test_get_by_tag_returns_matching_entries_newest_first() {
+    let (storage, dir) = temp_storage();
+    let first = storage.add_entry("first".to_string(), None, None).unwrap();
+    storage
+        .add_entry("untagged".to_string(), None, None)
+        .unwrap();
+    let third = storage.add_entry("third".to_string(), None, None).unwrap();
+    storage.add_tag(&first.id, "todo").unwrap();
+    storage.add_tag(&third.id, "TODO").unwrap();
+
+    let results = storage.get_by_tag("todo").unwrap();
+    let texts: Vec<&str> = results.iter().map(|e| e.text.as_str()).collect();
+    assert_eq!(texts, vec!["third", "first"]);
+    assert_eq!(storage.get_all(None).unwrap().len(), 3);
+    let _ = std::fs::remove_dir_all(dir);
+}
+
+#[test]
+fn test_entry_without_tags_field_deserializes_empty() {
+    let json = r#"{"id":"1","timestamp":"2024-01-01T00:00:00Z","text":"hi"}"#;
+    let entry: HistoryEntry = serde_json::from_str(json).unwrap();
+    assert!(entry.tags.is_empty());
+}
+
+// Tests for word/char counts and HistoryStorage::stats()
+#[test]
+fn test_add_entry_records_counts_and_duration() {
+    let (storage, dir) = temp_storage();
+    let entry = storage
+        .add_entry("héllo  big world".to_string(), None, Some(2500))
+        .unwrap();
+    assert_eq!(entry.char_count, 16);
+    assert_eq!(entry.word_count, 3);
+    assert_eq!(entry.audio_duration_ms, Some(2500));
+    let _ = std::fs::remove_dir_all(dir);
+}
+
+#[test]
+fn test_old_entries_get_counts_backfilled_on_load() {
+    let dir = std::env::temp_dir().join(format!("tambourine-test-{}", uuid::Uuid::new_v4()));
+    std::fs::create_dir_all(&dir).unwrap();
+    std::fs::write(
+        dir.join("history.json"),
+        r#"{"entries":[{"id":"1","timestamp":"2024-01-01T00:00:00Z","text":"two words"}]}"#,
+    )
+    .unwrap();
+
+    let storage = HistoryStorage::new(dir.clone(), None);
+    let entry = &storage.get_all(None).unwrap()[0];
+    assert_eq!(entry.word_count, 2);
+    assert_eq!(entry.char_count, 9);
+    assert_eq!(entry.audio_duration_ms, None);
+    let _ = std::fs::remove_dir_all(dir);
+}
+
+#[test]
+fn test_stats_totals_and_averages() {
+    let (storage, dir) = temp_storage();
+    storage
+        .add_entry("one two three".to_string(), None, Some(1000))
+        .unwrap();
+    storage.add_entry("four".to_string(), None, None).unwrap();
+
+    let stats = storage.stats().unwrap();
+    assert_eq!(stats.total_entries, 2);
+    assert_eq!(stats.total_words, 4);
+    assert_eq!(stats.total_chars, 17);
+    assert_eq!(stats.total_audio_duration_ms, 1000);
+    assert_eq!(stats.average_words, 2.0);
+    let _ = std::fs::remove_dir_all(dir);
+}
+
+#[test]
+fn test_stats_on_empty_history() {
+    let (storage, dir) = temp_storage();
+    let stats = storage.stats().unwrap();
+    assert_eq!(stats.total_entries, 0);
+    assert_eq!(stats.average_words, 0.0);
+    let _ = std::fs::remove_dir_all(dir);
+}
+
+// Tests for HistoryStorage::append_to_latest()
+#[test]
+fn test_append_to_latest_within_window() {
+    let (storage, dir) = temp_storage();
+    let entry = storage
+        .add_entry("first chunk".to_string(), None, Some(1000))
+        .unwrap();
+
+    let now = entry.timestamp + chrono::Duration::seconds(2);
+    let appended = storage
+        .append_to_latest("second", Some(500), 5000, now)
+        .unwrap()
+        .unwrap();
+    assert_eq!(appended.id, entry.id);
+    assert_eq!(appended.text, "first chunk second");
+    assert_eq!(appended.word_count, 3);
+    assert_eq!(appended.audio_duration_ms, Some(1500));
+    assert_eq!(appended.appended_at, Some(now));
+    assert_eq!(storage.get_all(None).unwrap().len(), 1);
+    let _ = std::fs::remove_dir_all(dir);
+}
+
+#[test]
+fn test_append_to_latest_window_follows_last_chunk() {
+    let (storage, dir) = temp_storage();
+    let entry = storage.add_entry("one".to_string(), None, None).unwrap();
+    let second = entry.timestamp + chrono::Duration::seconds(4);
+    storage.append_to_latest("two", None, 5000, second).unwrap();
+
+    // 8s after the entry was created but only 4s after the last chunk
+    let third = second + chrono::Duration::seconds(4);
+    let appended = storage
+        .append_to_latest("three", None, 5000, third)
+        .unwrap();
+    assert_eq!(appended.unwrap().text, "one two three");
+    let _ = std::fs::remove_dir_all(dir);
+}
+
+#[test]
+fn test_append_to_latest_outside_window_or_pinned() {
+    let (storage, dir) = temp_storage();
+    let entry = storage.add_entry("first".to_string(), None, None).unwrap();
+
+    let late = entry.timestamp + chrono::Duration::seconds(10);
+    assert!(storage
+        .append_to_latest("late", None, 5000, late)
+        .unwrap()
+        .is_none());
+
+    storage.set_pinned(&entry.id, true).unwrap();
+    assert!(storage
+        .append_to_latest("pinned", None, 5000, entry.timestamp)
+        .unwrap()
+        .is_none());
+    assert_eq!(storage.get_all(None).unwrap()[0].text, "first");
+    let _ = std::fs::remove_dir_all(dir);
+}
+
+#[test]
+fn test_append_to_latest_on_empty_history() {
+    let (storage, dir) = temp_storage();
+    assert!(storage
+        .append_to_latest("text", None, 5000, chrono::Utc::now())
+        .unwrap()
+        .is_none());
+    let _ = std::fs::remove_dir_all(dir);
+}
+
+#[test]
+fn test_concurrent_adds_and_reads_lose_nothing() {
+    const WRITERS: usize = 8;
+    const ENTRIES_PER_WRITER: usize = 25;
+    let (storage, dir) = temp_storage();
+
+    std::thread::scope(|scope| {
+        for writer in 0..WRITERS {
+            let storage = &storage;
+            scope.spawn(move || {
+                for i in 0..ENTRIES_PER_WRITER {
+                    storage
+                        .add_entry(format!("writer {} entry {}", writer, i), None, None)
+                        .unwrap();
+                }
+            });
+        }
+        for _ in 0..4 {
+            scope.spawn(|| {
+                for _ in 0..50 {
+                    // Readers only ever see complete entries
+                    for entry in storage.get_all(None).unwrap() {
+                        assert_eq!(entry.word_count, 4);
+                        assert_eq!(entry.char_count, entry.text.chars().count());
+                    }
+                }
+            });
+        }
+    });
+
+    let entries = storage.get_all(None).unwrap();
+    assert_eq!(entries.len(), WRITERS * ENTRIES_PER_WRITER);
+    let ids: std::collections::HashSet<_> = entries.iter().map(|e| e.id.clone()).collect();
+    assert_eq!(ids.len(), entries.len());
+
+    // The file on disk holds every entry, not an older snapshot
+    let reloaded = HistoryStorage::new(dir.clone(), None);
+    assert_eq!(
+        reloaded.get_all(None).unwrap().len(),
+        WRITERS * ENTRIES_PER_WRITER
+    );
+    let _ = std::fs::remove_dir_all(dir);
+}
+
+// Tests for crash-safe saving
+#[test]
+fn test_save_keeps_backup_of_previous_file() {
+    let (storage, dir) = temp_storage();
+    storage.add_entry("first".to_string(), None, None).unwrap();
+    storage.add_entry("second".to_string(), None, None).unwrap();
+
+    let backup = std::fs::read_to_string(dir.join("history.json.bak")).unwrap();
+    assert!(backup.contains("first"));
+    assert!(!backup.contains("second"));
+    assert!(!dir.join("history.json.tmp").exists());
+    let _ = std::fs::remove_dir_all(dir);
+}
+
+#[test]
+fn test_clear_removes_backup() {
+    let (storage, dir) = temp_storage();
+    storage.add_entry("secret".to_string(), None, None).unwrap();
+    storage.add_entry("other".to_string(), None, None).unwrap();
+    storage.clear(true).unwrap();
+
+    assert!(!dir.join("history.json.bak").exists());
+    let _ = std::fs::remove_dir_all(dir);
+}
+
+#[test]
+fn test_truncated_file_recovers_from_backup() {
+    let (storage, dir) = temp_storage();
+    storage.add_entry("first".to_string(), None, None).unwrap();
+    storage.add_entry("second".to_string(), None, None).unwrap();
+    drop(storage);
+
+    // Simulate a crash that left the main file half written
+    let path = dir.join("history.json");
+    let content = std::fs::read_to_string(&path).unwrap();
+    std::fs::write(&path, &content[..content.len() / 2]).unwrap();
+
+    let recovered = HistoryStorage::new(dir.clone(), None);
+    let texts: Vec<String> = recovered
+        .get_all(None)
+        .unwrap()
+        .into_iter()
+        .map(|e| e.text)
+        .collect();
+    assert_eq!(texts, vec!["first"]);
+    // The corrupt file is set aside rather than overwritten
+    assert!(dir.join("history.json.corrupt").exists());
+    let _ = std::fs::remove_dir_all(dir);
+}
+
+#[test]
+fn test_missing_file_recovers_from_backup() {
+    let (storage, dir) = temp_storage();
+    storage.add_entry("first".to_string(), None, None).unwrap();
+    storage.add_entry("second".to_string(), None, None).unwrap();
+    drop(storage);
+
+    // A crash between moving the old file aside and renaming the new one into place
+    std::fs::remove_file(dir.join("history.json")).unwrap();
+
+    let recovered = HistoryStorage::new(dir.clone(), None);
+    assert_eq!(recovered.get_all(None).unwrap()[0].text, "first");
+    let _ = std::fs::remove_dir_all(dir);
+}
+
+// Tests for custom history locations
+#[test]
+fn test_resolve_history_dir_uses_writable_custom_dir() {
+    let (_, dir) = temp_storage();
+    let custom = dir.join("custom");
+    let resolved = crate::history::resolve_history_dir(Some(&custom), dir.clone());
+    assert_eq!(resolved, custom);
+    assert!(custom.is_dir());
+    let _ = std::fs::remove_dir_all(dir);
+}
+
+#[test]
+fn test_resolve_history_dir_falls_back_when_custom_dir_is_unusable() {
+    let (_, dir) = temp_storage();
+    std::fs::create_dir_all(&dir).unwrap();
+    // A file where the directory should be
+    let blocked = dir.join("blocked");
+    std::fs::write(&blocked, "").unwrap();
+
+    let resolved = crate::history::resolve_history_dir(Some(&blocked), dir.clone());
+    assert_eq!(resolved, dir);
+    assert_eq!(crate::history::resolve_history_dir(None, dir.clone()), dir);
+    let _ = std::fs::remove_dir_all(dir);
+}
+
+#[test]
+fn test_relocate_with_migrate_moves_entries() {
+    let (storage, dir) = temp_storage();
+    storage.add_entry("first".to_string(), None, None).unwrap();
+    let new_dir = dir.join("elsewhere");
+
+    assert_eq!(storage.relocate(&new_dir, true).unwrap(), 1);
+    assert_eq!(storage.file_path().unwrap(), new_dir.join("history.json"));
+    storage.add_entry("second".to_string(), None, None).unwrap();
+
+    let reloaded = HistoryStorage::new(new_dir, None);
+    assert_eq!(reloaded.get_all(None).unwrap().len(), 2);
+    // The old file is left alone
+    let old = HistoryStorage::new(dir.clone(), None);
+    assert_eq!(old.get_all(None).unwrap().len(), 1);
+    let _ = std::fs::remove_dir_all(dir);
+}
+
+#[test]
+fn test_relocate_without_migrate_loads_existing_history() {
+    let (storage, dir) = temp_storage();
+    storage.add_entry("here".to_string(), None, None).unwrap();
+    let new_dir = dir.join("elsewhere");
+    HistoryStorage::new(new_dir.clone(), None)
+        .add_entry("there".to_string(), None, None)
+        .unwrap();
+
+    assert_eq!(storage.relocate(&new_dir, false).unwrap(), 1);
+    assert_eq!(storage.get_all(None).unwrap()[0].text, "there");
+    let _ = std::fs::remove_dir_all(dir);
+}
+
+// Tests for incognito mode
+#[test]
+fn test_incognito_entries_are_not_written_to_disk() {
+    let (storage, dir) = temp_storage();
+    storage.add_entry("saved".to_string(), None, None).unwrap();
+    storage.set_incognito(true);
+    let secret = storage.add_entry("secret".to_string(), None, None).unwrap();
+    assert!(secret.incognito);
+
+    // Still available within the session, e.g. for paste last
+    assert_eq!(storage.get_all(Some(1)).unwrap()[0].text, "secret");
+    let content = std::fs::read_to_string(dir.join("history.json")).unwrap();
+    assert!(!content.contains("secret"));
+
+    // Later saves leave incognito entries out too, even after incognito ends
+    storage.set_incognito(false);
+    storage.add_entry("after".to_string(), None, None).unwrap();
+    storage
+        .update(&secret.id, "still secret".to_string())
+        .unwrap();
+    let reloaded = HistoryStorage::new(dir.clone(), None);
+    let texts: Vec<String> = reloaded
+        .get_all(None)
+        .unwrap()
+        .into_iter()
+        .map(|e| e.text)
+        .collect();
+    assert_eq!(texts, vec!["after", "saved"]);
+    let _ = std::fs::remove_dir_all(dir);
+}
+
+#[test]
+fn test_incognito_does_not_append_to_saved_entry() {
+    let (storage, dir) = temp_storage();
+    let now = chrono::Utc::now();
+    storage.add_entry("saved".to_string(), None, None).unwrap();
+    storage.set_incognito(true);
+
+    let appended = storage
+        .append_to_latest("secret", None, 60_000, now)
+        .unwrap();
+    assert!(appended.is_none());
+
+    let secret = storage.add_entry("secret".to_string(), None, None).unwrap();
+    let appended = storage
+        .append_to_latest("more", None, 60_000, secret.timestamp)
+        .unwrap()
+        .unwrap();
+    assert_eq!(appended.text, "secret more");
+    assert!(appended.incognito);
+    let _ = std::fs::remove_dir_all(dir);
+}
+
+// Tests for encryption at rest
+#[test]
+fn test_encrypted_history_has_no_plaintext_on_disk() {
+    let (storage, dir) = temp_storage();
+    storage
+        .add_entry("my password is hunter2".to_string(), None, None)
+        .unwrap();
+    storage
+        .set_encryption(Some(HistoryKey::generate()))
+        .unwrap();
+    storage
+        .add_entry("second secret".to_string(), None, None)
+        .unwrap();
+
+    let content = std::fs::read_to_string(dir.join("history.json")).unwrap();
+    assert!(!content.contains("hunter2"));
+    assert!(!content.contains("second secret"));
+    // The plaintext backup from before encryption is gone, and the new backup is encrypted
+    let backup = std::fs::read_to_string(dir.join("history.json.bak")).unwrap();
+    assert!(!backup.contains("hunter2"));
+    let _ = std::fs::remove_dir_all(dir);
+}
+
+#[test]
+fn test_encrypted_history_is_locked_without_key() {
+    let (storage, dir) = temp_storage();
+    let key = HistoryKey::generate();
+    storage.add_entry("secret".to_string(), None, None).unwrap();
+    storage.set_encryption(Some(key.clone())).unwrap();
+    drop(storage);
+    let encrypted = std::fs::read_to_string(dir.join("history.json")).unwrap();
+
+    let locked = HistoryStorage::new(dir.clone(), None);
+    let status = locked.encryption_status().unwrap();
+    assert!(status.locked);
+    assert_eq!(status.key_source, Some(KeySource::Keychain));
+    assert!(locked.get_all(None).is_err());
+    assert!(locked.add_entry("new".to_string(), None, None).is_err());
+    assert!(locked.set_encryption(None).is_err());
+    // Nothing was written over the encrypted file
+    assert_eq!(
+        std::fs::read_to_string(dir.join("history.json")).unwrap(),
+        encrypted
+    );
+
+    assert!(locked.unlock(HistoryKey::generate()).is_err());
+    assert!(locked.encryption_status().unwrap().locked);
+    assert_eq!(locked.unlock(key).unwrap(), 1);
+    assert_eq!(locked.get_all(None).unwrap()[0].text, "secret");
+    let _ = std::fs::remove_dir_all(dir);
+}
+
+#[test]
+fn test_disabling_encryption_writes_plaintext() {
+    let (storage, dir) = temp_storage();
+    storage
+        .set_encryption(Some(HistoryKey::generate()))
+        .unwrap();
+    storage
+        .add_entry("visible again".to_string(), None, None)
+        .unwrap();
+    storage.set_encryption(None).unwrap();
+
+    let reloaded = HistoryStorage::new(dir.clone(), None);
+    assert!(!reloaded.encryption_status().unwrap().locked);
+    assert_eq!(reloaded.get_all(None).unwrap()[0].text, "visible again");
+    let _ = std::fs::remove_dir_all(dir);
+}
+
+#[test]
+fn test_missing_encrypted_history_starts_locked() {
+    let (storage, dir) = temp_storage();
+    let key = HistoryKey::generate();
+    storage.set_encryption(Some(key.clone())).unwrap();
+    storage.add_entry("secret".to_string(), None, None).unwrap();
+    drop(storage);
+    std::fs::remove_file(dir.join("history.json")).unwrap();
+    std::fs::remove_file(dir.join("history.json.bak")).unwrap();
+
+    let reopened = HistoryStorage::new(dir.clone(), Some(KeySource::Keychain));
+    let status = reopened.encryption_status().unwrap();
+    assert!(status.locked);
+    assert_eq!(status.key_source, Some(KeySource::Keychain));
+    // Nothing is written in plaintext while locked
+    assert!(reopened.add_entry("new".to_string(), None, None).is_err());
+    assert!(!dir.join("history.json").exists());
+
+    // Unlocking starts a new encrypted history
+    assert_eq!(reopened.unlock(key).unwrap(), 0);
+    reopened
+        .add_entry("after unlock".to_string(), None, None)
+        .unwrap();
+    let content = std::fs::read_to_string(dir.join("history.json")).unwrap();
+    assert!(!content.contains("after unlock"));
+    let _ = std::fs::remove_dir_all(dir);
+}
+
+#[test]
+fn test_unreadable_encrypted_history_starts_locked() {
+    let dir = std::env::temp_dir().join(format!("tambourine-test-{}", uuid::Uuid::new_v4()));
+    std::fs::create_dir_all(&dir).unwrap();
+    std::fs::write(dir.join("history.json"), "{ not json").unwrap();
+
+    let storage = HistoryStorage::new(dir.clone(), Some(KeySource::Passphrase));
+    let status = storage.encryption_status().unwrap();
+    assert!(status.locked);
+    assert_eq!(status.key_source, Some(KeySource::Passphrase));
+    assert!(storage.get_all(None).is_err());
+
+    // Without an expected key source an unreadable file means a fresh plaintext history
+    let plain = HistoryStorage::new(dir.clone(), None);
+    assert!(!plain.encryption_status().unwrap().locked);
+    let _ = std::fs::remove_dir_all(dir);
+}
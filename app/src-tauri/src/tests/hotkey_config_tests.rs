@@ -1,4 +1,4 @@
-use crate::settings::HotkeyConfig;
+use crate::settings::{validate_setting, HotkeyConfig};
 
 // Tests for HotkeyConfig::to_shortcut_string()
 #[test]
@@ -6,6 +6,7 @@ fn test_to_shortcut_string_single_modifier() {
     let hotkey = HotkeyConfig {
         key: "Space".to_string(),
         modifiers: vec!["Ctrl".to_string()],
+        modifier_taps: 0,
     };
     assert_eq!(hotkey.to_shortcut_string(), "ctrl+Space");
 }
@@ -15,6 +16,7 @@ fn test_to_shortcut_string_multiple_modifiers() {
     let hotkey = HotkeyConfig {
         key: "Space".to_string(),
         modifiers: vec!["Ctrl".to_string(), "Alt".to_string()],
+        modifier_taps: 0,
     };
     assert_eq!(hotkey.to_shortcut_string(), "ctrl+alt+Space");
 }
@@ -24,7 +26,76 @@ fn test_to_shortcut_string_preserves_key_case() {
     let hotkey = HotkeyConfig {
         key: "Backquote".to_string(),
         modifiers: vec!["CTRL".to_string(), "ALT".to_string()],
+        modifier_taps: 0,
     };
     // Modifiers should be lowercase, key should preserve case
     assert_eq!(hotkey.to_shortcut_string(), "ctrl+alt+Backquote");
 }
+
+// Tests for modifier-only bindings and HotkeyConfig::from_shortcut_string()
+#[test]
+fn test_modifier_only_to_shortcut_string() {
+    let hotkey = HotkeyConfig {
+        modifiers: vec!["Super".to_string()],
+        key: String::new(),
+        modifier_taps: 2,
+    };
+    assert!(hotkey.is_modifier_only());
+    assert_eq!(hotkey.to_shortcut_string(), "super+super");
+}
+
+#[test]
+fn test_from_shortcut_string_combo() {
+    let hotkey = HotkeyConfig::from_shortcut_string("ctrl+alt+Space").unwrap();
+    assert_eq!(hotkey, HotkeyConfig::default_toggle());
+}
+
+#[test]
+fn test_from_shortcut_string_modifier_only_round_trip() {
+    let hotkey = HotkeyConfig::from_shortcut_string("super+super").unwrap();
+    assert_eq!(hotkey.modifiers, vec!["super".to_string()]);
+    assert_eq!(hotkey.modifier_taps, 2);
+    assert_eq!(hotkey.to_shortcut_string(), "super+super");
+}
+
+#[test]
+fn test_from_shortcut_string_rejects_mixed_modifiers_without_key() {
+    assert!(HotkeyConfig::from_shortcut_string("ctrl+alt").is_err());
+}
+
+#[test]
+fn test_from_shortcut_string_rejects_empty_parts() {
+    assert!(HotkeyConfig::from_shortcut_string("ctrl++Space").is_err());
+}
+
+#[test]
+fn test_modifier_only_not_registrable() {
+    let hotkey = HotkeyConfig::from_shortcut_string("super+super").unwrap();
+    assert!(hotkey.to_shortcut().is_err());
+    assert!(hotkey.check_registrable().is_err());
+    assert!(HotkeyConfig::default_toggle().check_registrable().is_ok());
+}
+
+#[test]
+fn test_validate_setting_rejects_modifier_only_hotkeys() {
+    let modifier_only = serde_json::json!({"modifiers": ["super"], "key": "", "modifier_taps": 2});
+    assert!(validate_setting("toggle_hotkey", &modifier_only).is_err());
+    assert!(validate_setting("paste_slot_2_hotkey", &modifier_only).is_err());
+    assert!(validate_setting("paste_slot_2_hotkey", &serde_json::Value::Null).is_ok());
+}
+
+#[test]
+fn test_modifier_taps_omitted_for_combos() {
+    let json = serde_json::to_value(HotkeyConfig::default_toggle()).unwrap();
+    assert!(json.get("modifier_taps").is_none());
+    let parsed: HotkeyConfig =
+        serde_json::from_str(r#"{"modifiers":["ctrl"],"key":"Space"}"#).unwrap();
+    assert_eq!(parsed.modifier_taps, 0);
+}
+
+#[test]
+fn test_default_cancel_is_bare_escape() {
+    let hotkey = HotkeyConfig::default_cancel();
+    assert_eq!(hotkey.to_shortcut_string(), "Escape");
+    assert!(hotkey.to_shortcut().is_ok());
+}
@@ -0,0 +1,72 @@
+use crate::input_processing::{
+    preview_levels, validate_input_gain, validate_noise_gate_threshold, InputLevels,
+    InputProcessing, GATE_BLOCK_SAMPLES, MAX_INPUT_GAIN,
+};
+
+fn processing(gain: f64, gate_threshold: f64) -> InputProcessing {
+    InputProcessing {
+        gain,
+        gate_threshold,
+    }
+}
+
+#[test]
+fn test_validate_input_gain() {
+    assert!(validate_input_gain(1.0).is_ok());
+    assert!(validate_input_gain(MAX_INPUT_GAIN).is_ok());
+    assert!(validate_input_gain(-0.5).is_err());
+    assert!(validate_input_gain(MAX_INPUT_GAIN + 0.1).is_err());
+}
+
+#[test]
+fn test_validate_noise_gate_threshold() {
+    assert!(validate_noise_gate_threshold(0.0).is_ok());
+    assert!(validate_noise_gate_threshold(0.05).is_ok());
+    assert!(validate_noise_gate_threshold(1.5).is_err());
+}
+
+#[test]
+fn test_measure_levels() {
+    assert_eq!(InputLevels::measure(&[]), InputLevels::default());
+    let levels = InputLevels::measure(&[i16::MAX, -i16::MAX]);
+    assert_eq!(levels.peak, 1.0);
+    assert_eq!(levels.rms, 1.0);
+    assert!((InputLevels::measure(&[16384, 0]).peak - 0.5).abs() < 0.001);
+}
+
+#[test]
+fn test_default_processing_leaves_samples_unchanged() {
+    let mut samples = vec![1, -2, 300, i16::MIN];
+    InputProcessing::default().apply(&mut samples);
+    assert_eq!(samples, vec![1, -2, 300, i16::MIN]);
+}
+
+#[test]
+fn test_gain_saturates_instead_of_wrapping() {
+    let mut samples = vec![1000, -1000, 20000, -20000];
+    processing(2.0, 0.0).apply(&mut samples);
+    assert_eq!(samples, vec![2000, -2000, i16::MAX, i16::MIN]);
+}
+
+#[test]
+fn test_noise_gate_silences_quiet_blocks_only() {
+    // A quiet block of fan noise followed by a loud block of speech
+    let mut samples = vec![100; GATE_BLOCK_SAMPLES];
+    samples.extend(vec![8000; GATE_BLOCK_SAMPLES]);
+    processing(1.0, 0.05).apply(&mut samples);
+    assert!(samples[..GATE_BLOCK_SAMPLES].iter().all(|&s| s == 0));
+    assert!(samples[GATE_BLOCK_SAMPLES..].iter().all(|&s| s == 8000));
+}
+
+#[test]
+fn test_preview_reports_processed_levels_and_clipping() {
+    let samples = vec![10000; GATE_BLOCK_SAMPLES];
+    let preview = preview_levels(&samples, processing(4.0, 0.0));
+    assert!((preview.input.rms - 10000.0 / 32767.0).abs() < 0.001);
+    assert_eq!(preview.processed.peak, 1.0);
+    assert!(preview.clipped);
+
+    let preview = preview_levels(&samples, processing(1.0, 0.5));
+    assert_eq!(preview.processed.rms, 0.0);
+    assert!(!preview.clipped);
+}
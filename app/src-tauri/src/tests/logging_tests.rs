@@ -0,0 +1,67 @@
+use crate::logging::{rotated_path, RotatingFile};
+use std::io::Write;
+use std::path::PathBuf;
+
+fn temp_log_path() -> PathBuf {
+    let dir = std::env::temp_dir().join(format!("tambourine-test-{}", uuid::Uuid::new_v4()));
+    std::fs::create_dir_all(&dir).unwrap();
+    dir.join("test.log")
+}
+
+#[test]
+fn test_rotated_path_appends_index() {
+    assert_eq!(
+        rotated_path(&PathBuf::from("/logs/app.log"), 2),
+        PathBuf::from("/logs/app.log.2")
+    );
+}
+
+#[test]
+fn test_rotating_file_rotates_at_max_size() {
+    let path = temp_log_path();
+    let mut file = RotatingFile::open(path.clone(), 10, 2).unwrap();
+    file.write_all(b"first-line").unwrap();
+    file.write_all(b"second").unwrap();
+    file.flush().unwrap();
+
+    assert_eq!(std::fs::read_to_string(&path).unwrap(), "second");
+    assert_eq!(
+        std::fs::read_to_string(rotated_path(&path, 1)).unwrap(),
+        "first-line"
+    );
+    std::fs::remove_dir_all(path.parent().unwrap()).unwrap();
+}
+
+#[test]
+fn test_rotating_file_drops_oldest() {
+    let path = temp_log_path();
+    let mut file = RotatingFile::open(path.clone(), 4, 2).unwrap();
+    for chunk in ["aaaa", "bbbb", "cccc", "dddd"] {
+        file.write_all(chunk.as_bytes()).unwrap();
+    }
+    file.flush().unwrap();
+
+    assert_eq!(std::fs::read_to_string(&path).unwrap(), "dddd");
+    assert_eq!(
+        std::fs::read_to_string(rotated_path(&path, 1)).unwrap(),
+        "cccc"
+    );
+    assert_eq!(
+        std::fs::read_to_string(rotated_path(&path, 2)).unwrap(),
+        "bbbb"
+    );
+    assert!(!rotated_path(&path, 3).exists());
+    std::fs::remove_dir_all(path.parent().unwrap()).unwrap();
+}
+
+#[test]
+fn test_rotating_file_appends_to_existing() {
+    let path = temp_log_path();
+    std::fs::write(&path, "old").unwrap();
+    let mut file = RotatingFile::open(path.clone(), 100, 1).unwrap();
+    file.write_all(b"-new").unwrap();
+    file.flush().unwrap();
+
+    assert_eq!(std::fs::read_to_string(&path).unwrap(), "old-new");
+    std::fs::remove_dir_all(path.parent().unwrap()).unwrap();
+}
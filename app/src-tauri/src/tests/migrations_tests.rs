@@ -0,0 +1,105 @@
+use crate::history::HistoryStorage;
+use crate::migrations::{
+    migrate_history, migrate_settings, HISTORY_SCHEMA_VERSION, SETTINGS_SCHEMA_VERSION,
+};
+use crate::profiles::SettingsMap;
+use crate::settings::HotkeyConfig;
+use serde_json::json;
+
+fn settings(value: serde_json::Value) -> SettingsMap {
+    value.as_object().unwrap().clone()
+}
+
+// Tests for migrate_history()
+#[test]
+fn test_unversioned_history_is_stamped_with_current_version() {
+    let mut data = json!({ "entries": [] });
+    assert!(migrate_history(&mut data).unwrap());
+    assert_eq!(data["version"], json!(HISTORY_SCHEMA_VERSION));
+}
+
+#[test]
+fn test_current_history_is_left_unchanged() {
+    let mut data = json!({ "version": HISTORY_SCHEMA_VERSION, "entries": [] });
+    let original = data.clone();
+    assert!(!migrate_history(&mut data).unwrap());
+    assert_eq!(data, original);
+}
+
+#[test]
+fn test_history_migration_fills_required_entry_fields() {
+    let mut data = json!({ "entries": [{ "text": "hello" }, { "id": "kept" }] });
+    migrate_history(&mut data).unwrap();
+
+    let entries = data["entries"].as_array().unwrap();
+    assert!(entries[0]["id"].is_string());
+    assert!(entries[0]["timestamp"].is_string());
+    assert_eq!(entries[1]["id"], json!("kept"));
+    assert_eq!(entries[1]["text"], json!(""));
+}
+
+#[test]
+fn test_newer_history_version_is_not_downgraded() {
+    let mut data = json!({ "version": HISTORY_SCHEMA_VERSION + 1, "entries": [] });
+    assert!(!migrate_history(&mut data).unwrap());
+    assert_eq!(data["version"], json!(HISTORY_SCHEMA_VERSION + 1));
+}
+
+#[test]
+fn test_history_migration_rejects_non_object() {
+    assert!(migrate_history(&mut json!([])).is_err());
+}
+
+#[test]
+fn test_old_history_file_loads_instead_of_resetting() {
+    let dir = std::env::temp_dir().join(format!("tambourine-test-{}", uuid::Uuid::new_v4()));
+    std::fs::create_dir_all(&dir).unwrap();
+    // An entry written before ids and timestamps were required
+    std::fs::write(
+        dir.join("history.json"),
+        r#"{ "entries": [{ "text": "from an old version" }] }"#,
+    )
+    .unwrap();
+
+    let storage = HistoryStorage::new(dir.clone(), None);
+    let entries = storage.get_all(None).unwrap();
+    assert_eq!(entries.len(), 1);
+    assert_eq!(entries[0].text, "from an old version");
+    assert_eq!(entries[0].word_count, 4);
+    assert!(!dir.join("history.json.corrupt").exists());
+    let _ = std::fs::remove_dir_all(dir);
+}
+
+// Tests for migrate_settings()
+#[test]
+fn test_settings_migration_converts_shortcut_strings() {
+    let mut map = settings(json!({
+        "toggle_hotkey": "ctrl+alt+Space",
+        "hold_hotkey": { "modifiers": ["ctrl"], "key": "KeyH" },
+    }));
+    let version = migrate_settings("Settings", &mut map, 0);
+
+    assert_eq!(version, SETTINGS_SCHEMA_VERSION);
+    let toggle: HotkeyConfig = serde_json::from_value(map["toggle_hotkey"].clone()).unwrap();
+    assert_eq!(toggle, HotkeyConfig::default_toggle());
+    assert_eq!(
+        map["hold_hotkey"],
+        json!({ "modifiers": ["ctrl"], "key": "KeyH" })
+    );
+}
+
+#[test]
+fn test_settings_migration_drops_unparseable_shortcut() {
+    let mut map = settings(json!({ "toggle_hotkey": "", "sound_enabled": false }));
+    migrate_settings("Settings", &mut map, 0);
+    assert!(!map.contains_key("toggle_hotkey"));
+    assert_eq!(map["sound_enabled"], json!(false));
+}
+
+#[test]
+fn test_current_settings_are_not_migrated() {
+    let mut map = settings(json!({ "toggle_hotkey": "ctrl+alt+Space" }));
+    let version = migrate_settings("Settings", &mut map, SETTINGS_SCHEMA_VERSION);
+    assert_eq!(version, SETTINGS_SCHEMA_VERSION);
+    assert_eq!(map["toggle_hotkey"], json!("ctrl+alt+Space"));
+}
@@ -1,3 +1,25 @@
+mod audio_mute_tests;
+mod audio_tests;
+mod cli_tests;
+mod control_server_tests;
+mod history_crypto_tests;
+mod history_tests;
 mod hotkey_config_tests;
+mod input_processing_tests;
+mod logging_tests;
+mod migrations_tests;
+mod overlay_tests;
+mod permissions_tests;
+mod profiles_tests;
+mod recordings_tests;
+mod server_tests;
 mod settings_commands_tests;
 mod shortcut_tests;
+mod state_tests;
+mod target_app_tests;
+mod text_processing_tests;
+mod transcription_tests;
+mod tray_tests;
+mod vad_tests;
+mod voice_commands_tests;
+mod webhook_tests;
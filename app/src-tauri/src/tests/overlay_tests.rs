@@ -0,0 +1,191 @@
+use crate::commands::overlay::{
+    clamp_overlay_size, compute_overlay_position, monitor_id, resolve_overlay_theme,
+    OverlayPlacement, ScreenRect, MAX_OVERLAY_HEIGHT, MAX_OVERLAY_WIDTH, MIN_OVERLAY_SIZE,
+};
+use crate::settings::{
+    validate_overlay_opacity, validate_setting, OverlayAnchor, OverlaySize, OverlayTheme,
+    OverlayVisibility,
+};
+
+const SCREEN: ScreenRect = ScreenRect {
+    x: 0.0,
+    y: 0.0,
+    width: 1920.0,
+    height: 1080.0,
+};
+
+fn placement(anchor: OverlayAnchor, margin_x: f64, margin_y: f64) -> OverlayPlacement {
+    OverlayPlacement {
+        anchor,
+        margin_x,
+        margin_y,
+    }
+}
+
+// Tests for compute_overlay_position()
+#[test]
+fn test_overlay_bottom_right_anchor() {
+    let position = compute_overlay_position(
+        SCREEN,
+        48.0,
+        48.0,
+        placement(OverlayAnchor::BottomRight, 100.0, 50.0),
+    );
+    assert_eq!(position, (1772.0, 982.0));
+}
+
+#[test]
+fn test_overlay_top_left_anchor() {
+    let position = compute_overlay_position(
+        SCREEN,
+        48.0,
+        48.0,
+        placement(OverlayAnchor::TopLeft, 10.0, 20.0),
+    );
+    assert_eq!(position, (10.0, 20.0));
+}
+
+#[test]
+fn test_overlay_center_ignores_margins() {
+    let position = compute_overlay_position(
+        SCREEN,
+        100.0,
+        100.0,
+        placement(OverlayAnchor::Center, 500.0, 500.0),
+    );
+    assert_eq!(position, (910.0, 490.0));
+}
+
+#[test]
+fn test_overlay_offset_monitor() {
+    let screen = ScreenRect {
+        x: -1280.0,
+        y: 0.0,
+        width: 1280.0,
+        height: 720.0,
+    };
+    let position = compute_overlay_position(
+        screen,
+        48.0,
+        48.0,
+        placement(OverlayAnchor::TopLeft, 10.0, 10.0),
+    );
+    assert_eq!(position, (-1270.0, 10.0));
+}
+
+#[test]
+fn test_overlay_clamped_on_screen_with_oversized_margins() {
+    let position = compute_overlay_position(
+        SCREEN,
+        48.0,
+        48.0,
+        placement(OverlayAnchor::TopRight, 5000.0, 5000.0),
+    );
+    assert_eq!(position, (0.0, 1032.0));
+}
+
+#[test]
+fn test_overlay_visibility_serialization() {
+    assert_eq!(OverlayVisibility::default(), OverlayVisibility::Always);
+    let visibility: OverlayVisibility = serde_json::from_str("\"when_active\"").unwrap();
+    assert_eq!(visibility, OverlayVisibility::WhenActive);
+    assert_eq!(
+        serde_json::to_string(&OverlayVisibility::Never).unwrap(),
+        "\"never\""
+    );
+}
+
+#[test]
+fn test_resolve_overlay_theme_auto_follows_system() {
+    assert_eq!(
+        resolve_overlay_theme(OverlayTheme::Auto, Some(tauri::Theme::Light)),
+        OverlayTheme::Light
+    );
+    assert_eq!(
+        resolve_overlay_theme(OverlayTheme::Auto, Some(tauri::Theme::Dark)),
+        OverlayTheme::Dark
+    );
+    // Unknown OS appearance keeps the original dark look
+    assert_eq!(
+        resolve_overlay_theme(OverlayTheme::Auto, None),
+        OverlayTheme::Dark
+    );
+    assert_eq!(
+        resolve_overlay_theme(OverlayTheme::Light, Some(tauri::Theme::Dark)),
+        OverlayTheme::Light
+    );
+}
+
+#[test]
+fn test_validate_overlay_opacity_range() {
+    assert!(validate_overlay_opacity(0.1).is_ok());
+    assert!(validate_overlay_opacity(1.0).is_ok());
+    assert!(validate_overlay_opacity(0.05).is_err());
+    assert!(validate_overlay_opacity(1.5).is_err());
+    assert!(validate_overlay_opacity(f64::NAN).is_err());
+}
+
+#[test]
+fn test_monitor_id_uses_name() {
+    assert_eq!(monitor_id(Some("DELL U2720Q"), 2), "DELL U2720Q");
+}
+
+#[test]
+fn test_monitor_id_falls_back_to_index() {
+    assert_eq!(monitor_id(None, 1), "monitor-1");
+    assert_eq!(monitor_id(Some(""), 0), "monitor-0");
+}
+
+// Tests for clamp_overlay_size()
+#[test]
+fn test_clamp_overlay_size_keeps_sizes_in_range() {
+    assert_eq!(clamp_overlay_size(48.0, 48.0), (48.0, 48.0));
+    assert_eq!(clamp_overlay_size(200.0, 100.0), (200.0, 100.0));
+}
+
+#[test]
+fn test_clamp_overlay_size_enforces_minimum() {
+    assert_eq!(
+        clamp_overlay_size(0.0, -10.0),
+        (MIN_OVERLAY_SIZE, MIN_OVERLAY_SIZE)
+    );
+}
+
+#[test]
+fn test_clamp_overlay_size_enforces_maximum() {
+    assert_eq!(
+        clamp_overlay_size(5000.0, 3000.0),
+        (MAX_OVERLAY_WIDTH, MAX_OVERLAY_HEIGHT)
+    );
+}
+
+#[test]
+fn test_clamp_overlay_size_rejects_non_finite() {
+    assert_eq!(
+        clamp_overlay_size(f64::NAN, f64::INFINITY),
+        (MIN_OVERLAY_SIZE, MIN_OVERLAY_SIZE)
+    );
+}
+
+// Tests for OverlaySize
+#[test]
+fn test_overlay_size_presets() {
+    assert_eq!(OverlaySize::default(), OverlaySize::Medium);
+    assert_eq!(OverlaySize::Small.idle_size(), 36.0);
+    assert_eq!(OverlaySize::Medium.idle_size(), 48.0);
+    assert_eq!(OverlaySize::Large.idle_size(), 72.0);
+}
+
+#[test]
+fn test_overlay_size_presets_fit_clamp() {
+    for size in [OverlaySize::Small, OverlaySize::Medium, OverlaySize::Large] {
+        let side = size.idle_size();
+        assert_eq!(clamp_overlay_size(side, side), (side, side));
+    }
+}
+
+#[test]
+fn test_overlay_size_setting_validation() {
+    assert!(validate_setting("overlay_size", &serde_json::json!("large")).is_ok());
+    assert!(validate_setting("overlay_size", &serde_json::json!("huge")).is_err());
+}
@@ -0,0 +1,33 @@
+use crate::permissions::{parse_consent_value, PermissionState};
+
+#[test]
+fn test_parse_consent_value_allow() {
+    let output = "\r\nHKEY_CURRENT_USER\\Software\\Microsoft\\Windows\\CurrentVersion\\CapabilityAccessManager\\ConsentStore\\microphone\r\n    Value    REG_SZ    Allow\r\n\r\n";
+    assert_eq!(parse_consent_value(output), PermissionState::Granted);
+}
+
+#[test]
+fn test_parse_consent_value_deny() {
+    assert_eq!(
+        parse_consent_value("    Value    REG_SZ    Deny\n"),
+        PermissionState::Denied
+    );
+}
+
+#[test]
+fn test_parse_consent_value_unexpected_output() {
+    assert_eq!(parse_consent_value(""), PermissionState::Unknown);
+    assert_eq!(
+        parse_consent_value("    Value    REG_SZ    Prompt\n"),
+        PermissionState::Unknown
+    );
+}
+
+#[test]
+fn test_needs_attention() {
+    assert!(PermissionState::Denied.needs_attention());
+    assert!(PermissionState::NotDetermined.needs_attention());
+    assert!(!PermissionState::Granted.needs_attention());
+    assert!(!PermissionState::Unknown.needs_attention());
+    assert!(!PermissionState::NotApplicable.needs_attention());
+}
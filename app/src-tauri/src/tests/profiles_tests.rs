@@ -0,0 +1,72 @@
+use crate::profiles::{is_profile_key, Profiles, SettingsMap, DEFAULT_PROFILE_NAME};
+use serde_json::json;
+use std::collections::BTreeMap;
+
+fn settings(server_url: &str) -> SettingsMap {
+    let mut map = SettingsMap::new();
+    map.insert("server_url".to_string(), json!(server_url));
+    map
+}
+
+#[test]
+fn test_new_creates_default_profile() {
+    let profiles = Profiles::new(None, BTreeMap::new());
+    assert_eq!(profiles.active, DEFAULT_PROFILE_NAME);
+    let summaries = profiles.summaries();
+    assert_eq!(summaries.len(), 1);
+    assert!(summaries[0].is_active);
+}
+
+#[test]
+fn test_create_copies_live_settings() {
+    let mut profiles = Profiles::new(None, BTreeMap::new());
+    let name = profiles
+        .create("  Work ", &settings("http://work"))
+        .unwrap();
+    assert_eq!(name, "Work");
+    assert_eq!(profiles.snapshots["Work"], settings("http://work"));
+    assert_eq!(profiles.active, DEFAULT_PROFILE_NAME);
+}
+
+#[test]
+fn test_create_rejects_duplicate_and_empty_names() {
+    let mut profiles = Profiles::new(None, BTreeMap::new());
+    assert!(profiles.create("Default", &SettingsMap::new()).is_err());
+    assert!(profiles.create("   ", &SettingsMap::new()).is_err());
+}
+
+#[test]
+fn test_switch_saves_live_settings_and_returns_target() {
+    let mut profiles = Profiles::new(None, BTreeMap::new());
+    profiles.create("Work", &settings("http://work")).unwrap();
+
+    let next = profiles.switch("Work", settings("http://home")).unwrap();
+    assert_eq!(next, settings("http://work"));
+    assert_eq!(profiles.active, "Work");
+    assert_eq!(profiles.snapshots["Default"], settings("http://home"));
+}
+
+#[test]
+fn test_switch_to_missing_profile_changes_nothing() {
+    let mut profiles = Profiles::new(None, BTreeMap::new());
+    let before = profiles.clone();
+    assert!(profiles.switch("Nope", settings("http://home")).is_err());
+    assert_eq!(profiles, before);
+}
+
+#[test]
+fn test_delete_active_profile_is_rejected() {
+    let mut profiles = Profiles::new(None, BTreeMap::new());
+    profiles.create("Work", &SettingsMap::new()).unwrap();
+    assert!(profiles.delete("Default").is_err());
+    assert!(profiles.delete("Work").is_ok());
+    assert!(profiles.delete("Work").is_err());
+}
+
+#[test]
+fn test_is_profile_key() {
+    assert!(is_profile_key("profiles"));
+    assert!(is_profile_key("active_profile"));
+    assert!(is_profile_key("settings_version"));
+    assert!(!is_profile_key("server_url"));
+}
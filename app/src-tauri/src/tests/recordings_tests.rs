@@ -0,0 +1,203 @@
+use crate::recordings::{
+    convert_audio, decode_pcm16le, encode_wav, parse_captured_format, validate_audio_format,
+    AudioFormat, RecordingStorage,
+};
+use std::path::PathBuf;
+
+/// Create a recording storage backed by a fresh temporary directory
+fn temp_storage() -> (RecordingStorage, PathBuf) {
+    let dir = std::env::temp_dir().join(format!("tambourine-test-{}", uuid::Uuid::new_v4()));
+    (RecordingStorage::new(dir.clone()), dir)
+}
+
+// Tests for encode_wav()
+#[test]
+fn test_encode_wav_header() {
+    let wav = encode_wav(&[0, 1, -1], AudioFormat::default());
+    assert_eq!(&wav[0..4], b"RIFF");
+    assert_eq!(&wav[8..12], b"WAVE");
+    assert_eq!(u32::from_le_bytes(wav[24..28].try_into().unwrap()), 16000);
+    assert_eq!(u32::from_le_bytes(wav[40..44].try_into().unwrap()), 6);
+    assert_eq!(wav.len(), 44 + 6);
+}
+
+#[test]
+fn test_encode_wav_samples_little_endian() {
+    let wav = encode_wav(&[0x0102], AudioFormat::default());
+    assert_eq!(&wav[44..46], &[0x02, 0x01]);
+}
+
+#[test]
+fn test_encode_wav_stereo_header() {
+    let format = AudioFormat {
+        sample_rate: 44100,
+        channels: 2,
+    };
+    let wav = encode_wav(&[0, 0, 1, 1], format);
+    assert_eq!(u16::from_le_bytes(wav[22..24].try_into().unwrap()), 2);
+    // Byte rate and block align account for both channels
+    assert_eq!(
+        u32::from_le_bytes(wav[28..32].try_into().unwrap()),
+        44100 * 4
+    );
+    assert_eq!(u16::from_le_bytes(wav[32..34].try_into().unwrap()), 4);
+}
+
+// Tests for decode_pcm16le()
+#[test]
+fn test_decode_pcm16le() {
+    assert_eq!(
+        decode_pcm16le(&[0x01, 0x00, 0xff, 0xff, 0xff, 0x7f]),
+        Ok(vec![1, -1, i16::MAX])
+    );
+    assert_eq!(decode_pcm16le(&[]), Ok(vec![]));
+}
+
+#[test]
+fn test_decode_pcm16le_rejects_odd_length() {
+    assert!(decode_pcm16le(&[0x01, 0x00, 0x02]).is_err());
+}
+
+// Tests for parse_captured_format()
+#[test]
+fn test_parse_captured_format() {
+    assert_eq!(
+        parse_captured_format(Some("48000"), Some("2")),
+        Ok(AudioFormat {
+            sample_rate: 48_000,
+            channels: 2,
+        })
+    );
+    assert_eq!(
+        parse_captured_format(Some("44100"), None),
+        Ok(AudioFormat {
+            sample_rate: 44_100,
+            channels: 1,
+        })
+    );
+}
+
+#[test]
+fn test_parse_captured_format_rejects_bad_headers() {
+    assert!(parse_captured_format(None, Some("1")).is_err());
+    assert!(parse_captured_format(Some("0"), None).is_err());
+    assert!(parse_captured_format(Some("fast"), None).is_err());
+    assert!(parse_captured_format(Some("48000"), Some("-1")).is_err());
+}
+
+// Tests for convert_audio()
+fn format(sample_rate: u32, channels: u16) -> AudioFormat {
+    AudioFormat {
+        sample_rate,
+        channels,
+    }
+}
+
+/// One second of a sine wave at `frequency` Hz, duplicated across `channels`
+fn sine(frequency: f64, sample_rate: u32, channels: u16) -> Vec<i16> {
+    (0..sample_rate)
+        .flat_map(|i| {
+            let t = f64::from(i) / f64::from(sample_rate);
+            let sample = ((2.0 * std::f64::consts::PI * frequency * t).sin() * 10_000.0) as i16;
+            std::iter::repeat_n(sample, usize::from(channels))
+        })
+        .collect()
+}
+
+/// Count sign changes from negative to non-negative (one per cycle)
+fn rising_zero_crossings(samples: &[i16]) -> usize {
+    samples
+        .windows(2)
+        .filter(|pair| pair[0] < 0 && pair[1] >= 0)
+        .count()
+}
+
+#[test]
+fn test_convert_48k_stereo_to_16k_mono_length() {
+    let input = sine(440.0, 48_000, 2);
+    let output = convert_audio(&input, format(48_000, 2), format(16_000, 1));
+    assert_eq!(output.len(), 16_000);
+}
+
+#[test]
+fn test_convert_44k1_keeps_pitch() {
+    let input = sine(440.0, 44_100, 1);
+    let output = convert_audio(&input, format(44_100, 1), format(16_000, 1));
+    assert_eq!(output.len(), 16_000);
+    // One second of 440Hz has 440 cycles before and after resampling
+    let crossings = rising_zero_crossings(&output);
+    assert!((439..=441).contains(&crossings), "got {}", crossings);
+}
+
+#[test]
+fn test_convert_upsamples_with_interpolation() {
+    let output = convert_audio(&[0, 100], format(8_000, 1), format(16_000, 1));
+    assert_eq!(output, vec![0, 50, 100, 100]);
+}
+
+#[test]
+fn test_convert_downmix_averages_channels() {
+    let output = convert_audio(
+        &[100, 300, -200, 0, 7],
+        format(16_000, 2),
+        format(16_000, 1),
+    );
+    // The trailing partial frame is dropped
+    assert_eq!(output, vec![200, -100]);
+}
+
+#[test]
+fn test_convert_upmix_duplicates_mono() {
+    let output = convert_audio(&[5, -5], format(16_000, 1), format(16_000, 2));
+    assert_eq!(output, vec![5, 5, -5, -5]);
+}
+
+#[test]
+fn test_convert_same_format_is_unchanged() {
+    let input = vec![1, 2, 3];
+    assert_eq!(
+        convert_audio(&input, format(16_000, 1), format(16_000, 1)),
+        input
+    );
+}
+
+#[test]
+fn test_validate_audio_format() {
+    assert!(validate_audio_format(AudioFormat::default()).is_ok());
+    assert!(validate_audio_format(format(48_000, 2)).is_ok());
+    assert!(validate_audio_format(format(4_000, 1)).is_err());
+    assert!(validate_audio_format(format(96_000, 1)).is_err());
+    assert!(validate_audio_format(format(16_000, 0)).is_err());
+    assert!(validate_audio_format(format(16_000, 6)).is_err());
+}
+
+// Tests for RecordingStorage
+#[test]
+fn test_list_without_recordings_is_empty() {
+    let (storage, _dir) = temp_storage();
+    assert!(storage.list().unwrap().is_empty());
+}
+
+#[test]
+fn test_cleanup_keeps_newest_recordings() {
+    let (storage, dir) = temp_storage();
+    let mut saved = Vec::new();
+    for _ in 0..3 {
+        saved.push(storage.save_wav(&[0; 16], AudioFormat::default()).unwrap());
+        // Filenames have millisecond resolution
+        std::thread::sleep(std::time::Duration::from_millis(5));
+    }
+
+    assert_eq!(storage.cleanup(2).unwrap(), 1);
+    let remaining: Vec<String> = storage
+        .list()
+        .unwrap()
+        .into_iter()
+        .map(|r| r.file_name)
+        .collect();
+    assert_eq!(
+        remaining,
+        vec![saved[2].file_name.clone(), saved[1].file_name.clone()]
+    );
+    let _ = std::fs::remove_dir_all(dir);
+}
@@ -0,0 +1,109 @@
+use crate::commands::server::{server_request_headers, validate_server_url};
+use std::collections::BTreeMap;
+
+// Tests for validate_server_url()
+#[test]
+fn test_validate_server_url_accepts_http() {
+    assert_eq!(
+        validate_server_url("http://localhost:9000").unwrap(),
+        "http://localhost:9000"
+    );
+}
+
+#[test]
+fn test_validate_server_url_trims_whitespace_and_trailing_slash() {
+    assert_eq!(
+        validate_server_url("  https://dictation.example.com/ ").unwrap(),
+        "https://dictation.example.com"
+    );
+}
+
+#[test]
+fn test_validate_server_url_rejects_other_schemes() {
+    let error = validate_server_url("ftp://localhost:9000").unwrap_err();
+    assert!(error.contains("http"));
+}
+
+#[test]
+fn test_validate_server_url_rejects_missing_scheme() {
+    assert!(validate_server_url("localhost:9000").is_err());
+}
+
+#[test]
+fn test_validate_server_url_rejects_garbage() {
+    assert!(validate_server_url("not a url").is_err());
+}
+
+fn header_map(headers: &[(&str, &str)]) -> BTreeMap<String, String> {
+    headers
+        .iter()
+        .map(|(name, value)| (name.to_string(), value.to_string()))
+        .collect()
+}
+
+#[test]
+fn test_server_request_headers_sends_bearer_key() {
+    assert_eq!(
+        server_request_headers(Some("secret"), None, &BTreeMap::new()),
+        Ok(vec![(
+            "Authorization".to_string(),
+            "Bearer secret".to_string()
+        )])
+    );
+}
+
+#[test]
+fn test_server_request_headers_keeps_explicit_scheme() {
+    assert_eq!(
+        server_request_headers(
+            Some("Token secret"),
+            Some("Authorization"),
+            &BTreeMap::new()
+        ),
+        Ok(vec![(
+            "Authorization".to_string(),
+            "Token secret".to_string()
+        )])
+    );
+}
+
+#[test]
+fn test_server_request_headers_custom_header_gets_raw_key() {
+    let headers = header_map(&[("X-Team", "dictation")]);
+    assert_eq!(
+        server_request_headers(Some("secret"), Some("X-API-Key"), &headers),
+        Ok(vec![
+            ("X-Team".to_string(), "dictation".to_string()),
+            ("X-API-Key".to_string(), "secret".to_string()),
+        ])
+    );
+}
+
+#[test]
+fn test_server_request_headers_key_replaces_same_header() {
+    let headers = header_map(&[("authorization", "Bearer old")]);
+    assert_eq!(
+        server_request_headers(Some("new"), None, &headers),
+        Ok(vec![(
+            "Authorization".to_string(),
+            "Bearer new".to_string()
+        )])
+    );
+}
+
+#[test]
+fn test_server_request_headers_without_key() {
+    let headers = header_map(&[("X-Team", "dictation")]);
+    assert_eq!(
+        server_request_headers(Some("  "), None, &headers),
+        Ok(vec![("X-Team".to_string(), "dictation".to_string())])
+    );
+}
+
+#[test]
+fn test_server_request_headers_rejects_invalid_headers() {
+    assert!(server_request_headers(None, None, &header_map(&[("Bad Name", "x")])).is_err());
+    let injected = server_request_headers(Some("secret\r\nX-Evil: 1"), None, &BTreeMap::new());
+    assert!(injected.is_err());
+    assert!(!injected.unwrap_err().contains("secret"));
+}
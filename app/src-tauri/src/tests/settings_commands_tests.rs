@@ -1,4 +1,9 @@
-use crate::settings::HotkeyConfig;
+use crate::commands::text::{backspace_count, clipboard_text_to_restore, typing_chunks};
+use crate::settings::{
+    normalize_language, normalize_setting, untypeable_char, validate_setting, HotkeyConfig,
+    InsertionMethod, LogLevel, AUTO_PASTE_THRESHOLD_CHARS,
+};
+use serde_json::json;
 
 // Tests for HotkeyConfig
 #[test]
@@ -30,6 +35,7 @@ fn test_to_shortcut_string() {
     let hotkey = HotkeyConfig {
         modifiers: vec!["Ctrl".to_string(), "Alt".to_string()],
         key: "Space".to_string(),
+        modifier_taps: 0,
     };
     // Modifiers should be lowercased
     let result = hotkey.to_shortcut_string();
@@ -37,3 +43,243 @@ fn test_to_shortcut_string() {
     assert!(result.contains("alt"));
     assert!(result.contains("Space"));
 }
+
+// Tests for InsertionMethod::resolve()
+#[test]
+fn test_insertion_method_auto_types_short_text() {
+    assert_eq!(
+        InsertionMethod::Auto.resolve("hello"),
+        InsertionMethod::Type
+    );
+}
+
+#[test]
+fn test_insertion_method_auto_pastes_long_text() {
+    let text = "a".repeat(AUTO_PASTE_THRESHOLD_CHARS + 1);
+    assert_eq!(InsertionMethod::Auto.resolve(&text), InsertionMethod::Paste);
+}
+
+#[test]
+fn test_insertion_method_explicit_modes_unchanged() {
+    let text = "a".repeat(AUTO_PASTE_THRESHOLD_CHARS + 1);
+    assert_eq!(InsertionMethod::Type.resolve(&text), InsertionMethod::Type);
+    assert_eq!(InsertionMethod::Paste.resolve("hi"), InsertionMethod::Paste);
+}
+
+#[test]
+fn test_insertion_method_types_unicode_text() {
+    for text in [
+        "café naïve façade — déjà vu",
+        "日本語のテキスト",
+        "한국어 中文",
+        "party 🎉 👍🏽 👨‍👩‍👧",
+        "line one\nline two\tend",
+    ] {
+        assert_eq!(untypeable_char(text), None, "{}", text);
+        assert_eq!(InsertionMethod::Type.resolve(text), InsertionMethod::Type);
+        assert_eq!(InsertionMethod::Auto.resolve(text), InsertionMethod::Type);
+    }
+}
+
+#[test]
+fn test_insertion_method_pastes_untypeable_text() {
+    let text = "bell\u{7} here";
+    assert_eq!(untypeable_char(text), Some('\u{7}'));
+    assert_eq!(InsertionMethod::Type.resolve(text), InsertionMethod::Paste);
+    assert_eq!(InsertionMethod::Auto.resolve(text), InsertionMethod::Paste);
+    assert_eq!(untypeable_char("x\u{FFFF}"), Some('\u{FFFF}'));
+    assert_eq!(untypeable_char("x\u{FDD0}"), Some('\u{FDD0}'));
+}
+
+// Tests for normalize_language()
+#[test]
+fn test_normalize_language_accepts_known_code() {
+    assert_eq!(normalize_language("de"), "de");
+}
+
+#[test]
+fn test_normalize_language_strips_region_and_case() {
+    assert_eq!(normalize_language(" EN-us "), "en");
+    assert_eq!(normalize_language("pt_BR"), "pt");
+}
+
+#[test]
+fn test_normalize_language_unknown_falls_back_to_auto() {
+    assert_eq!(normalize_language("klingon"), "auto");
+    assert_eq!(normalize_language(""), "auto");
+}
+
+// Tests for settings import validation
+#[test]
+fn test_validate_setting_accepts_known_values() {
+    let hotkey = serde_json::to_value(HotkeyConfig::default_toggle()).unwrap();
+    assert!(validate_setting("toggle_hotkey", &hotkey).is_ok());
+    assert!(validate_setting("sound_enabled", &json!(false)).is_ok());
+    assert!(validate_setting("dry_run", &json!(true)).is_ok());
+    assert!(validate_setting("duck_level", &json!(40)).is_ok());
+    assert!(validate_setting("mute_mode", &json!("duck")).is_ok());
+    assert!(validate_setting("paste_slot_source", &json!("pinned")).is_ok());
+    assert!(validate_setting("hold_hotkey_enabled", &json!(false)).is_ok());
+    assert!(validate_setting("hotkeys_suspended", &json!(true)).is_ok());
+    assert!(validate_setting("incognito", &json!(true)).is_ok());
+    assert!(validate_setting("paste_slot_2_hotkey", &json!(null)).is_ok());
+    assert!(validate_setting("stt_provider", &json!(null)).is_ok());
+    assert!(validate_setting("overlay_custom_position", &json!({"x": 10.0, "y": 20.0})).is_ok());
+}
+
+#[test]
+fn test_validate_setting_rejects_invalid_server_url() {
+    assert!(validate_setting("server_url", &json!("http://localhost:8765")).is_ok());
+    assert!(validate_setting("server_url", &json!("ftp://localhost:8765")).is_err());
+    assert!(validate_setting("server_url", &json!("http://")).is_err());
+    assert!(validate_setting("server_url", &json!(8765)).is_err());
+}
+
+#[test]
+fn test_normalize_setting_normalizes_server_url() {
+    assert_eq!(
+        normalize_setting("server_url", json!(" https://dictation.example.com/ ")),
+        Ok(json!("https://dictation.example.com"))
+    );
+    assert!(normalize_setting("server_url", json!("ftp://example.com")).is_err());
+    assert_eq!(
+        normalize_setting("sound_enabled", json!(false)),
+        Ok(json!(false))
+    );
+}
+
+#[test]
+fn test_validate_setting_rejects_unknown_key() {
+    assert_eq!(
+        validate_setting("not_a_setting", &json!(true)),
+        Err("unknown setting".to_string())
+    );
+}
+
+#[test]
+fn test_validate_setting_rejects_type_mismatch() {
+    assert!(validate_setting("sound_enabled", &json!("yes")).is_err());
+    assert!(validate_setting("duck_level", &json!(300)).is_err());
+    assert!(validate_setting("mute_mode", &json!("loud")).is_err());
+    assert!(validate_setting("paste_slot_source", &json!("oldest")).is_err());
+    assert!(validate_setting("toggle_hotkey_enabled", &json!("no")).is_err());
+    assert!(validate_setting("hold_hotkey", &json!("ctrl+alt+Space")).is_err());
+}
+
+#[test]
+fn test_validate_setting_checks_rule_contents() {
+    let invalid_regex = json!([{"find": "(", "replace": "", "kind": "regex"}]);
+    assert!(validate_setting("text_replacements", &invalid_regex).is_err());
+    let empty_phrase = json!([{"phrase": "", "action": {"type": "new_line"}}]);
+    assert!(validate_setting("voice_commands", &empty_phrase).is_err());
+}
+
+#[test]
+fn test_typing_chunks_keeps_graphemes_together() {
+    // "e" + combining acute, a flag, and a ZWJ family emoji each stay one unit
+    let text = "ne\u{301}e 🇯🇵 👨‍👩‍👧!";
+    assert_eq!(
+        typing_chunks(text),
+        vec!["n", "e\u{301}", "e", " ", "🇯🇵", " ", "👨‍👩‍👧", "!"]
+    );
+}
+
+#[test]
+fn test_typing_chunks_multibyte_text() {
+    assert_eq!(typing_chunks("日本語"), vec!["日", "本", "語"]);
+    assert!(typing_chunks("").is_empty());
+}
+
+// Tests for backspace_count()
+#[test]
+fn test_backspace_count_plain_text() {
+    assert_eq!(backspace_count("hello world"), 11);
+    assert_eq!(backspace_count(""), 0);
+}
+
+#[test]
+fn test_backspace_count_emoji_is_one_each() {
+    // Skin tone modifier, ZWJ family and flag are several codepoints each
+    assert_eq!(backspace_count("👍🏽"), 1);
+    assert_eq!(backspace_count("👨‍👩‍👧"), 1);
+    assert_eq!(backspace_count("hi 🇯🇵!"), 5);
+}
+
+#[test]
+fn test_backspace_count_combining_marks() {
+    // NFD "café" and "ñ": the accents combine with the preceding letter
+    assert_eq!(backspace_count("cafe\u{301}"), 4);
+    assert_eq!(backspace_count("n\u{303}"), 1);
+}
+
+// Tests for clipboard_text_to_restore()
+#[test]
+fn test_clipboard_restore_keeps_previous_text() {
+    assert_eq!(
+        clipboard_text_to_restore(Some("copied".to_string()), || false),
+        Some("copied".to_string())
+    );
+}
+
+#[test]
+fn test_clipboard_restore_empties_empty_clipboard() {
+    assert_eq!(
+        clipboard_text_to_restore(None, || false),
+        Some(String::new())
+    );
+}
+
+#[test]
+fn test_clipboard_restore_leaves_image_alone() {
+    assert_eq!(clipboard_text_to_restore(None, || true), None);
+}
+
+#[test]
+fn test_validate_min_confidence_range() {
+    assert!(validate_setting("min_confidence", &json!(0.0)).is_ok());
+    assert!(validate_setting("min_confidence", &json!(0.85)).is_ok());
+    assert!(validate_setting("min_confidence", &json!(1.0)).is_ok());
+    assert!(validate_setting("min_confidence", &json!(1.5)).is_err());
+    assert!(validate_setting("min_confidence", &json!(-0.1)).is_err());
+    assert!(validate_setting("min_confidence", &json!("high")).is_err());
+}
+
+#[test]
+fn test_validate_request_timeout_ms_minimum() {
+    assert!(validate_setting("request_timeout_ms", &json!(30000)).is_ok());
+    assert!(validate_setting("request_timeout_ms", &json!(1000)).is_ok());
+    assert!(validate_setting("request_timeout_ms", &json!(0)).is_err());
+    assert!(validate_setting("request_timeout_ms", &json!(-5)).is_err());
+}
+
+#[test]
+fn test_validate_hold_start_delay_ms_maximum() {
+    assert!(validate_setting("hold_start_delay_ms", &json!(0)).is_ok());
+    assert!(validate_setting("hold_start_delay_ms", &json!(100)).is_ok());
+    assert!(validate_setting("hold_start_delay_ms", &json!(1000)).is_ok());
+    assert!(validate_setting("hold_start_delay_ms", &json!(1001)).is_err());
+    assert!(validate_setting("hold_start_delay_ms", &json!(-1)).is_err());
+}
+
+#[test]
+fn test_log_level_round_trips_through_filter() {
+    for level in [
+        LogLevel::Trace,
+        LogLevel::Debug,
+        LogLevel::Info,
+        LogLevel::Warn,
+        LogLevel::Error,
+    ] {
+        assert_eq!(LogLevel::from_filter(level.filter()), level);
+    }
+    assert_eq!(
+        LogLevel::from_filter(log::LevelFilter::Off),
+        LogLevel::Error
+    );
+}
+
+#[test]
+fn test_validate_log_level() {
+    assert!(validate_setting("log_level", &json!("debug")).is_ok());
+    assert!(validate_setting("log_level", &json!("verbose")).is_err());
+}
@@ -1,4 +1,8 @@
-use crate::normalize_shortcut_string;
+use crate::settings::{HotkeyAction, HotkeyConfig};
+use crate::{
+    effective_hotkey, find_duplicate_hotkeys, is_accidental_hold, is_double_tap, is_key_bounce,
+    normalize_shortcut_string,
+};
 
 #[test]
 fn test_normalize_ctrl_to_control() {
@@ -56,3 +60,227 @@ fn test_normalize_empty_string() {
 fn test_normalize_single_key() {
     assert_eq!(normalize_shortcut_string("Space"), "space");
 }
+
+// Tests for is_double_tap()
+#[test]
+fn test_double_tap_within_window() {
+    assert!(is_double_tap(1_000, 1_300, 400));
+}
+
+#[test]
+fn test_double_tap_at_window_boundary() {
+    assert!(is_double_tap(1_000, 1_400, 400));
+}
+
+#[test]
+fn test_double_tap_outside_window() {
+    assert!(!is_double_tap(1_000, 1_401, 400));
+}
+
+#[test]
+fn test_double_tap_requires_previous_release() {
+    assert!(!is_double_tap(0, 300, 400));
+}
+
+#[test]
+fn test_double_tap_ignores_clock_going_backwards() {
+    assert!(!is_double_tap(1_000, 900, 400));
+}
+
+fn hotkey(modifiers: &[&str], key: &str) -> HotkeyConfig {
+    HotkeyConfig {
+        modifiers: modifiers.iter().map(|m| m.to_string()).collect(),
+        key: key.to_string(),
+        modifier_taps: 0,
+    }
+}
+
+#[test]
+fn test_find_duplicate_hotkeys_none_for_defaults() {
+    let hotkeys: Vec<_> = HotkeyAction::ALL
+        .into_iter()
+        .filter_map(|action| Some((action, action.default_hotkey()?)))
+        .collect();
+    assert_eq!(find_duplicate_hotkeys(&hotkeys), None);
+}
+
+#[test]
+fn test_find_duplicate_hotkeys_reports_pair() {
+    let hotkeys = vec![
+        (HotkeyAction::Toggle, hotkey(&["ctrl", "alt"], "Space")),
+        (HotkeyAction::Hold, hotkey(&["ctrl", "alt"], "Backquote")),
+        (HotkeyAction::PasteLast, hotkey(&["ctrl", "alt"], "Space")),
+    ];
+    assert_eq!(
+        find_duplicate_hotkeys(&hotkeys),
+        Some((HotkeyAction::Toggle, HotkeyAction::PasteLast))
+    );
+}
+
+#[test]
+fn test_find_duplicate_hotkeys_normalizes_modifier_names() {
+    let hotkeys = vec![
+        (HotkeyAction::Toggle, hotkey(&["cmd", "shift"], "KeyA")),
+        (HotkeyAction::Hold, hotkey(&["super", "shift"], "KeyA")),
+    ];
+    assert_eq!(
+        find_duplicate_hotkeys(&hotkeys),
+        Some((HotkeyAction::Toggle, HotkeyAction::Hold))
+    );
+}
+
+#[test]
+fn test_find_duplicate_hotkeys_ignores_modifier_order() {
+    let hotkeys = vec![
+        (HotkeyAction::Toggle, hotkey(&["ctrl", "alt"], "Space")),
+        (HotkeyAction::Hold, hotkey(&["alt", "ctrl"], "Space")),
+    ];
+    assert_eq!(
+        find_duplicate_hotkeys(&hotkeys),
+        Some((HotkeyAction::Toggle, HotkeyAction::Hold))
+    );
+}
+
+#[test]
+fn test_show_window_hotkey_unbound_by_default() {
+    assert_eq!(HotkeyAction::ShowWindow.default_hotkey(), None);
+    assert_eq!(HotkeyAction::ShowWindow.store_key(), "show_window_hotkey");
+}
+
+#[test]
+fn test_find_duplicate_hotkeys_includes_show_window() {
+    let hotkeys = vec![
+        (HotkeyAction::Toggle, hotkey(&["ctrl", "alt"], "Space")),
+        (HotkeyAction::ShowWindow, hotkey(&["alt", "ctrl"], "Space")),
+    ];
+    assert_eq!(
+        find_duplicate_hotkeys(&hotkeys),
+        Some((HotkeyAction::Toggle, HotkeyAction::ShowWindow))
+    );
+}
+
+#[test]
+fn test_paste_slot_hotkeys_unbound_by_default() {
+    assert_eq!(HotkeyAction::PasteSlot2.default_hotkey(), None);
+    assert_eq!(HotkeyAction::PasteSlot3.default_hotkey(), None);
+    assert_eq!(HotkeyAction::PasteSlot2.store_key(), "paste_slot_2_hotkey");
+    assert_eq!(
+        serde_json::to_value(HotkeyAction::PasteSlot3).unwrap(),
+        serde_json::json!("paste_slot_3")
+    );
+}
+
+#[test]
+fn test_paste_slot_numbers() {
+    assert_eq!(HotkeyAction::PasteLast.paste_slot(), Some(1));
+    assert_eq!(HotkeyAction::PasteSlot2.paste_slot(), Some(2));
+    assert_eq!(HotkeyAction::PasteSlot3.paste_slot(), Some(3));
+    assert_eq!(HotkeyAction::ShowWindow.paste_slot(), None);
+}
+
+#[test]
+fn test_hotkey_enabled_keys_are_distinct() {
+    let keys: std::collections::HashSet<_> = HotkeyAction::ALL
+        .into_iter()
+        .map(HotkeyAction::enabled_key)
+        .collect();
+    assert_eq!(keys.len(), HotkeyAction::ALL.len());
+    for action in HotkeyAction::ALL {
+        assert_ne!(action.enabled_key(), action.store_key());
+    }
+    assert_eq!(HotkeyAction::Hold.enabled_key(), "hold_hotkey_enabled");
+}
+
+// Tests for effective_hotkey()
+#[test]
+fn test_effective_hotkey_valid_combo_matches() {
+    let config = hotkey(&["ctrl", "alt"], "Space");
+    let effective = effective_hotkey(HotkeyAction::Toggle, Some(&config), true);
+    assert!(effective.parsed);
+    assert_eq!(effective.error, None);
+    assert_eq!(effective.configured.as_deref(), Some("ctrl+alt+Space"));
+    assert_eq!(effective.normalized.as_deref(), Some("control+alt+space"));
+    assert!(effective.matches);
+}
+
+#[test]
+fn test_effective_hotkey_invalid_combo_falls_back_to_default() {
+    let config = hotkey(&["ctrl"], "NotAKey");
+    let effective = effective_hotkey(HotkeyAction::Toggle, Some(&config), true);
+    assert!(!effective.parsed);
+    assert!(effective.error.is_some());
+    let default = HotkeyConfig::default_toggle().to_shortcut_string();
+    assert_eq!(
+        effective.normalized,
+        Some(normalize_shortcut_string(&default))
+    );
+    assert!(effective.matches);
+}
+
+#[test]
+fn test_effective_hotkey_modifier_only_does_not_fall_back() {
+    let config = HotkeyConfig::from_shortcut_string("super+super").unwrap();
+    let effective = effective_hotkey(HotkeyAction::Toggle, Some(&config), true);
+    assert!(!effective.parsed);
+    assert!(effective
+        .error
+        .as_deref()
+        .is_some_and(|e| e.contains("Modifier-only")));
+    assert_eq!(effective.configured.as_deref(), Some("super+super"));
+    assert_eq!(effective.normalized, None);
+    assert!(!effective.matches);
+}
+
+#[test]
+fn test_effective_hotkey_unbound_or_invalid_without_default() {
+    let unbound = effective_hotkey(HotkeyAction::ShowWindow, None, true);
+    assert_eq!(unbound.configured, None);
+    assert!(!unbound.parsed);
+    assert!(!unbound.matches);
+
+    let config = hotkey(&["ctrl"], "NotAKey");
+    let invalid = effective_hotkey(HotkeyAction::ShowWindow, Some(&config), false);
+    assert_eq!(invalid.normalized, None);
+    assert!(!invalid.enabled);
+    assert!(!invalid.matches);
+}
+
+// Tests for is_key_bounce()
+#[test]
+fn test_key_bounce_within_debounce() {
+    assert!(is_key_bounce(1_000, 1_020, 50));
+}
+
+#[test]
+fn test_key_bounce_deliberate_release() {
+    assert!(!is_key_bounce(1_000, 1_050, 50));
+    assert!(!is_key_bounce(1_000, 1_300, 50));
+}
+
+#[test]
+fn test_key_bounce_disabled_with_zero() {
+    assert!(!is_key_bounce(1_000, 1_000, 0));
+}
+
+#[test]
+fn test_key_bounce_ignores_clock_going_backwards() {
+    assert!(!is_key_bounce(1_000, 900, 50));
+}
+
+// Tests for is_accidental_hold()
+#[test]
+fn test_accidental_hold_below_minimum() {
+    assert!(is_accidental_hold(120, 300));
+    assert!(is_accidental_hold(299, 300));
+}
+
+#[test]
+fn test_accidental_hold_at_or_above_minimum() {
+    assert!(!is_accidental_hold(300, 300));
+    assert!(!is_accidental_hold(2_000, 300));
+}
+
+#[test]
+fn test_accidental_hold_disabled_with_zero() {
+    assert!(!is_accidental_hold(0, 0));
+}
@@ -0,0 +1,159 @@
+use crate::settings::{HotkeyAction, HotkeyRegistration};
+use crate::state::{AppState, RecordingSource, MAX_PENDING_TRANSCRIPTIONS};
+use std::sync::atomic::Ordering;
+
+#[test]
+fn test_try_set_recording_starts_when_idle() {
+    let state = AppState::default();
+    assert!(state.try_set_recording(true));
+    assert!(state.is_recording.load(Ordering::SeqCst));
+}
+
+#[test]
+fn test_try_set_recording_ignores_second_start() {
+    let state = AppState::default();
+    assert!(state.try_set_recording(true));
+    assert!(!state.try_set_recording(true));
+    assert!(state.is_recording.load(Ordering::SeqCst));
+}
+
+#[test]
+fn test_try_set_recording_ignores_stop_when_idle() {
+    let state = AppState::default();
+    assert!(!state.try_set_recording(false));
+    assert!(!state.is_recording.load(Ordering::SeqCst));
+}
+
+#[test]
+fn test_try_set_recording_stops_once() {
+    let state = AppState::default();
+    state.try_set_recording(true);
+    assert!(state.try_set_recording(false));
+    assert!(!state.try_set_recording(false));
+}
+
+#[test]
+fn test_recording_state_when_idle() {
+    let state = AppState::default();
+    let recording_state = state.recording_state();
+    assert!(!recording_state.is_recording);
+    assert_eq!(recording_state.source, None);
+    assert_eq!(recording_state.started_at_ms, None);
+}
+
+#[test]
+fn test_recording_state_reports_session_while_recording() {
+    let state = AppState::default();
+    state.try_set_recording(true);
+    *state.recording_session.lock().unwrap() = Some((RecordingSource::Hold, 1_000));
+
+    let recording_state = state.recording_state();
+    assert!(recording_state.is_recording);
+    assert_eq!(recording_state.source, Some(RecordingSource::Hold));
+    assert_eq!(recording_state.started_at_ms, Some(1_000));
+}
+
+#[test]
+fn test_recording_state_ignores_stale_session_after_stop() {
+    let state = AppState::default();
+    *state.recording_session.lock().unwrap() = Some((RecordingSource::Toggle, 1_000));
+    assert_eq!(state.recording_state().source, None);
+}
+
+#[test]
+fn test_recording_source_serialization() {
+    let json = serde_json::to_string(&RecordingSource::Toggle).unwrap();
+    assert_eq!(json, "\"toggle\"");
+}
+
+#[test]
+fn test_recording_elapsed_ms() {
+    let state = AppState::default();
+    assert_eq!(state.recording_elapsed_ms(5_000), None);
+
+    state.try_set_recording(true);
+    *state.recording_session.lock().unwrap() = Some((RecordingSource::Hold, 1_000));
+    assert_eq!(state.recording_elapsed_ms(1_250), Some(250));
+    // Clock going backwards must not underflow
+    assert_eq!(state.recording_elapsed_ms(500), Some(0));
+}
+
+#[test]
+fn test_take_undoable_insertion_within_window() {
+    let state = AppState::default();
+    state.record_insertion(12, 1_000);
+    assert_eq!(state.take_undoable_insertion(5_000, 10_000), Some(12));
+    // Cleared after the first undo
+    assert_eq!(state.take_undoable_insertion(5_000, 10_000), None);
+}
+
+#[test]
+fn test_take_undoable_insertion_expires() {
+    let state = AppState::default();
+    state.record_insertion(12, 1_000);
+    assert_eq!(state.take_undoable_insertion(11_001, 10_000), None);
+}
+
+#[test]
+fn test_take_undoable_insertion_nothing_inserted() {
+    let state = AppState::default();
+    state.record_insertion(0, 1_000);
+    assert_eq!(state.take_undoable_insertion(1_000, 10_000), None);
+}
+
+#[test]
+fn test_stop_sounds_are_taken_in_order() {
+    let state = AppState::default();
+    state.queue_stop_sound(true);
+    state.queue_stop_sound(false);
+    state.queue_stop_sound(true);
+    assert!(state.take_stop_sound());
+    assert!(!state.take_stop_sound());
+    assert!(state.take_stop_sound());
+    assert!(!state.take_stop_sound());
+}
+
+#[test]
+fn test_stop_sound_queue_is_capped() {
+    let state = AppState::default();
+    state.queue_stop_sound(false);
+    for _ in 0..MAX_PENDING_TRANSCRIPTIONS {
+        state.queue_stop_sound(true);
+    }
+    // The oldest (silent) entry was dropped to make room
+    for _ in 0..MAX_PENDING_TRANSCRIPTIONS {
+        assert!(state.take_stop_sound());
+    }
+    assert!(!state.take_stop_sound());
+}
+
+#[test]
+fn test_clear_stop_sounds() {
+    let state = AppState::default();
+    state.queue_stop_sound(true);
+    state.queue_stop_sound(true);
+    state.clear_stop_sounds();
+    assert!(!state.take_stop_sound());
+}
+
+#[test]
+fn test_hotkey_registration_failures_keep_only_failed() {
+    let state = AppState::default();
+    let registration = |action, error: Option<&str>| HotkeyRegistration {
+        action,
+        shortcut: "ctrl+alt+Space".to_string(),
+        registered: error.is_none(),
+        error: error.map(str::to_string),
+    };
+    let failures = state.set_hotkey_registration_failures(&[
+        registration(HotkeyAction::Toggle, None),
+        registration(HotkeyAction::Hold, Some("already registered")),
+    ]);
+    assert_eq!(failures.len(), 1);
+    assert_eq!(failures[0].action, HotkeyAction::Hold);
+    assert_eq!(state.hotkey_registration_failures(), failures);
+
+    // A later successful pass clears them
+    state.set_hotkey_registration_failures(&[registration(HotkeyAction::Hold, None)]);
+    assert!(state.hotkey_registration_failures().is_empty());
+}
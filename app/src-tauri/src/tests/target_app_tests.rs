@@ -0,0 +1,63 @@
+use crate::target_app::{find_target_app, parse_app_lines, parse_wmctrl_list, RunningApp};
+
+fn app(id: &str, name: &str, title: Option<&str>) -> RunningApp {
+    RunningApp {
+        id: id.to_string(),
+        name: name.to_string(),
+        title: title.map(str::to_string),
+    }
+}
+
+#[test]
+fn test_find_target_app_prefers_exact_id_or_name() {
+    let apps = vec![
+        app("com.apple.Safari", "Safari", Some("Notes - Safari")),
+        app("com.apple.Notes", "Notes", None),
+    ];
+    assert_eq!(
+        find_target_app(&apps, "notes").map(|a| a.id.as_str()),
+        Some("com.apple.Notes")
+    );
+    assert_eq!(
+        find_target_app(&apps, "COM.APPLE.SAFARI").map(|a| a.name.as_str()),
+        Some("Safari")
+    );
+}
+
+#[test]
+fn test_find_target_app_falls_back_to_title() {
+    let apps = vec![app("0x01", "Code", Some("main.rs - tambourine - VS Code"))];
+    assert_eq!(
+        find_target_app(&apps, "tambourine").map(|a| a.id.as_str()),
+        Some("0x01")
+    );
+    assert!(find_target_app(&apps, "slack").is_none());
+    assert!(find_target_app(&apps, "  ").is_none());
+}
+
+#[test]
+fn test_parse_app_lines() {
+    let output =
+        "com.apple.Notes\tNotes\nmissing value\tHelper\n4120\tnotepad\tUntitled - Notepad\r\n\n";
+    assert_eq!(
+        parse_app_lines(output),
+        vec![
+            app("com.apple.Notes", "Notes", None),
+            app("4120", "notepad", Some("Untitled - Notepad")),
+        ]
+    );
+}
+
+#[test]
+fn test_parse_wmctrl_list() {
+    let output = "0x03a00003  0 Navigator.firefox     host Mozilla Firefox\n\
+                  0x01200004 -1 xfce4-panel.Xfce4-panel  host xfce4-panel\n\
+                  0x04000007  1 code.Code  host main.rs - tambourine\n";
+    assert_eq!(
+        parse_wmctrl_list(output),
+        vec![
+            app("0x03a00003", "firefox", Some("Mozilla Firefox")),
+            app("0x04000007", "Code", Some("main.rs - tambourine")),
+        ]
+    );
+}
@@ -0,0 +1,176 @@
+use crate::text_processing::{
+    apply_rules, apply_vocabulary, capitalize_first, format_for_insertion, format_timestamp,
+    normalize_vocabulary, validate_rules, validate_timestamp_format, ReplacementKind,
+    ReplacementRule, DEFAULT_TIMESTAMP_FORMAT, MAX_VOCABULARY_TERMS, MAX_VOCABULARY_TERM_CHARS,
+};
+use chrono::{TimeZone, Utc};
+
+fn rule(find: &str, replace: &str, kind: ReplacementKind) -> ReplacementRule {
+    ReplacementRule {
+        find: find.to_string(),
+        replace: replace.to_string(),
+        kind,
+    }
+}
+
+#[test]
+fn test_apply_plain_rule_replaces_all_occurrences() {
+    let rules = vec![rule("get hub", "GitHub", ReplacementKind::Plain)];
+    assert_eq!(
+        apply_rules("push to get hub, then get hub again", &rules),
+        "push to GitHub, then GitHub again"
+    );
+}
+
+#[test]
+fn test_apply_regex_rule_with_capture_group() {
+    let rules = vec![rule(r"(\d+) percent", "$1%", ReplacementKind::Regex)];
+    assert_eq!(apply_rules("up 20 percent", &rules), "up 20%");
+}
+
+#[test]
+fn test_apply_rules_in_order() {
+    let rules = vec![
+        rule("a", "b", ReplacementKind::Plain),
+        rule("b", "c", ReplacementKind::Plain),
+    ];
+    assert_eq!(apply_rules("a", &rules), "c");
+}
+
+#[test]
+fn test_apply_skips_invalid_regex() {
+    let rules = vec![rule("(", "x", ReplacementKind::Regex)];
+    assert_eq!(apply_rules("(text)", &rules), "(text)");
+}
+
+#[test]
+fn test_validate_reports_invalid_regex_index() {
+    let rules = vec![
+        rule("fine", "ok", ReplacementKind::Plain),
+        rule("[unclosed", "x", ReplacementKind::Regex),
+    ];
+    let err = validate_rules(&rules).unwrap_err();
+    assert!(err.starts_with("Rule 1:"), "{}", err);
+}
+
+#[test]
+fn test_validate_rejects_empty_find() {
+    let rules = vec![rule("", "x", ReplacementKind::Plain)];
+    assert!(validate_rules(&rules).unwrap_err().starts_with("Rule 0:"));
+}
+
+#[test]
+fn test_rule_kind_defaults_to_plain() {
+    let rule: ReplacementRule = serde_json::from_str(r#"{"find":"a","replace":"b"}"#).unwrap();
+    assert_eq!(rule.kind, ReplacementKind::Plain);
+}
+
+#[test]
+fn test_capitalize_first_skips_leading_punctuation() {
+    assert_eq!(capitalize_first("hello world"), "Hello world");
+    assert_eq!(capitalize_first("  \"quoted\""), "  \"Quoted\"");
+    assert_eq!(capitalize_first("élan"), "Élan");
+    assert_eq!(capitalize_first("123"), "123");
+}
+
+#[test]
+fn test_format_for_insertion_defaults_leave_text_unchanged() {
+    assert_eq!(format_for_insertion("hello", false, false), "hello");
+}
+
+#[test]
+fn test_format_for_insertion_appends_single_trailing_space() {
+    assert_eq!(format_for_insertion("hello", true, true), "Hello ");
+    assert_eq!(format_for_insertion("hello ", false, true), "hello ");
+    assert_eq!(format_for_insertion("", false, true), "");
+}
+
+fn terms(terms: &[&str]) -> Vec<String> {
+    terms.iter().map(|t| t.to_string()).collect()
+}
+
+#[test]
+fn test_apply_vocabulary_fixes_case() {
+    let vocabulary = terms(&["GitHub", "Tauri"]);
+    assert_eq!(
+        apply_vocabulary("push it to github and rebuild tauri.", &vocabulary),
+        "push it to GitHub and rebuild Tauri."
+    );
+}
+
+#[test]
+fn test_apply_vocabulary_joins_split_words() {
+    let vocabulary = terms(&["TypeScript", "PostgreSQL"]);
+    assert_eq!(
+        apply_vocabulary("rewrite it in type script on postgre sql", &vocabulary),
+        "rewrite it in TypeScript on PostgreSQL"
+    );
+}
+
+#[test]
+fn test_apply_vocabulary_fuzzy_matches_long_terms() {
+    let vocabulary = terms(&["Kubernetes", "Vite"]);
+    assert_eq!(
+        apply_vocabulary("deploy to kubernetis with vote", &vocabulary),
+        "deploy to Kubernetes with vote"
+    );
+}
+
+#[test]
+fn test_apply_vocabulary_does_not_join_across_punctuation() {
+    let vocabulary = terms(&["TypeScript"]);
+    assert_eq!(
+        apply_vocabulary("pick a type. Script it later", &vocabulary),
+        "pick a type. Script it later"
+    );
+}
+
+#[test]
+fn test_apply_vocabulary_without_terms_is_identity() {
+    assert_eq!(apply_vocabulary("hello world", &[]), "hello world");
+}
+
+#[test]
+fn test_normalize_vocabulary_trims_and_dedupes() {
+    assert_eq!(
+        normalize_vocabulary(terms(&[" Tauri ", "", "tauri", "GitHub", "  "])),
+        Ok(terms(&["Tauri", "GitHub"]))
+    );
+}
+
+#[test]
+fn test_normalize_vocabulary_enforces_limits() {
+    let too_many: Vec<String> = (0..=MAX_VOCABULARY_TERMS)
+        .map(|i| format!("term{}", i))
+        .collect();
+    assert!(normalize_vocabulary(too_many).is_err());
+
+    let too_long = "x".repeat(MAX_VOCABULARY_TERM_CHARS + 1);
+    assert!(normalize_vocabulary(vec![too_long]).is_err());
+}
+
+// Tests for timestamp prefixes
+#[test]
+fn test_validate_timestamp_format() {
+    assert!(validate_timestamp_format(DEFAULT_TIMESTAMP_FORMAT).is_ok());
+    assert!(validate_timestamp_format("%Y-%m-%d %H:%M:%S - ").is_ok());
+    assert!(validate_timestamp_format("").is_err());
+    assert!(validate_timestamp_format("%Q").is_err());
+    assert!(validate_timestamp_format("[%H:%").is_err());
+}
+
+#[test]
+fn test_format_timestamp_uses_format() {
+    let time = Utc.with_ymd_and_hms(2024, 3, 5, 9, 7, 0).unwrap();
+    assert_eq!(
+        format_timestamp(DEFAULT_TIMESTAMP_FORMAT, &time),
+        "[09:07] "
+    );
+    assert_eq!(format_timestamp("%d/%m %H:%M: ", &time), "05/03 09:07: ");
+}
+
+#[test]
+fn test_format_timestamp_falls_back_on_invalid_format() {
+    let time = Utc.with_ymd_and_hms(2024, 3, 5, 14, 30, 0).unwrap();
+    assert_eq!(format_timestamp("%Q", &time), "[14:30] ");
+}
@@ -0,0 +1,37 @@
+use crate::{TranscriptionError, TranscriptionErrorKind};
+
+#[test]
+fn test_transcription_error_payload_parses() {
+    let error: TranscriptionError = serde_json::from_str(
+        r#"{"kind":"network","message":"Disconnected from server","will_retry":true}"#,
+    )
+    .unwrap();
+    assert_eq!(
+        error,
+        TranscriptionError {
+            kind: TranscriptionErrorKind::Network,
+            message: "Disconnected from server".to_string(),
+            will_retry: true,
+        }
+    );
+}
+
+#[test]
+fn test_transcription_error_kinds_are_snake_case() {
+    for (kind, name) in [
+        (TranscriptionErrorKind::Network, "\"network\""),
+        (TranscriptionErrorKind::Timeout, "\"timeout\""),
+        (TranscriptionErrorKind::Server, "\"server\""),
+        (TranscriptionErrorKind::Parse, "\"parse\""),
+    ] {
+        assert_eq!(serde_json::to_string(&kind).unwrap(), name);
+    }
+}
+
+#[test]
+fn test_transcription_error_rejects_unknown_kind() {
+    let result = serde_json::from_str::<TranscriptionError>(
+        r#"{"kind":"bogus","message":"x","will_retry":false}"#,
+    );
+    assert!(result.is_err());
+}
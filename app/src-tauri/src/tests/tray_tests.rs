@@ -0,0 +1,38 @@
+use crate::tray::{tooltip_text, truncate_menu_label};
+
+#[test]
+fn test_truncate_menu_label_keeps_short_text() {
+    assert_eq!(truncate_menu_label("Hello world", 40), "Hello world");
+}
+
+#[test]
+fn test_truncate_menu_label_collapses_whitespace() {
+    assert_eq!(
+        truncate_menu_label("  first line\n\nsecond\tline ", 40),
+        "first line second line"
+    );
+}
+
+#[test]
+fn test_truncate_menu_label_adds_ellipsis() {
+    let label = truncate_menu_label("the quick brown fox jumps over the lazy dog", 10);
+    assert_eq!(label, "the quick…");
+    assert!(label.chars().count() <= 10);
+}
+
+#[test]
+fn test_truncate_menu_label_counts_characters_not_bytes() {
+    assert_eq!(truncate_menu_label("héllo wörld", 11), "héllo wörld");
+    assert_eq!(truncate_menu_label("héllo wörld", 6), "héllo…");
+}
+
+#[test]
+fn test_tooltip_text_lists_active_states() {
+    assert_eq!(tooltip_text(false, false), "Tambourine");
+    assert_eq!(tooltip_text(true, false), "Tambourine (hotkeys suspended)");
+    assert_eq!(tooltip_text(false, true), "Tambourine (incognito)");
+    assert_eq!(
+        tooltip_text(true, true),
+        "Tambourine (hotkeys suspended, incognito)"
+    );
+}
@@ -0,0 +1,51 @@
+use crate::vad::{SilenceDetector, VadEvent};
+
+fn detector() -> SilenceDetector {
+    SilenceDetector::new(0.02, 1500)
+}
+
+#[test]
+fn test_silence_before_speech_does_not_time_out() {
+    let mut vad = detector();
+    assert_eq!(vad.update(0.0, 0), None);
+    assert_eq!(vad.update(0.0, 5000), None);
+}
+
+#[test]
+fn test_speech_then_silence_reports_transitions() {
+    let mut vad = detector();
+    assert_eq!(vad.update(0.1, 0), Some(VadEvent::SpeechDetected));
+    assert_eq!(vad.update(0.1, 50), None);
+    assert_eq!(vad.update(0.0, 100), Some(VadEvent::SilenceDetected));
+    assert_eq!(vad.update(0.0, 150), None);
+}
+
+#[test]
+fn test_times_out_after_continuous_silence() {
+    let mut vad = detector();
+    vad.update(0.1, 0);
+    vad.update(0.0, 100);
+    assert_eq!(vad.update(0.0, 1599), None);
+    assert_eq!(vad.update(0.0, 1600), Some(VadEvent::SilenceTimeout));
+}
+
+#[test]
+fn test_short_pause_resets_silence_window() {
+    let mut vad = detector();
+    vad.update(0.1, 0);
+    vad.update(0.0, 100);
+    assert_eq!(vad.update(0.1, 1000), Some(VadEvent::SpeechDetected));
+    assert_eq!(vad.update(0.0, 1100), Some(VadEvent::SilenceDetected));
+    assert_eq!(vad.update(0.0, 2000), None);
+    assert_eq!(vad.update(0.0, 2600), Some(VadEvent::SilenceTimeout));
+}
+
+#[test]
+fn test_timeout_reported_once() {
+    let mut vad = detector();
+    vad.update(0.1, 0);
+    vad.update(0.0, 100);
+    vad.update(0.0, 1600);
+    assert_eq!(vad.update(0.0, 5000), None);
+    assert_eq!(vad.update(0.1, 5050), None);
+}
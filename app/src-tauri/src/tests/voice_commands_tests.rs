@@ -0,0 +1,87 @@
+use crate::voice_commands::{
+    match_command, normalize_phrase, validate_commands, VoiceAction, VoiceCommand,
+    MAX_BACKSPACE_COUNT,
+};
+
+fn command(phrase: &str, action: VoiceAction) -> VoiceCommand {
+    VoiceCommand {
+        phrase: phrase.to_string(),
+        action,
+    }
+}
+
+#[test]
+fn test_normalize_phrase_ignores_case_punctuation_and_spacing() {
+    assert_eq!(normalize_phrase("  New   Line. "), "new line");
+    assert_eq!(normalize_phrase("\"Delete that!\""), "delete that");
+    assert_eq!(normalize_phrase("..."), "");
+}
+
+#[test]
+fn test_match_builtin_command() {
+    assert_eq!(match_command("New line.", &[]), Some(VoiceAction::NewLine));
+    assert_eq!(
+        match_command("delete that", &[]),
+        Some(VoiceAction::DeleteLast)
+    );
+}
+
+#[test]
+fn test_match_requires_whole_transcription() {
+    assert_eq!(match_command("add a new line here", &[]), None);
+    assert_eq!(match_command("", &[]), None);
+}
+
+#[test]
+fn test_user_command_overrides_builtin() {
+    let user = vec![command("new line", VoiceAction::NewParagraph)];
+    assert_eq!(
+        match_command("new line", &user),
+        Some(VoiceAction::NewParagraph)
+    );
+}
+
+#[test]
+fn test_match_user_text_command() {
+    let user = vec![command(
+        "sign off",
+        VoiceAction::Text {
+            text: "Best regards".to_string(),
+        },
+    )];
+    assert_eq!(
+        match_command("Sign off.", &user),
+        Some(VoiceAction::Text {
+            text: "Best regards".to_string()
+        })
+    );
+}
+
+#[test]
+fn test_validate_rejects_empty_phrase_and_bad_backspace_count() {
+    let empty = vec![command("?!", VoiceAction::NewLine)];
+    assert!(validate_commands(&empty)
+        .unwrap_err()
+        .starts_with("Command 0:"));
+
+    let too_many = vec![
+        command("ok", VoiceAction::Tab),
+        command(
+            "wipe",
+            VoiceAction::Backspace {
+                count: MAX_BACKSPACE_COUNT + 1,
+            },
+        ),
+    ];
+    assert!(validate_commands(&too_many)
+        .unwrap_err()
+        .starts_with("Command 1:"));
+}
+
+#[test]
+fn test_voice_action_serialization() {
+    let json = serde_json::to_value(VoiceAction::Backspace { count: 3 }).unwrap();
+    assert_eq!(json, serde_json::json!({"type": "backspace", "count": 3}));
+    let action: VoiceAction = serde_json::from_str(r#"{"type":"new_line"}"#).unwrap();
+    assert_eq!(action, VoiceAction::NewLine);
+}
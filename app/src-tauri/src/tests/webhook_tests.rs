@@ -0,0 +1,51 @@
+use crate::webhook::{validate_webhook_secret, validate_webhook_url, TranscriptionPayload};
+
+// Tests for validate_webhook_url()
+#[test]
+fn test_validate_webhook_url_keeps_path_and_trailing_slash() {
+    assert_eq!(
+        validate_webhook_url(" https://hooks.example.com/dictation/ ").unwrap(),
+        "https://hooks.example.com/dictation/"
+    );
+}
+
+#[test]
+fn test_validate_webhook_url_accepts_http() {
+    assert!(validate_webhook_url("http://localhost:5678/webhook").is_ok());
+}
+
+#[test]
+fn test_validate_webhook_url_rejects_other_schemes_and_garbage() {
+    assert!(validate_webhook_url("ftp://hooks.example.com")
+        .unwrap_err()
+        .contains("http"));
+    assert!(validate_webhook_url("hooks.example.com/dictation").is_err());
+    assert!(validate_webhook_url("").is_err());
+}
+
+// Tests for validate_webhook_secret()
+#[test]
+fn test_validate_webhook_secret_rejects_control_characters_without_echoing() {
+    assert!(validate_webhook_secret("s3cret-value").is_ok());
+    let error = validate_webhook_secret("s3cret\r\nX-Injected: 1").unwrap_err();
+    assert!(!error.contains("s3cret"));
+}
+
+#[test]
+fn test_transcription_payload_serializes_expected_fields() {
+    let payload = TranscriptionPayload {
+        text: "Hello world".to_string(),
+        timestamp: "2024-05-01T12:00:00Z".parse().unwrap(),
+        duration_ms: Some(1500),
+        language: None,
+    };
+    assert_eq!(
+        serde_json::to_value(&payload).unwrap(),
+        serde_json::json!({
+            "text": "Hello world",
+            "timestamp": "2024-05-01T12:00:00Z",
+            "duration_ms": 1500,
+            "language": null,
+        })
+    );
+}
@@ -0,0 +1,293 @@
+//! Post-processing applied to transcriptions before they are inserted or stored.
+
+use chrono::format::{Item, StrftimeItems};
+use chrono::{DateTime, Local, TimeZone};
+use regex::Regex;
+use serde::{Deserialize, Serialize};
+use std::fmt::{Display, Write};
+use tauri::AppHandle;
+
+/// Store key holding the user's replacement rules
+pub const TEXT_REPLACEMENTS_KEY: &str = "text_replacements";
+
+/// Store key holding the user's custom vocabulary
+pub const CUSTOM_VOCABULARY_KEY: &str = "custom_vocabulary";
+
+/// Store key for the strftime format of the time prefixed when `prepend_timestamp` is on
+pub const TIMESTAMP_FORMAT_KEY: &str = "timestamp_format";
+
+/// Timestamp format used when none is set or the saved one is invalid
+pub const DEFAULT_TIMESTAMP_FORMAT: &str = "[%H:%M] ";
+
+/// Most vocabulary terms kept, so the list stays cheap to match against
+pub const MAX_VOCABULARY_TERMS: usize = 200;
+
+/// Longest vocabulary term, in characters
+pub const MAX_VOCABULARY_TERM_CHARS: usize = 64;
+
+/// Terms whose letters span at most this many transcribed words are matched
+const MAX_VOCABULARY_SPAN_WORDS: usize = 3;
+
+/// Terms at least this long (ignoring case, spaces and punctuation) also match
+/// transcriptions that are one edit away
+const MIN_FUZZY_TERM_CHARS: usize = 6;
+
+/// How a replacement rule matches text
+#[derive(Debug, Clone, Copy, Default, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum ReplacementKind {
+    /// Exact (case-sensitive) substring
+    #[default]
+    Plain,
+    /// Regular expression; the replacement may reference groups as `$1`
+    Regex,
+}
+
+/// A user-defined find/replace rule
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+pub struct ReplacementRule {
+    pub find: String,
+    pub replace: String,
+    #[serde(default)]
+    pub kind: ReplacementKind,
+}
+
+/// Check that every rule can be applied, reporting the index of the first bad one
+pub fn validate_rules(rules: &[ReplacementRule]) -> Result<(), String> {
+    for (index, rule) in rules.iter().enumerate() {
+        if rule.find.is_empty() {
+            return Err(format!("Rule {}: find text is empty", index));
+        }
+        if rule.kind == ReplacementKind::Regex {
+            Regex::new(&rule.find)
+                .map_err(|e| format!("Rule {}: invalid regex '{}': {}", index, rule.find, e))?;
+        }
+    }
+    Ok(())
+}
+
+/// Apply replacement rules to `text` in order.
+/// Rules that fail to compile are skipped (they are rejected when saved).
+pub fn apply_rules(text: &str, rules: &[ReplacementRule]) -> String {
+    rules
+        .iter()
+        .fold(text.to_string(), |text, rule| match rule.kind {
+            ReplacementKind::Plain if !rule.find.is_empty() => {
+                text.replace(&rule.find, &rule.replace)
+            }
+            ReplacementKind::Plain => text,
+            ReplacementKind::Regex => match Regex::new(&rule.find) {
+                Ok(re) => re.replace_all(&text, rule.replace.as_str()).into_owned(),
+                Err(e) => {
+                    log::warn!("Skipping invalid replacement regex '{}': {}", rule.find, e);
+                    text
+                }
+            },
+        })
+}
+
+/// Read the saved replacement rules, or none if unset/invalid
+pub fn rules_from_store(app: &AppHandle) -> Vec<ReplacementRule> {
+    crate::get_setting_from_store(app, TEXT_REPLACEMENTS_KEY, Vec::new())
+}
+
+/// Trim, drop empty and case-insensitively duplicate terms, and enforce the
+/// MAX_VOCABULARY_TERMS / MAX_VOCABULARY_TERM_CHARS limits
+pub fn normalize_vocabulary(terms: Vec<String>) -> Result<Vec<String>, String> {
+    let mut normalized: Vec<String> = Vec::new();
+    for term in terms {
+        let term = term.trim();
+        if vocabulary_key(term).is_empty() {
+            continue;
+        }
+        if term.chars().count() > MAX_VOCABULARY_TERM_CHARS {
+            return Err(format!(
+                "Vocabulary term '{}' is longer than {} characters",
+                term, MAX_VOCABULARY_TERM_CHARS
+            ));
+        }
+        if !normalized
+            .iter()
+            .any(|t| t.to_lowercase() == term.to_lowercase())
+        {
+            normalized.push(term.to_string());
+        }
+    }
+    if normalized.len() > MAX_VOCABULARY_TERMS {
+        return Err(format!(
+            "Custom vocabulary is limited to {} terms, got {}",
+            MAX_VOCABULARY_TERMS,
+            normalized.len()
+        ));
+    }
+    Ok(normalized)
+}
+
+/// Lowercased letters and digits of `text`, used to compare words with terms
+fn vocabulary_key(text: &str) -> String {
+    text.chars()
+        .filter(|c| c.is_alphanumeric())
+        .flat_map(char::to_lowercase)
+        .collect()
+}
+
+/// Levenshtein distance between two strings, in chars
+fn edit_distance(a: &str, b: &str) -> usize {
+    let b: Vec<char> = b.chars().collect();
+    let mut previous: Vec<usize> = (0..=b.len()).collect();
+    for (i, a_char) in a.chars().enumerate() {
+        let mut current = vec![i + 1; b.len() + 1];
+        for (j, b_char) in b.iter().enumerate() {
+            let substitution = previous[j] + usize::from(a_char != *b_char);
+            current[j + 1] = substitution.min(previous[j + 1] + 1).min(current[j] + 1);
+        }
+        previous = current;
+    }
+    previous[b.len()]
+}
+
+/// Whether transcribed words (as a vocabulary key) should be replaced by `term_key`
+fn matches_term(words_key: &str, term_key: &str) -> bool {
+    words_key == term_key
+        || (term_key.chars().count() >= MIN_FUZZY_TERM_CHARS
+            && edit_distance(words_key, term_key) <= 1)
+}
+
+/// Correct transcribed words to the spelling of custom vocabulary terms.
+///
+/// A run of up to MAX_VOCABULARY_SPAN_WORDS words separated only by spaces or
+/// hyphens is replaced by a term when their letters match it ignoring case and
+/// punctuation ("type script" -> "TypeScript"), or are one edit away for terms of
+/// at least MIN_FUZZY_TERM_CHARS letters ("kubernetis" -> "Kubernetes").
+/// Longer runs and earlier terms win.
+pub fn apply_vocabulary(text: &str, terms: &[String]) -> String {
+    let term_keys: Vec<(String, &String)> = terms
+        .iter()
+        .map(|term| (vocabulary_key(term), term))
+        .filter(|(key, _)| !key.is_empty())
+        .collect();
+    if term_keys.is_empty() {
+        return text.to_string();
+    }
+
+    let word_re = Regex::new(r"[\p{L}\p{N}]+(?:['’][\p{L}\p{N}]+)*").expect("valid word regex");
+    let words: Vec<(usize, usize)> = word_re
+        .find_iter(text)
+        .map(|m| (m.start(), m.end()))
+        .collect();
+
+    let mut result = String::with_capacity(text.len());
+    let mut copied_to = 0;
+    let mut index = 0;
+    while index < words.len() {
+        let replacement = (1..=MAX_VOCABULARY_SPAN_WORDS.min(words.len() - index))
+            .rev()
+            .filter(|&span| {
+                // Only join words separated by spaces or hyphens
+                words[index..index + span].windows(2).all(|pair| {
+                    text[pair[0].1..pair[1].0]
+                        .chars()
+                        .all(|c| c == ' ' || c == '-')
+                })
+            })
+            .find_map(|span| {
+                let (start, _) = words[index];
+                let (_, end) = words[index + span - 1];
+                let words_key = vocabulary_key(&text[start..end]);
+                term_keys
+                    .iter()
+                    .find(|(term_key, _)| matches_term(&words_key, term_key))
+                    .map(|(_, term)| (span, start, end, *term))
+            });
+
+        match replacement {
+            Some((span, start, end, term)) => {
+                result.push_str(&text[copied_to..start]);
+                result.push_str(term);
+                copied_to = end;
+                index += span;
+            }
+            None => index += 1,
+        }
+    }
+    result.push_str(&text[copied_to..]);
+    result
+}
+
+/// Read the saved custom vocabulary, or none if unset/invalid
+pub fn vocabulary_from_store(app: &AppHandle) -> Vec<String> {
+    crate::get_setting_from_store(app, CUSTOM_VOCABULARY_KEY, Vec::new())
+}
+
+/// Uppercase the first alphabetic character, leaving everything else untouched
+pub fn capitalize_first(text: &str) -> String {
+    match text.char_indices().find(|(_, c)| c.is_alphabetic()) {
+        Some((index, c)) => {
+            let rest = &text[index + c.len_utf8()..];
+            format!("{}{}{}", &text[..index], c.to_uppercase(), rest)
+        }
+        None => text.to_string(),
+    }
+}
+
+/// Apply the insertion-time formatting options to `text`
+pub fn format_for_insertion(
+    text: &str,
+    auto_capitalize_first: bool,
+    append_trailing_space: bool,
+) -> String {
+    let mut text = if auto_capitalize_first {
+        capitalize_first(text)
+    } else {
+        text.to_string()
+    };
+    if append_trailing_space && !text.is_empty() && !text.ends_with(char::is_whitespace) {
+        text.push(' ');
+    }
+    text
+}
+
+/// Check that `format` is a strftime format that can render a local time
+pub fn validate_timestamp_format(format: &str) -> Result<(), String> {
+    if format.is_empty() {
+        return Err("Timestamp format is empty".to_string());
+    }
+    if StrftimeItems::new(format).any(|item| matches!(item, Item::Error)) {
+        return Err(format!("Invalid timestamp format '{}'", format));
+    }
+    // Some specifiers parse but can't be rendered; formatting a sample catches those
+    let mut rendered = String::new();
+    write!(rendered, "{}", Local::now().format(format))
+        .map_err(|_| format!("Timestamp format '{}' cannot be rendered", format))
+}
+
+/// Render `time` with `format`, using DEFAULT_TIMESTAMP_FORMAT if `format` is invalid
+pub fn format_timestamp<Tz: TimeZone>(format: &str, time: &DateTime<Tz>) -> String
+where
+    Tz::Offset: Display,
+{
+    let format = match validate_timestamp_format(format) {
+        Ok(()) => format,
+        Err(e) => {
+            log::warn!("{}, using {:?}", e, DEFAULT_TIMESTAMP_FORMAT);
+            DEFAULT_TIMESTAMP_FORMAT
+        }
+    };
+    time.format(format).to_string()
+}
+
+/// The current time prefix to insert before a transcription, or None when
+/// `prepend_timestamp` is off. With `for_history`, also None unless
+/// `timestamp_in_history` is on.
+pub fn timestamp_prefix_from_store(app: &AppHandle, for_history: bool) -> Option<String> {
+    let enabled = crate::get_setting_from_store(app, "prepend_timestamp", false)
+        && (!for_history || crate::get_setting_from_store(app, "timestamp_in_history", false));
+    enabled.then(|| {
+        let format: String = crate::get_setting_from_store(
+            app,
+            TIMESTAMP_FORMAT_KEY,
+            DEFAULT_TIMESTAMP_FORMAT.to_string(),
+        );
+        format_timestamp(&format, &Local::now())
+    })
+}
@@ -0,0 +1,275 @@
+//! System tray icon and menu.
+
+use crate::history::HistoryStorage;
+use crate::state::AppState;
+use arboard::Clipboard;
+use std::sync::atomic::Ordering;
+use tauri::{
+    image::Image,
+    menu::{CheckMenuItem, Menu, MenuItem, PredefinedMenuItem},
+    tray::{MouseButton, MouseButtonState, TrayIconBuilder, TrayIconEvent},
+    AppHandle, Manager, Wry,
+};
+
+/// ID of the app's tray icon
+const TRAY_ID: &str = "main";
+
+/// Menu item ID of the last-transcription entry
+const LAST_TRANSCRIPTION_ID: &str = "last_transcription";
+
+/// Longest last-transcription preview shown in the menu, in characters
+const MAX_PREVIEW_CHARS: usize = 40;
+
+/// Label shown while the history is empty
+const EMPTY_HISTORY_LABEL: &str = "No transcriptions yet";
+
+/// Menu item ID of the suspend-hotkeys toggle
+const SUSPEND_HOTKEYS_ID: &str = "suspend_hotkeys";
+
+/// Menu item ID of the incognito toggle
+const INCOGNITO_ID: &str = "incognito";
+
+/// Tooltip of the tray icon
+const TOOLTIP: &str = "Tambourine";
+
+/// Tray menu items updated after startup
+pub struct TrayMenu {
+    last_transcription: MenuItem<Wry>,
+    suspend_hotkeys: CheckMenuItem<Wry>,
+    incognito: CheckMenuItem<Wry>,
+}
+
+/// Tooltip of the tray icon, listing suspended hotkeys and incognito mode so they
+/// show without opening the menu
+pub fn tooltip_text(hotkeys_suspended: bool, incognito: bool) -> String {
+    let mut states = Vec::new();
+    if hotkeys_suspended {
+        states.push("hotkeys suspended");
+    }
+    if incognito {
+        states.push("incognito");
+    }
+    if states.is_empty() {
+        TOOLTIP.to_string()
+    } else {
+        format!("{} ({})", TOOLTIP, states.join(", "))
+    }
+}
+
+fn hotkeys_suspended(app: &AppHandle) -> bool {
+    app.state::<AppState>()
+        .hotkeys_suspended
+        .load(Ordering::SeqCst)
+}
+
+fn incognito(app: &AppHandle) -> bool {
+    app.try_state::<HistoryStorage>()
+        .is_some_and(|history| history.is_incognito())
+}
+
+/// Collapse a transcription onto one line and shorten it to `max_chars`,
+/// ending with an ellipsis when truncated
+pub fn truncate_menu_label(text: &str, max_chars: usize) -> String {
+    let line = text.split_whitespace().collect::<Vec<_>>().join(" ");
+    if line.chars().count() <= max_chars {
+        return line;
+    }
+    let truncated: String = line.chars().take(max_chars.saturating_sub(1)).collect();
+    format!("{}…", truncated.trim_end())
+}
+
+/// Text of the most recent history entry, if any
+fn last_transcription(app: &AppHandle) -> Option<String> {
+    let history = app.try_state::<HistoryStorage>()?;
+    match history.get_all(Some(1)) {
+        Ok(entries) => entries.into_iter().next().map(|entry| entry.text),
+        Err(e) => {
+            log::warn!("Failed to read history for tray menu: {}", e);
+            None
+        }
+    }
+}
+
+/// Update the last-transcription item to match the history. Call after the
+/// history changes.
+pub fn refresh_last_transcription(app: &AppHandle) {
+    let Some(menu) = app.try_state::<TrayMenu>() else {
+        return;
+    };
+    let (label, enabled) = match last_transcription(app) {
+        Some(text) => (truncate_menu_label(&text, MAX_PREVIEW_CHARS), true),
+        None => (EMPTY_HISTORY_LABEL.to_string(), false),
+    };
+    if let Err(e) = menu
+        .last_transcription
+        .set_text(label)
+        .and_then(|_| menu.last_transcription.set_enabled(enabled))
+    {
+        log::warn!("Failed to update tray menu: {}", e);
+    }
+}
+
+/// Load the tray icon. The idle icon is a macOS template (tinted to match the menu
+/// bar); the recording icon is colored so it stands out.
+fn tray_icon(recording: bool) -> tauri::Result<Image<'static>> {
+    // The @2x versions are automatically used for retina displays
+    let icon_bytes: &[u8] = if recording {
+        include_bytes!("../icons/tray-icon-recording@2x.png")
+    } else {
+        include_bytes!("../icons/tray-iconTemplate@2x.png")
+    };
+    Image::from_bytes(icon_bytes)
+}
+
+/// Switch the tray icon between the idle and recording variants
+pub fn set_recording_indicator(app: &AppHandle, recording: bool) {
+    let Some(tray) = app.tray_by_id(TRAY_ID) else {
+        return;
+    };
+    let result = tray_icon(recording).and_then(|icon| {
+        tray.set_icon(Some(icon))?;
+        tray.set_icon_as_template(!recording)
+    });
+    if let Err(e) = result {
+        log::warn!("Failed to update tray icon: {}", e);
+    }
+}
+
+/// Check the suspend-hotkeys and incognito items and update the tooltip to match
+/// the current state. Call after either changes.
+pub fn refresh_tray_status(app: &AppHandle) {
+    let suspended = hotkeys_suspended(app);
+    let incognito = incognito(app);
+    if let Some(menu) = app.try_state::<TrayMenu>() {
+        if let Err(e) = menu
+            .suspend_hotkeys
+            .set_checked(suspended)
+            .and_then(|_| menu.incognito.set_checked(incognito))
+        {
+            log::warn!("Failed to update tray menu: {}", e);
+        }
+    }
+    if let Some(tray) = app.tray_by_id(TRAY_ID) {
+        if let Err(e) = tray.set_tooltip(Some(tooltip_text(suspended, incognito))) {
+            log::warn!("Failed to update tray tooltip: {}", e);
+        }
+    }
+}
+
+/// Flip hotkey suspension from the tray menu
+fn toggle_hotkeys_suspended(app: &AppHandle) {
+    if let Err(e) = crate::set_hotkeys_suspended(app, !hotkeys_suspended(app)) {
+        log::error!("Failed to toggle hotkey suspension: {}", e);
+        // The menu item toggled itself on click, so put it back
+        refresh_tray_status(app);
+    }
+}
+
+/// Flip incognito mode from the tray menu
+fn toggle_incognito(app: &AppHandle) {
+    if let Err(e) = crate::commands::history::set_incognito_mode(app, !incognito(app)) {
+        log::error!("Failed to toggle incognito mode: {}", e);
+        refresh_tray_status(app);
+    }
+}
+
+/// Copy the most recent transcription to the clipboard
+fn copy_last_transcription(app: &AppHandle) {
+    let Some(text) = last_transcription(app) else {
+        return;
+    };
+    let result = Clipboard::new().and_then(|mut clipboard| clipboard.set_text(text));
+    if let Err(e) = result {
+        log::error!("Failed to copy last transcription: {}", e);
+    }
+}
+
+pub fn setup_tray(app: &AppHandle) -> Result<(), Box<dyn std::error::Error>> {
+    let last_transcription_item = MenuItem::with_id(
+        app,
+        LAST_TRANSCRIPTION_ID,
+        EMPTY_HISTORY_LABEL,
+        false,
+        None::<&str>,
+    )?;
+    let separator = PredefinedMenuItem::separator(app)?;
+    let suspended = hotkeys_suspended(app);
+    let incognito = incognito(app);
+    let suspend_hotkeys_item = CheckMenuItem::with_id(
+        app,
+        SUSPEND_HOTKEYS_ID,
+        "Suspend Hotkeys",
+        true,
+        suspended,
+        None::<&str>,
+    )?;
+    let incognito_item = CheckMenuItem::with_id(
+        app,
+        INCOGNITO_ID,
+        "Incognito",
+        true,
+        incognito,
+        None::<&str>,
+    )?;
+    let show_item = MenuItem::with_id(app, "show", "Show Window", true, None::<&str>)?;
+    let quit_item = MenuItem::with_id(app, "quit", "Quit", true, None::<&str>)?;
+    let menu = Menu::with_items(
+        app,
+        &[
+            &last_transcription_item,
+            &separator,
+            &suspend_hotkeys_item,
+            &incognito_item,
+            &show_item,
+            &quit_item,
+        ],
+    )?;
+
+    app.manage(TrayMenu {
+        last_transcription: last_transcription_item,
+        suspend_hotkeys: suspend_hotkeys_item,
+        incognito: incognito_item,
+    });
+    refresh_last_transcription(app);
+
+    let _tray = TrayIconBuilder::with_id(TRAY_ID)
+        .icon(tray_icon(false)?)
+        .icon_as_template(true)
+        .tooltip(tooltip_text(suspended, incognito))
+        .menu(&menu)
+        .show_menu_on_left_click(false)
+        .on_menu_event(|app, event| match event.id.as_ref() {
+            LAST_TRANSCRIPTION_ID => copy_last_transcription(app),
+            SUSPEND_HOTKEYS_ID => toggle_hotkeys_suspended(app),
+            INCOGNITO_ID => toggle_incognito(app),
+            "show" => {
+                if let Some(window) = app.get_webview_window("main") {
+                    let _ = window.show();
+                    let _ = window.set_focus();
+                }
+            }
+            "quit" => crate::quit_app(app),
+            _ => {}
+        })
+        .on_tray_icon_event(|tray, event| {
+            if let TrayIconEvent::Click {
+                button: MouseButton::Left,
+                button_state: MouseButtonState::Up,
+                ..
+            } = event
+            {
+                let app = tray.app_handle();
+                if let Some(window) = app.get_webview_window("main") {
+                    if window.is_visible().unwrap_or(false) {
+                        let _ = window.hide();
+                    } else {
+                        let _ = window.show();
+                        let _ = window.set_focus();
+                    }
+                }
+            }
+        })
+        .build(app)?;
+
+    Ok(())
+}
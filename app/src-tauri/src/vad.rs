@@ -0,0 +1,80 @@
+//! Silence detection for hands-free recording.
+//!
+//! The overlay samples the mic's RMS level while recording and emits it as
+//! `recording-level` events. When `vad_enabled` is set, those levels are fed
+//! through a `SilenceDetector` which decides when the speaker has finished.
+
+/// Default time the input must stay below the threshold before recording stops
+pub const DEFAULT_SILENCE_TIMEOUT_MS: u64 = 1500;
+
+/// Default RMS level (0.0-1.0) below which input is treated as silence
+pub const DEFAULT_VAD_THRESHOLD: f32 = 0.02;
+
+/// Transition reported by `SilenceDetector::update`
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum VadEvent {
+    /// Input rose above the threshold after silence (or for the first time)
+    SpeechDetected,
+    /// Input fell below the threshold after speech
+    SilenceDetected,
+    /// Input has stayed below the threshold for the full timeout window
+    SilenceTimeout,
+}
+
+/// Tracks input levels for one recording session.
+///
+/// The timeout only starts counting once speech has been heard, so a slow
+/// start doesn't end the recording, and any level above the threshold resets
+/// it, so natural pauses shorter than the window don't either.
+#[derive(Debug, Clone)]
+pub struct SilenceDetector {
+    threshold: f32,
+    silence_timeout_ms: u64,
+    heard_speech: bool,
+    /// Unix millis when the current run of silence began (None while speaking)
+    silence_started_ms: Option<u64>,
+    timed_out: bool,
+}
+
+impl SilenceDetector {
+    pub fn new(threshold: f32, silence_timeout_ms: u64) -> Self {
+        Self {
+            threshold,
+            silence_timeout_ms,
+            heard_speech: false,
+            silence_started_ms: None,
+            timed_out: false,
+        }
+    }
+
+    /// Feed an RMS level sampled at `now_ms`, returning a transition if one occurred.
+    /// `SilenceTimeout` is reported at most once per session.
+    pub fn update(&mut self, level: f32, now_ms: u64) -> Option<VadEvent> {
+        if self.timed_out {
+            return None;
+        }
+
+        if level >= self.threshold {
+            let was_silent = !self.heard_speech || self.silence_started_ms.is_some();
+            self.heard_speech = true;
+            self.silence_started_ms = None;
+            return was_silent.then_some(VadEvent::SpeechDetected);
+        }
+
+        if !self.heard_speech {
+            return None;
+        }
+
+        match self.silence_started_ms {
+            None => {
+                self.silence_started_ms = Some(now_ms);
+                Some(VadEvent::SilenceDetected)
+            }
+            Some(started) if now_ms.saturating_sub(started) >= self.silence_timeout_ms => {
+                self.timed_out = true;
+                Some(VadEvent::SilenceTimeout)
+            }
+            Some(_) => None,
+        }
+    }
+}
@@ -0,0 +1,104 @@
+//! Spoken phrases that trigger editing actions instead of being typed literally.
+
+use serde::{Deserialize, Serialize};
+use tauri::AppHandle;
+
+/// Store key holding the user-defined voice commands
+pub const VOICE_COMMANDS_KEY: &str = "voice_commands";
+
+/// Upper bound for a single backspace action, to keep a typo from wiping a document
+pub const MAX_BACKSPACE_COUNT: u32 = 500;
+
+/// Action performed when a voice command phrase is recognized
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum VoiceAction {
+    /// Press Enter once
+    NewLine,
+    /// Press Enter twice
+    NewParagraph,
+    /// Press Tab once
+    Tab,
+    /// Press Backspace `count` times
+    Backspace { count: u32 },
+    /// Backspace over the text inserted by the previous dictation
+    DeleteLast,
+    /// Insert the given text instead of the spoken phrase
+    Text { text: String },
+}
+
+/// A phrase-to-action mapping
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+pub struct VoiceCommand {
+    pub phrase: String,
+    pub action: VoiceAction,
+}
+
+impl VoiceCommand {
+    fn new(phrase: &str, action: VoiceAction) -> Self {
+        Self {
+            phrase: phrase.to_string(),
+            action,
+        }
+    }
+}
+
+/// Commands available without any configuration
+pub fn builtin_commands() -> Vec<VoiceCommand> {
+    vec![
+        VoiceCommand::new("new line", VoiceAction::NewLine),
+        VoiceCommand::new("new paragraph", VoiceAction::NewParagraph),
+        VoiceCommand::new("tab", VoiceAction::Tab),
+        VoiceCommand::new("backspace", VoiceAction::Backspace { count: 1 }),
+        VoiceCommand::new("delete that", VoiceAction::DeleteLast),
+    ]
+}
+
+/// Normalize a phrase for matching: lowercase, surrounding punctuation removed,
+/// and internal whitespace collapsed ("New line." and "new  line" both become "new line")
+pub fn normalize_phrase(phrase: &str) -> String {
+    phrase
+        .trim_matches(|c: char| !c.is_alphanumeric())
+        .split_whitespace()
+        .collect::<Vec<_>>()
+        .join(" ")
+        .to_lowercase()
+}
+
+/// Check user-defined commands, reporting the index of the first bad one
+pub fn validate_commands(commands: &[VoiceCommand]) -> Result<(), String> {
+    for (index, command) in commands.iter().enumerate() {
+        if normalize_phrase(&command.phrase).is_empty() {
+            return Err(format!("Command {}: phrase is empty", index));
+        }
+        if let VoiceAction::Backspace { count } = command.action {
+            if count == 0 || count > MAX_BACKSPACE_COUNT {
+                return Err(format!(
+                    "Command {}: backspace count must be between 1 and {}",
+                    index, MAX_BACKSPACE_COUNT
+                ));
+            }
+        }
+    }
+    Ok(())
+}
+
+/// Find the action for a transcription that consists solely of a command phrase.
+/// User-defined commands take precedence over built-in ones.
+pub fn match_command(text: &str, user_commands: &[VoiceCommand]) -> Option<VoiceAction> {
+    let spoken = normalize_phrase(text);
+    if spoken.is_empty() {
+        return None;
+    }
+    user_commands
+        .iter()
+        .cloned()
+        .chain(builtin_commands())
+        .find(|command| normalize_phrase(&command.phrase) == spoken)
+        .map(|command| command.action)
+}
+
+/// Read the saved user-defined commands, or none if unset/invalid
+pub fn commands_from_store(app: &AppHandle) -> Vec<VoiceCommand> {
+    crate::get_setting_from_store(app, VOICE_COMMANDS_KEY, Vec::new())
+}
@@ -0,0 +1,103 @@
+//! Optional webhook notified of each completed transcription, for logging
+//! dictations to the user's own service.
+//!
+//! When `transcription_webhook_url` is set, every transcription added to history is
+//! POSTed there as JSON on a background thread, after the text was inserted, so a slow
+//! or unreachable endpoint never holds up dictation. Failures are only logged.
+//! Nothing is sent in incognito mode.
+
+use chrono::{DateTime, Utc};
+use serde::Serialize;
+use std::time::Duration;
+use tauri::{AppHandle, Url};
+
+/// Store key for the URL transcriptions are POSTed to
+pub const TRANSCRIPTION_WEBHOOK_URL_KEY: &str = "transcription_webhook_url";
+
+/// Store key for the secret sent with each request. Excluded from settings exports.
+pub const TRANSCRIPTION_WEBHOOK_SECRET_KEY: &str = "transcription_webhook_secret";
+
+/// Header carrying the secret, so the receiving service can reject other senders
+pub const WEBHOOK_SECRET_HEADER: &str = "X-Webhook-Secret";
+
+/// Timeout for the whole request, including connecting
+const WEBHOOK_TIMEOUT: Duration = Duration::from_secs(5);
+
+/// JSON body of a webhook request
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct TranscriptionPayload {
+    pub text: String,
+    pub timestamp: DateTime<Utc>,
+    pub duration_ms: Option<u64>,
+    pub language: Option<String>,
+}
+
+/// Validate a webhook URL, returning it trimmed. The scheme must be http or https
+/// and the host must be non-empty. The path is kept as is.
+pub fn validate_webhook_url(url: &str) -> Result<String, String> {
+    let trimmed = url.trim();
+    let parsed =
+        Url::parse(trimmed).map_err(|e| format!("Invalid webhook URL '{}': {}", trimmed, e))?;
+    if !matches!(parsed.scheme(), "http" | "https") {
+        return Err(format!(
+            "Unsupported scheme '{}': webhook URL must start with http:// or https://",
+            parsed.scheme()
+        ));
+    }
+    if parsed.host_str().is_none_or(str::is_empty) {
+        return Err(format!("Webhook URL '{}' has no host", trimmed));
+    }
+    Ok(trimmed.to_string())
+}
+
+/// Check that a secret can be sent as a header value. The secret itself is never
+/// included in the error.
+pub fn validate_webhook_secret(secret: &str) -> Result<(), String> {
+    if secret.chars().any(|c| c.is_control()) {
+        return Err("Webhook secret contains control characters".to_string());
+    }
+    Ok(())
+}
+
+/// POST `payload` to the configured webhook in the background, if one is set
+pub fn notify_transcription(app: &AppHandle, payload: TranscriptionPayload) {
+    let url: Option<String> =
+        crate::get_setting_from_store(app, TRANSCRIPTION_WEBHOOK_URL_KEY, None);
+    let Some(url) = url.filter(|url| !url.trim().is_empty()) else {
+        return;
+    };
+    let secret: Option<String> =
+        crate::get_setting_from_store(app, TRANSCRIPTION_WEBHOOK_SECRET_KEY, None);
+
+    std::thread::spawn(move || match send(&url, secret.as_deref(), &payload) {
+        Ok(status) => log::debug!("Transcription webhook returned status {}", status),
+        Err(e) => log::warn!("Transcription webhook failed: {}", e),
+    });
+}
+
+fn send(url: &str, secret: Option<&str>, payload: &TranscriptionPayload) -> Result<u16, String> {
+    let url = validate_webhook_url(url)?;
+    let body = serde_json::to_string(payload)
+        .map_err(|e| format!("Failed to serialize webhook payload: {}", e))?;
+    let agent: ureq::Agent = ureq::Agent::config_builder()
+        .timeout_global(Some(WEBHOOK_TIMEOUT))
+        .http_status_as_error(false)
+        .build()
+        .into();
+
+    let mut request = agent.post(&url).header("Content-Type", "application/json");
+    if let Some(secret) = secret.filter(|secret| !secret.is_empty()) {
+        request = request.header(WEBHOOK_SECRET_HEADER, secret);
+    }
+    let status = request
+        .send(&body)
+        .map_err(|e| format!("Request to {} failed: {}", url, e))?
+        .status()
+        .as_u16();
+
+    if (200..300).contains(&status) {
+        Ok(status)
+    } else {
+        Err(format!("{} returned status {}", url, status))
+    }
+}
@@ -20,8 +20,14 @@ import {
 	useAvailableProvidersListener,
 	useRefreshServerQueriesOnConnect,
 	useSettings,
+	useSettingsResetListener,
 } from "./lib/queries";
-import { type ConfigResponse, type HotkeyConfig, tauriAPI } from "./lib/tauri";
+import {
+	type ConfigResponse,
+	type HotkeyConfig,
+	type HotkeyRegistration,
+	tauriAPI,
+} from "./lib/tauri";
 import { useRecordingStore } from "./stores/recordingStore";
 import "./app-main.css";
 
@@ -228,6 +234,9 @@ export default function App() {
 	// Listen for available providers from overlay window (must stay mounted)
 	useAvailableProvidersListener();
 
+	// Reload settings after they are reset to defaults
+	useSettingsResetListener();
+
 	// Refresh server-side queries when connection is established
 	useRefreshServerQueriesOnConnect(connectionState);
 
@@ -269,6 +278,40 @@ export default function App() {
 		};
 	}, []);
 
+	// Surface hotkeys that couldn't be registered, including at startup before this
+	// window was listening, so the user can pick a different combo
+	useEffect(() => {
+		let isMounted = true;
+		let unlisten: (() => void) | undefined;
+
+		const showFailures = (failures: HotkeyRegistration[]) => {
+			for (const failure of failures) {
+				notifications.show({
+					title: "Hotkey Unavailable",
+					message: `Could not register the ${failure.action.replaceAll("_", " ")} hotkey (${failure.shortcut}). Choose a different combination in Settings.`,
+					color: "red",
+					autoClose: false,
+				});
+			}
+		};
+
+		tauriAPI.getShortcutRegistrationFailures().then((failures) => {
+			if (isMounted) showFailures(failures);
+		});
+		tauriAPI.onShortcutRegistrationFailed(showFailures).then((fn) => {
+			if (isMounted) {
+				unlisten = fn;
+			} else {
+				fn();
+			}
+		});
+
+		return () => {
+			isMounted = false;
+			unlisten?.();
+		};
+	}, []);
+
 	return (
 		<div className="app-layout">
 			<Sidebar activeView={activeView} onViewChange={setActiveView} />
@@ -24,20 +24,74 @@ import {
 	useSettings,
 	useTypeText,
 } from "./lib/queries";
+import {
+	concatPcm16,
+	RECORDING_CAPTURE_PROCESSOR,
+	type RecordingCaptureMessage,
+} from "./lib/recordingCapture";
+import recordingCaptureWorkletUrl from "./lib/recordingCapture.worklet.ts?worker&url";
 import {
 	type CleanupPromptSections,
 	type ConnectionState,
+	DEFAULT_MIN_CONFIDENCE,
+	DEFAULT_OVERLAY_OPACITY,
+	DEFAULT_REQUEST_TIMEOUT_MS,
+	isLowConfidence,
+	type OverlayAppearance,
+	type TranscriptionErrorKind,
 	tauriAPI,
 } from "./lib/tauri";
 import { useRecordingStore } from "./stores/recordingStore";
 import "./overlay-global.css";
 
+// Emit mic level at ~20Hz while recording
+const RECORDING_LEVEL_INTERVAL_MS = 50;
+
+// How long the overlay shows the error state before returning to idle
+const ERROR_DISPLAY_MS = 3000;
+
+// Colors for each overlay theme
+const OVERLAY_PALETTES = {
+	dark: {
+		background: "rgba(0, 0, 0, 0.9)",
+		foreground: "white",
+		barColor: "#eeeeee",
+		visualizerBackground: "#000000",
+		button: "bg-black text-white hover:bg-gray-900",
+	},
+	light: {
+		background: "rgba(255, 255, 255, 0.9)",
+		foreground: "black",
+		barColor: "#222222",
+		visualizerBackground: "#ffffff",
+		button: "bg-white text-black hover:bg-gray-100",
+	},
+} as const;
+
 // Zod schemas for message validation
 const RecordingCompleteMessageSchema = z.object({
 	type: z.literal("recording-complete"),
 	hasContent: z.boolean().optional(),
 });
 
+// Language the server detected (or was configured with) for the last transcription
+const TranscriptionLanguageMessageSchema = z.object({
+	type: z.literal("transcription-language"),
+	language: z.string(),
+});
+
+// Confidence (0.0-1.0) of the last transcription, for providers that report one
+const TranscriptionConfidenceMessageSchema = z.object({
+	type: z.literal("transcription-confidence"),
+	confidence: z.number().min(0).max(1),
+});
+
+// Buffered transcription so far (only sent when streaming is enabled)
+const TranscriptionPartialMessageSchema = z.object({
+	type: z.literal("transcription-partial"),
+	text: z.string(),
+});
+
 // Config response schemas (relayed to main window for notifications)
 const ConfigUpdatedMessageSchema = z.object({
 	type: z.literal("config-updated"),
@@ -73,6 +127,37 @@ const AvailableProvidersMessageSchema = z.object({
 	),
 });
 
+// Any server message, for reporting transcription messages that failed validation
+const MessageTypeSchema = z.object({ type: z.string() });
+
+const TRANSCRIPTION_MESSAGE_TYPES = new Set([
+	"recording-complete",
+	"transcription-confidence",
+	"transcription-language",
+	"transcription-partial",
+]);
+
+// WebRTC offer request to the server, with the configured auth headers.
+// The timeout applies to each connection attempt separately.
+async function offerRequestParams(serverUrl: string, timeoutMs: number) {
+	return {
+		endpoint: `${serverUrl}/api/offer`,
+		headers: new Headers(await tauriAPI.getServerRequestHeaders()),
+		timeout: timeoutMs,
+	};
+}
+
+// Best-effort message from an RTVI error (an RTVIMessage with data.error, or anything else)
+function serverErrorMessage(error: unknown): string {
+	const parsed = z
+		.object({ data: z.object({ error: z.string() }) })
+		.safeParse(error);
+	if (parsed.success) {
+		return parsed.data.data.error;
+	}
+	return error instanceof Error ? error.message : String(error);
+}
+
 // Non-empty array type for type-safe batched sends
 type NonEmptyArray<T> = [T, ...T[]];
 
@@ -81,7 +166,11 @@ type ConfigMessage =
 	| { type: "set-prompt-sections"; data: { sections: CleanupPromptSections } }
 	| { type: "set-stt-provider"; data: { provider: string } }
 	| { type: "set-llm-provider"; data: { provider: string } }
-	| { type: "set-stt-timeout"; data: { timeout_seconds: number } };
+	| { type: "set-stt-timeout"; data: { timeout_seconds: number } }
+	| { type: "set-language"; data: { language: string } }
+	| { type: "set-streaming"; data: { enabled: boolean } }
+	| { type: "set-input-gain"; data: { gain: number } }
+	| { type: "set-noise-gate"; data: { threshold: number } };
 
 // Helper to send multiple config messages - only callable with non-empty list
 function sendConfigMessages(
@@ -98,9 +187,11 @@ function RecordingControl() {
 	const queryClient = useQueryClient();
 	const {
 		state,
+		pendingTranscriptions,
 		setClient,
 		startRecording,
 		stopRecording,
+		cancelRecording,
 		handleResponse,
 		handleConnected,
 		handleDisconnected,
@@ -111,9 +202,13 @@ function RecordingControl() {
 
 	// Ref for tracking drag state
 	const hasDragStartedRef = useRef(false);
+	// Whether the native window drag started by startDragging is in progress
+	const windowDragRef = useRef(false);
 
 	const { data: serverUrl } = useServerUrl();
 	const { data: settings } = useSettings();
+	const requestTimeoutMs =
+		settings?.request_timeout_ms ?? DEFAULT_REQUEST_TIMEOUT_MS;
 
 	// Track if we've ever connected (to distinguish initial connection from reconnection)
 	const hasConnectedRef = useRef(false);
@@ -124,6 +219,20 @@ function RecordingControl() {
 	// Accumulate LLM text chunks (RTVIObserver streams text in chunks)
 	const llmTextAccumulatorRef = useRef("");
 
+	// Language reported by the server for the current transcription
+	const transcriptionLanguageRef = useRef<string | null>(null);
+
+	// Confidence reported by the server for the current transcription (null if not reported)
+	const transcriptionConfidenceRef = useRef<number | null>(null);
+
+	// Recording start time, and the durations of stopped recordings awaiting
+	// transcription (oldest first), stored with the history entry
+	const recordingStartedAtRef = useRef<number | null>(null);
+	const pendingDurationsMsRef = useRef<(number | null)[]>([]);
+
+	// Set when a recording is cancelled so its captured audio isn't saved
+	const discardCaptureRef = useRef(false);
+
 	// Track previous settings to detect actual changes (for syncing while connected)
 	const prevSettingsRef = useRef(settings);
 
@@ -146,28 +255,77 @@ function RecordingControl() {
 
 		// Initial connection only
 		if (!previousUrl) {
-			client
-				.connect({
-					webrtcRequestParams: { endpoint: `${serverUrl}/api/offer` },
-				})
+			offerRequestParams(serverUrl, requestTimeoutMs)
+				.then((webrtcRequestParams) =>
+					client.connect({ webrtcRequestParams }),
+				)
 				.catch((error: unknown) => {
 					console.error("[Pipecat] Connection failed:", error);
 				});
 		}
-	}, [client, serverUrl]);
+	}, [client, serverUrl, requestTimeoutMs]);
 
 	// TanStack Query hooks
 	const typeTextMutation = useTypeText();
 	const addHistoryEntry = useAddHistoryEntry();
 
-	// Response timeout (10s)
+	// Error indicator, cleared after ERROR_DISPLAY_MS or when the next recording starts
+	const [hasError, setHasError] = useState(false);
+	const { start: startErrorTimeout, clear: clearErrorTimeout } = useTimeout(
+		() => setHasError(false),
+		ERROR_DISPLAY_MS,
+	);
+
+	const showErrorIndicator = useCallback(() => {
+		setHasError(true);
+		startErrorTimeout();
+	}, [startErrorTimeout]);
+
+	const reportTranscriptionError = useCallback(
+		(kind: TranscriptionErrorKind, message: string, willRetry = false) => {
+			console.error(`[Pipecat] Transcription error (${kind}):`, message);
+			showErrorIndicator();
+			tauriAPI.emitTranscriptionError({
+				kind,
+				message,
+				will_retry: willRetry,
+			});
+		},
+		[showErrorIndicator],
+	);
+
+	// Response timeout (request_timeout_ms) for the oldest pending transcription;
+	// Rust restores audio on the error
 	const { start: startResponseTimeout, clear: clearResponseTimeout } =
 		useTimeout(() => {
-			const currentState = useRecordingStore.getState().state;
-			if (currentState === "processing") {
-				handleResponse(); // Reset to idle
+			if (useRecordingStore.getState().pendingTranscriptions > 0) {
+				reportTranscriptionError(
+					"timeout",
+					`Timed out waiting for transcription after ${requestTimeoutMs / 1000}s`,
+				);
+				pendingDurationsMsRef.current.shift();
+				handleResponse();
 			}
-		}, 10000);
+		}, requestTimeoutMs);
+
+	// Finish the oldest pending transcription, returning its recording duration
+	const resolveTranscription = useCallback(() => {
+		clearResponseTimeout();
+		const durationMs = pendingDurationsMsRef.current.shift() ?? null;
+		handleResponse(); // Back to idle once nothing is pending
+		return durationMs;
+	}, [clearResponseTimeout, handleResponse]);
+
+	// Each transcription gets its own deadline once it reaches the front of the queue
+	// (resolveTranscription clears the timer, so this restarts it for the next one)
+	useEffect(() => {
+		if (pendingTranscriptions > 0) {
+			startResponseTimeout();
+		} else {
+			clearResponseTimeout();
+		}
+		tauriAPI.emitTranscriptionQueueChanged(pendingTranscriptions);
+	}, [pendingTranscriptions, startResponseTimeout, clearResponseTimeout]);
 
 	// Keep store client in sync
 	useEffect(() => {
@@ -187,6 +345,130 @@ function RecordingControl() {
 		return unsubscribe;
 	}, []);
 
+	// Sample the local mic track's RMS level while recording and emit it for level indicators.
+	// Reuses the pipecat capture track rather than opening a second stream.
+	useEffect(() => {
+		if (state !== "recording" || !client) return;
+
+		const track = client.tracks()?.local?.audio;
+		if (!track) return;
+
+		const audioContext = new AudioContext();
+		const source = audioContext.createMediaStreamSource(
+			new MediaStream([track]),
+		);
+		const analyser = audioContext.createAnalyser();
+		analyser.fftSize = 1024;
+		source.connect(analyser);
+		const samples = new Float32Array(analyser.fftSize);
+
+		const interval = setInterval(() => {
+			analyser.getFloatTimeDomainData(samples);
+			let sumOfSquares = 0;
+			for (const sample of samples) {
+				sumOfSquares += sample * sample;
+			}
+			const rms = Math.sqrt(sumOfSquares / samples.length);
+			tauriAPI.emitRecordingLevel(Math.min(1, rms));
+		}, RECORDING_LEVEL_INTERVAL_MS);
+
+		return () => {
+			clearInterval(interval);
+			source.disconnect();
+			audioContext.close().catch(() => {});
+		};
+	}, [state, client]);
+
+	// Capture PCM from the mic track at its native rate and channel count while recording
+	// and hand it to Rust, which converts it to the configured sample_rate/channels and
+	// saves it as WAV once recording stops (opt-in via the save_recordings setting).
+	// An AudioWorklet does the capture off the main thread, and the samples are sent
+	// as raw bytes rather than JSON.
+	const saveRecordings = settings?.save_recordings ?? false;
+	useEffect(() => {
+		if (state !== "recording" || !client || !saveRecordings) return;
+
+		const track = client.tracks()?.local?.audio;
+		if (!track) return;
+
+		const channels = Math.min(2, track.getSettings().channelCount ?? 1);
+		const audioContext = new AudioContext();
+		const sampleRate = audioContext.sampleRate;
+		const chunks: Int16Array[] = [];
+		let source: MediaStreamAudioSourceNode | null = null;
+		let capture: AudioWorkletNode | null = null;
+		let stopped = false;
+		discardCaptureRef.current = false;
+
+		const ready = audioContext.audioWorklet
+			.addModule(recordingCaptureWorkletUrl)
+			.then(() => {
+				if (stopped) return;
+				source = audioContext.createMediaStreamSource(new MediaStream([track]));
+				capture = new AudioWorkletNode(
+					audioContext,
+					RECORDING_CAPTURE_PROCESSOR,
+					{
+						channelCount: channels,
+						channelCountMode: "explicit",
+						processorOptions: { channels },
+					},
+				);
+				capture.port.onmessage = (
+					event: MessageEvent<RecordingCaptureMessage>,
+				) => {
+					if (event.data.type === "samples") {
+						chunks.push(event.data.samples);
+					}
+				};
+				source.connect(capture);
+				capture.connect(audioContext.destination);
+			})
+			.catch((error: unknown) => {
+				console.error("[Recording] Failed to start capture:", error);
+			});
+
+		return () => {
+			stopped = true;
+			const discard = discardCaptureRef.current;
+			discardCaptureRef.current = false;
+
+			const finish = () => {
+				source?.disconnect();
+				capture?.disconnect();
+				audioContext.close().catch(() => {});
+				if (discard) return;
+
+				const samples = concatPcm16(chunks);
+				if (samples.length > 0) {
+					tauriAPI
+						.saveRecording(samples, sampleRate, channels)
+						.catch((error: unknown) => {
+							console.error("[Recording] Failed to save recording:", error);
+						});
+				}
+			};
+
+			// Ask the worklet for the samples it hasn't posted yet before saving
+			ready.then(() => {
+				if (!capture) {
+					finish();
+					return;
+				}
+				capture.port.onmessage = (
+					event: MessageEvent<RecordingCaptureMessage>,
+				) => {
+					if (event.data.type === "samples") {
+						chunks.push(event.data.samples);
+					} else {
+						finish();
+					}
+				};
+				capture.port.postMessage("flush");
+			});
+		};
+	}, [state, client, saveRecordings]);
+
 	// Auto-resize window to fit content using Mantine's useResizeObserver
 	useEffect(() => {
 		if (rect.width > 0 && rect.height > 0) {
@@ -196,23 +478,60 @@ function RecordingControl() {
 
 	// Handle start/stop recording from hotkeys
 	const onStartRecording = useCallback(async () => {
+		clearErrorTimeout();
+		setHasError(false);
+		recordingStartedAtRef.current = Date.now();
 		await startRecording();
-	}, [startRecording]);
+	}, [startRecording, clearErrorTimeout]);
 
 	const onStopRecording = useCallback(() => {
+		const durationMs =
+			recordingStartedAtRef.current !== null
+				? Date.now() - recordingStartedAtRef.current
+				: null;
+		recordingStartedAtRef.current = null;
 		if (stopRecording()) {
-			startResponseTimeout();
+			pendingDurationsMsRef.current.push(durationMs);
+			tauriAPI.emitTranscribing();
 		}
-	}, [stopRecording, startResponseTimeout]);
+	}, [stopRecording]);
+
+	const onCancelRecording = useCallback(() => {
+		recordingStartedAtRef.current = null;
+		discardCaptureRef.current = true;
+		cancelRecording();
+	}, [cancelRecording]);
+
+	// Resume a recording that was started before this window (re)loaded
+	const recordingRecoveryCheckedRef = useRef(false);
+	useEffect(() => {
+		if (state !== "idle" || recordingRecoveryCheckedRef.current) return;
+		recordingRecoveryCheckedRef.current = true;
+
+		tauriAPI
+			.getRecordingState()
+			.then(async (recordingState) => {
+				if (!recordingState.is_recording) return;
+				console.debug("[Recording] Resuming in-progress recording");
+				await onStartRecording();
+				recordingStartedAtRef.current =
+					recordingState.started_at_ms ?? recordingStartedAtRef.current;
+			})
+			.catch((error) => {
+				console.error("[Recording] Failed to get recording state:", error);
+			});
+	}, [state, onStartRecording]);
 
 	// Hotkey event listeners
 	useEffect(() => {
 		let unlistenStart: (() => void) | undefined;
 		let unlistenStop: (() => void) | undefined;
+		let unlistenCancel: (() => void) | undefined;
 
 		const setup = async () => {
 			unlistenStart = await tauriAPI.onStartRecording(onStartRecording);
 			unlistenStop = await tauriAPI.onStopRecording(onStopRecording);
+			unlistenCancel = await tauriAPI.onCancelRecording(onCancelRecording);
 		};
 
 		setup();
@@ -220,8 +539,67 @@ function RecordingControl() {
 		return () => {
 			unlistenStart?.();
 			unlistenStop?.();
+			unlistenCancel?.();
+		};
+	}, [onStartRecording, onStopRecording, onCancelRecording]);
+
+	// Overlay opacity, theme and size. Refetched on settings changes too, since profile
+	// switches and imports replace the stored values without an appearance event.
+	const [appearance, setAppearance] = useState<OverlayAppearance>({
+		opacity: DEFAULT_OVERLAY_OPACITY,
+		theme: "dark",
+		size: "medium",
+		scale: 1,
+	});
+	useEffect(() => {
+		let unlistenAppearance: (() => void) | undefined;
+		let unlistenSettings: (() => void) | undefined;
+
+		const refresh = () => {
+			tauriAPI
+				.getOverlayAppearance()
+				.then(setAppearance)
+				.catch((error) => {
+					console.error("[Overlay] Failed to get appearance:", error);
+				});
+		};
+
+		const setup = async () => {
+			unlistenAppearance =
+				await tauriAPI.onOverlayAppearanceChanged(setAppearance);
+			unlistenSettings = await tauriAPI.onSettingsChanged(refresh);
+		};
+
+		refresh();
+		setup();
+
+		return () => {
+			unlistenAppearance?.();
+			unlistenSettings?.();
 		};
-	}, [onStartRecording, onStopRecording]);
+	}, []);
+	const palette = OVERLAY_PALETTES[appearance.theme];
+
+	// Incognito mode (history kept in memory only) gets a dashed border
+	const [incognito, setIncognito] = useState(false);
+	useEffect(() => {
+		let unlisten: (() => void) | undefined;
+
+		tauriAPI
+			.getIncognito()
+			.then(setIncognito)
+			.catch((error) => {
+				console.error("[Overlay] Failed to get incognito mode:", error);
+			});
+		const setup = async () => {
+			unlisten = await tauriAPI.onIncognitoChanged(setIncognito);
+		};
+		setup();
+
+		return () => {
+			unlisten?.();
+		};
+	}, []);
 
 	// Listen for settings changes from main window and invalidate cache to trigger sync
 	useEffect(() => {
@@ -354,6 +732,32 @@ function RecordingControl() {
 					},
 				});
 			}
+			if (hasChanged("language")) {
+				messages.push({
+					type: "set-language",
+					data: { language: currentSettings?.language as string },
+				});
+			}
+			if (hasChanged("streaming_enabled")) {
+				messages.push({
+					type: "set-streaming",
+					data: { enabled: currentSettings?.streaming_enabled ?? false },
+				});
+			}
+			if (hasChanged("input_gain")) {
+				messages.push({
+					type: "set-input-gain",
+					data: { gain: currentSettings?.input_gain as number },
+				});
+			}
+			if (hasChanged("noise_gate_threshold")) {
+				messages.push({
+					type: "set-noise-gate",
+					data: {
+						threshold: currentSettings?.noise_gate_threshold as number,
+					},
+				});
+			}
 
 			return messages;
 		},
@@ -406,10 +810,20 @@ function RecordingControl() {
 		useCallback(() => {
 			console.debug("[Pipecat] Disconnected");
 
+			// Reconnection: only if we've connected before (not on initial connection failure)
+			const willReconnect = Boolean(
+				hasConnectedRef.current && serverUrl && client,
+			);
+
 			// Check if we were recording/processing when disconnect happened
 			const currentState = useRecordingStore.getState().state;
 			if (currentState === "recording" || currentState === "processing") {
 				console.warn("[Pipecat] Disconnected during recording/processing");
+				reportTranscriptionError(
+					"network",
+					"Disconnected from server",
+					willReconnect,
+				);
 				try {
 					client?.enableMic(false);
 					// Also stop the track to release the mic (removes OS mic indicator)
@@ -422,23 +836,32 @@ function RecordingControl() {
 				}
 			}
 
+			pendingDurationsMsRef.current = [];
 			handleDisconnected();
 
-			// Reconnection: only if we've connected before (not on initial connection failure)
 			// SmallWebRTC already tried to reconnect (3 attempts) and gave up
-			if (hasConnectedRef.current && serverUrl && client) {
+			if (willReconnect && serverUrl && client) {
 				setTimeout(async () => {
 					try {
 						await client.disconnect(); // Reset client state
 						await client.connect({
-							webrtcRequestParams: { endpoint: `${serverUrl}/api/offer` },
+							webrtcRequestParams: await offerRequestParams(
+								serverUrl,
+								requestTimeoutMs,
+							),
 						});
 					} catch (error: unknown) {
 						console.error("[Pipecat] Reconnection failed:", error);
 					}
 				}, 3000);
 			}
-		}, [client, serverUrl, handleDisconnected]),
+		}, [
+			client,
+			serverUrl,
+			requestTimeoutMs,
+			handleDisconnected,
+			reportTranscriptionError,
+		]),
 	);
 
 	// LLM text streaming handlers (using official RTVI protocol via RTVIObserver)
@@ -461,25 +884,47 @@ function RecordingControl() {
 	useRTVIClientEvent(
 		RTVIEvent.BotLlmStopped,
 		useCallback(async () => {
-			clearResponseTimeout();
 			const text = llmTextAccumulatorRef.current.trim();
 			llmTextAccumulatorRef.current = "";
-
-			if (text) {
+			const language = transcriptionLanguageRef.current;
+			const confidence = transcriptionConfidenceRef.current;
+			transcriptionLanguageRef.current = null;
+			transcriptionConfidenceRef.current = null;
+			// Resolve before typing so the next queued transcription isn't held up
+			const audioDurationMs = resolveTranscription();
+
+			const minConfidence = settings?.min_confidence ?? DEFAULT_MIN_CONFIDENCE;
+
+			if (
+				text &&
+				confidence !== null &&
+				isLowConfidence(confidence, minConfidence)
+			) {
+				console.debug(
+					`[Pipecat] Holding back low-confidence transcription (${confidence} < ${minConfidence}):`,
+					text,
+				);
+				tauriAPI.emitTranscriptionLowConfidence({ text, confidence });
+			} else if (text) {
 				console.debug("[Pipecat] LLM response:", text);
+				tauriAPI.emitTranscriptionComplete(text);
 				try {
 					await typeTextMutation.mutateAsync(text);
 				} catch (error) {
 					console.error("[Pipecat] Failed to type text:", error);
+					showErrorIndicator();
 				}
-				addHistoryEntry.mutate(text);
+				addHistoryEntry.mutate({ text, language, audioDurationMs });
+			} else {
+				// Whitespace only (e.g. background noise)
+				tauriAPI.emitTranscriptionEmpty();
 			}
-			handleResponse();
 		}, [
-			clearResponseTimeout,
+			settings?.min_confidence,
+			resolveTranscription,
 			typeTextMutation,
 			addHistoryEntry,
-			handleResponse,
+			showErrorIndicator,
 		]),
 	);
 
@@ -491,8 +936,36 @@ function RecordingControl() {
 				const recordingCompleteResult =
 					RecordingCompleteMessageSchema.safeParse(message);
 				if (recordingCompleteResult.success) {
-					clearResponseTimeout();
-					handleResponse();
+					// Nothing was transcribed
+					transcriptionConfidenceRef.current = null;
+					resolveTranscription();
+					tauriAPI.emitTranscriptionEmpty();
+					return;
+				}
+
+				// Partials are display-only; the final text is typed once on BotLlmStopped
+				const transcriptionPartialResult =
+					TranscriptionPartialMessageSchema.safeParse(message);
+				if (transcriptionPartialResult.success) {
+					tauriAPI.emitTranscriptionPartial(
+						transcriptionPartialResult.data.text,
+					);
+					return;
+				}
+
+				const transcriptionConfidenceResult =
+					TranscriptionConfidenceMessageSchema.safeParse(message);
+				if (transcriptionConfidenceResult.success) {
+					transcriptionConfidenceRef.current =
+						transcriptionConfidenceResult.data.confidence;
+					return;
+				}
+
+				const transcriptionLanguageResult =
+					TranscriptionLanguageMessageSchema.safeParse(message);
+				if (transcriptionLanguageResult.success) {
+					transcriptionLanguageRef.current =
+						transcriptionLanguageResult.data.language;
 					return;
 				}
 
@@ -528,17 +1001,40 @@ function RecordingControl() {
 					});
 					return;
 				}
+
+				// A transcription message that didn't match its schema
+				const messageType = MessageTypeSchema.safeParse(message);
+				if (
+					messageType.success &&
+					TRANSCRIPTION_MESSAGE_TYPES.has(messageType.data.type)
+				) {
+					reportTranscriptionError(
+						"parse",
+						`Malformed ${messageType.data.type} message from server`,
+					);
+				}
 			},
-			[clearResponseTimeout, handleResponse],
+			[resolveTranscription, reportTranscriptionError],
 		),
 	);
 
 	// Error handlers
 	useRTVIClientEvent(
 		RTVIEvent.Error,
-		useCallback((error: unknown) => {
-			console.error("[Pipecat] Error:", error);
-		}, []),
+		useCallback(
+			(error: unknown) => {
+				console.error("[Pipecat] Error:", error);
+				const { state: currentState, pendingTranscriptions: pending } =
+					useRecordingStore.getState();
+				if (currentState === "recording" || pending > 0) {
+					reportTranscriptionError("server", serverErrorMessage(error));
+				}
+				if (pending > 0) {
+					resolveTranscription();
+				}
+			},
+			[resolveTranscription, reportTranscriptionError],
+		),
 	);
 
 	useRTVIClientEvent(
@@ -557,6 +1053,25 @@ function RecordingControl() {
 		}
 	}, [state, onStartRecording, onStopRecording]);
 
+	// Tell the backend the window drag is over, so later moves aren't persisted
+	const endWindowDrag = useCallback(() => {
+		if (!windowDragRef.current) return;
+		windowDragRef.current = false;
+		tauriAPI.endDragging().catch((error) => {
+			console.error("Failed to end overlay drag:", error);
+		});
+	}, []);
+
+	// The OS owns the pointer during a window drag, so the pointerup ending it may
+	// never reach the page: the first move with no button pressed ends it too
+	useEffect(() => {
+		const handlePointerMove = (event: PointerEvent) => {
+			if (event.buttons === 0) endWindowDrag();
+		};
+		window.addEventListener("pointermove", handlePointerMove);
+		return () => window.removeEventListener("pointermove", handlePointerMove);
+	}, [endWindowDrag]);
+
 	// Drag handler using @use-gesture/react
 	// Handles unfocused window dragging (data-tauri-drag-region doesn't work on unfocused windows)
 	const bindDrag = useDrag(
@@ -572,12 +1087,14 @@ function RecordingControl() {
 			// Start dragging once threshold is exceeded
 			if (!memo && distance > DRAG_THRESHOLD) {
 				hasDragStartedRef.current = true;
+				windowDragRef.current = true;
 				tauriAPI.startDragging();
 				return true; // memo = true (dragging started)
 			}
 
 			if (last) {
 				hasDragStartedRef.current = false;
+				endWindowDrag();
 			}
 
 			return memo;
@@ -585,54 +1102,67 @@ function RecordingControl() {
 		{ filterTaps: true },
 	);
 
+	// The size preset zooms the overlay; the unzoomed wrapper is what gets
+	// measured, so the window is resized to the zoomed size
 	return (
 		<div
 			ref={containerRef}
-			role="application"
-			{...bindDrag()}
-			style={{
-				width: "fit-content",
-				height: "fit-content",
-				backgroundColor: "rgba(0, 0, 0, 0.9)",
-				borderRadius: 12,
-				border: "1px solid rgba(128, 128, 128, 0.9)",
-				padding: 2,
-				cursor: "grab",
-				userSelect: "none",
-			}}
+			style={{ width: "fit-content", height: "fit-content" }}
 		>
-			{state === "processing" ||
-			state === "disconnected" ||
-			state === "connecting" ? (
-				<div
-					style={{
-						width: 48,
-						height: 48,
-						display: "flex",
-						alignItems: "center",
-						justifyContent: "center",
-					}}
-				>
-					<Loader size="sm" color="white" />
-				</div>
-			) : (
-				<UserAudioComponent
-					onClick={handleClick}
-					isMicEnabled={state === "recording"}
-					noIcon={true}
-					noDevicePicker={true}
-					noVisualizer={state !== "recording"}
-					visualizerProps={{
-						barColor: "#eeeeee",
-						backgroundColor: "#000000",
-					}}
-					classNames={{
-						button: "bg-black text-white hover:bg-gray-900",
-					}}
-				>
-					{state !== "recording" && <Logo className="size-5" />}
-				</UserAudioComponent>
-			)}
+			<div
+				role="application"
+				{...bindDrag()}
+				title={incognito ? "Incognito: history is not saved" : undefined}
+				style={{
+					width: "fit-content",
+					height: "fit-content",
+					zoom: appearance.scale,
+					backgroundColor: palette.background,
+					opacity: appearance.opacity,
+					borderRadius: 12,
+					border: hasError
+						? "1px solid rgba(239, 68, 68, 0.9)"
+						: incognito
+							? "1px dashed rgba(168, 85, 247, 0.9)"
+							: "1px solid rgba(128, 128, 128, 0.9)",
+					padding: 2,
+					cursor: "grab",
+					userSelect: "none",
+				}}
+			>
+				{state === "processing" ||
+				state === "disconnected" ||
+				state === "connecting" ? (
+					<div
+						style={{
+							width: 48,
+							height: 48,
+							display: "flex",
+							alignItems: "center",
+							justifyContent: "center",
+						}}
+					>
+						<Loader size="sm" color={palette.foreground} />
+					</div>
+				) : (
+					<UserAudioComponent
+						onClick={handleClick}
+						isMicEnabled={state === "recording"}
+						noIcon={true}
+						noDevicePicker={true}
+						noVisualizer={state !== "recording"}
+						visualizerProps={{
+							barColor: palette.barColor,
+							backgroundColor: palette.visualizerBackground,
+						}}
+						classNames={{
+							button: palette.button,
+						}}
+					>
+						{state !== "recording" && <Logo className="size-5" />}
+					</UserAudioComponent>
+				)}
+			</div>
 		</div>
 	);
 }
@@ -669,11 +1199,30 @@ export default function OverlayApp() {
 		};
 	}, []);
 
-	// Apply selected microphone when settings or client changes
+	// Apply selected microphone when settings or client changes.
+	// Falls back to the system default if the selected device is no longer available.
 	useEffect(() => {
-		if (client && devicesReady && settings?.selected_mic_id) {
-			client.updateMic(settings.selected_mic_id);
+		const selectedMicId = settings?.selected_mic_id;
+		if (!client || !devicesReady || !selectedMicId) {
+			return;
 		}
+
+		client
+			.getAllMics()
+			.then((mics) => {
+				if (mics.some((mic) => mic.deviceId === selectedMicId)) {
+					client.updateMic(selectedMicId);
+				} else {
+					console.warn(
+						`[Pipecat] Selected microphone ${selectedMicId} not found, using system default`,
+					);
+					client.updateMic("default");
+					tauriAPI.emitInputDeviceFallback(selectedMicId);
+				}
+			})
+			.catch((error: unknown) => {
+				console.error("[Pipecat] Failed to list microphones:", error);
+			});
 	}, [client, devicesReady, settings?.selected_mic_id]);
 
 	if (!client || !devicesReady) {
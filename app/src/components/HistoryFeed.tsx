@@ -1,15 +1,13 @@
 import { ActionIcon, Button, Group, Modal, Text } from "@mantine/core";
 import { useClipboard, useDisclosure } from "@mantine/hooks";
-import { useQueryClient } from "@tanstack/react-query";
 import { format, isToday, isYesterday } from "date-fns";
 import { Copy, MessageSquare, Trash2 } from "lucide-react";
-import { useEffect } from "react";
 import {
 	useClearHistory,
 	useDeleteHistoryEntry,
 	useHistory,
+	useHistorySync,
 } from "../lib/queries";
-import { tauriAPI } from "../lib/tauri";
 
 function formatTime(timestamp: string): string {
 	return format(new Date(timestamp), "h:mm a");
@@ -48,7 +46,6 @@ function groupHistoryByDate(
 }
 
 export function HistoryFeed() {
-	const queryClient = useQueryClient();
 	const { data: history, isLoading, error } = useHistory(100);
 	const deleteEntry = useDeleteHistoryEntry();
 	const clearHistory = useClearHistory();
@@ -56,22 +53,8 @@ export function HistoryFeed() {
 	const [confirmOpened, { open: openConfirm, close: closeConfirm }] =
 		useDisclosure(false);
 
-	// Listen for history changes from other windows (e.g., overlay after transcription)
-	useEffect(() => {
-		let unlisten: (() => void) | undefined;
-
-		const setup = async () => {
-			unlisten = await tauriAPI.onHistoryChanged(() => {
-				queryClient.invalidateQueries({ queryKey: ["history"] });
-			});
-		};
-
-		setup();
-
-		return () => {
-			unlisten?.();
-		};
-	}, [queryClient]);
+	// Apply changes from any window (e.g. the overlay after a transcription)
+	useHistorySync();
 
 	const handleDelete = (id: string) => {
 		deleteEntry.mutate(id);
@@ -1,7 +1,7 @@
 import { Kbd } from "@mantine/core";
 import { useEffect } from "react";
 import { useRecordHotkeys } from "react-hotkeys-hook";
-import type { HotkeyConfig } from "../lib/tauri";
+import { type HotkeyConfig, hotkeyDisplayParts } from "../lib/tauri";
 
 interface HotkeyInputProps {
 	label: string;
@@ -291,8 +291,11 @@ export function HotkeyInput({
 					</div>
 				) : (
 					<>
-						{value.modifiers.concat([value.key]).map((part) => (
-							<Kbd key={part}>{formatKeyForDisplay(part)}</Kbd>
+						{hotkeyDisplayParts(value).map((part, index) => (
+							// biome-ignore lint/suspicious/noArrayIndexKey: a double-tapped modifier repeats
+							<Kbd key={`${part}-${index}`}>
+								{formatKeyForDisplay(part)}
+							</Kbd>
 						))}
 						<span className="hotkey-hint">Click to change</span>
 					</>
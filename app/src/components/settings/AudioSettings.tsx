@@ -1,4 +1,6 @@
-import { Switch, Tooltip } from "@mantine/core";
+import { Button, Switch, Tooltip } from "@mantine/core";
+import { useState } from "react";
+import { type MicTestResult, testMicrophone } from "../../lib/microphone";
 import {
 	useIsAudioMuteSupported,
 	useSettings,
@@ -21,11 +23,72 @@ export function AudioSettings() {
 		updateAutoMuteAudio.mutate(checked);
 	};
 
+	const [isTestingMic, setIsTestingMic] = useState(false);
+	const [micTestResult, setMicTestResult] = useState<MicTestResult | null>(
+		null,
+	);
+	const [micTestError, setMicTestError] = useState<string | null>(null);
+
+	const handleTestMicrophone = async () => {
+		setIsTestingMic(true);
+		setMicTestResult(null);
+		setMicTestError(null);
+		try {
+			setMicTestResult(
+				await testMicrophone(settings?.selected_mic_id ?? null),
+			);
+		} catch (error) {
+			console.error("Microphone test failed:", error);
+			setMicTestError(
+				"Could not open the selected microphone. Is it connected?",
+			);
+		} finally {
+			setIsTestingMic(false);
+		}
+	};
+
+	const micTestDescription = () => {
+		if (isTestingMic) return "Speak now...";
+		if (micTestError) return micTestError;
+		if (!micTestResult) {
+			return "Record a few seconds locally to check your mic picks up sound";
+		}
+		if (micTestResult.isSilent) {
+			return `No sound from ${micTestResult.deviceLabel || "the microphone"}. Check it isn't muted.`;
+		}
+		return `Peak ${Math.round(micTestResult.peak * 100)}%, average ${Math.round(micTestResult.rms * 100)}%`;
+	};
+
 	return (
 		<div className="settings-section animate-in animate-in-delay-2">
 			<h3 className="settings-section-title">Audio</h3>
 			<div className="settings-card">
 				<DeviceSelector />
+				<div className="settings-row" style={{ marginTop: 16 }}>
+					<div>
+						<p className="settings-label">Test microphone</p>
+						<p
+							className="settings-description"
+							style={
+								micTestError || micTestResult?.isSilent
+									? { color: "#ef4444" }
+									: undefined
+							}
+						>
+							{micTestDescription()}
+						</p>
+					</div>
+					<Button
+						onClick={handleTestMicrophone}
+						loading={isTestingMic}
+						disabled={isLoading}
+						color="gray"
+						variant="light"
+						size="sm"
+					>
+						Test
+					</Button>
+				</div>
 				<div className="settings-row" style={{ marginTop: 16 }}>
 					<div>
 						<p className="settings-label">Sound feedback</p>
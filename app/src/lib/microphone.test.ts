@@ -0,0 +1,24 @@
+import { describe, expect, it } from "vitest";
+import { measureLevels } from "./microphone";
+
+describe("measureLevels", () => {
+	it("reports silence for an empty clip", () => {
+		expect(measureLevels(new Float32Array())).toEqual({ peak: 0, rms: 0 });
+	});
+
+	it("measures peak and RMS", () => {
+		const levels = measureLevels(new Float32Array([0.5, -0.5, 0.5, -0.5]));
+		expect(levels.peak).toBe(0.5);
+		expect(levels.rms).toBeCloseTo(0.5);
+	});
+
+	it("uses the largest magnitude as the peak", () => {
+		const levels = measureLevels(new Float32Array([0.1, -0.8, 0.2]));
+		expect(levels.peak).toBeCloseTo(0.8);
+	});
+
+	it("clamps levels to full scale", () => {
+		const levels = measureLevels(new Float32Array([1.5, -1.5]));
+		expect(levels).toEqual({ peak: 1, rms: 1 });
+	});
+});
@@ -0,0 +1,110 @@
+// Local microphone check: records a short clip from the configured input device
+// and reports its levels. Nothing is sent to the server or added to history.
+
+export const DEFAULT_MIC_TEST_DURATION_MS = 3000;
+
+export const MAX_MIC_TEST_DURATION_MS = 10000;
+
+// Peaks below this (about -40 dBFS) mean the mic delivered no usable signal
+export const SILENT_PEAK_THRESHOLD = 0.01;
+
+export interface MicLevels {
+	// Fractions of full scale (0-1)
+	peak: number;
+	rms: number;
+}
+
+export interface MicTestResult extends MicLevels {
+	durationMs: number;
+	// Label of the device that was actually opened
+	deviceLabel: string;
+	// No usable signal: muted, disconnected or dead mic
+	isSilent: boolean;
+}
+
+export function measureLevels(samples: Float32Array): MicLevels {
+	if (samples.length === 0) {
+		return { peak: 0, rms: 0 };
+	}
+	let peak = 0;
+	let sumOfSquares = 0;
+	for (const sample of samples) {
+		peak = Math.max(peak, Math.abs(sample));
+		sumOfSquares += sample * sample;
+	}
+	return {
+		peak: Math.min(1, peak),
+		rms: Math.min(1, Math.sqrt(sumOfSquares / samples.length)),
+	};
+}
+
+// Record from deviceId (null = system default) for durationMs and measure the clip.
+// An exact device constraint makes a missing or unplugged selection fail instead of
+// silently falling back to another mic. With playback, the clip is played afterwards.
+export async function testMicrophone(
+	deviceId: string | null,
+	durationMs = DEFAULT_MIC_TEST_DURATION_MS,
+	playback = false,
+): Promise<MicTestResult> {
+	const duration = Math.min(
+		Math.max(durationMs, 100),
+		MAX_MIC_TEST_DURATION_MS,
+	);
+	const stream = await navigator.mediaDevices.getUserMedia({
+		audio: deviceId ? { deviceId: { exact: deviceId } } : true,
+	});
+	const track = stream.getAudioTracks()[0];
+	const audioContext = new AudioContext();
+
+	try {
+		const source = audioContext.createMediaStreamSource(stream);
+		const processor = audioContext.createScriptProcessor(4096, 1, 1);
+		const chunks: Float32Array[] = [];
+		processor.onaudioprocess = (event) => {
+			chunks.push(new Float32Array(event.inputBuffer.getChannelData(0)));
+		};
+		source.connect(processor);
+		processor.connect(audioContext.destination);
+
+		await new Promise((resolve) => setTimeout(resolve, duration));
+		processor.disconnect();
+		source.disconnect();
+
+		const length = chunks.reduce((total, chunk) => total + chunk.length, 0);
+		const samples = new Float32Array(length);
+		let offset = 0;
+		for (const chunk of chunks) {
+			samples.set(chunk, offset);
+			offset += chunk.length;
+		}
+		const levels = measureLevels(samples);
+
+		if (playback && samples.length > 0) {
+			const buffer = audioContext.createBuffer(
+				1,
+				samples.length,
+				audioContext.sampleRate,
+			);
+			buffer.copyToChannel(samples, 0);
+			const player = audioContext.createBufferSource();
+			player.buffer = buffer;
+			player.connect(audioContext.destination);
+			await new Promise((resolve) => {
+				player.onended = resolve;
+				player.start();
+			});
+		}
+
+		return {
+			...levels,
+			durationMs: duration,
+			deviceLabel: track?.label ?? "",
+			isSilent: levels.peak < SILENT_PEAK_THRESHOLD,
+		};
+	} finally {
+		for (const streamTrack of stream.getTracks()) {
+			streamTrack.stop();
+		}
+		audioContext.close().catch(() => {});
+	}
+}
@@ -2,10 +2,16 @@ import { useMutation, useQuery, useQueryClient } from "@tanstack/react-query";
 import { invoke } from "@tauri-apps/api/core";
 import { useEffect, useRef } from "react";
 import {
+	applyHistoryChange,
 	type AvailableProvidersData,
 	type CleanupPromptSections,
 	configAPI,
+	enabledHotkeyBindings,
+	type HistoryEntry,
 	type HotkeyConfig,
+	type HotkeyType,
+	hotkeyRegistrationError,
+	type PasteSlot,
 	tauriAPI,
 	validateHotkeyNotDuplicate,
 } from "./tauri";
@@ -79,18 +85,19 @@ export function useUpdateToggleHotkey() {
 			// Validate no duplicate
 			const error = validateHotkeyNotDuplicate(
 				hotkey,
-				{
-					toggle: settings.toggle_hotkey,
-					hold: settings.hold_hotkey,
-					paste_last: settings.paste_last_hotkey,
-				},
+				enabledHotkeyBindings(settings),
 				"toggle",
 			);
 			if (error) throw new Error(error);
 
 			// Save and re-register
 			await tauriAPI.updateToggleHotkey(hotkey);
-			await tauriAPI.registerShortcuts();
+			const registrations = await tauriAPI.registerShortcuts();
+			const registrationError = hotkeyRegistrationError(
+				registrations,
+				"toggle",
+			);
+			if (registrationError) throw new Error(registrationError);
 		},
 		onSuccess: () => {
 			queryClient.invalidateQueries({ queryKey: ["settings"] });
@@ -108,18 +115,19 @@ export function useUpdateHoldHotkey() {
 			// Validate no duplicate
 			const error = validateHotkeyNotDuplicate(
 				hotkey,
-				{
-					toggle: settings.toggle_hotkey,
-					hold: settings.hold_hotkey,
-					paste_last: settings.paste_last_hotkey,
-				},
+				enabledHotkeyBindings(settings),
 				"hold",
 			);
 			if (error) throw new Error(error);
 
 			// Save and re-register
 			await tauriAPI.updateHoldHotkey(hotkey);
-			await tauriAPI.registerShortcuts();
+			const registrations = await tauriAPI.registerShortcuts();
+			const registrationError = hotkeyRegistrationError(
+				registrations,
+				"hold",
+			);
+			if (registrationError) throw new Error(registrationError);
 		},
 		onSuccess: () => {
 			queryClient.invalidateQueries({ queryKey: ["settings"] });
@@ -137,18 +145,120 @@ export function useUpdatePasteLastHotkey() {
 			// Validate no duplicate
 			const error = validateHotkeyNotDuplicate(
 				hotkey,
-				{
-					toggle: settings.toggle_hotkey,
-					hold: settings.hold_hotkey,
-					paste_last: settings.paste_last_hotkey,
-				},
+				enabledHotkeyBindings(settings),
 				"paste_last",
 			);
 			if (error) throw new Error(error);
 
 			// Save and re-register
 			await tauriAPI.updatePasteLastHotkey(hotkey);
-			await tauriAPI.registerShortcuts();
+			const registrations = await tauriAPI.registerShortcuts();
+			const registrationError = hotkeyRegistrationError(
+				registrations,
+				"paste_last",
+			);
+			if (registrationError) throw new Error(registrationError);
+		},
+		onSuccess: () => {
+			queryClient.invalidateQueries({ queryKey: ["settings"] });
+		},
+	});
+}
+
+export function useUpdateShowWindowHotkey() {
+	const queryClient = useQueryClient();
+	return useMutation({
+		mutationFn: async (hotkey: HotkeyConfig | null) => {
+			if (hotkey) {
+				// Get current settings for validation
+				const settings = await tauriAPI.getSettings();
+
+				// Validate no duplicate
+				const error = validateHotkeyNotDuplicate(
+					hotkey,
+					enabledHotkeyBindings(settings),
+					"show_window",
+				);
+				if (error) throw new Error(error);
+			}
+
+			// Save and re-register
+			await tauriAPI.updateShowWindowHotkey(hotkey);
+			const registrations = await tauriAPI.registerShortcuts();
+			const registrationError = hotkeyRegistrationError(
+				registrations,
+				"show_window",
+			);
+			if (registrationError) throw new Error(registrationError);
+		},
+		onSuccess: () => {
+			queryClient.invalidateQueries({ queryKey: ["settings"] });
+		},
+	});
+}
+
+export function useUpdatePasteSlotHotkey(slot: PasteSlot) {
+	const queryClient = useQueryClient();
+	const hotkeyType = slot === 2 ? "paste_slot_2" : "paste_slot_3";
+	return useMutation({
+		mutationFn: async (hotkey: HotkeyConfig | null) => {
+			if (hotkey) {
+				// Get current settings for validation
+				const settings = await tauriAPI.getSettings();
+
+				// Validate no duplicate
+				const error = validateHotkeyNotDuplicate(
+					hotkey,
+					enabledHotkeyBindings(settings),
+					hotkeyType,
+				);
+				if (error) throw new Error(error);
+			}
+
+			// Save and re-register
+			await tauriAPI.updatePasteSlotHotkey(slot, hotkey);
+			const registrations = await tauriAPI.registerShortcuts();
+			const registrationError = hotkeyRegistrationError(
+				registrations,
+				hotkeyType,
+			);
+			if (registrationError) throw new Error(registrationError);
+		},
+		onSuccess: () => {
+			queryClient.invalidateQueries({ queryKey: ["settings"] });
+		},
+	});
+}
+
+export function useUpdateHotkeyEnabled() {
+	const queryClient = useQueryClient();
+	return useMutation({
+		mutationFn: async ({
+			type,
+			enabled,
+		}: {
+			type: HotkeyType;
+			enabled: boolean;
+		}) => {
+			if (enabled) {
+				// Re-enabling must not collide with a combo reused while it was disabled
+				const settings = await tauriAPI.getSettings();
+				const hotkey = settings[`${type}_hotkey`];
+				if (hotkey) {
+					const error = validateHotkeyNotDuplicate(
+						hotkey,
+						enabledHotkeyBindings(settings),
+						type,
+					);
+					if (error) throw new Error(error);
+				}
+			}
+
+			// Save and re-register (disabled hotkeys are skipped)
+			await tauriAPI.updateHotkeyEnabled(type, enabled);
+			const registrations = await tauriAPI.registerShortcuts();
+			const registrationError = hotkeyRegistrationError(registrations, type);
+			if (registrationError) throw new Error(registrationError);
 		},
 		onSuccess: () => {
 			queryClient.invalidateQueries({ queryKey: ["settings"] });
@@ -229,39 +339,77 @@ export function useHistory(limit?: number) {
 	});
 }
 
-export function useAddHistoryEntry() {
+// Keep cached history lists in step with "history-changed" events, updating them
+// in place where possible instead of refetching
+export function useHistorySync() {
 	const queryClient = useQueryClient();
+	useEffect(() => {
+		let unlisten: (() => void) | undefined;
+		let cancelled = false;
+
+		tauriAPI
+			.onHistoryChanged((change) => {
+				for (const [queryKey, entries] of queryClient.getQueriesData<
+					HistoryEntry[]
+				>({ queryKey: ["history"] })) {
+					const limit = queryKey[1] as number | undefined;
+					const updated = entries
+						? applyHistoryChange(entries, change, limit)
+						: null;
+					if (updated) {
+						queryClient.setQueryData(queryKey, updated);
+					} else {
+						queryClient.invalidateQueries({ queryKey, exact: true });
+					}
+				}
+			})
+			.then((unlistenFn) => {
+				if (cancelled) {
+					unlistenFn();
+				} else {
+					unlisten = unlistenFn;
+				}
+			});
+
+		return () => {
+			cancelled = true;
+			unlisten?.();
+		};
+	}, [queryClient]);
+}
+
+// History mutations need no invalidation: Rust emits "history-changed" to every
+// window, and useHistorySync applies it to the cached lists
+export function useAddHistoryEntry() {
 	return useMutation({
-		mutationFn: (text: string) => tauriAPI.addHistoryEntry(text),
-		onSuccess: () => {
-			queryClient.invalidateQueries({ queryKey: ["history"] });
-			// Notify other windows about history change
-			tauriAPI.emitHistoryChanged();
-		},
+		mutationFn: ({
+			text,
+			language,
+			audioDurationMs,
+		}: {
+			text: string;
+			language?: string | null;
+			audioDurationMs?: number | null;
+		}) => tauriAPI.addHistoryEntry(text, language, audioDurationMs),
 	});
 }
 
 export function useDeleteHistoryEntry() {
-	const queryClient = useQueryClient();
 	return useMutation({
 		mutationFn: (id: string) => tauriAPI.deleteHistoryEntry(id),
-		onSuccess: () => {
-			queryClient.invalidateQueries({ queryKey: ["history"] });
-			// Notify other windows about history change
-			tauriAPI.emitHistoryChanged();
-		},
+	});
+}
+
+export function useUpdateHistoryEntry() {
+	return useMutation({
+		mutationFn: ({ id, newText }: { id: string; newText: string }) =>
+			tauriAPI.updateHistoryEntry(id, newText),
 	});
 }
 
 export function useClearHistory() {
-	const queryClient = useQueryClient();
 	return useMutation({
 		mutationFn: () => tauriAPI.clearHistory(),
-		onSuccess: () => {
-			queryClient.invalidateQueries({ queryKey: ["history"] });
-			// Notify other windows about history change
-			tauriAPI.emitHistoryChanged();
-		},
 	});
 }
 
@@ -308,6 +456,24 @@ export function useAvailableProvidersListener() {
 	}, [queryClient]);
 }
 
+/**
+ * Refetch settings (and everything derived from them) after reset_settings.
+ * Call from a component that stays mounted.
+ */
+export function useSettingsResetListener() {
+	const queryClient = useQueryClient();
+
+	useEffect(() => {
+		const unlistenPromise = tauriAPI.onSettingsReset(() => {
+			queryClient.invalidateQueries();
+		});
+
+		return () => {
+			unlistenPromise.then((unlisten) => unlisten());
+		};
+	}, [queryClient]);
+}
+
 /**
  * Hook to read available providers from the cache.
  * The data is populated by useAvailableProvidersListener which should be
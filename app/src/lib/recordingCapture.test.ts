@@ -0,0 +1,38 @@
+import { describe, expect, it } from "vitest";
+import { concatPcm16, interleavePcm16 } from "./recordingCapture";
+
+describe("interleavePcm16", () => {
+	it("converts mono samples to 16-bit PCM", () => {
+		const pcm = interleavePcm16([new Float32Array([0, 1, -1, 0.5])], 1);
+		expect(Array.from(pcm)).toEqual([0, 32767, -32767, 16384]);
+	});
+
+	it("interleaves channels frame by frame", () => {
+		const pcm = interleavePcm16(
+			[new Float32Array([1, 0]), new Float32Array([-1, 0.5])],
+			2,
+		);
+		expect(Array.from(pcm)).toEqual([32767, -32767, 0, 16384]);
+	});
+
+	it("clamps samples beyond full scale", () => {
+		const pcm = interleavePcm16([new Float32Array([2, -2])], 1);
+		expect(Array.from(pcm)).toEqual([32767, -32767]);
+	});
+
+	it("fills missing channels with silence", () => {
+		const pcm = interleavePcm16([new Float32Array([1, 1])], 2);
+		expect(Array.from(pcm)).toEqual([32767, 0, 32767, 0]);
+	});
+});
+
+describe("concatPcm16", () => {
+	it("joins chunks in order", () => {
+		const joined = concatPcm16([
+			new Int16Array([1, 2]),
+			new Int16Array([]),
+			new Int16Array([3]),
+		]);
+		expect(Array.from(joined)).toEqual([1, 2, 3]);
+	});
+});
@@ -0,0 +1,45 @@
+// Recording capture for save_recordings: an AudioWorklet (recordingCapture.worklet.ts)
+// converts the mic track to interleaved 16-bit PCM off the main thread, and the
+// result is sent to Rust as raw bytes.
+
+// Name the worklet processor is registered under
+export const RECORDING_CAPTURE_PROCESSOR = "recording-capture";
+
+// Frames the worklet collects before posting them to the main thread
+export const RECORDING_CAPTURE_BATCH_FRAMES = 4096;
+
+// Messages posted by the worklet. "flushed" answers a "flush" request, after the
+// last samples were posted.
+export type RecordingCaptureMessage =
+	| { type: "samples"; samples: Int16Array }
+	| { type: "flushed" };
+
+// Interleave one block of channel data frame by frame and convert it to 16-bit PCM.
+// Channels missing from `channelData` are filled with silence.
+export function interleavePcm16(
+	channelData: Float32Array[],
+	channels: number,
+): Int16Array {
+	const frames = channelData[0]?.length ?? 0;
+	const interleaved = new Int16Array(frames * channels);
+	for (let frame = 0; frame < frames; frame++) {
+		for (let channel = 0; channel < channels; channel++) {
+			const sample = channelData[channel]?.[frame] ?? 0;
+			const clamped = Math.max(-1, Math.min(1, sample));
+			interleaved[frame * channels + channel] = Math.round(clamped * 0x7fff);
+		}
+	}
+	return interleaved;
+}
+
+// Join PCM chunks into one buffer
+export function concatPcm16(chunks: Int16Array[]): Int16Array {
+	const length = chunks.reduce((total, chunk) => total + chunk.length, 0);
+	const joined = new Int16Array(length);
+	let offset = 0;
+	for (const chunk of chunks) {
+		joined.set(chunk, offset);
+		offset += chunk.length;
+	}
+	return joined;
+}
@@ -0,0 +1,70 @@
+// AudioWorklet processor capturing the mic for saved recordings. Loaded with
+// audioWorklet.addModule, so it runs on the audio rendering thread.
+
+import {
+	concatPcm16,
+	interleavePcm16,
+	RECORDING_CAPTURE_BATCH_FRAMES,
+	RECORDING_CAPTURE_PROCESSOR,
+	type RecordingCaptureMessage,
+} from "./recordingCapture";
+
+// AudioWorkletGlobalScope isn't part of the DOM lib
+declare abstract class AudioWorkletProcessor {
+	readonly port: MessagePort;
+	constructor(options?: AudioWorkletNodeOptions);
+}
+declare function registerProcessor(
+	name: string,
+	processorCtor: new (
+		options: AudioWorkletNodeOptions,
+	) => AudioWorkletProcessor,
+): void;
+
+class RecordingCaptureProcessor extends AudioWorkletProcessor {
+	private readonly channels: number;
+	private pending: Int16Array[] = [];
+	private pendingFrames = 0;
+
+	constructor(options: AudioWorkletNodeOptions) {
+		super(options);
+		this.channels = options.processorOptions?.channels ?? 1;
+		this.port.onmessage = (event: MessageEvent<string>) => {
+			if (event.data === "flush") {
+				this.flush();
+				this.post({ type: "flushed" });
+			}
+		};
+	}
+
+	process(inputs: Float32Array[][]): boolean {
+		const input = inputs[0];
+		if (input && input.length > 0) {
+			const block = interleavePcm16(input, this.channels);
+			this.pending.push(block);
+			this.pendingFrames += block.length / this.channels;
+			if (this.pendingFrames >= RECORDING_CAPTURE_BATCH_FRAMES) {
+				this.flush();
+			}
+		}
+		return true;
+	}
+
+	// Post the collected samples, transferring the buffer instead of copying it
+	private flush() {
+		if (this.pending.length === 0) return;
+		const samples = concatPcm16(this.pending);
+		this.pending = [];
+		this.pendingFrames = 0;
+		this.post({ type: "samples", samples }, [samples.buffer]);
+	}
+
+	private post(
+		message: RecordingCaptureMessage,
+		transfer: Transferable[] = [],
+	) {
+		this.port.postMessage(message, transfer);
+	}
+}
+
+registerProcessor(RECORDING_CAPTURE_PROCESSOR, RecordingCaptureProcessor);
@@ -1,9 +1,14 @@
 import { describe, expect, it } from "vitest";
 import {
+	applyHistoryChange,
 	createHotkeyDuplicateSchema,
+	type HistoryEntry,
 	type HotkeyConfig,
 	HotkeyConfigSchema,
+	hotkeyDisplayParts,
 	hotkeyIsSameAs,
+	hotkeyRegistrationError,
+	isLowConfidence,
 	validateHotkeyNotDuplicate,
 } from "./tauri";
 
@@ -24,6 +29,24 @@ describe("HotkeyConfigSchema", () => {
 		expect(result.success).toBe(false);
 	});
 
+	it("accepts an empty key for modifier-only bindings", () => {
+		const result = HotkeyConfigSchema.safeParse({
+			modifiers: ["super"],
+			key: "",
+			modifier_taps: 2,
+		});
+		expect(result.success).toBe(true);
+	});
+
+	it("rejects an empty key without modifier taps", () => {
+		const result = HotkeyConfigSchema.safeParse({
+			modifiers: ["super"],
+			key: "",
+			modifier_taps: 0,
+		});
+		expect(result.success).toBe(false);
+	});
+
 	it("accepts empty modifiers", () => {
 		const result = HotkeyConfigSchema.safeParse({
 			modifiers: [],
@@ -41,6 +64,20 @@ describe("HotkeyConfigSchema", () => {
 	});
 });
 
+describe("hotkeyDisplayParts", () => {
+	it("lists modifiers then the key for combos", () => {
+		expect(
+			hotkeyDisplayParts({ modifiers: ["ctrl", "alt"], key: "Space" }),
+		).toEqual(["ctrl", "alt", "Space"]);
+	});
+
+	it("repeats the modifier for each tap of a modifier-only binding", () => {
+		expect(
+			hotkeyDisplayParts({ modifiers: ["super"], key: "", modifier_taps: 2 }),
+		).toEqual(["super", "super"]);
+	});
+});
+
 describe("hotkeyIsSameAs", () => {
 	it("returns true for identical hotkeys", () => {
 		const a: HotkeyConfig = { modifiers: ["ctrl", "alt"], key: "Space" };
@@ -179,4 +216,129 @@ describe("validateHotkeyNotDuplicate", () => {
 		);
 		expect(result).toBe("This shortcut is already used for the hold hotkey");
 	});
+
+	it("skips an unset show window hotkey", () => {
+		const result = validateHotkeyNotDuplicate(
+			{ modifiers: ["ctrl", "shift"], key: "W" },
+			{ ...allHotkeys, show_window: null },
+			"toggle",
+		);
+		expect(result).toBeNull();
+	});
+
+	it("detects a duplicate of the show window hotkey", () => {
+		const result = validateHotkeyNotDuplicate(
+			{ modifiers: ["ctrl", "shift"], key: "W" },
+			{ ...allHotkeys, show_window: { modifiers: ["ctrl", "shift"], key: "W" } },
+			"toggle",
+		);
+		expect(result).toBe(
+			"This shortcut is already used for the show window hotkey",
+		);
+	});
+});
+
+describe("hotkeyRegistrationError", () => {
+	const registrations = [
+		{
+			action: "toggle" as const,
+			shortcut: "ctrl+alt+Space",
+			registered: true,
+			error: null,
+		},
+		{
+			action: "hold" as const,
+			shortcut: "ctrl+alt+Backquote",
+			registered: false,
+			error: "HotKey already registered",
+		},
+	];
+
+	it("returns null when the hotkey registered", () => {
+		expect(hotkeyRegistrationError(registrations, "toggle")).toBeNull();
+	});
+
+	it("returns null when the hotkey is missing from the results", () => {
+		expect(hotkeyRegistrationError(registrations, "paste_last")).toBeNull();
+	});
+
+	it("names the conflicting shortcut when registration failed", () => {
+		expect(hotkeyRegistrationError(registrations, "hold")).toBe(
+			"ctrl+alt+Backquote for the hold hotkey could not be registered; it may be in use by another application",
+		);
+	});
+});
+
+describe("isLowConfidence", () => {
+	it("holds back transcriptions below the threshold", () => {
+		expect(isLowConfidence(0.4, 0.6)).toBe(true);
+	});
+
+	it("inserts transcriptions at or above the threshold", () => {
+		expect(isLowConfidence(0.6, 0.6)).toBe(false);
+		expect(isLowConfidence(0.9, 0.6)).toBe(false);
+	});
+
+	it("inserts when the server reports no confidence", () => {
+		expect(isLowConfidence(null, 0.6)).toBe(false);
+	});
+});
+
+function historyEntry(id: string, text = id): HistoryEntry {
+	return {
+		id,
+		timestamp: "2024-01-01T00:00:00Z",
+		text,
+		pinned: false,
+		language: null,
+		char_count: text.length,
+		word_count: 1,
+		audio_duration_ms: null,
+		edited_at: null,
+		tags: [],
+		appended_at: null,
+		incognito: false,
+	};
+}
+
+describe("applyHistoryChange", () => {
+	const entries = [historyEntry("b"), historyEntry("a")];
+
+	it("puts added entries first and keeps the limit", () => {
+		const change = { kind: "added", entry: historyEntry("c") } as const;
+		expect(applyHistoryChange(entries, change)?.map((e) => e.id)).toEqual([
+			"c",
+			"b",
+			"a",
+		]);
+		expect(applyHistoryChange(entries, change, 2)?.map((e) => e.id)).toEqual(
+			["c", "b"],
+		);
+	});
+
+	it("replaces updated entries in place", () => {
+		const updated = applyHistoryChange(entries, {
+			kind: "updated",
+			entry: historyEntry("a", "edited"),
+		});
+		expect(updated?.map((e) => e.text)).toEqual(["b", "edited"]);
+	});
+
+	it("removes deleted entries", () => {
+		expect(
+			applyHistoryChange(entries, { kind: "deleted", id: "b" })?.map(
+				(e) => e.id,
+			),
+		).toEqual(["a"]);
+	});
+
+	it("refetches a full list after a delete", () => {
+		expect(applyHistoryChange(entries, { kind: "deleted", id: "b" }, 2)).toBe(
+			null,
+		);
+	});
+
+	it("refetches after a reset", () => {
+		expect(applyHistoryChange(entries, { kind: "reset" })).toBe(null);
+	});
 });
@@ -1,6 +1,5 @@
 import { invoke } from "@tauri-apps/api/core";
 import { emit, listen, type UnlistenFn } from "@tauri-apps/api/event";
-import { getCurrentWindow } from "@tauri-apps/api/window";
 import { Store } from "@tauri-apps/plugin-store";
 import ky from "ky";
 import { z } from "zod";
@@ -26,19 +25,189 @@ interface TypeTextResult {
 
 export interface HotkeyConfig {
 	modifiers: string[];
+	// Empty for modifier-only bindings (e.g. double-tap Command)
 	key: string;
+	// Tap count for modifier-only bindings, omitted for modifier+key combos
+	modifier_taps?: number;
 }
 
-// Zod schema for HotkeyConfig validation
-export const HotkeyConfigSchema = z.object({
-	modifiers: z.array(z.string()),
-	key: z.string().min(1, "Key is required"),
-});
+// Zod schema for HotkeyConfig validation. The key may only be empty for
+// modifier-only bindings, which load and display but are rejected by Rust when
+// registered (global shortcuts need a main key).
+export const HotkeyConfigSchema = z
+	.object({
+		modifiers: z.array(z.string()),
+		key: z.string(),
+		modifier_taps: z.number().int().nonnegative().optional(),
+	})
+	.refine(
+		(hotkey) => hotkey.key.length > 0 || (hotkey.modifier_taps ?? 0) > 0,
+		{ message: "Key is required", path: ["key"] },
+	);
+
+// Keys to show for a hotkey: a modifier-only binding repeats its modifier once
+// per tap (double-tap Command shows as two Command keys)
+export function hotkeyDisplayParts(hotkey: HotkeyConfig): string[] {
+	if (hotkey.key === "" && (hotkey.modifier_taps ?? 0) > 0) {
+		const modifier = hotkey.modifiers[0] ?? "";
+		return Array.from({ length: hotkey.modifier_taps ?? 0 }, () => modifier);
+	}
+	return [...hotkey.modifiers, hotkey.key];
+}
 
-interface HistoryEntry {
+export interface HistoryEntry {
 	id: string;
 	timestamp: string;
 	text: string;
+	pinned: boolean;
+	language: string | null;
+	char_count: number;
+	word_count: number;
+	audio_duration_ms: number | null;
+	edited_at: string | null;
+	tags: string[];
+	// When a chunk was last appended in append mode
+	appended_at: string | null;
+	// Added in incognito mode: gone after quitting, never written to disk
+	incognito: boolean;
+}
+
+// Payload of "history-changed", emitted by Rust after every change to the history
+export type HistoryChange =
+	| { kind: "added"; entry: HistoryEntry }
+	| { kind: "updated"; entry: HistoryEntry }
+	| { kind: "deleted"; id: string }
+	// Many entries changed (clear, import, prune, unlock, new location)
+	| { kind: "reset" };
+
+// Where the key for an encrypted history comes from
+export type HistoryKeySource = "keychain" | "passphrase";
+
+export interface HistoryEncryptionStatus {
+	// null for a plaintext history
+	key_source: HistoryKeySource | null;
+	// The key is unavailable, so history can't be read or written until unlocked
+	locked: boolean;
+}
+
+export interface HistoryStats {
+	total_entries: number;
+	total_words: number;
+	total_chars: number;
+	total_audio_duration_ms: number;
+	average_words: number;
+	average_chars: number;
+}
+
+export type OverlayAnchor =
+	| "top_left"
+	| "top_right"
+	| "bottom_left"
+	| "bottom_right"
+	| "center";
+
+export type OverlayVisibility = "always" | "when_active" | "never";
+
+export type OverlayTheme = "light" | "dark" | "auto";
+
+// Overlay size preset; the idle overlay is 36, 48 or 72px
+export type OverlaySize = "small" | "medium" | "large";
+
+// Overlay look with "auto" already resolved to the OS theme
+export interface OverlayAppearance {
+	opacity: number;
+	theme: Exclude<OverlayTheme, "auto">;
+	size: OverlaySize;
+	// Zoom applied to the overlay's content, from size
+	scale: number;
+}
+
+export type TranscriptionErrorKind = "network" | "timeout" | "server" | "parse";
+
+export interface TranscriptionError {
+	kind: TranscriptionErrorKind;
+	message: string;
+	// Whether the overlay will retry (e.g. reconnect to the server)
+	will_retry: boolean;
+}
+
+// Credentials sent with every request to the server (excluded from settings exports)
+export interface ServerAuth {
+	api_key: string | null;
+	// Header carrying the API key; null means "Authorization", where a bare key is sent as "Bearer <key>"
+	auth_header: string | null;
+	headers: Record<string, string>;
+}
+
+// Each transcription added to history is POSTed to url as
+// { text, timestamp, duration_ms, language }, except in incognito mode
+export interface TranscriptionWebhook {
+	url: string | null;
+	// Sent in the X-Webhook-Secret header when set
+	secret: string | null;
+}
+
+export interface LowConfidenceTranscription {
+	text: string;
+	confidence: number;
+}
+
+// A connected monitor; bounds are in logical pixels
+export interface MonitorInfo {
+	id: string;
+	name: string | null;
+	x: number;
+	y: number;
+	width: number;
+	height: number;
+	scale_factor: number;
+	is_primary: boolean;
+}
+
+// Peak and RMS as fractions of full scale (0-1)
+export interface InputLevels {
+	peak: number;
+	rms: number;
+}
+
+export interface InputLevelPreview {
+	input: InputLevels;
+	processed: InputLevels;
+	// The gain pushed the signal to full scale
+	clipped: boolean;
+}
+
+// "auto" pastes long text and types short snippets
+export type InsertionMethod = "type" | "paste" | "auto";
+
+// An application that can be chosen as target_app; id is a bundle ID (macOS),
+// window ID (Linux) or process ID (Windows)
+export interface RunningApp {
+	id: string;
+	name: string;
+	title: string | null;
+}
+
+export type PermissionState =
+	| "granted"
+	| "denied"
+	| "not_determined"
+	| "unknown"
+	| "not_applicable";
+
+export type Permission = "accessibility" | "microphone";
+
+// Best-effort per platform; only "denied"/"not_determined" mean recording or typing may fail
+export interface PermissionStatus {
+	accessibility: PermissionState;
+	microphone: PermissionState;
+}
+
+interface SavedRecording {
+	file_name: string;
+	path: string;
+	created_at: string;
+	size_bytes: number;
 }
 
 export interface PromptSection {
@@ -56,16 +225,210 @@ export interface AppSettings {
 	toggle_hotkey: HotkeyConfig;
 	hold_hotkey: HotkeyConfig;
 	paste_last_hotkey: HotkeyConfig;
+	// Shows/hides the main window; null until configured
+	show_window_hotkey: HotkeyConfig | null;
+	// Paste the 2nd/3rd history entry; null until configured
+	paste_slot_2_hotkey: HotkeyConfig | null;
+	paste_slot_3_hotkey: HotkeyConfig | null;
+	paste_slot_source: PasteSlotSource;
+	// Disabled hotkeys are not registered at all, freeing their combos for other apps
+	hotkey_enabled: Record<HotkeyType, boolean>;
+	cancel_hotkey: HotkeyConfig;
 	selected_mic_id: string | null;
 	sound_enabled: boolean;
+	// Play the stop sound even when nothing was transcribed
+	play_sound_on_empty: boolean;
+	// Transcriptions the server scores below this (0.0-1.0) are held back instead of inserted
+	min_confidence: number;
+	sound_volume: number;
+	custom_start_sound_path: string | null;
+	custom_stop_sound_path: string | null;
+	// Played after a transcription is inserted / when one fails (built-in tones when null)
+	custom_complete_sound_path: string | null;
+	custom_error_sound_path: string | null;
 	cleanup_prompt_sections: CleanupPromptSections | null;
 	stt_provider: string | null;
 	llm_provider: string | null;
 	auto_mute_audio: boolean;
+	mute_exclusions: string[];
+	mute_mode: MuteMode;
+	duck_level: number;
 	stt_timeout_seconds: number | null;
 	server_url: string;
+	language: string;
+	save_recordings: boolean;
+	// Format saved recordings are resampled/downmixed to (8000-48000 Hz, 1-2 channels)
+	sample_rate: number;
+	channels: number;
+	// Linear multiplier for mic audio (0-8). High gain amplifies noise and clips
+	// speech, which hurts transcription more than a quiet mic does.
+	input_gain: number;
+	// RMS level (0-1 of full scale) below which mic audio is silenced; 0 turns the gate off
+	noise_gate_threshold: number;
+	history_max_entries: number;
+	history_max_age_days: number;
+	// Directory for history.json instead of the app data directory; null = default
+	history_storage_path: string | null;
+	vad_enabled: boolean;
+	streaming_enabled: boolean;
+	silence_timeout_ms: number;
+	auto_capitalize_first: boolean;
+	append_trailing_space: boolean;
+	// Run the whole pipeline (including history) but emit dry-run-result instead of inserting
+	dry_run: boolean;
+	// Extend the latest history entry with transcriptions completing within append_window_ms
+	append_mode: boolean;
+	append_window_ms: number;
+	// Prefix each dictation with the current time, rendered with timestamp_format
+	prepend_timestamp: boolean;
+	// strftime format, e.g. "[%H:%M] "
+	timestamp_format: string;
+	// Also keep the time prefix in the history entry
+	timestamp_in_history: boolean;
+	// App (name, ID or window title) focused before inserting; null inserts into the current focus
+	target_app: string | null;
+	// Pause after each typed character when typing (0 = fastest, higher = more reliable)
+	type_delay_ms: number;
+	// Deadline for each connection attempt and for each transcription after recording stops
+	request_timeout_ms: number;
+	voice_commands_enabled: boolean;
+	hold_start_delay_ms: number;
+	hold_stop_grace_ms: number;
+	min_recording_ms: number;
+	// Toggle releases this soon after the press are treated as keyboard bounce and ignored
+	toggle_debounce_ms: number;
+	overlay_visibility: OverlayVisibility;
+	overlay_opacity: number;
+	overlay_theme: OverlayTheme;
+	overlay_size: OverlaySize;
+	// Monitor ID from listMonitors; null follows the main window
+	overlay_monitor: string | null;
+	main_window_always_on_top: boolean;
+	// Closing the main window hides it to the tray instead of quitting
+	close_to_tray: boolean;
+	// Local HTTP control API on 127.0.0.1 (see getControlServer)
+	control_server_enabled: boolean;
+	control_server_port: number;
+}
+
+export type MuteMode = "mute" | "duck";
+
+export interface ControlServerStatus {
+	enabled: boolean;
+	port: number;
+	// Port it is listening on; null when stopped (or the port was unavailable)
+	listening_port: number | null;
+	// Sent as "Authorization: Bearer <token>"; created when first enabled
+	token: string | null;
+}
+
+// Whether paste slots count from the newest entry or from pinned entries first
+export type PasteSlotSource = "recent" | "pinned";
+
+export type PasteSlot = 2 | 3;
+
+export type LogLevel = "trace" | "debug" | "info" | "warn" | "error";
+
+export type SoundKind = "start" | "stop" | "complete" | "error";
+
+export type ReplacementKind = "plain" | "regex";
+
+export interface ReplacementRule {
+	find: string;
+	replace: string;
+	kind: ReplacementKind;
+}
+
+export type VoiceAction =
+	| { type: "new_line" }
+	| { type: "new_paragraph" }
+	| { type: "tab" }
+	| { type: "backspace"; count: number }
+	| { type: "delete_last" }
+	| { type: "text"; text: string };
+
+export interface VoiceCommand {
+	phrase: string;
+	action: VoiceAction;
+}
+
+export type RecordingSource = "toggle" | "hold";
+
+export interface RecordingState {
+	is_recording: boolean;
+	source: RecordingSource | null;
+	started_at_ms: number | null;
+}
+
+export type HistoryExportFormat = "json" | "text";
+
+export interface HistoryImportResult {
+	imported: number;
+	skipped: number;
+}
+
+export interface ProfileSummary {
+	name: string;
+	is_active: boolean;
+}
+
+export interface RejectedSetting {
+	key: string;
+	reason: string;
+}
+
+export interface SettingsImportResult {
+	applied: string[];
+	rejected: RejectedSetting[];
 }
 
+export const DEFAULT_DUCK_LEVEL = 20;
+
+export const DEFAULT_SILENCE_TIMEOUT_MS = 1500;
+
+export const DEFAULT_HISTORY_MAX_ENTRIES = 1000;
+
+export const DEFAULT_HOLD_START_DELAY_MS = 100;
+
+export const MAX_HOLD_START_DELAY_MS = 1000;
+
+export const DEFAULT_HOLD_STOP_GRACE_MS = 300;
+
+export const DEFAULT_MIN_RECORDING_MS = 300;
+
+export const DEFAULT_TOGGLE_DEBOUNCE_MS = 50;
+
+export const DEFAULT_OVERLAY_OPACITY = 1.0;
+
+export const DEFAULT_TYPE_DELAY_MS = 0;
+
+export const DEFAULT_CONTROL_SERVER_PORT = 8766;
+
+// How long each server request (and the wait for a transcription) may take
+export const DEFAULT_REQUEST_TIMEOUT_MS = 30000;
+
+export const MIN_REQUEST_TIMEOUT_MS = 1000;
+
+// 0 inserts every transcription regardless of confidence
+export const DEFAULT_MIN_CONFIDENCE = 0;
+
+// 16kHz mono is what most speech models expect
+export const DEFAULT_SAMPLE_RATE = 16000;
+
+export const DEFAULT_CHANNELS = 1;
+
+export const DEFAULT_INPUT_GAIN = 1.0;
+
+export const MAX_INPUT_GAIN = 8.0;
+
+// 0 disables the noise gate
+export const DEFAULT_NOISE_GATE_THRESHOLD = 0;
+
+export const DEFAULT_APPEND_WINDOW_MS = 10000;
+
+// Time prefix used when prepend_timestamp is on (strftime format)
+export const DEFAULT_TIMESTAMP_FORMAT = "[%H:%M] ";
+
 export const DEFAULT_SERVER_URL = "http://127.0.0.1:8765";
 
 // ============================================================================
@@ -89,6 +452,12 @@ export const defaultPasteLastHotkey: HotkeyConfig = {
 	key: "Period",
 };
 
+// Only registered while recording, so Escape isn't captured globally otherwise
+export const defaultCancelHotkey: HotkeyConfig = {
+	modifiers: [],
+	key: "Escape",
+};
+
 // ============================================================================
 // Store helpers
 // ============================================================================
@@ -111,30 +480,63 @@ async function getStore(): Promise<Store> {
  */
 export function hotkeyIsSameAs(a: HotkeyConfig, b: HotkeyConfig): boolean {
 	if (a.key.toLowerCase() !== b.key.toLowerCase()) return false;
+	if ((a.modifier_taps ?? 0) !== (b.modifier_taps ?? 0)) return false;
 	if (a.modifiers.length !== b.modifiers.length) return false;
 	return a.modifiers.every((mod) =>
 		b.modifiers.some((other) => mod.toLowerCase() === other.toLowerCase()),
 	);
 }
 
-type HotkeyType = "toggle" | "hold" | "paste_last";
+export type HotkeyType =
+	| "toggle"
+	| "hold"
+	| "paste_last"
+	| "show_window"
+	| "paste_slot_2"
+	| "paste_slot_3";
 
 const HOTKEY_LABELS: Record<HotkeyType, string> = {
 	toggle: "toggle",
 	hold: "hold",
 	paste_last: "paste last",
+	show_window: "show window",
+	paste_slot_2: "paste slot 2",
+	paste_slot_3: "paste slot 3",
 };
 
+const HOTKEY_TYPES = Object.keys(HOTKEY_LABELS) as HotkeyType[];
+
+// Unset hotkeys (show_window and paste slots before they are configured) are skipped
+type HotkeyBindings = Partial<Record<HotkeyType, HotkeyConfig | null>>;
+
+/**
+ * The enabled hotkeys from settings, for duplicate checks.
+ * Disabled hotkeys are left out since their combos are free.
+ */
+export function enabledHotkeyBindings(settings: AppSettings): HotkeyBindings {
+	const bindings: HotkeyBindings = {};
+	for (const type of HOTKEY_TYPES) {
+		if (settings.hotkey_enabled[type]) {
+			bindings[type] = settings[`${type}_hotkey`];
+		}
+	}
+	return bindings;
+}
+
 /**
  * Create a Zod schema for validating a hotkey doesn't conflict with existing hotkeys
  */
 export function createHotkeyDuplicateSchema(
-	allHotkeys: Record<HotkeyType, HotkeyConfig>,
+	allHotkeys: HotkeyBindings,
 	excludeType: HotkeyType,
 ) {
 	return HotkeyConfigSchema.superRefine((hotkey, ctx) => {
 		for (const [type, existing] of Object.entries(allHotkeys)) {
-			if (type !== excludeType && hotkeyIsSameAs(hotkey, existing)) {
+			if (
+				type !== excludeType &&
+				existing &&
+				hotkeyIsSameAs(hotkey, existing)
+			) {
 				ctx.addIssue({
 					code: "custom",
 					message: `This shortcut is already used for the ${HOTKEY_LABELS[type as HotkeyType]} hotkey`,
@@ -151,11 +553,7 @@ export function createHotkeyDuplicateSchema(
  */
 export function validateHotkeyNotDuplicate(
 	newHotkey: HotkeyConfig,
-	allHotkeys: {
-		toggle: HotkeyConfig;
-		hold: HotkeyConfig;
-		paste_last: HotkeyConfig;
-	},
+	allHotkeys: HotkeyBindings,
 	excludeType: HotkeyType,
 ): string | null {
 	const schema = createHotkeyDuplicateSchema(allHotkeys, excludeType);
@@ -166,6 +564,86 @@ export function validateHotkeyNotDuplicate(
 	return null;
 }
 
+/** Result of registering one global hotkey with the OS */
+export interface HotkeyRegistration {
+	action: HotkeyType;
+	shortcut: string;
+	registered: boolean;
+	error: string | null;
+}
+
+/** How one hotkey is matched against shortcut events, for debugging */
+export interface EffectiveHotkey {
+	action: HotkeyType;
+	enabled: boolean;
+	// Stored combo (or the default), null when unbound
+	configured: string | null;
+	parsed: boolean;
+	error: string | null;
+	// Normalized combo events are compared against (the default if configured didn't parse)
+	normalized: string | null;
+	// Normalized string the OS reports for the shortcut
+	event_string: string | null;
+	// False means events for this hotkey are logged as "Unknown shortcut"
+	matches: boolean;
+}
+
+/**
+ * Get an error message if the hotkey of the given type failed to register
+ * (usually because another application owns the combo), null otherwise
+ */
+export function hotkeyRegistrationError(
+	registrations: HotkeyRegistration[],
+	type: HotkeyType,
+): string | null {
+	const registration = registrations.find((r) => r.action === type);
+	if (!registration || registration.registered) {
+		return null;
+	}
+	return `${registration.shortcut} for the ${HOTKEY_LABELS[type]} hotkey could not be registered; it may be in use by another application`;
+}
+
+/**
+ * Whether a transcription should be held back instead of inserted.
+ * Never true when the server didn't report a confidence.
+ */
+export function isLowConfidence(
+	confidence: number | null,
+	minConfidence: number,
+): boolean {
+	return confidence !== null && confidence < minConfidence;
+}
+
+/**
+ * Apply a history change to a newest-first list fetched with `limit`.
+ * Returns null when the list has to be refetched instead.
+ */
+export function applyHistoryChange(
+	entries: HistoryEntry[],
+	change: HistoryChange,
+	limit?: number,
+): HistoryEntry[] | null {
+	switch (change.kind) {
+		case "added": {
+			const rest = entries.filter((entry) => entry.id !== change.entry.id);
+			const updated = [change.entry, ...rest];
+			return limit === undefined ? updated : updated.slice(0, limit);
+		}
+		case "updated":
+			return entries.map((entry) =>
+				entry.id === change.entry.id ? change.entry : entry,
+			);
+		case "deleted":
+			// A full list may have more entries to show in its place
+			if (limit !== undefined && entries.length >= limit) {
+				return null;
+			}
+			return entries.filter((entry) => entry.id !== change.id);
+		case "reset":
+			return null;
+	}
+}
+
 // ============================================================================
 // Tauri API
 // ============================================================================
@@ -180,6 +658,12 @@ export const tauriAPI = {
 		}
 	},
 
+	// Type or paste text verbatim (no recording, transcription or post-processing).
+	// Omitting method uses the configured insertion method.
+	async insertText(text: string, method?: InsertionMethod): Promise<void> {
+		return invoke("insert_text", { text, method });
+	},
+
 	async getServerUrl(): Promise<string> {
 		return invoke("get_server_url");
 	},
@@ -192,9 +676,27 @@ export const tauriAPI = {
 		return listen("recording-stop", callback);
 	},
 
+	async onCancelRecording(callback: () => void): Promise<UnlistenFn> {
+		return listen("recording-cancelled", callback);
+	},
+
+	// Whole seconds since the current recording started, emitted once per second
+	async onRecordingElapsed(
+		callback: (seconds: number) => void,
+	): Promise<UnlistenFn> {
+		return listen<number>("recording-elapsed", (event) =>
+			callback(event.payload),
+		);
+	},
+
 	// Settings API - using store plugin directly
 	async getSettings(): Promise<AppSettings> {
 		const store = await getStore();
+		const hotkeyEnabled = {} as Record<HotkeyType, boolean>;
+		for (const type of HOTKEY_TYPES) {
+			hotkeyEnabled[type] =
+				(await store.get<boolean>(`${type}_hotkey_enabled`)) ?? true;
+		}
 		return {
 			toggle_hotkey:
 				(await store.get<HotkeyConfig>("toggle_hotkey")) ?? defaultToggleHotkey,
@@ -203,9 +705,34 @@ export const tauriAPI = {
 			paste_last_hotkey:
 				(await store.get<HotkeyConfig>("paste_last_hotkey")) ??
 				defaultPasteLastHotkey,
+			show_window_hotkey:
+				(await store.get<HotkeyConfig | null>("show_window_hotkey")) ?? null,
+			paste_slot_2_hotkey:
+				(await store.get<HotkeyConfig | null>("paste_slot_2_hotkey")) ?? null,
+			paste_slot_3_hotkey:
+				(await store.get<HotkeyConfig | null>("paste_slot_3_hotkey")) ?? null,
+			paste_slot_source:
+				(await store.get<PasteSlotSource>("paste_slot_source")) ?? "recent",
+			hotkey_enabled: hotkeyEnabled,
+			cancel_hotkey:
+				(await store.get<HotkeyConfig>("cancel_hotkey")) ??
+				defaultCancelHotkey,
 			selected_mic_id:
 				(await store.get<string | null>("selected_mic_id")) ?? null,
 			sound_enabled: (await store.get<boolean>("sound_enabled")) ?? true,
+			play_sound_on_empty:
+				(await store.get<boolean>("play_sound_on_empty")) ?? false,
+			min_confidence:
+				(await store.get<number>("min_confidence")) ?? DEFAULT_MIN_CONFIDENCE,
+			sound_volume: (await store.get<number>("sound_volume")) ?? 1,
+			custom_start_sound_path:
+				(await store.get<string | null>("custom_start_sound_path")) ?? null,
+			custom_stop_sound_path:
+				(await store.get<string | null>("custom_stop_sound_path")) ?? null,
+			custom_complete_sound_path:
+				(await store.get<string | null>("custom_complete_sound_path")) ?? null,
+			custom_error_sound_path:
+				(await store.get<string | null>("custom_error_sound_path")) ?? null,
 			cleanup_prompt_sections:
 				(await store.get<CleanupPromptSections | null>(
 					"cleanup_prompt_sections",
@@ -213,9 +740,86 @@ export const tauriAPI = {
 			stt_provider: (await store.get<string | null>("stt_provider")) ?? null,
 			llm_provider: (await store.get<string | null>("llm_provider")) ?? null,
 			auto_mute_audio: (await store.get<boolean>("auto_mute_audio")) ?? false,
+			mute_exclusions:
+				(await store.get<string[]>("mute_exclusions")) ?? [],
+			mute_mode: (await store.get<MuteMode>("mute_mode")) ?? "mute",
+			duck_level: (await store.get<number>("duck_level")) ?? DEFAULT_DUCK_LEVEL,
 			stt_timeout_seconds:
 				(await store.get<number | null>("stt_timeout_seconds")) ?? null,
 			server_url: (await store.get<string>("server_url")) ?? DEFAULT_SERVER_URL,
+			language: (await store.get<string>("language")) ?? "auto",
+			save_recordings: (await store.get<boolean>("save_recordings")) ?? false,
+			sample_rate:
+				(await store.get<number>("sample_rate")) ?? DEFAULT_SAMPLE_RATE,
+			channels: (await store.get<number>("channels")) ?? DEFAULT_CHANNELS,
+			input_gain: (await store.get<number>("input_gain")) ?? DEFAULT_INPUT_GAIN,
+			noise_gate_threshold:
+				(await store.get<number>("noise_gate_threshold")) ??
+				DEFAULT_NOISE_GATE_THRESHOLD,
+			history_max_entries:
+				(await store.get<number>("history_max_entries")) ??
+				DEFAULT_HISTORY_MAX_ENTRIES,
+			history_max_age_days:
+				(await store.get<number>("history_max_age_days")) ?? 0,
+			history_storage_path:
+				(await store.get<string | null>("history_storage_path")) ?? null,
+			vad_enabled: (await store.get<boolean>("vad_enabled")) ?? false,
+			streaming_enabled:
+				(await store.get<boolean>("streaming_enabled")) ?? false,
+			silence_timeout_ms:
+				(await store.get<number>("silence_timeout_ms")) ??
+				DEFAULT_SILENCE_TIMEOUT_MS,
+			auto_capitalize_first:
+				(await store.get<boolean>("auto_capitalize_first")) ?? false,
+			append_trailing_space:
+				(await store.get<boolean>("append_trailing_space")) ?? false,
+			dry_run: (await store.get<boolean>("dry_run")) ?? false,
+			append_mode: (await store.get<boolean>("append_mode")) ?? false,
+			append_window_ms:
+				(await store.get<number>("append_window_ms")) ??
+				DEFAULT_APPEND_WINDOW_MS,
+			prepend_timestamp:
+				(await store.get<boolean>("prepend_timestamp")) ?? false,
+			timestamp_format:
+				(await store.get<string>("timestamp_format")) ??
+				DEFAULT_TIMESTAMP_FORMAT,
+			timestamp_in_history:
+				(await store.get<boolean>("timestamp_in_history")) ?? false,
+			target_app: (await store.get<string>("target_app")) ?? null,
+			type_delay_ms:
+				(await store.get<number>("type_delay_ms")) ?? DEFAULT_TYPE_DELAY_MS,
+			request_timeout_ms:
+				(await store.get<number>("request_timeout_ms")) ??
+				DEFAULT_REQUEST_TIMEOUT_MS,
+			voice_commands_enabled:
+				(await store.get<boolean>("voice_commands_enabled")) ?? false,
+			hold_start_delay_ms:
+				(await store.get<number>("hold_start_delay_ms")) ??
+				DEFAULT_HOLD_START_DELAY_MS,
+			hold_stop_grace_ms:
+				(await store.get<number>("hold_stop_grace_ms")) ??
+				DEFAULT_HOLD_STOP_GRACE_MS,
+			min_recording_ms:
+				(await store.get<number>("min_recording_ms")) ??
+				DEFAULT_MIN_RECORDING_MS,
+			toggle_debounce_ms:
+				(await store.get<number>("toggle_debounce_ms")) ??
+				DEFAULT_TOGGLE_DEBOUNCE_MS,
+			overlay_visibility:
+				(await store.get<OverlayVisibility>("overlay_visibility")) ?? "always",
+			overlay_opacity:
+				(await store.get<number>("overlay_opacity")) ?? DEFAULT_OVERLAY_OPACITY,
+			overlay_theme: (await store.get<OverlayTheme>("overlay_theme")) ?? "dark",
+			overlay_size: (await store.get<OverlaySize>("overlay_size")) ?? "medium",
+			overlay_monitor: (await store.get<string>("overlay_monitor")) ?? null,
+			main_window_always_on_top:
+				(await store.get<boolean>("main_window_always_on_top")) ?? false,
+			close_to_tray: (await store.get<boolean>("close_to_tray")) ?? false,
+			control_server_enabled:
+				(await store.get<boolean>("control_server_enabled")) ?? false,
+			control_server_port:
+				(await store.get<number>("control_server_port")) ??
+				DEFAULT_CONTROL_SERVER_PORT,
 		};
 	},
 
@@ -237,18 +841,91 @@ export const tauriAPI = {
 		await store.save();
 	},
 
+	// null unbinds the hotkey
+	async updateShowWindowHotkey(hotkey: HotkeyConfig | null): Promise<void> {
+		const store = await getStore();
+		await store.set("show_window_hotkey", hotkey);
+		await store.save();
+	},
+
+	// null unbinds the slot
+	async updatePasteSlotHotkey(
+		slot: PasteSlot,
+		hotkey: HotkeyConfig | null,
+	): Promise<void> {
+		const store = await getStore();
+		await store.set(`paste_slot_${slot}_hotkey`, hotkey);
+		await store.save();
+	},
+
+	async updateHotkeyEnabled(type: HotkeyType, enabled: boolean): Promise<void> {
+		const store = await getStore();
+		await store.set(`${type}_hotkey_enabled`, enabled);
+		await store.save();
+	},
+
+	async updatePasteSlotSource(source: PasteSlotSource): Promise<void> {
+		const store = await getStore();
+		await store.set("paste_slot_source", source);
+		await store.save();
+	},
+
+	async updateCancelHotkey(hotkey: HotkeyConfig): Promise<void> {
+		const store = await getStore();
+		await store.set("cancel_hotkey", hotkey);
+		await store.save();
+	},
+
 	async updateSelectedMic(micId: string | null): Promise<void> {
 		const store = await getStore();
 		await store.set("selected_mic_id", micId);
 		await store.save();
 	},
 
+	async updateCloseToTray(enabled: boolean): Promise<void> {
+		const store = await getStore();
+		await store.set("close_to_tray", enabled);
+		await store.save();
+	},
+
 	async updateSoundEnabled(enabled: boolean): Promise<void> {
 		const store = await getStore();
 		await store.set("sound_enabled", enabled);
 		await store.save();
 	},
 
+	async updatePlaySoundOnEmpty(enabled: boolean): Promise<void> {
+		const store = await getStore();
+		await store.set("play_sound_on_empty", enabled);
+		await store.save();
+	},
+
+	// Must be between 0.0 and 1.0
+	async updateMinConfidence(minConfidence: number): Promise<void> {
+		const store = await getStore();
+		await store.set("min_confidence", minConfidence);
+		await store.save();
+	},
+
+	async updateSoundVolume(volume: number): Promise<void> {
+		const store = await getStore();
+		await store.set("sound_volume", Math.min(1, Math.max(0, volume)));
+		await store.save();
+	},
+
+	async updateCustomSoundPath(
+		which: SoundKind,
+		path: string | null,
+	): Promise<void> {
+		const store = await getStore();
+		await store.set(`custom_${which}_sound_path`, path);
+		await store.save();
+	},
+
+	async previewSound(which: SoundKind): Promise<void> {
+		return invoke("preview_sound", { which });
+	},
+
 	async updateCleanupPromptSections(
 		sections: CleanupPromptSections | null,
 	): Promise<void> {
@@ -275,116 +952,938 @@ export const tauriAPI = {
 		await store.save();
 	},
 
-	async updateSTTTimeout(timeoutSeconds: number | null): Promise<void> {
+	async updateMuteExclusions(exclusions: string[]): Promise<void> {
 		const store = await getStore();
-		await store.set("stt_timeout_seconds", timeoutSeconds);
+		await store.set("mute_exclusions", exclusions);
 		await store.save();
 	},
 
-	async updateServerUrl(url: string): Promise<void> {
+	async updateMuteMode(mode: MuteMode): Promise<void> {
 		const store = await getStore();
-		await store.set("server_url", url);
+		await store.set("mute_mode", mode);
 		await store.save();
 	},
 
-	async isAudioMuteSupported(): Promise<boolean> {
-		return invoke("is_audio_mute_supported");
+	async updateDuckLevel(level: number): Promise<void> {
+		const store = await getStore();
+		await store.set("duck_level", Math.min(100, Math.max(0, Math.round(level))));
+		await store.save();
 	},
 
-	async resetHotkeysToDefaults(): Promise<void> {
+	async updateVadEnabled(enabled: boolean): Promise<void> {
 		const store = await getStore();
-		await store.set("toggle_hotkey", defaultToggleHotkey);
-		await store.set("hold_hotkey", defaultHoldHotkey);
-		await store.set("paste_last_hotkey", defaultPasteLastHotkey);
+		await store.set("vad_enabled", enabled);
 		await store.save();
 	},
 
-	async registerShortcuts(): Promise<void> {
-		return invoke("register_shortcuts");
+	async updateSilenceTimeout(timeoutMs: number): Promise<void> {
+		const store = await getStore();
+		await store.set("silence_timeout_ms", timeoutMs);
+		await store.save();
 	},
 
-	async unregisterShortcuts(): Promise<void> {
-		return invoke("unregister_shortcuts");
+	async updateHoldStartDelay(delayMs: number): Promise<void> {
+		const store = await getStore();
+		await store.set(
+			"hold_start_delay_ms",
+			Math.min(MAX_HOLD_START_DELAY_MS, Math.max(0, Math.round(delayMs))),
+		);
+		await store.save();
 	},
 
-	// History API
-	async addHistoryEntry(text: string): Promise<HistoryEntry> {
-		return invoke("add_history_entry", { text });
+	async updateHoldStopGrace(graceMs: number): Promise<void> {
+		const store = await getStore();
+		await store.set("hold_stop_grace_ms", Math.max(0, Math.round(graceMs)));
+		await store.save();
 	},
 
-	async getHistory(limit?: number): Promise<HistoryEntry[]> {
-		return invoke("get_history", { limit });
+	async updateRequestTimeoutMs(timeoutMs: number): Promise<void> {
+		const store = await getStore();
+		await store.set(
+			"request_timeout_ms",
+			Math.max(MIN_REQUEST_TIMEOUT_MS, Math.round(timeoutMs)),
+		);
+		await store.save();
 	},
 
-	async deleteHistoryEntry(id: string): Promise<boolean> {
-		return invoke("delete_history_entry", { id });
+	async updateMinRecordingMs(minMs: number): Promise<void> {
+		const store = await getStore();
+		await store.set("min_recording_ms", Math.max(0, Math.round(minMs)));
+		await store.save();
 	},
 
-	async clearHistory(): Promise<void> {
-		return invoke("clear_history");
+	async updateToggleDebounceMs(debounceMs: number): Promise<void> {
+		const store = await getStore();
+		await store.set("toggle_debounce_ms", Math.max(0, Math.round(debounceMs)));
+		await store.save();
 	},
 
-	// Overlay API
-	async resizeOverlay(width: number, height: number): Promise<void> {
-		return invoke("resize_overlay", { width, height });
+	async updateStreamingEnabled(enabled: boolean): Promise<void> {
+		const store = await getStore();
+		await store.set("streaming_enabled", enabled);
+		await store.save();
 	},
 
-	async startDragging(): Promise<void> {
-		const window = getCurrentWindow();
-		return window.startDragging();
+	async updateAutoCapitalizeFirst(enabled: boolean): Promise<void> {
+		const store = await getStore();
+		await store.set("auto_capitalize_first", enabled);
+		await store.save();
 	},
 
-	// Connection state sync between windows
-	async emitConnectionState(state: ConnectionState): Promise<void> {
-		return emit("connection-state-changed", { state });
+	async updateAppendTrailingSpace(enabled: boolean): Promise<void> {
+		const store = await getStore();
+		await store.set("append_trailing_space", enabled);
+		await store.save();
 	},
 
-	async onConnectionStateChanged(
-		callback: (state: ConnectionState) => void,
-	): Promise<UnlistenFn> {
-		return listen<{ state: ConnectionState }>(
-			"connection-state-changed",
-			(event) => {
-				callback(event.payload.state);
-			},
-		);
+	async updateDryRun(enabled: boolean): Promise<void> {
+		const store = await getStore();
+		await store.set("dry_run", enabled);
+		await store.save();
 	},
 
-	// History sync between windows
-	async emitHistoryChanged(): Promise<void> {
-		return emit("history-changed", {});
+	async updateAppendMode(enabled: boolean): Promise<void> {
+		const store = await getStore();
+		await store.set("append_mode", enabled);
+		await store.save();
 	},
 
-	async onHistoryChanged(callback: () => void): Promise<UnlistenFn> {
-		return listen("history-changed", () => {
-			callback();
-		});
+	async updateAppendWindowMs(windowMs: number): Promise<void> {
+		const store = await getStore();
+		await store.set("append_window_ms", windowMs);
+		await store.save();
 	},
 
-	// Settings sync between windows (main -> overlay)
-	async emitSettingsChanged(): Promise<void> {
-		return emit("settings-changed", {});
+	async updatePrependTimestamp(enabled: boolean): Promise<void> {
+		const store = await getStore();
+		await store.set("prepend_timestamp", enabled);
+		await store.save();
 	},
 
-	async onSettingsChanged(callback: () => void): Promise<UnlistenFn> {
-		return listen("settings-changed", () => {
-			callback();
-		});
+	async updateTimestampInHistory(enabled: boolean): Promise<void> {
+		const store = await getStore();
+		await store.set("timestamp_in_history", enabled);
+		await store.save();
 	},
 
-	// Reconnect request (main -> overlay)
-	async emitReconnect(): Promise<void> {
-		return emit("request-reconnect", {});
+	// Rejects formats that chrono can't render
+	async setTimestampFormat(format: string): Promise<void> {
+		return invoke("set_timestamp_format", { format });
 	},
 
-	async onReconnect(callback: () => void): Promise<UnlistenFn> {
-		return listen("request-reconnect", () => {
-			callback();
-		});
+	async updateTargetApp(target: string | null): Promise<void> {
+		const store = await getStore();
+		await store.set("target_app", target);
+		await store.save();
 	},
 
-	// Config response sync between windows (overlay -> main)
-	async emitConfigResponse(response: ConfigResponse): Promise<void> {
+	async listRunningApps(): Promise<RunningApp[]> {
+		return invoke("list_running_apps");
+	},
+
+	async updateVoiceCommandsEnabled(enabled: boolean): Promise<void> {
+		const store = await getStore();
+		await store.set("voice_commands_enabled", enabled);
+		await store.save();
+	},
+
+	// 0 = unlimited for both limits
+	async updateHistoryLimits(
+		maxEntries: number,
+		maxAgeDays: number,
+	): Promise<void> {
+		const store = await getStore();
+		await store.set("history_max_entries", maxEntries);
+		await store.set("history_max_age_days", maxAgeDays);
+		await store.save();
+	},
+
+	async updateAudioFormat(sampleRate: number, channels: number): Promise<void> {
+		const store = await getStore();
+		await store.set("sample_rate", sampleRate);
+		await store.set("channels", channels);
+		await store.save();
+	},
+
+	async updateInputGain(gain: number): Promise<void> {
+		const store = await getStore();
+		await store.set("input_gain", gain);
+		await store.save();
+	},
+
+	async updateNoiseGateThreshold(threshold: number): Promise<void> {
+		const store = await getStore();
+		await store.set("noise_gate_threshold", threshold);
+		await store.save();
+	},
+
+	async updateSTTTimeout(timeoutSeconds: number | null): Promise<void> {
+		const store = await getStore();
+		await store.set("stt_timeout_seconds", timeoutSeconds);
+		await store.save();
+	},
+
+	// Validated in Rust (http/https scheme, non-empty host) before persisting
+	async updateServerUrl(url: string): Promise<string> {
+		return invoke("set_server_url", { url });
+	},
+
+	async testServerConnection(): Promise<number> {
+		return invoke("test_server_connection");
+	},
+
+	async getServerAuth(): Promise<ServerAuth> {
+		const store = await getStore();
+		return {
+			api_key: (await store.get<string>("server_api_key")) ?? null,
+			auth_header: (await store.get<string>("server_auth_header")) ?? null,
+			headers:
+				(await store.get<Record<string, string>>("server_headers")) ?? {},
+		};
+	},
+
+	// Header names and values are validated in Rust before persisting
+	async setServerAuth(auth: ServerAuth): Promise<void> {
+		return invoke("set_server_auth", {
+			apiKey: auth.api_key,
+			authHeader: auth.auth_header,
+			headers: auth.headers,
+		});
+	},
+
+	async getTranscriptionWebhook(): Promise<TranscriptionWebhook> {
+		const store = await getStore();
+		return {
+			url: (await store.get<string>("transcription_webhook_url")) ?? null,
+			secret: (await store.get<string>("transcription_webhook_secret")) ?? null,
+		};
+	},
+
+	// The URL must be http(s); a null or empty url turns the webhook off.
+	// Returns the URL as saved.
+	async setTranscriptionWebhook(
+		webhook: TranscriptionWebhook,
+	): Promise<string | null> {
+		return invoke("set_transcription_webhook", {
+			url: webhook.url,
+			secret: webhook.secret,
+		});
+	},
+
+	// Headers (including the API key) to attach to every request to the server
+	async getServerRequestHeaders(): Promise<Record<string, string>> {
+		return invoke("get_server_request_headers");
+	},
+
+	async setLanguage(language: string): Promise<string> {
+		return invoke("set_language", { language });
+	},
+
+	async getTextReplacements(): Promise<ReplacementRule[]> {
+		return invoke("get_text_replacements");
+	},
+
+	async setTextReplacements(rules: ReplacementRule[]): Promise<void> {
+		return invoke("set_text_replacements", { rules });
+	},
+
+	async getCustomVocabulary(): Promise<string[]> {
+		return invoke("get_custom_vocabulary");
+	},
+
+	// Names and jargon whose spelling is restored in transcriptions (at most 200 terms of 64 chars).
+	// Returns the saved list, trimmed and de-duplicated.
+	async setCustomVocabulary(terms: string[]): Promise<string[]> {
+		return invoke("set_custom_vocabulary", { terms });
+	},
+
+	async getVoiceCommands(): Promise<VoiceCommand[]> {
+		return invoke("get_voice_commands");
+	},
+
+	async getBuiltinVoiceCommands(): Promise<VoiceCommand[]> {
+		return invoke("get_builtin_voice_commands");
+	},
+
+	async setVoiceCommands(commands: VoiceCommand[]): Promise<void> {
+		return invoke("set_voice_commands", { commands });
+	},
+
+	async getRecordingState(): Promise<RecordingState> {
+		return invoke("get_recording_state");
+	},
+
+	// Where settings.json, history.json and saved recordings live
+	async getDataDirPath(): Promise<string> {
+		return invoke("get_data_dir_path");
+	},
+
+	// Directory history.json is kept in right now (falls back to the data
+	// directory when the custom location was unavailable at startup)
+	async getHistoryStoragePath(): Promise<string> {
+		return invoke("get_history_storage_path");
+	},
+
+	// Must be a writable absolute path (null = data directory). With migrate the
+	// current entries move along; otherwise history already there is loaded.
+	async setHistoryStoragePath(
+		path: string | null,
+		migrate: boolean,
+	): Promise<string> {
+		return invoke("set_history_storage_path", { path, migrate });
+	},
+
+	// Opens the data directory in the OS file manager
+	async openDataDir(): Promise<void> {
+		return invoke("open_data_dir");
+	},
+
+	// Current log file; rotated logs (.1, .2, ...) sit beside it
+	async getLogPath(): Promise<string> {
+		return invoke("get_log_path");
+	},
+
+	// Reveals the log file in the OS file manager
+	async openLogs(): Promise<void> {
+		return invoke("open_logs");
+	},
+
+	// Applied immediately and saved for future launches (RUST_LOG wins at startup)
+	async setLogLevel(level: LogLevel): Promise<void> {
+		return invoke("set_log_level", { level });
+	},
+
+	async getLogLevel(): Promise<LogLevel> {
+		return invoke("get_log_level");
+	},
+
+	// Returns the number of settings written
+	async exportSettings(path: string): Promise<number> {
+		return invoke("export_settings", { path });
+	},
+
+	async importSettings(path: string): Promise<SettingsImportResult> {
+		return invoke("import_settings", { path });
+	},
+
+	// Profiles are kept; history too unless clearHistory is set
+	async resetSettings(clearHistory = false): Promise<void> {
+		return invoke("reset_settings", { clearHistory });
+	},
+
+	async listProfiles(): Promise<ProfileSummary[]> {
+		return invoke("list_profiles");
+	},
+
+	// New profiles start as a copy of the current settings
+	async createProfile(name: string): Promise<ProfileSummary> {
+		return invoke("create_profile", { name });
+	},
+
+	// Emits settings-changed so every window picks up the profile's settings
+	async switchProfile(name: string): Promise<void> {
+		return invoke("switch_profile", { name });
+	},
+
+	async deleteProfile(name: string): Promise<void> {
+		return invoke("delete_profile", { name });
+	},
+
+	// Backspaces over the last insertion; only within 10s of it, and only once.
+	// Resolves to the number of characters deleted.
+	async undoLastInsertion(): Promise<number> {
+		return invoke("undo_last_insertion");
+	},
+
+	async checkPermissions(): Promise<PermissionStatus> {
+		return invoke("check_permissions");
+	},
+
+	// Opens the system settings pane for the permission (or the first missing one)
+	async requestPermissions(permission?: Permission): Promise<void> {
+		return invoke("request_permissions", { permission: permission ?? null });
+	},
+
+	async isAudioMuteSupported(): Promise<boolean> {
+		return invoke("is_audio_mute_supported");
+	},
+
+	async isPerAppMuteSupported(): Promise<boolean> {
+		return invoke("is_per_app_mute_supported");
+	},
+
+	async resetHotkeysToDefaults(): Promise<void> {
+		const store = await getStore();
+		await store.set("toggle_hotkey", defaultToggleHotkey);
+		await store.set("hold_hotkey", defaultHoldHotkey);
+		await store.set("paste_last_hotkey", defaultPasteLastHotkey);
+		await store.set("cancel_hotkey", defaultCancelHotkey);
+		await store.delete("show_window_hotkey");
+		await store.delete("paste_slot_2_hotkey");
+		await store.delete("paste_slot_3_hotkey");
+		for (const type of HOTKEY_TYPES) {
+			await store.delete(`${type}_hotkey_enabled`);
+		}
+		await store.save();
+	},
+
+	async parseHotkey(shortcut: string): Promise<HotkeyConfig> {
+		return invoke("parse_hotkey", { shortcut });
+	},
+
+	async registerShortcuts(): Promise<HotkeyRegistration[]> {
+		return invoke("register_shortcuts");
+	},
+
+	async getEffectiveHotkeys(): Promise<EffectiveHotkey[]> {
+		return invoke("get_effective_hotkeys");
+	},
+
+	// Unregisters every global hotkey until resumed, including across restarts
+	async suspendHotkeys(): Promise<void> {
+		return invoke("suspend_hotkeys");
+	},
+
+	async resumeHotkeys(): Promise<HotkeyRegistration[]> {
+		return invoke("resume_hotkeys");
+	},
+
+	async getHotkeysSuspended(): Promise<boolean> {
+		return invoke("get_hotkeys_suspended");
+	},
+
+	// While enabled, new history entries (and recordings) are never written to disk
+	async setIncognito(enabled: boolean): Promise<void> {
+		return invoke("set_incognito", { enabled });
+	},
+
+	async getIncognito(): Promise<boolean> {
+		return invoke("get_incognito");
+	},
+
+	// True if the combo is free (or already registered by this app)
+	async checkHotkeyAvailable(config: HotkeyConfig): Promise<boolean> {
+		return invoke("check_hotkey_available", { config });
+	},
+
+	async unregisterShortcuts(): Promise<void> {
+		return invoke("unregister_shortcuts");
+	},
+
+	// History API
+	async addHistoryEntry(
+		text: string,
+		language?: string | null,
+		audioDurationMs?: number | null,
+	): Promise<HistoryEntry> {
+		return invoke("add_history_entry", { text, language, audioDurationMs });
+	},
+
+	async getHistoryStats(): Promise<HistoryStats> {
+		return invoke("get_history_stats");
+	},
+
+	async getHistoryEncryption(): Promise<HistoryEncryptionStatus> {
+		return invoke("get_history_encryption");
+	},
+
+	// Also migrates an existing plaintext history; null stores it as plaintext
+	// again. Passphrases need at least 8 characters and are never stored.
+	async setHistoryEncryption(
+		keySource: HistoryKeySource | null,
+		passphrase?: string,
+	): Promise<HistoryEncryptionStatus> {
+		return invoke("set_history_encryption", { keySource, passphrase });
+	},
+
+	// Returns the number of entries loaded; fails (staying locked) for a wrong key
+	async unlockHistory(passphrase?: string): Promise<number> {
+		return invoke("unlock_history", { passphrase });
+	},
+
+	async getHistory(
+		limit?: number,
+		pinnedFirst?: boolean,
+	): Promise<HistoryEntry[]> {
+		return invoke("get_history", { limit, pinnedFirst });
+	},
+
+	async searchHistory(query: string, limit?: number): Promise<HistoryEntry[]> {
+		return invoke("search_history", { query, limit });
+	},
+
+	// Entries timestamped within [start, end], newest first
+	async getHistoryRange(
+		start: Date,
+		end: Date,
+		limit?: number,
+	): Promise<HistoryEntry[]> {
+		return invoke("get_history_range", {
+			startTs: start.toISOString(),
+			endTs: end.toISOString(),
+			limit,
+		});
+	},
+
+	async deleteHistoryEntry(id: string): Promise<boolean> {
+		return invoke("delete_history_entry", { id });
+	},
+
+	// Replace an entry's text; the original timestamp is kept
+	async updateHistoryEntry(id: string, newText: string): Promise<HistoryEntry> {
+		return invoke("update_history_entry", { id, newText });
+	},
+
+	// Tags are matched case-insensitively
+	async addHistoryTag(id: string, tag: string): Promise<HistoryEntry> {
+		return invoke("add_history_tag", { id, tag });
+	},
+
+	async removeHistoryTag(id: string, tag: string): Promise<HistoryEntry> {
+		return invoke("remove_history_tag", { id, tag });
+	},
+
+	async getHistoryByTag(tag: string): Promise<HistoryEntry[]> {
+		return invoke("get_history_by_tag", { tag });
+	},
+
+	async setHistoryPinned(id: string, pinned: boolean): Promise<boolean> {
+		return invoke("set_history_pinned", { id, pinned });
+	},
+
+	async copyHistoryEntry(id: string): Promise<void> {
+		return invoke("copy_history_entry", { id });
+	},
+
+	// Types/pastes the entry into the focused app like a new transcription
+	async pasteHistoryEntry(id: string): Promise<void> {
+		return invoke("paste_history_entry", { id });
+	},
+
+	async clearHistory(includePinned?: boolean): Promise<void> {
+		return invoke("clear_history", { includePinned });
+	},
+
+	// Removes unpinned entries older than before. Returns the number removed.
+	async clearHistoryBefore(before: Date): Promise<number> {
+		return invoke("clear_history_before", { before: before.toISOString() });
+	},
+
+	async clearHistoryOlderThanDays(days: number): Promise<number> {
+		return tauriAPI.clearHistoryBefore(
+			new Date(Date.now() - days * 24 * 60 * 60 * 1000),
+		);
+	},
+
+	// Returns the number of entries written
+	async exportHistory(
+		format: HistoryExportFormat,
+		path: string,
+		oldestFirst?: boolean,
+	): Promise<number> {
+		return invoke("export_history", { format, path, oldestFirst });
+	},
+
+	async importHistory(
+		path: string,
+		dedupe: boolean,
+	): Promise<HistoryImportResult> {
+		return invoke("import_history", { path, dedupe });
+	},
+
+	// Returns the number of entries removed
+	async pruneHistory(): Promise<number> {
+		return invoke("prune_history");
+	},
+
+	// Saved recordings API
+	// Samples are interleaved when channels > 1 and sent as raw bytes, with the
+	// format in headers; Rust converts them to the configured sample_rate/channels
+	// before saving
+	async saveRecording(
+		samples: Int16Array,
+		sampleRate: number,
+		channels = 1,
+	): Promise<SavedRecording | null> {
+		const bytes = new Uint8Array(
+			samples.buffer,
+			samples.byteOffset,
+			samples.byteLength,
+		);
+		return invoke("save_recording", bytes, {
+			headers: {
+				"x-sample-rate": String(sampleRate),
+				"x-channels": String(channels),
+			},
+		});
+	},
+
+	// Levels of a block of mono mic samples before and after the configured gain and
+	// noise gate, for calibrating them while watching a live meter
+	async previewInputLevel(samples: number[]): Promise<InputLevelPreview> {
+		return invoke("preview_input_level", { samples });
+	},
+
+	async getSavedRecordings(): Promise<SavedRecording[]> {
+		return invoke("get_saved_recordings");
+	},
+
+	// Overlay API
+	async resizeOverlay(width: number, height: number): Promise<void> {
+		return invoke("resize_overlay", { width, height });
+	},
+
+	async setOverlayPosition(
+		anchor: OverlayAnchor,
+		marginX: number,
+		marginY: number,
+	): Promise<void> {
+		return invoke("set_overlay_position", { anchor, marginX, marginY });
+	},
+
+	async startDragging(): Promise<void> {
+		return invoke("start_overlay_drag");
+	},
+
+	async endDragging(): Promise<void> {
+		return invoke("end_overlay_drag");
+	},
+
+	async resetOverlayPosition(): Promise<void> {
+		return invoke("reset_overlay_position");
+	},
+
+	async listMonitors(): Promise<MonitorInfo[]> {
+		return invoke("list_monitors");
+	},
+
+	// Keeps the overlay on this monitor at the configured anchor; replaces any dragged position
+	async moveOverlayToMonitor(id: string): Promise<void> {
+		return invoke("move_overlay_to_monitor", { id });
+	},
+
+	// Applied to the overlay window immediately
+	async setOverlayVisibility(visibility: OverlayVisibility): Promise<void> {
+		return invoke("set_overlay_visibility", { visibility });
+	},
+
+	// Reflects the OS state, which may have been changed in system settings
+	async getAutostart(): Promise<boolean> {
+		return invoke("get_autostart");
+	},
+
+	async setAutostart(enabled: boolean): Promise<void> {
+		return invoke("set_autostart", { enabled });
+	},
+
+	async getControlServer(): Promise<ControlServerStatus> {
+		return invoke("get_control_server");
+	},
+
+	// Starts or stops the server right away; port defaults to the saved one
+	async setControlServer(
+		enabled: boolean,
+		port?: number,
+	): Promise<ControlServerStatus> {
+		return invoke("set_control_server", { enabled, port });
+	},
+
+	// Returns the new token; the old one stops working immediately
+	async regenerateControlServerToken(): Promise<string> {
+		return invoke("regenerate_control_server_token");
+	},
+
+	// Applied to the main window immediately; the overlay is always on top regardless
+	async setMainWindowAlwaysOnTop(alwaysOnTop: boolean): Promise<void> {
+		return invoke("set_main_window_always_on_top", { alwaysOnTop });
+	},
+
+	// Opacity must be between 0.1 and 1.0; applied to the overlay immediately
+	async setOverlayAppearance(
+		opacity: number,
+		theme: OverlayTheme,
+	): Promise<void> {
+		return invoke("set_overlay_appearance", { opacity, theme });
+	},
+
+	// Set the overlay size preset; the overlay resizes immediately
+	async setOverlaySizePreset(preset: OverlaySize): Promise<void> {
+		return invoke("set_overlay_size_preset", { preset });
+	},
+
+	async getOverlayAppearance(): Promise<OverlayAppearance> {
+		return invoke("get_overlay_appearance");
+	},
+
+	// Appearance setting or OS theme changed (Rust -> overlay)
+	async onOverlayAppearanceChanged(
+		callback: (appearance: OverlayAppearance) => void,
+	): Promise<UnlistenFn> {
+		return listen<OverlayAppearance>("overlay-appearance-changed", (event) => {
+			callback(event.payload);
+		});
+	},
+
+	// Mic level (0.0-1.0) while recording (overlay -> all windows)
+	async emitRecordingLevel(level: number): Promise<void> {
+		return emit("recording-level", { level });
+	},
+
+	async onRecordingLevel(
+		callback: (level: number) => void,
+	): Promise<UnlistenFn> {
+		return listen<{ level: number }>("recording-level", (event) => {
+			callback(event.payload.level);
+		});
+	},
+
+	// Silence detection transitions while recording with vad_enabled (Rust -> all windows)
+	async onSpeechDetected(callback: () => void): Promise<UnlistenFn> {
+		return listen("speech-detected", callback);
+	},
+
+	async onSilenceDetected(callback: () => void): Promise<UnlistenFn> {
+		return listen("silence-detected", callback);
+	},
+
+	// Interim transcription text while recording with streaming_enabled (overlay -> all windows).
+	// Display only - text is inserted once, on transcription-complete.
+	async emitTranscriptionPartial(text: string): Promise<void> {
+		return emit("transcription-partial", { text });
+	},
+
+	async onTranscriptionPartial(
+		callback: (text: string) => void,
+	): Promise<UnlistenFn> {
+		return listen<{ text: string }>("transcription-partial", (event) => {
+			callback(event.payload.text);
+		});
+	},
+
+	// Recording stopped, waiting for the server's transcription (overlay -> all windows)
+	async emitTranscribing(): Promise<void> {
+		return emit("transcribing", {});
+	},
+
+	async onTranscribing(callback: () => void): Promise<UnlistenFn> {
+		return listen("transcribing", () => {
+			callback();
+		});
+	},
+
+	// Empty text means the recording contained no speech
+	async emitTranscriptionComplete(text: string): Promise<void> {
+		return emit("transcription-complete", { text });
+	},
+
+	async onTranscriptionComplete(
+		callback: (text: string) => void,
+	): Promise<UnlistenFn> {
+		return listen<{ text: string }>("transcription-complete", (event) => {
+			callback(event.payload.text);
+		});
+	},
+
+	// Stopped recordings still awaiting transcription changed (overlay -> all windows)
+	async emitTranscriptionQueueChanged(depth: number): Promise<void> {
+		return emit("transcription-queue-changed", { depth });
+	},
+
+	async onTranscriptionQueueChanged(
+		callback: (depth: number) => void,
+	): Promise<UnlistenFn> {
+		return listen<{ depth: number }>("transcription-queue-changed", (event) => {
+			callback(event.payload.depth);
+		});
+	},
+
+	async getTranscriptionQueueDepth(): Promise<number> {
+		return invoke("get_transcription_queue_depth");
+	},
+
+	// Failures from the latest registration pass, including the one at startup
+	async getShortcutRegistrationFailures(): Promise<HotkeyRegistration[]> {
+		return invoke("get_shortcut_registration_failures");
+	},
+
+	// Nothing was transcribed, so nothing is inserted or added to history (overlay -> all windows)
+	async emitTranscriptionEmpty(): Promise<void> {
+		return emit("transcription-empty", {});
+	},
+
+	async onTranscriptionEmpty(callback: () => void): Promise<UnlistenFn> {
+		return listen("transcription-empty", () => {
+			callback();
+		});
+	},
+
+	// Transcription scored below min_confidence and was not inserted (overlay -> all windows).
+	// Insert anyway with typeText and addHistoryEntry.
+	async emitTranscriptionLowConfidence(
+		payload: LowConfidenceTranscription,
+	): Promise<void> {
+		return emit("transcription-low-confidence", payload);
+	},
+
+	async onTranscriptionLowConfidence(
+		callback: (payload: LowConfidenceTranscription) => void,
+	): Promise<UnlistenFn> {
+		return listen<LowConfidenceTranscription>(
+			"transcription-low-confidence",
+			(event) => {
+				callback(event.payload);
+			},
+		);
+	},
+
+	// Transcription failed (overlay -> all windows); Rust ends the recording and restores audio
+	async emitTranscriptionError(error: TranscriptionError): Promise<void> {
+		return emit("transcription-error", error);
+	},
+
+	async onTranscriptionError(
+		callback: (error: TranscriptionError) => void,
+	): Promise<UnlistenFn> {
+		return listen<TranscriptionError>("transcription-error", (event) => {
+			callback(event.payload);
+		});
+	},
+
+	// A recording was not started because a permission is missing (Rust -> all windows)
+	async onPermissionDenied(
+		callback: (permission: Permission) => void,
+	): Promise<UnlistenFn> {
+		return listen<{ permission: Permission }>("permission-denied", (event) => {
+			callback(event.payload.permission);
+		});
+	},
+
+	// With dry_run on, the text typeText would have inserted (or the voice command it would have run)
+	async onDryRunResult(
+		callback: (text: string, voiceAction: VoiceAction | null) => void,
+	): Promise<UnlistenFn> {
+		return listen<{ text: string; voice_action: VoiceAction | null }>(
+			"dry-run-result",
+			(event) => {
+				callback(event.payload.text, event.payload.voice_action);
+			},
+		);
+	},
+
+	// Hotkeys were suspended or resumed (from a command or the tray menu)
+	async onHotkeysSuspendedChanged(
+		callback: (suspended: boolean) => void,
+	): Promise<UnlistenFn> {
+		return listen<boolean>("hotkeys-suspended-changed", (event) => {
+			callback(event.payload);
+		});
+	},
+
+	// Incognito mode was turned on or off (from a command or the tray menu)
+	async onIncognitoChanged(
+		callback: (incognito: boolean) => void,
+	): Promise<UnlistenFn> {
+		return listen<boolean>("incognito-changed", (event) => {
+			callback(event.payload);
+		});
+	},
+
+	// Some hotkeys couldn't be registered (usually owned by another application)
+	async onShortcutRegistrationFailed(
+		callback: (failures: HotkeyRegistration[]) => void,
+	): Promise<UnlistenFn> {
+		return listen<HotkeyRegistration[]>(
+			"shortcut-registration-failed",
+			(event) => {
+				callback(event.payload);
+			},
+		);
+	},
+
+	// target_app couldn't be focused, so the text went to the current focus
+	async onTargetAppUnavailable(
+		callback: (target: string, reason: string) => void,
+	): Promise<UnlistenFn> {
+		return listen<{ target: string; reason: string }>(
+			"target-app-unavailable",
+			(event) => {
+				callback(event.payload.target, event.payload.reason);
+			},
+		);
+	},
+
+	// Connection state sync between windows
+	async emitConnectionState(state: ConnectionState): Promise<void> {
+		return emit("connection-state-changed", { state });
+	},
+
+	async onConnectionStateChanged(
+		callback: (state: ConnectionState) => void,
+	): Promise<UnlistenFn> {
+		return listen<{ state: ConnectionState }>(
+			"connection-state-changed",
+			(event) => {
+				callback(event.payload.state);
+			},
+		);
+	},
+
+	// Emitted by Rust to every window whenever the history changes
+	async onHistoryChanged(
+		callback: (change: HistoryChange) => void,
+	): Promise<UnlistenFn> {
+		return listen<HistoryChange>("history-changed", (event) => {
+			callback(event.payload);
+		});
+	},
+
+	// Settings sync between windows (main -> overlay)
+	async emitSettingsChanged(): Promise<void> {
+		return emit("settings-changed", {});
+	},
+
+	async onSettingsChanged(callback: () => void): Promise<UnlistenFn> {
+		return listen("settings-changed", () => {
+			callback();
+		});
+	},
+
+	// All settings were reset to defaults (Rust -> all windows)
+	async onSettingsReset(callback: () => void): Promise<UnlistenFn> {
+		return listen("settings-reset", () => {
+			callback();
+		});
+	},
+
+	// Selected microphone missing, fell back to system default (overlay -> main)
+	async emitInputDeviceFallback(missingDeviceId: string): Promise<void> {
+		return emit("input-device-fallback", { missingDeviceId });
+	},
+
+	async onInputDeviceFallback(
+		callback: (missingDeviceId: string) => void,
+	): Promise<UnlistenFn> {
+		return listen<{ missingDeviceId: string }>(
+			"input-device-fallback",
+			(event) => {
+				callback(event.payload.missingDeviceId);
+			},
+		);
+	},
+
+	// Reconnect request (main -> overlay)
+	async emitReconnect(): Promise<void> {
+		return emit("request-reconnect", {});
+	},
+
+	async onReconnect(callback: () => void): Promise<UnlistenFn> {
+		return listen("request-reconnect", () => {
+			callback();
+		});
+	},
+
+	// Config response sync between windows (overlay -> main)
+	async emitConfigResponse(response: ConfigResponse): Promise<void> {
 		return emit("config-response", response);
 	},
 
@@ -433,9 +1932,10 @@ export interface AvailableProvidersData {
 }
 
 // Create ky instance with sensible defaults for API calls
-function createApiClient(serverUrl: string) {
+function createApiClient(serverUrl: string, headers: Record<string, string>) {
 	return ky.create({
 		prefixUrl: serverUrl,
+		headers,
 		timeout: 10000,
 		retry: {
 			limit: 2,
@@ -447,7 +1947,10 @@ function createApiClient(serverUrl: string) {
 export const configAPI = {
 	// Static prompt defaults (runtime config goes via data channel)
 	getDefaultSections: async (serverUrl: string) => {
-		const api = createApiClient(serverUrl);
+		const api = createApiClient(
+			serverUrl,
+			await tauriAPI.getServerRequestHeaders(),
+		);
 		return api
 			.get("api/prompt/sections/default")
 			.json<DefaultSectionsResponse>();
@@ -12,9 +12,14 @@ type ConnectionState =
 	| "recording" // Mic enabled, streaming audio
 	| "processing"; // Waiting for server response
 
+// Stopped recordings whose transcription may still be in flight; older ones are assumed lost
+const MAX_PENDING_TRANSCRIPTIONS = 5;
+
 interface RecordingState {
 	state: ConnectionState;
 	client: PipecatClient | null;
+	// Stopped recordings awaiting a server response, answered in order (FIFO)
+	pendingTranscriptions: number;
 
 	// Actions
 	setClient: (client: PipecatClient | null) => void;
@@ -25,7 +30,8 @@ interface RecordingState {
 	handleDisconnected: () => void;
 	startRecording: () => Promise<boolean>; // Returns false if not in valid state
 	stopRecording: () => boolean; // Returns false if not in valid state
-	handleResponse: () => void;
+	cancelRecording: () => boolean; // Returns false if not in valid state
+	handleResponse: () => void; // Resolves the oldest pending transcription
 
 	// Configuration via data channel
 	sendConfigMessage: (type: string, data: unknown) => boolean;
@@ -34,6 +40,7 @@ interface RecordingState {
 export const useRecordingStore = create<RecordingState>((set, get) => ({
 	state: "disconnected",
 	client: null,
+	pendingTranscriptions: 0,
 
 	setClient: (client) => set({ client }),
 	setState: (state) => set({ state }),
@@ -46,17 +53,19 @@ export const useRecordingStore = create<RecordingState>((set, get) => ({
 	},
 
 	handleDisconnected: () => {
-		// Only reset connection state - keep client reference since we reuse it for reconnection
-		set({ state: "disconnected" });
+		// Only reset connection state - keep client reference since we reuse it for reconnection.
+		// In-flight transcriptions are lost with the connection.
+		set({ state: "disconnected", pendingTranscriptions: 0 });
 	},
 
 	startRecording: async () => {
 		const { state, client } = get();
-		if (state !== "idle" || !client) {
+		// Earlier recordings may still be processing; the server flushes their buffer first
+		if ((state !== "idle" && state !== "processing") || !client) {
 			return false;
 		}
 
-		// Signal server to reset buffer and enable mic
+		// Signal server to start a new buffer and enable mic
 		try {
 			client.sendClientMessage("start-recording", {});
 
@@ -103,7 +112,13 @@ export const useRecordingStore = create<RecordingState>((set, get) => ({
 		// Try to send stop message to server
 		try {
 			client.sendClientMessage("stop-recording", {});
-			set({ state: "processing" });
+			set({
+				state: "processing",
+				pendingTranscriptions: Math.min(
+					get().pendingTranscriptions + 1,
+					MAX_PENDING_TRANSCRIPTIONS,
+				),
+			});
 			return true;
 		} catch (error) {
 			console.warn("[Recording] Failed to send stop message:", error);
@@ -112,9 +127,39 @@ export const useRecordingStore = create<RecordingState>((set, get) => ({
 		}
 	},
 
+	cancelRecording: () => {
+		const { state, client } = get();
+		if (state !== "recording" || !client) {
+			return false;
+		}
+
+		// Release the mic the same way stopRecording() does
+		try {
+			client.enableMic(false);
+			const tracks = client.tracks();
+			if (tracks?.local?.audio) {
+				tracks.local.audio.stop();
+			}
+		} catch (error) {
+			console.warn("[Recording] Failed to release mic:", error);
+		}
+
+		// Tell the server to drop its buffer - no transcription is produced
+		try {
+			client.sendClientMessage("cancel-recording", {});
+			set({ state: get().pendingTranscriptions > 0 ? "processing" : "idle" });
+		} catch (error) {
+			console.warn("[Recording] Failed to send cancel message:", error);
+			set({ state: "disconnected" });
+		}
+		return true;
+	},
+
 	handleResponse: () => {
-		const { state } = get();
-		if (state === "processing") {
+		const { state, pendingTranscriptions } = get();
+		const remaining = Math.max(pendingTranscriptions - 1, 0);
+		set({ pendingTranscriptions: remaining });
+		if (state === "processing" && remaining === 0) {
 			// Track is already stopped in stopRecording(), just transition state
 			set({ state: "idle" });
 		}
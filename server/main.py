@@ -40,6 +40,7 @@ from pipecat.transports.smallwebrtc.transport import SmallWebRTCTransport
 from api.config_server import config_router
 from config.settings import Settings
 from processors.configuration import ConfigurationHandler
+from processors.input_gain import InputGainProcessor
 from processors.llm import TranscriptionToLLMConverter
 from processors.transcription_buffer import TranscriptionBufferProcessor
 from services.providers import (
@@ -119,6 +120,7 @@ async def run_pipeline(
     # Initialize processors
     transcription_to_llm = TranscriptionToLLMConverter()
     transcription_buffer = TranscriptionBufferProcessor()
+    input_gain = InputGainProcessor()
 
     # RTVIProcessor handles the RTVI protocol (client messages, server responses)
     rtvi_processor = RTVIProcessor()
@@ -130,6 +132,7 @@ async def run_pipeline(
         llm_switcher=llm_switcher,
         llm_converter=transcription_to_llm,
         transcription_buffer=transcription_buffer,
+        input_gain=input_gain,
         stt_services=stt_services,
         llm_services=llm_services,
     )
@@ -153,6 +156,9 @@ async def run_pipeline(
         if msg_type == "stop-recording":
             await transcription_buffer.stop_recording()
             return
+        if msg_type == "cancel-recording":
+            await transcription_buffer.cancel_recording()
+            return
 
         # Handle configuration messages
         await config_handler.handle_client_message(msg_type, data)
@@ -161,6 +167,7 @@ async def run_pipeline(
     pipeline = Pipeline(
         [
             transport.input(),
+            input_gain,  # Applies input gain and noise gate before STT
             rtvi_processor,  # Handles RTVI protocol messages
             stt_switcher,
             transcription_buffer,
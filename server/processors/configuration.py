@@ -10,10 +10,12 @@ from enum import StrEnum
 from typing import TYPE_CHECKING, Any
 
 from loguru import logger
-from pipecat.frames.frames import ManuallySwitchServiceFrame
+from pipecat.frames.frames import ManuallySwitchServiceFrame, STTUpdateSettingsFrame
 from pipecat.processors.frame_processor import FrameDirection
 from pipecat.processors.frameworks.rtvi import RTVIProcessor, RTVIServerMessageFrame
+from pipecat.transcriptions.language import Language
 
+from processors.input_gain import MAX_INPUT_GAIN
 from services.provider_registry import LLMProviderId, STTProviderId
 
 if TYPE_CHECKING:
@@ -22,6 +24,7 @@ if TYPE_CHECKING:
     from pipecat.services.ai_services import STTService
     from pipecat.services.llm_service import LLMService
 
+    from processors.input_gain import InputGainProcessor
     from processors.llm import TranscriptionToLLMConverter
     from processors.transcription_buffer import TranscriptionBufferProcessor
 
@@ -35,6 +38,10 @@ class ConfigurationHandler:
     - set-llm-provider: Switch LLM service
     - set-prompt-sections: Update LLM prompt
     - set-stt-timeout: Update transcription timeout
+    - set-language: Update transcription language ("auto" for detection)
+    - set-streaming: Enable/disable partial transcription messages
+    - set-input-gain: Update the linear gain applied to incoming audio
+    - set-noise-gate: Update the level below which incoming audio is silenced
 
     All configuration is scoped to this pipeline instance.
     """
@@ -46,6 +53,7 @@ class ConfigurationHandler:
         llm_switcher: LLMSwitcher,
         llm_converter: TranscriptionToLLMConverter,
         transcription_buffer: TranscriptionBufferProcessor,
+        input_gain: InputGainProcessor,
         stt_services: dict[STTProviderId, STTService],
         llm_services: dict[LLMProviderId, LLMService],
     ) -> None:
@@ -57,6 +65,7 @@ class ConfigurationHandler:
             llm_switcher: LLMSwitcher for LLM services
             llm_converter: TranscriptionToLLMConverter for prompt configuration
             transcription_buffer: TranscriptionBufferProcessor for timeout configuration
+            input_gain: InputGainProcessor for gain and noise gate configuration
             stt_services: Dictionary mapping STT provider IDs to services
             llm_services: Dictionary mapping LLM provider IDs to services
         """
@@ -65,6 +74,7 @@ class ConfigurationHandler:
         self._llm_switcher = llm_switcher
         self._llm_converter = llm_converter
         self._transcription_buffer = transcription_buffer
+        self._input_gain = input_gain
         self._stt_services = stt_services
         self._llm_services = llm_services
 
@@ -95,6 +105,10 @@ class ConfigurationHandler:
             ),
             "set-prompt-sections": lambda: self._set_prompt_sections(data.get("sections")),
             "set-stt-timeout": lambda: self._set_stt_timeout(data.get("timeout_seconds")),
+            "set-language": lambda: self._set_language(data.get("language")),
+            "set-streaming": lambda: self._set_streaming(data.get("enabled")),
+            "set-input-gain": lambda: self._set_input_gain(data.get("gain")),
+            "set-noise-gate": lambda: self._set_noise_gate(data.get("threshold")),
             "get-available-providers": self._send_available_providers,
         }
 
@@ -194,6 +208,81 @@ class ConfigurationHandler:
         logger.info(f"Set STT timeout to: {timeout_seconds}s")
         await self._send_config_success("stt-timeout", timeout_seconds)
 
+    async def _set_language(self, language: str | None) -> None:
+        """Set the transcription language on the active STT service.
+
+        Args:
+            language: ISO 639-1 language code, or "auto" to let the service detect it
+        """
+        if not language:
+            await self._send_config_error("language", "Language value is required")
+            return
+
+        if language == "auto":
+            stt_language: Language | None = None
+        else:
+            try:
+                stt_language = Language(language)
+            except ValueError:
+                await self._send_config_error("language", f"Unknown language: {language}")
+                return
+
+        await self._stt_switcher.process_frame(
+            STTUpdateSettingsFrame(settings={"language": stt_language}),
+            FrameDirection.DOWNSTREAM,
+        )
+        logger.info(f"Set transcription language to: {language}")
+        await self._send_config_success("language", language)
+
+    async def _set_streaming(self, enabled: bool | None) -> None:
+        """Enable or disable partial transcription messages.
+
+        Args:
+            enabled: Whether to stream partial transcriptions while recording
+        """
+        if enabled is None:
+            await self._send_config_error("streaming", "Enabled value is required")
+            return
+
+        self._transcription_buffer.set_streaming_enabled(bool(enabled))
+        await self._send_config_success("streaming", bool(enabled))
+
+    async def _set_input_gain(self, gain: float | None) -> None:
+        """Set the linear gain applied to incoming audio.
+
+        Args:
+            gain: Multiplier between 0.0 and MAX_INPUT_GAIN (1.0 = unchanged)
+        """
+        if gain is None:
+            await self._send_config_error("input-gain", "Gain value is required")
+            return
+
+        if gain < 0.0 or gain > MAX_INPUT_GAIN:
+            await self._send_config_error(
+                "input-gain", f"Gain must be between 0.0 and {MAX_INPUT_GAIN}"
+            )
+            return
+
+        self._input_gain.set_input_gain(gain)
+        await self._send_config_success("input-gain", gain)
+
+    async def _set_noise_gate(self, threshold: float | None) -> None:
+        """Set the noise gate threshold for incoming audio.
+
+        Args:
+            threshold: RMS level between 0.0 and 1.0 below which audio is silenced (0.0 = off)
+        """
+        if threshold is None:
+            await self._send_config_error("noise-gate", "Threshold value is required")
+            return
+
+        if threshold < 0.0 or threshold > 1.0:
+            await self._send_config_error("noise-gate", "Threshold must be between 0.0 and 1.0")
+            return
+
+        self._input_gain.set_noise_gate_threshold(threshold)
+        await self._send_config_success("noise-gate", threshold)
+
     async def _send_available_providers(self) -> None:
         """Send available providers with model info from instantiated services."""
         from services.provider_registry import get_llm_provider_labels, get_stt_provider_labels
@@ -0,0 +1,105 @@
+"""Input gain and noise gate for the incoming microphone stream.
+
+Boosts a quiet microphone by a linear gain and silences blocks of audio whose
+level stays below a threshold (e.g. fan noise between words). The desktop app
+applies the same processing to saved recordings and level previews, so the
+constants here mirror app/src-tauri/src/input_processing.rs.
+
+VAD runs inside the input transport, before this processor, so it always sees
+the unprocessed signal.
+"""
+
+from __future__ import annotations
+
+import math
+import sys
+from array import array
+from typing import Any, Final
+
+from pipecat.frames.frames import Frame, InputAudioRawFrame
+from pipecat.processors.frame_processor import FrameDirection, FrameProcessor
+
+from utils.logger import logger
+
+DEFAULT_INPUT_GAIN: Final[float] = 1.0
+
+# Boosting further mostly amplifies noise and clips speech, which degrades
+# transcription more than a quiet signal does
+MAX_INPUT_GAIN: Final[float] = 8.0
+
+# Gate off
+DEFAULT_NOISE_GATE_THRESHOLD: Final[float] = 0.0
+
+# 10ms at 16kHz; gating whole blocks avoids chopping up individual waveform cycles
+GATE_BLOCK_SAMPLES: Final[int] = 160
+
+_SAMPLE_MIN: Final[int] = -32768
+_SAMPLE_MAX: Final[int] = 32767
+
+
+def apply_gain_and_gate(audio: bytes, gain: float, gate_threshold: float) -> bytes:
+    """Apply gain and a noise gate to 16-bit little-endian PCM.
+
+    Gain saturates at full scale instead of wrapping. After gain, each block of
+    GATE_BLOCK_SAMPLES samples whose RMS (as a fraction of full scale) is below
+    gate_threshold is replaced with silence.
+
+    Args:
+        audio: Interleaved 16-bit little-endian PCM
+        gain: Linear multiplier (1.0 leaves the level unchanged)
+        gate_threshold: RMS level in 0.0-1.0 below which a block is silenced (0.0 = off)
+
+    Returns:
+        The processed PCM, the same length as the input
+    """
+    if gain == 1.0 and gate_threshold <= 0.0:
+        return audio
+
+    samples = array("h")
+    samples.frombytes(audio[: len(audio) - len(audio) % 2])
+    if sys.byteorder == "big":
+        samples.byteswap()
+
+    if gain != 1.0:
+        for i, sample in enumerate(samples):
+            samples[i] = max(_SAMPLE_MIN, min(_SAMPLE_MAX, round(sample * gain)))
+
+    if gate_threshold > 0.0:
+        for start in range(0, len(samples), GATE_BLOCK_SAMPLES):
+            block = samples[start : start + GATE_BLOCK_SAMPLES]
+            rms = math.sqrt(sum(s * s for s in block) / len(block)) / _SAMPLE_MAX
+            if rms < gate_threshold:
+                samples[start : start + len(block)] = array("h", [0]) * len(block)
+
+    if sys.byteorder == "big":
+        samples.byteswap()
+    return samples.tobytes() + audio[len(audio) - len(audio) % 2 :]
+
+
+class InputGainProcessor(FrameProcessor):
+    """Applies the configured input gain and noise gate to incoming audio frames."""
+
+    def __init__(self, **kwargs: Any) -> None:
+        """Initialize with gain and gate disabled."""
+        super().__init__(**kwargs)
+        self._gain = DEFAULT_INPUT_GAIN
+        self._gate_threshold = DEFAULT_NOISE_GATE_THRESHOLD
+
+    def set_input_gain(self, gain: float) -> None:
+        """Set the linear input gain (0.0 to MAX_INPUT_GAIN)."""
+        self._gain = gain
+        logger.info(f"Set input gain to: {gain}")
+
+    def set_noise_gate_threshold(self, threshold: float) -> None:
+        """Set the noise gate RMS threshold (0.0 to 1.0, 0.0 disables the gate)."""
+        self._gate_threshold = threshold
+        logger.info(f"Set noise gate threshold to: {threshold}")
+
+    async def process_frame(self, frame: Frame, direction: FrameDirection) -> None:
+        """Process incoming audio frames and pass everything else through."""
+        await super().process_frame(frame, direction)
+
+        if isinstance(frame, InputAudioRawFrame):
+            frame.audio = apply_gain_and_gate(frame.audio, self._gain, self._gate_threshold)
+
+        await self.push_frame(frame, direction)
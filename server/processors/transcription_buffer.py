@@ -111,6 +111,8 @@ class TranscriptionBufferProcessor(FrameProcessor):
         self._draining_event: asyncio.Event = asyncio.Event()
         # Configurable timeout for waiting for STT transcriptions (can be updated at runtime)
         self._transcription_wait_timeout = DEFAULT_TRANSCRIPTION_WAIT_TIMEOUT_SECONDS
+        # When enabled, the buffer is sent to the client as it grows (display only)
+        self._streaming_enabled = False
 
     def set_transcription_timeout(self, seconds: float) -> None:
         """Set the transcription wait timeout.
@@ -126,6 +128,16 @@ class TranscriptionBufferProcessor(FrameProcessor):
         """Get the current transcription wait timeout."""
         return self._transcription_wait_timeout
 
+    def set_streaming_enabled(self, enabled: bool) -> None:
+        """Enable or disable partial transcription messages.
+
+        Args:
+            enabled: Whether to send a transcription-partial message each time
+                     the buffer grows. The final text is still emitted once.
+        """
+        self._streaming_enabled = enabled
+        logger.info(f"Partial transcriptions {'enabled' if enabled else 'disabled'}")
+
     async def cleanup(self) -> None:
         """Clean up processor resources including internal tasks.
 
@@ -172,18 +184,42 @@ class TranscriptionBufferProcessor(FrameProcessor):
         """Stop recording - called from RTVI on_client_message handler."""
         await self._handle_stop_recording(direction)
 
+    async def cancel_recording(self) -> None:
+        """Cancel recording - discard the buffer without emitting anything."""
+        await self._handle_cancel_recording()
+
     # =========================================================================
     # State Transition Handlers
     # =========================================================================
 
     async def _handle_start_recording(self) -> None:
-        """Transition to RecordingState from any state."""
+        """Transition to RecordingState from any state.
+
+        If the previous recording is still waiting for STT, its buffer is emitted
+        first so back-to-back recordings each get a transcription.
+        """
         # Cancel any pending tasks from previous states
         self._cancel_timeout()
         self._cancel_draining()
+        match self._state:
+            case WaitingForSTTState(buffer=buffer) | DrainingState(buffer=buffer) as state:
+                if buffer.strip():
+                    logger.info(f"Start-recording received, flushing buffer: '{buffer.strip()}'")
+                    await self._emit_transcription(state, state.direction)
+                else:
+                    await self._emit_empty_response(state.direction)
+            case _:
+                pass
         logger.info("Start-recording received, entering RecordingState")
         self._state = RecordingState()
 
+    async def _handle_cancel_recording(self) -> None:
+        """Transition to IdleState from any state, dropping buffered text."""
+        self._cancel_timeout()
+        self._cancel_draining()
+        logger.info("Cancel-recording received, discarding buffer")
+        self._state = IdleState()
+
     async def _handle_stop_recording(self, direction: FrameDirection) -> None:
         """Handle stop-recording based on current state."""
         match self._state:
@@ -304,6 +340,9 @@ class TranscriptionBufferProcessor(FrameProcessor):
             case IdleState():
                 # Ignore transcriptions when idle (shouldn't happen)
                 logger.warning(f"Received transcription while idle: '{frame.text}'")
+                return
+
+        await self._emit_partial(direction)
 
     # =========================================================================
     # Timeout Handler
@@ -399,6 +438,23 @@ class TranscriptionBufferProcessor(FrameProcessor):
         )
         await self.push_frame(consolidated_frame, direction)
 
+        # Report the transcription language so the client can store it with the history entry
+        if state.language:
+            language_frame = RTVIServerMessageFrame(
+                data={"type": "transcription-language", "language": str(state.language.value)}
+            )
+            await self.push_frame(language_frame, direction)
+
+    async def _emit_partial(self, direction: FrameDirection) -> None:
+        """Send the buffered text so far to the client, if streaming is enabled."""
+        if not self._streaming_enabled or isinstance(self._state, IdleState):
+            return
+
+        frame = RTVIServerMessageFrame(
+            data={"type": "transcription-partial", "text": self._state.buffer.strip()}
+        )
+        await self.push_frame(frame, direction)
+
     async def _emit_empty_response(self, direction: FrameDirection) -> None:
         """Send an empty response message to the client."""
         frame = RTVIServerMessageFrame(data={"type": "recording-complete", "hasContent": False})
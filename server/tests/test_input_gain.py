@@ -0,0 +1,49 @@
+"""Tests for the input gain and noise gate applied to incoming audio."""
+
+from array import array
+
+from processors.input_gain import GATE_BLOCK_SAMPLES, apply_gain_and_gate
+
+
+def pcm(samples: list[int]) -> bytes:
+    """Encode samples as 16-bit little-endian PCM."""
+    return b"".join(sample.to_bytes(2, "little", signed=True) for sample in samples)
+
+
+def decode(audio: bytes) -> list[int]:
+    """Decode 16-bit little-endian PCM."""
+    return [
+        int.from_bytes(audio[i : i + 2], "little", signed=True) for i in range(0, len(audio), 2)
+    ]
+
+
+class TestApplyGainAndGate:
+    """Tests for apply_gain_and_gate() function."""
+
+    def test_defaults_leave_audio_unchanged(self) -> None:
+        """Unity gain with the gate off returns the input as-is."""
+        audio = pcm([1, -2, 300, -32768])
+        assert apply_gain_and_gate(audio, 1.0, 0.0) == audio
+
+    def test_gain_saturates_instead_of_wrapping(self) -> None:
+        """Samples pushed past full scale are clamped."""
+        result = apply_gain_and_gate(pcm([1000, -1000, 20000, -20000]), 2.0, 0.0)
+        assert decode(result) == [2000, -2000, 32767, -32768]
+
+    def test_gate_silences_quiet_blocks_only(self) -> None:
+        """A quiet block is zeroed while a loud block passes through."""
+        samples = [100] * GATE_BLOCK_SAMPLES + [8000] * GATE_BLOCK_SAMPLES
+        result = decode(apply_gain_and_gate(pcm(samples), 1.0, 0.05))
+        assert result[:GATE_BLOCK_SAMPLES] == [0] * GATE_BLOCK_SAMPLES
+        assert result[GATE_BLOCK_SAMPLES:] == [8000] * GATE_BLOCK_SAMPLES
+
+    def test_gate_applies_after_gain(self) -> None:
+        """Gain can lift a block above the gate threshold."""
+        samples = [1000] * GATE_BLOCK_SAMPLES
+        assert decode(apply_gain_and_gate(pcm(samples), 1.0, 0.05)) == [0] * GATE_BLOCK_SAMPLES
+        assert decode(apply_gain_and_gate(pcm(samples), 4.0, 0.05)) == [4000] * GATE_BLOCK_SAMPLES
+
+    def test_output_length_matches_input(self) -> None:
+        """A partial block and a trailing odd byte are preserved in length."""
+        audio = array("h", [5] * (GATE_BLOCK_SAMPLES + 3)).tobytes() + b"\x01"
+        assert len(apply_gain_and_gate(audio, 2.0, 0.5)) == len(audio)